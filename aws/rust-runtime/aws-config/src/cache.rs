@@ -8,6 +8,8 @@
 //! [`ExpiringCache`] implements two important features:
 //! 1. Respect expiry of contents
 //! 2. Deduplicate load requests to prevent thundering herds when no value is present.
+//!
+//! Values may also be replaced ahead of their expiration, e.g. by a background refresh task.
 
 use std::future::Future;
 use std::marker::PhantomData;
@@ -74,6 +76,23 @@ where
         future.await.map(|(value, _expiry)| value.clone())
     }
 
+    /// Expiration time of the cached value, if any.
+    pub(crate) async fn expiry(&self) -> Option<SystemTime> {
+        self.value
+            .read()
+            .await
+            .get()
+            .map(|(_value, expiry)| *expiry)
+    }
+
+    /// Replaces the cached value, whether or not it is expired.
+    pub(crate) async fn set(&self, value: T, expiry: SystemTime) {
+        let cell = OnceCell::new();
+        cell.set((value, expiry))
+            .unwrap_or_else(|_| unreachable!("the cell is empty"));
+        *self.value.write().await = cell;
+    }
+
     /// If the value is expired, clears the cache. Otherwise, yields the current value.
     pub(crate) async fn yield_or_clear_if_expired(&self, now: SystemTime) -> Option<T> {
         // Short-circuit if the value is not expired
@@ -160,4 +179,26 @@ mod tests {
             .is_none());
         assert!(cache.get().await.is_none());
     }
+
+    #[tokio::test]
+    async fn set_replaces_value() {
+        let cache = ExpiringCache::<Credentials, CredentialsError>::new(Duration::from_secs(10));
+        assert_eq!(None, cache.expiry().await);
+
+        let (creds, expiry) = credentials(100).unwrap();
+        cache.set(creds, expiry).await;
+        assert_eq!(Some(epoch_secs(100)), cache.expiry().await);
+
+        let (creds, expiry) = credentials(200).unwrap();
+        cache.set(creds, expiry).await;
+        assert_eq!(Some(epoch_secs(200)), cache.expiry().await);
+        assert_eq!(
+            Some(epoch_secs(200)),
+            cache
+                .yield_or_clear_if_expired(epoch_secs(150))
+                .await
+                .unwrap()
+                .expiry()
+        );
+    }
 }
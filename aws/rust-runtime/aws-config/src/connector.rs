@@ -8,6 +8,8 @@
 use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_client::erase::DynConnector;
 use aws_smithy_client::http_connector::ConnectorSettings;
+use aws_smithy_client::tls::TlsSettingsError;
+use aws_smithy_types::error::display::DisplayErrorContext;
 use std::sync::Arc;
 
 // unused when all crate features are disabled
@@ -30,30 +32,50 @@ fn base(
 }
 
 /// Given `ConnectorSettings` and an `AsyncSleep`, create a `DynConnector` from defaults depending on what cargo features are activated.
-#[cfg(feature = "rustls")]
+///
+/// # Panics
+///
+/// Panics if the [`TlsSettings`](aws_smithy_client::tls::TlsSettings) of `settings` are invalid.
+/// Use [`try_default_connector`] to handle that error.
 pub fn default_connector(
     settings: &ConnectorSettings,
     sleep: Option<Arc<dyn AsyncSleep>>,
 ) -> Option<DynConnector> {
-    let hyper = base(settings, sleep).build(aws_smithy_client::conns::https());
-    Some(DynConnector::new(hyper))
+    try_default_connector(settings, sleep)
+        .unwrap_or_else(|err| panic!("invalid TLS settings: {}", DisplayErrorContext(&err)))
 }
 
 /// Given `ConnectorSettings` and an `AsyncSleep`, create a `DynConnector` from defaults depending on what cargo features are activated.
+///
+/// Returns an error if the [`TlsSettings`](aws_smithy_client::tls::TlsSettings) of `settings` are invalid,
+/// e.g. if the CA bundle contains no certificates.
+#[cfg(feature = "rustls")]
+pub fn try_default_connector(
+    settings: &ConnectorSettings,
+    sleep: Option<Arc<dyn AsyncSleep>>,
+) -> Result<Option<DynConnector>, TlsSettingsError> {
+    let hyper = base(settings, sleep).build_https()?;
+    Ok(Some(DynConnector::new(hyper)))
+}
+
+/// Given `ConnectorSettings` and an `AsyncSleep`, create a `DynConnector` from defaults depending on what cargo features are activated.
+///
+/// Returns an error if the [`TlsSettings`](aws_smithy_client::tls::TlsSettings) of `settings` are invalid,
+/// e.g. if the CA bundle contains no certificates.
 #[cfg(all(not(feature = "rustls"), feature = "native-tls"))]
-pub fn default_connector(
+pub fn try_default_connector(
     settings: &ConnectorSettings,
     sleep: Option<Arc<dyn AsyncSleep>>,
-) -> Option<DynConnector> {
-    let hyper = base(settings, sleep).build(aws_smithy_client::conns::native_tls());
-    Some(DynConnector::new(hyper))
+) -> Result<Option<DynConnector>, TlsSettingsError> {
+    let hyper = base(settings, sleep).build_native_tls()?;
+    Ok(Some(DynConnector::new(hyper)))
 }
 
 /// Given `ConnectorSettings` and an `AsyncSleep`, create a `DynConnector` from defaults depending on what cargo features are activated.
 #[cfg(not(any(feature = "rustls", feature = "native-tls")))]
-pub fn default_connector(
+pub fn try_default_connector(
     _settings: &ConnectorSettings,
     _sleep: Option<Arc<dyn AsyncSleep>>,
-) -> Option<DynConnector> {
-    None
+) -> Result<Option<DynConnector>, TlsSettingsError> {
+    Ok(None)
 }
@@ -5,15 +5,27 @@
 
 //! Credentials Provider for external process
 
-use crate::json_credentials::{json_parse_loop, InvalidJsonCredentials, RefreshableCredentials};
+use crate::json_credentials::{json_parse_loop, InvalidJsonCredentials};
+use crate::provider_config::ProviderConfig;
+use aws_smithy_async::future::timeout::Timeout;
+use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_json::deserialize::Token;
 use aws_types::credentials::{future, CredentialsError, ProvideCredentials};
 use aws_types::{credentials, Credentials};
+use std::borrow::Cow;
 use std::fmt;
-use std::process::Command;
-use std::time::SystemTime;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
+use tokio::process::Command;
+
+/// Default amount of time the external process may run before it is killed
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Maximum number of bytes of `stderr` included in error messages
+const MAX_STDERR_LEN: usize = 1024;
 
 #[derive(Clone)]
 pub(crate) struct CommandWithSensitiveArgs<T>(T);
@@ -77,8 +89,14 @@ where
 /// The `Expiration` is optional, and must be given in the RFC 3339 date time format (e.g.,
 /// `2022-05-26T12:34:56.789Z`).
 ///
+/// The process is run every time credentials are requested: wrap this provider in a
+/// [`LazyCachingCredentialsProvider`](crate::meta::credentials::LazyCachingCredentialsProvider),
+/// as the default credentials chain does, to reuse credentials until shortly before their `Expiration`.
+///
 /// If the external process exits with a non-zero status, then the contents of `stderr`
-/// will be output as part of the credentials provider error message.
+/// will be output as part of the credentials provider error message. If it does not exit
+/// within the [timeout](Builder::timeout) (60 seconds by default), it is killed. The process
+/// is run by Tokio, so this provider must be used within a Tokio runtime.
 ///
 /// This credentials provider is included in the profile credentials provider, and can be
 /// configured using the `credential_process` attribute. For example:
@@ -90,6 +108,8 @@ where
 #[derive(Debug)]
 pub struct CredentialProcessProvider {
     command: CommandWithSensitiveArgs<String>,
+    timeout: Duration,
+    sleep: Option<Arc<dyn AsyncSleep>>,
 }
 
 impl ProvideCredentials for CredentialProcessProvider {
@@ -104,8 +124,15 @@ impl ProvideCredentials for CredentialProcessProvider {
 impl CredentialProcessProvider {
     /// Create new [`CredentialProcessProvider`] with the `command` needed to execute the external process.
     pub fn new(command: String) -> Self {
-        Self {
-            command: CommandWithSensitiveArgs::new(command),
+        Self::builder(command).build()
+    }
+
+    /// Builder for a [`CredentialProcessProvider`] running `command`
+    pub fn builder(command: impl Into<String>) -> Builder {
+        Builder {
+            command: command.into(),
+            timeout: None,
+            provider_config: None,
         }
     }
 
@@ -113,6 +140,13 @@ impl CredentialProcessProvider {
         // Security: command arguments must be redacted at debug level
         tracing::debug!(command = %self.command, "loading credentials from external process");
 
+        let sleep = self.sleep.as_ref().ok_or_else(|| {
+            CredentialsError::invalid_configuration(
+                "the credential process provider requires a sleep implementation to enforce its timeout. \
+                 Enable the `rt-tokio` feature or configure a sleep implementation with `ProviderConfig::with_sleep`",
+            )
+        })?;
+
         let mut command = if cfg!(windows) {
             let mut command = Command::new("cmd.exe");
             command.args(&["/C", self.command.unredacted()]);
@@ -122,23 +156,40 @@ impl CredentialProcessProvider {
             command.args(&["-c", self.command.unredacted()]);
             command
         };
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // the process is killed when the timeout drops it
+            .kill_on_drop(true);
 
-        let output = command.output().map_err(|e| {
+        let child = command.spawn().map_err(|e| {
             CredentialsError::provider_error(format!(
                 "Error retrieving credentials from external process: {}",
                 e
             ))
         })?;
+        let output = Timeout::new(child.wait_with_output(), sleep.sleep(self.timeout))
+            .await
+            .map_err(|_| {
+                tracing::warn!(command = %self.command, timeout = ?self.timeout, "external process timed out and was killed");
+                CredentialsError::provider_timed_out(self.timeout)
+            })?
+            .map_err(|e| {
+                CredentialsError::provider_error(format!(
+                    "Error retrieving credentials from external process: could not read output: {}",
+                    e
+                ))
+            })?;
 
         // Security: command arguments can be logged at trace level
         tracing::trace!(command = ?command, status = ?output.status, "executed command (unredacted)");
 
         if !output.status.success() {
-            let reason =
-                std::str::from_utf8(&output.stderr).unwrap_or("could not decode stderr as UTF-8");
             return Err(CredentialsError::provider_error(format!(
                 "Error retrieving credentials: external process exited with code {}. Stderr: {}",
-                output.status, reason
+                output.status,
+                truncated_stderr(&output.stderr)
             )));
         }
 
@@ -150,17 +201,16 @@ impl CredentialProcessProvider {
         })?;
 
         match parse_credential_process_json_credentials(output) {
-            Ok(RefreshableCredentials {
+            Ok(ProcessCredentials {
                 access_key_id,
                 secret_access_key,
                 session_token,
                 expiration,
-                ..
             }) => Ok(Credentials::new(
                 access_key_id,
                 secret_access_key,
-                Some(session_token.to_string()),
-                expiration.into(),
+                session_token.map(|token| token.to_string()),
+                expiration,
                 "CredentialProcess",
             )),
             Err(invalid) => Err(CredentialsError::provider_error(format!(
@@ -171,53 +221,119 @@ impl CredentialProcessProvider {
     }
 }
 
+/// Builder for [`CredentialProcessProvider`]
+///
+/// Construct one through [`CredentialProcessProvider::builder`].
+#[derive(Debug)]
+pub struct Builder {
+    command: String,
+    timeout: Option<Duration>,
+    provider_config: Option<ProviderConfig>,
+}
+
+impl Builder {
+    /// Override the configuration used for this provider
+    ///
+    /// The sleep implementation of the configuration is used to enforce the timeout.
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.provider_config = Some(provider_config.clone());
+        self
+    }
+
+    /// Amount of time the external process may run before it is killed
+    ///
+    /// Defaults to 60 seconds. Loading credentials fails when the configuration has no sleep
+    /// implementation to enforce the timeout.
+    ///
+    /// The command is run by a shell (`sh -c` or `cmd.exe /C`) and only that shell is killed:
+    /// processes it started in the background may outlive it.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Build a [`CredentialProcessProvider`]
+    pub fn build(self) -> CredentialProcessProvider {
+        let conf = self.provider_config.unwrap_or_default();
+        CredentialProcessProvider {
+            command: CommandWithSensitiveArgs::new(self.command),
+            timeout: self.timeout.unwrap_or(DEFAULT_TIMEOUT),
+            sleep: conf.sleep(),
+        }
+    }
+}
+
+/// Decode and truncate `stderr` for inclusion in error messages
+fn truncated_stderr(stderr: &[u8]) -> Cow<'_, str> {
+    let stderr = match std::str::from_utf8(stderr) {
+        Ok(stderr) => stderr.trim(),
+        Err(_) => return "could not decode stderr as UTF-8".into(),
+    };
+    if stderr.len() <= MAX_STDERR_LEN {
+        return stderr.into();
+    }
+    let mut end = MAX_STDERR_LEN;
+    while !stderr.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!(
+        "{}... ({} bytes truncated)",
+        &stderr[..end],
+        stderr.len() - end
+    )
+    .into()
+}
+
+/// Credentials returned by a credential process
+#[derive(Debug)]
+pub(crate) struct ProcessCredentials<'a> {
+    pub(crate) access_key_id: Cow<'a, str>,
+    pub(crate) secret_access_key: Cow<'a, str>,
+    pub(crate) session_token: Option<Cow<'a, str>>,
+    pub(crate) expiration: Option<SystemTime>,
+}
+
 /// Deserialize a credential_process response from a string
 ///
-/// Returns an error if the response cannot be successfully parsed or is missing keys.
+/// Returns an error if the response cannot be successfully parsed, is missing keys or if a key
+/// has a value of the wrong type.
 ///
-/// Keys are case insensitive.
+/// Keys are case insensitive. Unknown keys are ignored.
 pub(crate) fn parse_credential_process_json_credentials(
     credentials_response: &str,
-) -> Result<RefreshableCredentials<'_>, InvalidJsonCredentials> {
+) -> Result<ProcessCredentials<'_>, InvalidJsonCredentials> {
     let mut version = None;
     let mut access_key_id = None;
     let mut secret_access_key = None;
     let mut session_token = None;
     let mut expiration = None;
     json_parse_loop(credentials_response.as_bytes(), |key, value| {
-        match (key, value) {
-            /*
-             "Version": 1,
-             "AccessKeyId": "ASIARTESTID",
-             "SecretAccessKey": "TESTSECRETKEY",
-             "SessionToken": "TESTSESSIONTOKEN",
-             "Expiration": "2022-05-02T18:36:00+00:00"
-            */
-            (key, Token::ValueNumber { value, .. }) if key.eq_ignore_ascii_case("Version") => {
-                version = Some(i32::try_from(*value).map_err(|err| {
-                    InvalidJsonCredentials::InvalidField {
+        /*
+         "Version": 1,
+         "AccessKeyId": "ASIARTESTID",
+         "SecretAccessKey": "TESTSECRETKEY",
+         "SessionToken": "TESTSESSIONTOKEN",
+         "Expiration": "2022-05-02T18:36:00+00:00"
+        */
+        if key.eq_ignore_ascii_case("Version") {
+            version = Some(match value {
+                Token::ValueNumber { value, .. } => {
+                    i32::try_from(*value).map_err(|err| InvalidJsonCredentials::InvalidField {
                         field: "Version",
                         err: err.into(),
-                    }
-                })?);
-            }
-            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("AccessKeyId") => {
-                access_key_id = Some(value.to_unescaped()?)
-            }
-            (key, Token::ValueString { value, .. })
-                if key.eq_ignore_ascii_case("SecretAccessKey") =>
-            {
-                secret_access_key = Some(value.to_unescaped()?)
-            }
-            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("SessionToken") => {
-                session_token = Some(value.to_unescaped()?)
-            }
-            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("Expiration") => {
-                expiration = Some(value.to_unescaped()?)
-            }
-
-            _ => {}
-        };
+                    })?
+                }
+                _ => return Err(unexpected_type("Version", "a number")),
+            });
+        } else if key.eq_ignore_ascii_case("AccessKeyId") {
+            access_key_id = Some(string_field("AccessKeyId", value)?);
+        } else if key.eq_ignore_ascii_case("SecretAccessKey") {
+            secret_access_key = Some(string_field("SecretAccessKey", value)?);
+        } else if key.eq_ignore_ascii_case("SessionToken") {
+            session_token = optional_string_field("SessionToken", value)?;
+        } else if key.eq_ignore_ascii_case("Expiration") {
+            expiration = optional_string_field("Expiration", value)?;
+        }
         Ok(())
     })?;
 
@@ -226,7 +342,7 @@ pub(crate) fn parse_credential_process_json_credentials(
         None => return Err(InvalidJsonCredentials::MissingField("Version")),
         Some(version) => {
             return Err(InvalidJsonCredentials::InvalidField {
-                field: "version",
+                field: "Version",
                 err: format!("unknown version number: {}", version).into(),
             })
         }
@@ -235,21 +351,22 @@ pub(crate) fn parse_credential_process_json_credentials(
     let access_key_id = access_key_id.ok_or(InvalidJsonCredentials::MissingField("AccessKeyId"))?;
     let secret_access_key =
         secret_access_key.ok_or(InvalidJsonCredentials::MissingField("SecretAccessKey"))?;
-    let session_token = session_token.ok_or(InvalidJsonCredentials::MissingField("Token"))?;
-    let expiration = expiration.ok_or(InvalidJsonCredentials::MissingField("Expiration"))?;
-    let expiration =
-        SystemTime::try_from(OffsetDateTime::parse(&expiration, &Rfc3339).map_err(|err| {
-            InvalidJsonCredentials::InvalidField {
-                field: "Expiration",
-                err: err.into(),
-            }
-        })?)
-        .map_err(|_| {
-            InvalidJsonCredentials::Other(
-                "credential expiration time cannot be represented by a DateTime".into(),
-            )
-        })?;
-    Ok(RefreshableCredentials {
+    let expiration = expiration
+        .map(|expiration| {
+            SystemTime::try_from(OffsetDateTime::parse(&expiration, &Rfc3339).map_err(|err| {
+                InvalidJsonCredentials::InvalidField {
+                    field: "Expiration",
+                    err: err.into(),
+                }
+            })?)
+            .map_err(|_| {
+                InvalidJsonCredentials::Other(
+                    "credential expiration time cannot be represented by a DateTime".into(),
+                )
+            })
+        })
+        .transpose()?;
+    Ok(ProcessCredentials {
         access_key_id,
         secret_access_key,
         session_token,
@@ -257,11 +374,54 @@ pub(crate) fn parse_credential_process_json_credentials(
     })
 }
 
+fn unexpected_type(field: &'static str, expected: &str) -> InvalidJsonCredentials {
+    InvalidJsonCredentials::InvalidField {
+        field,
+        err: format!("expected {}", expected).into(),
+    }
+}
+
+/// A required, non-empty string field
+fn string_field<'a>(
+    field: &'static str,
+    value: &Token<'a>,
+) -> Result<Cow<'a, str>, InvalidJsonCredentials> {
+    match value {
+        Token::ValueString { value, .. } => {
+            let value = value.to_unescaped()?;
+            if value.is_empty() {
+                Err(InvalidJsonCredentials::InvalidField {
+                    field,
+                    err: "value must not be empty".into(),
+                })
+            } else {
+                Ok(value)
+            }
+        }
+        _ => Err(unexpected_type(field, "a string")),
+    }
+}
+
+/// An optional string field that may be `null`
+fn optional_string_field<'a>(
+    field: &'static str,
+    value: &Token<'a>,
+) -> Result<Option<Cow<'a, str>>, InvalidJsonCredentials> {
+    match value {
+        Token::ValueNull { .. } => Ok(None),
+        _ => string_field(field, value).map(Some),
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::credential_process::CredentialProcessProvider;
-    use aws_types::credentials::ProvideCredentials;
-    use std::time::SystemTime;
+    use crate::credential_process::{
+        parse_credential_process_json_credentials, CredentialProcessProvider,
+    };
+    use crate::provider_config::ProviderConfig;
+    use aws_smithy_async::rt::sleep::TokioSleep;
+    use aws_types::credentials::{CredentialsError, ProvideCredentials};
+    use std::time::{Duration, Instant, SystemTime};
     use time::format_description::well_known::Rfc3339;
     use time::OffsetDateTime;
 
@@ -285,4 +445,148 @@ mod test {
             )
         );
     }
+
+    #[tokio::test]
+    async fn credentials_are_not_cached() {
+        // `$$` expands to the PID of the shell, which differs every time the process runs
+        let provider = CredentialProcessProvider::new(String::from(
+            r#"echo "{ \"Version\": 1, \"AccessKeyId\": \"ASIARTESTID\", \"SecretAccessKey\": \"TESTSECRETKEY\", \"SessionToken\": \"$$\", \"Expiration\": \"2999-01-01T00:00:00Z\" }""#,
+        ));
+        let first = provider.provide_credentials().await.expect("valid creds");
+        let second = provider.provide_credentials().await.expect("valid creds");
+        assert_ne!(first.session_token(), second.session_token());
+    }
+
+    #[tokio::test]
+    async fn sleep_implementation_is_required() {
+        let provider = CredentialProcessProvider::builder("echo")
+            .configure(&ProviderConfig::no_configuration())
+            .build();
+        let err = provider
+            .provide_credentials()
+            .await
+            .expect_err("the timeout can't be enforced");
+        assert!(
+            matches!(err, CredentialsError::InvalidConfiguration { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn process_is_killed_after_timeout() {
+        let provider = CredentialProcessProvider::builder("sleep 10")
+            .configure(&ProviderConfig::no_configuration().with_sleep(TokioSleep::new()))
+            .timeout(Duration::from_millis(100))
+            .build();
+        let start = Instant::now();
+        let err = provider
+            .provide_credentials()
+            .await
+            .expect_err("process should time out");
+        assert!(
+            matches!(err, CredentialsError::ProviderTimedOut { .. }),
+            "{:?}",
+            err
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn process_closing_its_output_is_killed_after_timeout() {
+        let provider = CredentialProcessProvider::builder("exec >&- 2>&-; sleep 10")
+            .configure(&ProviderConfig::no_configuration().with_sleep(TokioSleep::new()))
+            .timeout(Duration::from_millis(100))
+            .build();
+        let start = Instant::now();
+        let err = provider
+            .provide_credentials()
+            .await
+            .expect_err("process should time out");
+        assert!(
+            matches!(err, CredentialsError::ProviderTimedOut { .. }),
+            "{:?}",
+            err
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn stderr_is_included_in_errors() {
+        let provider =
+            CredentialProcessProvider::new(String::from("echo 'not authorized' >&2; exit 3"));
+        let err = provider
+            .provide_credentials()
+            .await
+            .expect_err("process fails");
+        let message = format!("{}", err);
+        assert!(message.contains("Stderr: not authorized"), "{}", message);
+    }
+
+    #[test]
+    fn command_args_are_redacted_in_debug_output() {
+        let provider =
+            CredentialProcessProvider::new(String::from("/opt/bin/creds --password hunter2"));
+        let debug = format!("{:?}", provider);
+        assert!(!debug.contains("hunter2"), "{}", debug);
+        assert!(
+            debug.contains("/opt/bin/creds ** arguments redacted **"),
+            "{}",
+            debug
+        );
+    }
+
+    #[test]
+    fn session_token_and_expiration_are_optional() {
+        let creds = parse_credential_process_json_credentials(
+            r#"{ "Version": 1, "AccessKeyId": "AKID", "SecretAccessKey": "SECRET", "SessionToken": null }"#,
+        )
+        .expect("valid credentials");
+        assert_eq!("AKID", creds.access_key_id);
+        assert_eq!(None, creds.session_token);
+        assert_eq!(None, creds.expiration);
+    }
+
+    #[test]
+    fn invalid_responses() {
+        for (response, expected) in [
+            (
+                r#"{ "AccessKeyId": "AKID", "SecretAccessKey": "SECRET" }"#,
+                "Expected field `Version` in response but it was missing",
+            ),
+            (
+                r#"{ "Version": 2, "AccessKeyId": "AKID", "SecretAccessKey": "SECRET" }"#,
+                "Invalid field in response: `Version`. unknown version number: 2",
+            ),
+            (
+                r#"{ "Version": "1", "AccessKeyId": "AKID", "SecretAccessKey": "SECRET" }"#,
+                "Invalid field in response: `Version`. expected a number",
+            ),
+            (
+                r#"{ "Version": 1, "SecretAccessKey": "SECRET" }"#,
+                "Expected field `AccessKeyId` in response but it was missing",
+            ),
+            (
+                r#"{ "Version": 1, "AccessKeyId": 1234, "SecretAccessKey": "SECRET" }"#,
+                "Invalid field in response: `AccessKeyId`. expected a string",
+            ),
+            (
+                r#"{ "Version": 1, "AccessKeyId": "AKID", "SecretAccessKey": "" }"#,
+                "Invalid field in response: `SecretAccessKey`. value must not be empty",
+            ),
+            (
+                r#"{ "Version": 1, "AccessKeyId": "AKID", "SecretAccessKey": "SECRET", "Expiration": "tomorrow" }"#,
+                "Invalid field in response: `Expiration`.",
+            ),
+        ] {
+            let err =
+                parse_credential_process_json_credentials(response).expect_err("invalid response");
+            assert!(
+                format!("{}", err).contains(expected),
+                "expected `{}` to contain `{}`",
+                err,
+                expected
+            );
+        }
+    }
 }
@@ -4,7 +4,9 @@
  */
 
 //! Default Provider chains for [`region`](default_provider::region), [`credentials`](default_provider::credentials),
-//! [retries](default_provider::retry_config), [timeouts](default_provider::timeout_config) and [app name](default_provider::app_name).
+//! [retries](default_provider::retry_config), [timeouts](default_provider::timeout_config), [app name](default_provider::app_name),
+//! [endpoint URLs](default_provider::endpoint_url), [FIPS and dual-stack endpoints](default_provider::endpoint_variant)
+//! and [CA bundles](default_provider::ca_bundle).
 //!
 //! Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
 //! if you need to set custom configuration options to override the default resolution chain.
@@ -33,6 +35,24 @@ pub mod app_name;
 /// if you need to set custom configuration options to override the default resolution chain.
 pub mod timeout_config;
 
+/// Default endpoint URL provider chain
+///
+/// Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
+/// if you need to set custom configuration options to override the default resolution chain.
+pub mod endpoint_url;
+
+/// Default FIPS and dual-stack endpoint settings provider chain
+///
+/// Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
+/// if you need to set custom configuration options to override the default resolution chain.
+pub mod endpoint_variant;
+
+/// Default CA bundle provider chain
+///
+/// Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
+/// if you need to set custom configuration options to override the default resolution chain.
+pub mod ca_bundle;
+
 /// Default credentials provider chain
 ///
 /// Typically, this module is used via [`load_from_env`](crate::load_from_env) or [`from_env`](crate::from_env). It should only be used directly
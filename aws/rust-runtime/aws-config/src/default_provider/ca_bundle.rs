@@ -0,0 +1,175 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::environment::ca_bundle::EnvironmentVariableCaBundleProvider;
+use crate::profile::ca_bundle;
+use crate::provider_config::ProviderConfig;
+use aws_types::os_shim_internal::Fs;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Default CA bundle provider chain
+///
+/// This provider will check the following sources in order:
+/// 1. [Environment variables](EnvironmentVariableCaBundleProvider)
+/// 2. [Profile file](crate::profile::ca_bundle::ProfileFileCaBundleProvider)
+///
+/// The first path found is read and returned as PEM-encoded certificates.
+pub fn default_provider() -> Builder {
+    Builder::default()
+}
+
+/// Default provider builder for the CA bundle
+#[derive(Debug, Default)]
+pub struct Builder {
+    env_provider: EnvironmentVariableCaBundleProvider,
+    profile_file: ca_bundle::Builder,
+    fs: Fs,
+}
+
+impl Builder {
+    #[doc(hidden)]
+    /// Configure the default chain
+    ///
+    /// Exposed for overriding the environment when unit-testing providers
+    pub fn configure(mut self, configuration: &ProviderConfig) -> Self {
+        self.env_provider = EnvironmentVariableCaBundleProvider::new_with_env(configuration.env());
+        self.profile_file = self.profile_file.configure(configuration);
+        self.fs = configuration.fs();
+        self
+    }
+
+    /// Override the profile name used by this provider
+    pub fn profile_name(mut self, name: &str) -> Self {
+        self.profile_file = self.profile_file.profile_name(name);
+        self
+    }
+
+    /// Load the path of the CA bundle from the default chain
+    pub async fn ca_bundle_path(self) -> Option<String> {
+        match self.env_provider.ca_bundle_path() {
+            Some(path) => Some(path),
+            None => self.profile_file.build().ca_bundle_path().await,
+        }
+    }
+
+    /// Load the CA bundle from the default chain
+    ///
+    /// Returns `Ok(None)` if no CA bundle is configured, and an error if the configured CA bundle
+    /// can't be read.
+    pub async fn ca_bundle(self) -> Result<Option<Vec<u8>>, CaBundleError> {
+        let fs = self.fs.clone();
+        let path = match self.ca_bundle_path().await {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        match fs.read_to_end(&path).await {
+            Ok(ca_bundle) => Ok(Some(ca_bundle)),
+            Err(source) => Err(CaBundleError { path, source }),
+        }
+    }
+}
+
+/// The configured CA bundle could not be read
+#[derive(Debug)]
+pub struct CaBundleError {
+    path: String,
+    source: std::io::Error,
+}
+
+impl CaBundleError {
+    /// The path of the CA bundle
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl Display for CaBundleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to read the CA bundle `{}`", self.path)
+    }
+}
+
+impl Error for CaBundleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    fn provider_config(env: &[(&str, &str)]) -> ProviderConfig {
+        let fs = Fs::from_slice(&[
+            ("test_config", "[default]\nca_bundle = /profile.pem"),
+            ("/profile.pem", "profile bundle"),
+            ("/env.pem", "env bundle"),
+        ]);
+        let mut vars = vec![("AWS_CONFIG_FILE", "test_config")];
+        vars.extend_from_slice(env);
+        ProviderConfig::no_configuration()
+            .with_fs(fs)
+            .with_env(Env::from_slice(&vars))
+            .with_http_connector(no_traffic_connector())
+    }
+
+    #[tokio::test]
+    async fn ca_bundle_from_profile() {
+        let conf = provider_config(&[]);
+        assert_eq!(
+            Some(b"profile bundle".to_vec()),
+            Builder::default()
+                .configure(&conf)
+                .ca_bundle()
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn prefer_env_to_profile() {
+        let conf = provider_config(&[("AWS_CA_BUNDLE", "/env.pem")]);
+        assert_eq!(
+            Some(b"env bundle".to_vec()),
+            Builder::default()
+                .configure(&conf)
+                .ca_bundle()
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_ca_bundle() {
+        let conf = provider_config(&[("AWS_CA_BUNDLE", "/missing.pem")]);
+        let err = Builder::default()
+            .configure(&conf)
+            .ca_bundle()
+            .await
+            .expect_err("the CA bundle doesn't exist");
+        assert_eq!("/missing.pem", err.path());
+        assert_eq!(
+            "failed to read the CA bundle `/missing.pem`",
+            err.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn no_ca_bundle() {
+        let conf = ProviderConfig::no_configuration().with_http_connector(no_traffic_connector());
+        assert_eq!(
+            None,
+            Builder::default()
+                .configure(&conf)
+                .ca_bundle()
+                .await
+                .unwrap()
+        );
+    }
+}
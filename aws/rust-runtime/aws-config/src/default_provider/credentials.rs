@@ -10,6 +10,8 @@ use aws_types::credentials::{self, future, ProvideCredentials};
 use tracing::Instrument;
 
 use crate::environment::credentials::EnvironmentVariableCredentialsProvider;
+use crate::explain::{self, Explanation, Source};
+use crate::meta::credentials::lazy_caching::RefreshMetrics;
 use crate::meta::credentials::{CredentialsProviderChain, LazyCachingCredentialsProvider};
 use crate::meta::region::ProvideRegion;
 use crate::provider_config::ProviderConfig;
@@ -67,6 +69,13 @@ impl DefaultCredentialsChain {
         Builder::default()
     }
 
+    /// Returns counters of the credential loads made by this chain
+    ///
+    /// See [`LazyCachingCredentialsProvider::refresh_metrics`].
+    pub fn refresh_metrics(&self) -> RefreshMetrics {
+        self.0.refresh_metrics()
+    }
+
     async fn credentials(&self) -> credentials::Result {
         self.0
             .provide_credentials()
@@ -95,6 +104,7 @@ pub struct Builder {
     region_override: Option<Box<dyn ProvideRegion>>,
     region_chain: crate::default_provider::region::Builder,
     conf: Option<ProviderConfig>,
+    profile_override: Option<String>,
 }
 
 impl Builder {
@@ -179,6 +189,46 @@ impl Builder {
         self
     }
 
+    /// Refresh credentials in a background task before they expire.
+    ///
+    /// See [`background_refresh`](crate::meta::credentials::lazy_caching::Builder::background_refresh).
+    ///
+    /// Defaults to `false`.
+    pub fn background_refresh(mut self, enabled: bool) -> Self {
+        self.set_background_refresh(Some(enabled));
+        self
+    }
+
+    /// Refresh credentials in a background task before they expire.
+    ///
+    /// See [`background_refresh`](crate::meta::credentials::lazy_caching::Builder::background_refresh).
+    pub fn set_background_refresh(&mut self, enabled: Option<bool>) -> &mut Self {
+        self.credential_cache.set_background_refresh(enabled);
+        self
+    }
+
+    /// Amount of time after their expiration during which the last loaded credentials are
+    /// served when loading new credentials fails.
+    ///
+    /// See [`expired_credentials_grace_period`](crate::meta::credentials::lazy_caching::Builder::expired_credentials_grace_period).
+    pub fn expired_credentials_grace_period(mut self, grace_period: Duration) -> Self {
+        self.set_expired_credentials_grace_period(Some(grace_period));
+        self
+    }
+
+    /// Amount of time after their expiration during which the last loaded credentials are
+    /// served when loading new credentials fails.
+    ///
+    /// See [`expired_credentials_grace_period`](crate::meta::credentials::lazy_caching::Builder::expired_credentials_grace_period).
+    pub fn set_expired_credentials_grace_period(
+        &mut self,
+        grace_period: Option<Duration>,
+    ) -> &mut Self {
+        self.credential_cache
+            .set_expired_credentials_grace_period(grace_period);
+        self
+    }
+
     /// Add an additional credential source for the ProfileProvider
     ///
     /// Assume role profiles may specify named credential sources:
@@ -203,12 +253,32 @@ impl Builder {
         self
     }
 
+    /// Set the provider of MFA token codes for profiles that define an `mfa_serial`
+    ///
+    /// See [`mfa_token_code_provider`](crate::profile::credentials::Builder::mfa_token_code_provider)
+    pub fn mfa_token_code_provider(
+        mut self,
+        provider: impl crate::sts::mfa::ProvideMfaTokenCode + 'static,
+    ) -> Self {
+        self.profile_file_builder = self.profile_file_builder.mfa_token_code_provider(provider);
+        self
+    }
+
+    /// Cache the credentials of profile assume role chains in a file shared with other processes
+    ///
+    /// See [`file_cache`](crate::profile::credentials::Builder::file_cache)
+    pub fn file_cache(mut self, enabled: bool) -> Self {
+        self.profile_file_builder = self.profile_file_builder.file_cache(enabled);
+        self
+    }
+
     /// Override the profile name used by this provider
     ///
     /// When unset, the value of the `AWS_PROFILE` environment variable will be used.
     pub fn profile_name(mut self, name: &str) -> Self {
         self.profile_file_builder = self.profile_file_builder.profile_name(name);
         self.region_chain = self.region_chain.profile_name(name);
+        self.profile_override = Some(name.to_string());
         self
     }
 
@@ -233,6 +303,60 @@ impl Builder {
     /// This function will panic if no connector has been set and neither `rustls` and `native-tls`
     /// features have both been disabled.
     pub async fn build(self) -> DefaultCredentialsChain {
+        let (conf, provider_chain, credential_cache) = self.build_chain().await;
+        let cached_provider = credential_cache.configure(&conf).load(provider_chain);
+
+        DefaultCredentialsChain(cached_provider.build())
+    }
+
+    /// Explain which provider of the chain provides credentials and why the others do not
+    ///
+    /// Credentials are loaded without caching. See [`explain`](crate::explain) for more information.
+    ///
+    /// ## Panics
+    /// This function will panic if no connector has been set and neither `rustls` and `native-tls`
+    /// features have both been disabled.
+    pub async fn explain(self) -> Explanation {
+        let profile_override = self.profile_override.clone();
+        let (conf, provider_chain, _) = self.build_chain().await;
+        let mut explanation = provider_chain.explain().await;
+        let env = conf.env();
+        explanation.set_source(
+            "Environment",
+            explain::env_var_source(&env, &["AWS_ACCESS_KEY_ID"]),
+        );
+        explanation.set_source(
+            "Profile",
+            Source::Profile {
+                profile: explain::selected_profile_name(&env, profile_override.as_deref()),
+                key: None,
+            },
+        );
+        explanation.set_source(
+            "WebIdentityToken",
+            explain::env_var_source(&env, &["AWS_WEB_IDENTITY_TOKEN_FILE"]),
+        );
+        explanation.set_source(
+            "EcsContainer",
+            explain::env_var_source(
+                &env,
+                &[
+                    "AWS_CONTAINER_CREDENTIALS_RELATIVE_URI",
+                    "AWS_CONTAINER_CREDENTIALS_FULL_URI",
+                ],
+            ),
+        );
+        explanation.set_source("Ec2InstanceMetadata", Source::InstanceMetadata);
+        explanation
+    }
+
+    async fn build_chain(
+        self,
+    ) -> (
+        ProviderConfig,
+        CredentialsProviderChain,
+        crate::meta::credentials::lazy_caching::Builder,
+    ) {
         let region = match self.region_override {
             Some(provider) => provider.region().await,
             None => self.region_chain.build().region().await,
@@ -251,9 +375,7 @@ impl Builder {
             .or_else("WebIdentityToken", web_identity_token_provider)
             .or_else("EcsContainer", ecs_provider)
             .or_else("Ec2InstanceMetadata", imds_provider);
-        let cached_provider = self.credential_cache.configure(&conf).load(provider_chain);
-
-        DefaultCredentialsChain(cached_provider.build())
+        (conf, provider_chain, self.credential_cache)
     }
 }
 
@@ -339,6 +461,38 @@ mod test {
     make_test!(imds_disabled);
     make_test!(imds_default_chain_retries);
 
+    /// Like `make_test!`, but the IMDS client used by the default chain enables IMDSv1 fallback
+    macro_rules! make_imds_v1_fallback_test {
+        ($name: ident) => {
+            #[traced_test]
+            #[tokio::test]
+            async fn $name() {
+                crate::test_case::TestEnvironment::from_dir(concat!(
+                    "./test-data/default-provider-chain/",
+                    stringify!($name)
+                ))
+                .unwrap()
+                .execute(|conf| async move {
+                    let imds_client = crate::imds::Client::builder()
+                        .configure(&conf)
+                        .imds_v1_fallback(true)
+                        .build()
+                        .await
+                        .expect("valid IMDS client");
+                    crate::default_provider::credentials::Builder::default()
+                        .imds_client(imds_client)
+                        .configure(conf)
+                        .build()
+                        .await
+                })
+                .await
+            }
+        };
+    }
+
+    make_imds_v1_fallback_test!(imds_v1_fallback);
+    make_imds_v1_fallback_test!(imds_v1_disabled);
+
     make_test!(ecs_assume_role);
     make_test!(ecs_credentials);
     make_test!(ecs_credentials_invalid_profile);
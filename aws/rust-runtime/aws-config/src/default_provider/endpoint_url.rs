@@ -0,0 +1,119 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::environment::endpoint_url::EnvironmentVariableEndpointUrlProvider;
+use crate::profile::endpoint_url;
+use crate::provider_config::ProviderConfig;
+use aws_types::endpoint::EndpointUrlConfig;
+
+/// Default Endpoint URL Provider chain
+///
+/// Endpoint URLs are loaded from the following sources. For a given service, the first source
+/// configuring a URL wins:
+/// 1. The `AWS_ENDPOINT_URL_<SERVICE>` [environment variables](EnvironmentVariableEndpointUrlProvider)
+/// 2. The `AWS_ENDPOINT_URL` environment variable
+/// 3. The `[services]` section of the [profile file](crate::profile::endpoint_url::ProfileFileEndpointUrlProvider)
+/// 4. The `endpoint_url` property of the profile file
+pub fn default_provider() -> Builder {
+    Builder::default()
+}
+
+/// Default provider builder for [`EndpointUrlConfig`]
+#[derive(Debug, Default)]
+pub struct Builder {
+    env_provider: EnvironmentVariableEndpointUrlProvider,
+    profile_file: endpoint_url::Builder,
+}
+
+impl Builder {
+    #[doc(hidden)]
+    /// Configure the default chain
+    ///
+    /// Exposed for overriding the environment when unit-testing providers
+    pub fn configure(mut self, configuration: &ProviderConfig) -> Self {
+        self.env_provider =
+            EnvironmentVariableEndpointUrlProvider::new_with_env(configuration.env());
+        self.profile_file = self.profile_file.configure(configuration);
+        self
+    }
+
+    /// Override the profile name used by this provider
+    pub fn profile_name(mut self, name: &str) -> Self {
+        self.profile_file = self.profile_file.profile_name(name);
+        self
+    }
+
+    /// Build an [`EndpointUrlConfig`] from the default chain
+    pub async fn endpoint_url_config(self) -> Option<EndpointUrlConfig> {
+        let from_env = self.env_provider.endpoint_url_config();
+        let from_profile = self.profile_file.build().endpoint_url_config().await;
+        match (from_env, from_profile) {
+            (Some(from_env), Some(from_profile)) => Some(from_env.merge(&from_profile)),
+            (from_env, from_profile) => from_env.or(from_profile),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    const PROFILE: &str = "\
+        [default]\n\
+        endpoint_url = http://profile\n\
+        services = local\n\
+        [services local]\n\
+        s3 =\n  endpoint_url = http://profile-s3\n\
+        sts =\n  endpoint_url = http://profile-sts\n\
+    ";
+
+    async fn endpoint_url_config(env: &[(&str, &str)]) -> Option<EndpointUrlConfig> {
+        let fs = Fs::from_slice(&[("test_config", PROFILE)]);
+        let mut vars = vec![("AWS_CONFIG_FILE", "test_config")];
+        vars.extend_from_slice(env);
+        Builder::default()
+            .configure(
+                &ProviderConfig::no_configuration()
+                    .with_fs(fs)
+                    .with_env(Env::from_slice(&vars))
+                    .with_http_connector(no_traffic_connector()),
+            )
+            .endpoint_url_config()
+            .await
+    }
+
+    #[tokio::test]
+    async fn load_from_profile() {
+        let config = endpoint_url_config(&[]).await.expect("endpoint URLs set");
+        assert_eq!(config.endpoint_url("s3"), Some("http://profile-s3"));
+        assert_eq!(config.endpoint_url("iam"), Some("http://profile"));
+    }
+
+    #[tokio::test]
+    async fn service_env_var_overrides_profile() {
+        let config = endpoint_url_config(&[("AWS_ENDPOINT_URL_S3", "http://env-s3")])
+            .await
+            .expect("endpoint URLs set");
+        assert_eq!(config.endpoint_url("s3"), Some("http://env-s3"));
+        assert_eq!(config.endpoint_url("sts"), Some("http://profile-sts"));
+        assert_eq!(config.endpoint_url("iam"), Some("http://profile"));
+    }
+
+    #[tokio::test]
+    async fn global_env_var_overrides_profile() {
+        let config = endpoint_url_config(&[
+            ("AWS_ENDPOINT_URL", "http://env"),
+            ("AWS_ENDPOINT_URL_S3", "http://env-s3"),
+        ])
+        .await
+        .expect("endpoint URLs set");
+        assert_eq!(config.endpoint_url("s3"), Some("http://env-s3"));
+        assert_eq!(config.endpoint_url("sts"), Some("http://env"));
+        assert_eq!(config.endpoint_url("iam"), Some("http://env"));
+    }
+}
@@ -0,0 +1,93 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::environment::endpoint_variant::EnvironmentVariableEndpointVariantProvider;
+use crate::profile::endpoint_variant;
+use crate::provider_config::ProviderConfig;
+
+/// Default FIPS and dual-stack endpoint settings provider chain
+///
+/// This provider will check the following sources in order:
+/// 1. [Environment variables](EnvironmentVariableEndpointVariantProvider)
+/// 2. [Profile file](crate::profile::endpoint_variant::ProfileFileEndpointVariantProvider)
+pub fn default_provider() -> Builder {
+    Builder::default()
+}
+
+/// Default provider builder for the FIPS and dual-stack endpoint settings
+#[derive(Debug, Default)]
+pub struct Builder {
+    env_provider: EnvironmentVariableEndpointVariantProvider,
+    profile_file: endpoint_variant::Builder,
+}
+
+impl Builder {
+    #[doc(hidden)]
+    /// Configure the default chain
+    ///
+    /// Exposed for overriding the environment when unit-testing providers
+    pub fn configure(mut self, configuration: &ProviderConfig) -> Self {
+        self.env_provider =
+            EnvironmentVariableEndpointVariantProvider::new_with_env(configuration.env());
+        self.profile_file = self.profile_file.configure(configuration);
+        self
+    }
+
+    /// Override the profile name used by this provider
+    pub fn profile_name(mut self, name: &str) -> Self {
+        self.profile_file = self.profile_file.profile_name(name);
+        self
+    }
+
+    /// Load the FIPS setting from the default chain
+    pub async fn use_fips(self) -> Option<bool> {
+        match self.env_provider.use_fips() {
+            Some(use_fips) => Some(use_fips),
+            None => self.profile_file.build().use_fips().await,
+        }
+    }
+
+    /// Load the dual-stack setting from the default chain
+    pub async fn use_dual_stack(self) -> Option<bool> {
+        match self.env_provider.use_dual_stack() {
+            Some(use_dual_stack) => Some(use_dual_stack),
+            None => self.profile_file.build().use_dual_stack().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::os_shim_internal::{Env, Fs};
+
+    fn provider_config(env: &[(&str, &str)]) -> ProviderConfig {
+        let fs = Fs::from_slice(&[(
+            "test_config",
+            "[default]\nuse_fips_endpoint = true\nuse_dualstack_endpoint = true",
+        )]);
+        let mut vars = vec![("AWS_CONFIG_FILE", "test_config")];
+        vars.extend_from_slice(env);
+        ProviderConfig::no_configuration()
+            .with_fs(fs)
+            .with_env(Env::from_slice(&vars))
+            .with_http_connector(no_traffic_connector())
+    }
+
+    #[tokio::test]
+    async fn prefer_env_to_profile() {
+        let conf = provider_config(&[("AWS_USE_FIPS_ENDPOINT", "false")]);
+        assert_eq!(
+            Some(false),
+            Builder::default().configure(&conf).use_fips().await
+        );
+        assert_eq!(
+            Some(true),
+            Builder::default().configure(&conf).use_dual_stack().await
+        );
+    }
+}
@@ -6,6 +6,7 @@
 use aws_types::region::Region;
 
 use crate::environment::region::EnvironmentVariableRegionProvider;
+use crate::explain::{self, Explanation, Outcome, ProfileSettings, Source, Step};
 use crate::meta::region::{ProvideRegion, RegionProviderChain};
 use crate::provider_config::ProviderConfig;
 use crate::{imds, profile};
@@ -42,6 +43,8 @@ pub struct Builder {
     env_provider: EnvironmentVariableRegionProvider,
     profile_file: profile::region::Builder,
     imds: imds::region::Builder,
+    conf: Option<ProviderConfig>,
+    profile_override: Option<String>,
 }
 
 impl Builder {
@@ -53,12 +56,14 @@ impl Builder {
         self.env_provider = EnvironmentVariableRegionProvider::new_with_env(configuration.env());
         self.profile_file = self.profile_file.configure(configuration);
         self.imds = self.imds.configure(configuration);
+        self.conf = Some(configuration.clone());
         self
     }
 
     /// Override the profile name used by this provider
     pub fn profile_name(mut self, name: &str) -> Self {
         self.profile_file = self.profile_file.profile_name(name);
+        self.profile_override = Some(name.to_string());
         self
     }
 
@@ -70,6 +75,62 @@ impl Builder {
                 .or_else(self.imds.build()),
         )
     }
+
+    /// Explain which provider of the chain provides the region and why the others do not
+    ///
+    /// See [`explain`](crate::explain) for more information.
+    pub async fn explain(self) -> Explanation {
+        let conf = self.conf.unwrap_or_default();
+        let env = conf.env();
+        let mut explanation = Explanation::new("region");
+
+        let env_source = explain::env_var_source(&env, &["AWS_REGION", "AWS_DEFAULT_REGION"]);
+        let mut region = self.env_provider.region().await;
+        explanation.push(match &region {
+            Some(region) => Step::new("Environment", Outcome::Success)
+                .with_reason(format!("`{}`", region))
+                .with_source(env_source),
+            None => Step::new("Environment", Outcome::NotLoaded)
+                .with_reason("neither `AWS_REGION` nor `AWS_DEFAULT_REGION` is set")
+                .with_source(env_source),
+        });
+
+        let settings = ProfileSettings::load(&conf, self.profile_override.as_deref()).await;
+        let profile_source = settings.source("region");
+        explanation.push(if region.is_some() {
+            Step::new("Profile", Outcome::Skipped)
+                .with_reason("a previous provider provided a region")
+                .with_source(profile_source)
+        } else {
+            region = ProvideRegion::region(&self.profile_file.build()).await;
+            match &region {
+                Some(region) => Step::new("Profile", Outcome::Success)
+                    .with_reason(format!("`{}`", region))
+                    .with_source(profile_source),
+                None => Step::new("Profile", Outcome::NotLoaded)
+                    .with_reason(settings.get("region").err().unwrap_or_else(|| {
+                        format!("profile `{}` does not set a region", settings.profile())
+                    }))
+                    .with_source(profile_source),
+            }
+        });
+
+        explanation.push(if region.is_some() {
+            Step::new("Ec2InstanceMetadata", Outcome::Skipped)
+                .with_reason("a previous provider provided a region")
+                .with_source(Source::InstanceMetadata)
+        } else {
+            let step = match self.imds.build().region().await {
+                Some(region) => Step::new("Ec2InstanceMetadata", Outcome::Success)
+                    .with_reason(format!("`{}`", region)),
+                None => Step::new("Ec2InstanceMetadata", Outcome::NotLoaded).with_reason(
+                    "IMDS is disabled or did not return a region, see the logs for details",
+                ),
+            };
+            step.with_source(Source::InstanceMetadata)
+        });
+        explanation
+    }
 }
 
 impl ProvideRegion for DefaultRegionChain {
@@ -3,9 +3,11 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use aws_smithy_types::retry::RetryConfig;
+use aws_smithy_types::retry::{RetryConfig, RetryConfigBuilder};
+use std::cmp::Ordering;
 
 use crate::environment::retry_config::EnvironmentVariableRetryConfigProvider;
+use crate::explain::{self, Explanation, Outcome, Source, Step};
 use crate::profile;
 use crate::provider_config::ProviderConfig;
 
@@ -54,6 +56,8 @@ pub fn default_provider() -> Builder {
 pub struct Builder {
     env_provider: EnvironmentVariableRetryConfigProvider,
     profile_file: profile::retry_config::Builder,
+    conf: Option<ProviderConfig>,
+    profile_override: Option<String>,
 }
 
 impl Builder {
@@ -64,12 +68,14 @@ impl Builder {
         self.env_provider =
             EnvironmentVariableRetryConfigProvider::new_with_env(configuration.env());
         self.profile_file = self.profile_file.configure(configuration);
+        self.conf = Some(configuration.clone());
         self
     }
 
     /// Override the profile name used by this provider
     pub fn profile_name(mut self, name: &str) -> Self {
         self.profile_file = self.profile_file.profile_name(name);
+        self.profile_override = Some(name.to_string());
         self
     }
 
@@ -88,17 +94,140 @@ impl Builder {
         // Both of these can return errors due to invalid config settings and we want to surface those as early as possible
         // hence, we'll panic if any config values are invalid (missing values are OK though)
         // We match this instead of unwrapping so we can print the error with the `Display` impl instead of the `Debug` impl that unwrap uses
+        match self.resolve(&mut Explanation::new("retry config")).await {
+            Ok(retry_config) => retry_config,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Explain which provider sets each field of the retry config
+    ///
+    /// Unlike [`retry_config`](Builder::retry_config), invalid settings are reported as an error
+    /// step instead of panicking. See [`explain`](crate::explain) for more information.
+    pub async fn explain(self) -> Explanation {
+        let mut explanation = Explanation::new("retry config");
+        let _ = self.resolve(&mut explanation).await;
+        explanation
+    }
+
+    /// Resolve the retry config, recording how each field was resolved in `explanation`
+    async fn resolve(self, explanation: &mut Explanation) -> Result<RetryConfig, String> {
+        let conf = self.conf.unwrap_or_default();
+        let profile = explain::selected_profile_name(&conf.env(), self.profile_override.as_deref());
+
         let builder_from_env = match self.env_provider.retry_config_builder() {
             Ok(retry_config_builder) => retry_config_builder,
-            Err(err) => panic!("{}", err),
+            Err(err) => {
+                explanation
+                    .push(Step::new("Environment", Outcome::Error).with_reason(err.to_string()));
+                explanation.push(
+                    Step::new("Profile", Outcome::Skipped)
+                        .with_reason("a previous provider failed"),
+                );
+                explanation.push(
+                    Step::new("Default", Outcome::Skipped)
+                        .with_reason("a previous provider failed"),
+                );
+                return Err(err.to_string());
+            }
         };
         let builder_from_profile = match self.profile_file.build().retry_config_builder().await {
             Ok(retry_config_builder) => retry_config_builder,
-            Err(err) => panic!("{}", err),
+            Err(err) => {
+                explanation.push(Step::new("Profile", Outcome::Error).with_reason(err.to_string()));
+                explanation.push(
+                    Step::new("Default", Outcome::Skipped)
+                        .with_reason("a previous provider failed"),
+                );
+                return Err(err.to_string());
+            }
         };
 
-        builder_from_env
-            .take_unset_from(builder_from_profile)
-            .build()
+        let retry_config = builder_from_env
+            .clone()
+            .take_unset_from(builder_from_profile.clone())
+            .build();
+        for field in [Field::MaxAttempts, Field::RetryMode] {
+            let providers = [
+                (
+                    "Environment",
+                    field.is_set(&builder_from_env),
+                    Source::EnvironmentVariable(field.env_var().into()),
+                ),
+                (
+                    "Profile",
+                    field.is_set(&builder_from_profile),
+                    Source::Profile {
+                        profile: profile.clone(),
+                        key: Some(field.profile_key().into()),
+                    },
+                ),
+                ("Default", true, Source::Default),
+            ];
+            let resolved_by = providers
+                .iter()
+                .position(|(_, is_set, _)| *is_set)
+                .expect("the default is always set");
+            for (index, (provider, _, source)) in providers.into_iter().enumerate() {
+                let step = match index.cmp(&resolved_by) {
+                    Ordering::Less => {
+                        Step::new(provider, Outcome::NotLoaded).with_reason("not set")
+                    }
+                    Ordering::Equal => Step::new(provider, Outcome::Success)
+                        .with_reason(format!("`{}`", field.value(&retry_config))),
+                    Ordering::Greater => Step::new(provider, Outcome::Skipped)
+                        .with_reason("set by a previous provider"),
+                };
+                explanation.push(step.with_field(field.name()).with_source(source));
+            }
+        }
+        Ok(retry_config)
+    }
+}
+
+/// Field of the retry config that providers set independently
+#[derive(Clone, Copy)]
+enum Field {
+    MaxAttempts,
+    RetryMode,
+}
+
+impl Field {
+    fn name(self) -> &'static str {
+        match self {
+            Field::MaxAttempts => "max attempts",
+            Field::RetryMode => "retry mode",
+        }
+    }
+
+    fn env_var(self) -> &'static str {
+        match self {
+            Field::MaxAttempts => "AWS_MAX_ATTEMPTS",
+            Field::RetryMode => "AWS_RETRY_MODE",
+        }
+    }
+
+    fn profile_key(self) -> &'static str {
+        match self {
+            Field::MaxAttempts => "max_attempts",
+            Field::RetryMode => "retry_mode",
+        }
+    }
+
+    /// Whether `builder` sets this field
+    fn is_set(self, builder: &RetryConfigBuilder) -> bool {
+        let mut unset = builder.clone();
+        match self {
+            Field::MaxAttempts => unset.set_max_attempts(None),
+            Field::RetryMode => unset.set_mode(None),
+        };
+        unset != *builder
+    }
+
+    fn value(self, retry_config: &RetryConfig) -> String {
+        match self {
+            Field::MaxAttempts => retry_config.max_attempts().to_string(),
+            Field::RetryMode => format!("{:?}", retry_config.mode()),
+        }
     }
 }
@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::explain::{Explanation, Outcome, Source, Step};
 use crate::provider_config::ProviderConfig;
 use aws_sdk_sso::config::timeout::TimeoutConfig;
 use std::time::Duration;
@@ -38,4 +39,33 @@ impl Builder {
             .connect_timeout(SDK_DEFAULT_CONNECT_TIMEOUT)
             .build()
     }
+
+    /// Explain how each field of the timeout config is resolved
+    ///
+    /// The explanation is built from the timeout config that [`timeout_config`](Builder::timeout_config)
+    /// resolves. See [`explain`](crate::explain) for more information.
+    pub async fn explain(self) -> Explanation {
+        let timeout_config = self.timeout_config().await;
+        let mut explanation = Explanation::new("timeout config");
+        let fields = [
+            ("connect timeout", timeout_config.connect_timeout()),
+            ("read timeout", timeout_config.read_timeout()),
+            ("operation timeout", timeout_config.operation_timeout()),
+            (
+                "operation attempt timeout",
+                timeout_config.operation_attempt_timeout(),
+            ),
+        ];
+        for (field, timeout) in fields {
+            let step = match timeout {
+                Some(timeout) => {
+                    Step::new("Default", Outcome::Success).with_reason(format!("{:?}", timeout))
+                }
+                None => Step::new("Default", Outcome::NotLoaded)
+                    .with_reason("the SDK sets no default, so there is no timeout"),
+            };
+            explanation.push(step.with_field(field).with_source(Source::Default));
+        }
+        explanation
+    }
 }
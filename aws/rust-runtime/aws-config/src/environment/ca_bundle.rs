@@ -0,0 +1,67 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_types::os_shim_internal::Env;
+
+const ENV_VAR_CA_BUNDLE: &str = "AWS_CA_BUNDLE";
+
+/// Load the path of a CA bundle from the `AWS_CA_BUNDLE` environment variable.
+///
+/// The CA bundle is a PEM file of certificates that are trusted in addition to the platform's root
+/// certificates.
+#[derive(Debug, Default)]
+pub struct EnvironmentVariableCaBundleProvider {
+    env: Env,
+}
+
+impl EnvironmentVariableCaBundleProvider {
+    /// Create a new `EnvironmentVariableCaBundleProvider`
+    pub fn new() -> Self {
+        Self { env: Env::real() }
+    }
+
+    #[doc(hidden)]
+    /// Create a CA bundle provider from a given `Env`
+    ///
+    /// This method is used for tests that need to override environment variables.
+    pub fn new_with_env(env: Env) -> Self {
+        Self { env }
+    }
+
+    /// Attempts to load the CA bundle path from the `AWS_CA_BUNDLE` environment variable.
+    pub fn ca_bundle_path(&self) -> Option<String> {
+        match self.env.get(ENV_VAR_CA_BUNDLE) {
+            Ok(path) if !path.trim().is_empty() => Some(path),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::EnvironmentVariableCaBundleProvider;
+    use aws_types::os_shim_internal::Env;
+
+    fn test_provider(vars: &[(&str, &str)]) -> EnvironmentVariableCaBundleProvider {
+        EnvironmentVariableCaBundleProvider::new_with_env(Env::from_slice(vars))
+    }
+
+    #[test]
+    fn no_ca_bundle() {
+        assert_eq!(None, test_provider(&[]).ca_bundle_path());
+        assert_eq!(
+            None,
+            test_provider(&[("AWS_CA_BUNDLE", "")]).ca_bundle_path()
+        );
+    }
+
+    #[test]
+    fn ca_bundle_path() {
+        assert_eq!(
+            Some("/etc/ssl/corporate.pem".to_string()),
+            test_provider(&[("AWS_CA_BUNDLE", "/etc/ssl/corporate.pem")]).ca_bundle_path()
+        );
+    }
+}
@@ -0,0 +1,103 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_types::endpoint::EndpointUrlConfig;
+use aws_types::os_shim_internal::Env;
+
+const ENV_VAR_ENDPOINT_URL: &str = "AWS_ENDPOINT_URL";
+
+/// Load endpoint URLs from the `AWS_ENDPOINT_URL` and `AWS_ENDPOINT_URL_<SERVICE>` environment
+/// variables.
+///
+/// `<SERVICE>` is the service ID, uppercased with spaces replaced by underscores, e.g.
+/// `AWS_ENDPOINT_URL_DYNAMODB` or `AWS_ENDPOINT_URL_SECRETS_MANAGER`. A service-specific URL takes
+/// precedence over `AWS_ENDPOINT_URL`.
+#[derive(Debug, Default)]
+pub struct EnvironmentVariableEndpointUrlProvider {
+    env: Env,
+}
+
+impl EnvironmentVariableEndpointUrlProvider {
+    /// Create a new `EnvironmentVariableEndpointUrlProvider`
+    pub fn new() -> Self {
+        Self { env: Env::real() }
+    }
+
+    #[doc(hidden)]
+    /// Create an endpoint URL provider from a given `Env`
+    ///
+    /// This method is used for tests that need to override environment variables.
+    pub fn new_with_env(env: Env) -> Self {
+        Self { env }
+    }
+
+    /// Attempts to load endpoint URLs from the environment.
+    ///
+    /// Returns `None` when none of the environment variables are set.
+    pub fn endpoint_url_config(&self) -> Option<EndpointUrlConfig> {
+        let mut builder = EndpointUrlConfig::builder();
+        builder.set_global(non_empty(self.env.get(ENV_VAR_ENDPOINT_URL).ok()));
+        let service_prefix = format!("{}_", ENV_VAR_ENDPOINT_URL);
+        for key in self.env.keys_with_prefix(&service_prefix) {
+            if let Some(url) = non_empty(self.env.get(&key).ok()) {
+                builder.set_service(&key[service_prefix.len()..], url);
+            }
+        }
+        let config = builder.build();
+        (!config.is_empty()).then(|| config)
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+#[cfg(test)]
+mod test {
+    use super::EnvironmentVariableEndpointUrlProvider;
+    use aws_types::os_shim_internal::Env;
+
+    fn test_provider(vars: &[(&str, &str)]) -> EnvironmentVariableEndpointUrlProvider {
+        EnvironmentVariableEndpointUrlProvider::new_with_env(Env::from_slice(vars))
+    }
+
+    #[test]
+    fn no_endpoint_urls() {
+        assert_eq!(None, test_provider(&[]).endpoint_url_config());
+        assert_eq!(
+            None,
+            test_provider(&[("AWS_ENDPOINT_URL", "")]).endpoint_url_config()
+        );
+    }
+
+    #[test]
+    fn global_and_service_endpoint_urls() {
+        let config = test_provider(&[
+            ("AWS_ENDPOINT_URL", "http://localhost:4566"),
+            ("AWS_ENDPOINT_URL_DYNAMODB", "http://localhost:8000"),
+            ("AWS_ENDPOINT_URL_SECRETS_MANAGER", "http://localhost:9000"),
+        ])
+        .endpoint_url_config()
+        .expect("endpoint URLs set");
+        assert_eq!(
+            config.endpoint_url("DynamoDB"),
+            Some("http://localhost:8000")
+        );
+        assert_eq!(
+            config.endpoint_url("Secrets Manager"),
+            Some("http://localhost:9000")
+        );
+        assert_eq!(config.endpoint_url("S3"), Some("http://localhost:4566"));
+    }
+
+    #[test]
+    fn service_endpoint_url_only() {
+        let config = test_provider(&[("AWS_ENDPOINT_URL_S3", "http://localhost:9000")])
+            .endpoint_url_config()
+            .expect("endpoint URLs set");
+        assert_eq!(config.endpoint_url("s3"), Some("http://localhost:9000"));
+        assert_eq!(config.endpoint_url("sts"), None);
+    }
+}
@@ -0,0 +1,109 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_types::os_shim_internal::Env;
+
+const ENV_VAR_USE_FIPS: &str = "AWS_USE_FIPS_ENDPOINT";
+const ENV_VAR_USE_DUAL_STACK: &str = "AWS_USE_DUALSTACK_ENDPOINT";
+
+/// Load the FIPS and dual-stack endpoint settings from the `AWS_USE_FIPS_ENDPOINT` and
+/// `AWS_USE_DUALSTACK_ENDPOINT` environment variables.
+///
+/// Both variables accept `true` or `false`, case-insensitively.
+#[derive(Debug, Default)]
+pub struct EnvironmentVariableEndpointVariantProvider {
+    env: Env,
+}
+
+impl EnvironmentVariableEndpointVariantProvider {
+    /// Create a new `EnvironmentVariableEndpointVariantProvider`
+    pub fn new() -> Self {
+        Self { env: Env::real() }
+    }
+
+    #[doc(hidden)]
+    /// Create an endpoint variant provider from a given `Env`
+    ///
+    /// This method is used for tests that need to override environment variables.
+    pub fn new_with_env(env: Env) -> Self {
+        Self { env }
+    }
+
+    /// Attempts to load the FIPS setting from the `AWS_USE_FIPS_ENDPOINT` environment variable.
+    pub fn use_fips(&self) -> Option<bool> {
+        self.load_bool(ENV_VAR_USE_FIPS)
+    }
+
+    /// Attempts to load the dual-stack setting from the `AWS_USE_DUALSTACK_ENDPOINT` environment
+    /// variable.
+    pub fn use_dual_stack(&self) -> Option<bool> {
+        self.load_bool(ENV_VAR_USE_DUAL_STACK)
+    }
+
+    fn load_bool(&self, var: &str) -> Option<bool> {
+        let value = self.env.get(var).ok()?;
+        match parse_bool(&value) {
+            Some(value) => Some(value),
+            None => {
+                tracing::warn!(
+                    "`{}` environment variable value `{}` was invalid, expected `true` or `false`",
+                    var,
+                    value
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Parse `true` or `false`, case-insensitively
+pub(crate) fn parse_bool(value: &str) -> Option<bool> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if value.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::EnvironmentVariableEndpointVariantProvider;
+    use aws_types::os_shim_internal::Env;
+    use tracing_test::traced_test;
+
+    fn test_provider(vars: &[(&str, &str)]) -> EnvironmentVariableEndpointVariantProvider {
+        EnvironmentVariableEndpointVariantProvider::new_with_env(Env::from_slice(vars))
+    }
+
+    #[test]
+    fn no_settings() {
+        let provider = test_provider(&[]);
+        assert_eq!(None, provider.use_fips());
+        assert_eq!(None, provider.use_dual_stack());
+    }
+
+    #[test]
+    fn settings_are_case_insensitive() {
+        let provider = test_provider(&[
+            ("AWS_USE_FIPS_ENDPOINT", "TRUE"),
+            ("AWS_USE_DUALSTACK_ENDPOINT", "False"),
+        ]);
+        assert_eq!(Some(true), provider.use_fips());
+        assert_eq!(Some(false), provider.use_dual_stack());
+    }
+
+    #[traced_test]
+    #[test]
+    fn invalid_setting() {
+        let provider = test_provider(&[("AWS_USE_FIPS_ENDPOINT", "yes")]);
+        assert_eq!(None, provider.use_fips());
+        assert!(logs_contain(
+            "`AWS_USE_FIPS_ENDPOINT` environment variable value `yes` was invalid"
+        ));
+    }
+}
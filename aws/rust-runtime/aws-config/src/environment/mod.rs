@@ -7,10 +7,22 @@
 pub mod app_name;
 pub use app_name::EnvironmentVariableAppNameProvider;
 
+/// Load the path of a CA bundle from the environment
+pub mod ca_bundle;
+pub use ca_bundle::EnvironmentVariableCaBundleProvider;
+
 /// Load credentials from the environment
 pub mod credentials;
 pub use credentials::EnvironmentVariableCredentialsProvider;
 
+/// Load the FIPS and dual-stack endpoint settings from the environment
+pub mod endpoint_variant;
+pub use endpoint_variant::EnvironmentVariableEndpointVariantProvider;
+
+/// Load endpoint URLs from the environment
+pub mod endpoint_url;
+pub use endpoint_url::EnvironmentVariableEndpointUrlProvider;
+
 /// Load regions from the environment
 pub mod region;
 pub use region::EnvironmentVariableRegionProvider;
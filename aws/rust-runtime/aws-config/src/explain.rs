@@ -0,0 +1,494 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Explain how credentials and configuration were resolved
+//!
+//! The default provider chains try a series of providers in order. When a chain resolves an
+//! unexpected value, an [`Explanation`] shows, for every provider that was tried, what happened
+//! and why:
+//!
+//! ```no_run
+//! # async fn example() {
+//! use aws_config::default_provider::{credentials, region};
+//!
+//! let explanation = credentials::DefaultCredentialsChain::builder().explain().await;
+//! // `credentials`
+//! //   Environment: not loaded (environment variable `AWS_ACCESS_KEY_ID`): environment variable not set
+//! //   Profile: success (profile `default`): provided credentials for access key `AKIDEXAMPLE`
+//! //   ...
+//! println!("{}", explanation);
+//! if let Some(step) = explanation.selected() {
+//!     println!("credentials were provided by {}", step.provider());
+//! }
+//!
+//! let explanation = region::Builder::default().explain().await;
+//! println!("{}", explanation);
+//! # }
+//! ```
+//!
+//! Explanations are produced by loading every setting again, bypassing any cache. They are meant
+//! for debugging, tests and command line tools rather than for the hot path.
+
+use crate::profile::{self, ProfileSet};
+use crate::provider_config::ProviderConfig;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use aws_types::os_shim_internal::Env;
+use std::borrow::Cow;
+use std::fmt::{self, Display, Formatter};
+
+/// How a setting was resolved by a provider chain
+///
+/// Steps are listed in the order the providers were tried. Settings composed of several fields
+/// (e.g. retry config) have steps for each field, and one successful step per field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Explanation {
+    setting: Cow<'static, str>,
+    steps: Vec<Step>,
+}
+
+impl Explanation {
+    pub(crate) fn new(setting: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            setting: setting.into(),
+            steps: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, step: Step) {
+        self.steps.push(step);
+    }
+
+    /// Set the source of the steps of `provider` that do not have a source yet
+    pub(crate) fn set_source(&mut self, provider: &str, source: Source) {
+        for step in self
+            .steps
+            .iter_mut()
+            .filter(|step| step.provider == provider && step.source.is_none())
+        {
+            step.source = Some(source.clone());
+        }
+    }
+
+    /// The setting that was resolved, e.g. `credentials` or `region`
+    pub fn setting(&self) -> &str {
+        &self.setting
+    }
+
+    /// The steps taken to resolve the setting, in order
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// The step of the provider named `provider` for `field`
+    ///
+    /// `field` is `None` for settings that are resolved as a whole, e.g. credentials or region,
+    /// and the name of the field otherwise, e.g. `Some("max attempts")` for retry config.
+    pub fn step(&self, field: Option<&str>, provider: &str) -> Option<&Step> {
+        self.steps
+            .iter()
+            .find(|step| step.field.as_deref() == field && step.provider == provider)
+    }
+
+    /// The first step that successfully resolved the setting, if any
+    ///
+    /// For settings composed of several fields, this is the step that resolved the first field.
+    pub fn selected(&self) -> Option<&Step> {
+        self.steps
+            .iter()
+            .find(|step| step.outcome == Outcome::Success)
+    }
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`", self.setting)?;
+        for step in &self.steps {
+            write!(f, "\n  {}", step)?;
+        }
+        Ok(())
+    }
+}
+
+/// A provider that was tried while resolving a setting
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    field: Option<Cow<'static, str>>,
+    provider: Cow<'static, str>,
+    outcome: Outcome,
+    reason: Option<String>,
+    source: Option<Source>,
+}
+
+impl Step {
+    pub(crate) fn new(provider: impl Into<Cow<'static, str>>, outcome: Outcome) -> Self {
+        Self {
+            field: None,
+            provider: provider.into(),
+            outcome,
+            reason: None,
+            source: None,
+        }
+    }
+
+    pub(crate) fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+
+    pub(crate) fn with_source(mut self, source: Source) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub(crate) fn with_field(mut self, field: impl Into<Cow<'static, str>>) -> Self {
+        self.field = Some(field.into());
+        self
+    }
+
+    /// The field of the setting this step resolved, for settings composed of several fields
+    pub fn field(&self) -> Option<&str> {
+        self.field.as_deref()
+    }
+
+    /// Name of the provider, e.g. `Environment` or `Profile`
+    pub fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    /// What happened when the provider was tried
+    pub fn outcome(&self) -> Outcome {
+        self.outcome
+    }
+
+    /// Why the provider had this outcome, e.g. the error it returned
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    /// Where the provider looked for the setting
+    pub fn source(&self) -> Option<&Source> {
+        self.source.as_ref()
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(field) = &self.field {
+            write!(f, "{}: ", field)?;
+        }
+        write!(f, "{}: {}", self.provider, self.outcome)?;
+        if let Some(source) = &self.source {
+            write!(f, " ({})", source)?;
+        }
+        if let Some(reason) = &self.reason {
+            write!(f, ": {}", reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of a [`Step`]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The provider was not tried, e.g. because a previous provider already resolved the setting
+    Skipped,
+    /// The provider was tried but did not have a value for the setting
+    NotLoaded,
+    /// The provider failed. Provider chains stop at the first error.
+    Error,
+    /// The provider resolved the setting
+    Success,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Skipped => write!(f, "skipped"),
+            Outcome::NotLoaded => write!(f, "not loaded"),
+            Outcome::Error => write!(f, "error"),
+            Outcome::Success => write!(f, "success"),
+        }
+    }
+}
+
+/// Where a provider looked for a setting
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// An environment variable
+    EnvironmentVariable(Cow<'static, str>),
+    /// A profile of the shared config and credentials files, see [`profile`](crate::profile)
+    Profile {
+        /// Name of the profile
+        profile: String,
+        /// Key of the profile, when the setting is read from a single key
+        key: Option<Cow<'static, str>>,
+    },
+    /// The EC2 instance metadata service, see [`imds`](crate::imds)
+    InstanceMetadata,
+    /// A default value of the SDK
+    Default,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::EnvironmentVariable(name) => write!(f, "environment variable `{}`", name),
+            Source::Profile { profile, key: None } => write!(f, "profile `{}`", profile),
+            Source::Profile {
+                profile,
+                key: Some(key),
+            } => write!(f, "key `{}` of profile `{}`", key, profile),
+            Source::InstanceMetadata => write!(f, "EC2 instance metadata"),
+            Source::Default => write!(f, "SDK default"),
+        }
+    }
+}
+
+/// The first of `names` that is set in the environment, or the first of `names` if none is set
+pub(crate) fn env_var_source(env: &Env, names: &[&'static str]) -> Source {
+    let name = names
+        .iter()
+        .find(|name| env.get(name).is_ok())
+        .or_else(|| names.first())
+        .expect("at least one environment variable name");
+    Source::EnvironmentVariable(Cow::Borrowed(name))
+}
+
+/// The profile selected by `profile_override` or `$AWS_PROFILE`
+pub(crate) fn selected_profile_name(env: &Env, profile_override: Option<&str>) -> String {
+    profile_override
+        .map(ToString::to_string)
+        .or_else(|| env.get("AWS_PROFILE").ok())
+        .unwrap_or_else(|| "default".to_string())
+}
+
+/// Raw settings of the selected profile
+///
+/// Providers validate settings themselves, this only reads values to explain where they came from.
+#[derive(Debug)]
+pub(crate) struct ProfileSettings {
+    profile: String,
+    profile_set: Result<ProfileSet, String>,
+}
+
+impl ProfileSettings {
+    pub(crate) async fn load(conf: &ProviderConfig, profile_override: Option<&str>) -> Self {
+        let profile_set = profile::load(&conf.fs(), &conf.env(), &Default::default())
+            .await
+            .map_err(|err| format!("{}", DisplayErrorContext(&err)));
+        Self {
+            profile: selected_profile_name(&conf.env(), profile_override),
+            profile_set,
+        }
+    }
+
+    pub(crate) fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    /// Value of `key` in the selected profile, or why it could not be read
+    pub(crate) fn get(&self, key: &str) -> Result<&str, String> {
+        let profile_set = self.profile_set.as_ref().map_err(Clone::clone)?;
+        let profile = profile_set
+            .get_profile(&self.profile)
+            .ok_or_else(|| format!("profile `{}` is not defined", self.profile))?;
+        profile
+            .get(key)
+            .ok_or_else(|| format!("profile `{}` does not set `{}`", self.profile, key))
+    }
+
+    pub(crate) fn source(&self, key: &'static str) -> Source {
+        Source::Profile {
+            profile: self.profile.clone(),
+            key: Some(key.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::default_provider::{region, retry_config, timeout_config};
+    use crate::environment::credentials::EnvironmentVariableCredentialsProvider;
+    use crate::explain::{Explanation, Outcome, Source, Step};
+    use crate::meta::credentials::CredentialsProviderChain;
+    use crate::provider_config::ProviderConfig;
+    use aws_types::os_shim_internal::{Env, Fs};
+    use aws_types::Credentials;
+
+    fn provider_config(env: &[(&str, &str)], config_file: &str) -> ProviderConfig {
+        ProviderConfig::no_configuration()
+            .with_env(Env::from_slice(env))
+            .with_fs(Fs::from_slice(&[("config", config_file)]))
+    }
+
+    fn outcomes(explanation: &Explanation) -> Vec<(&str, Outcome)> {
+        explanation
+            .steps()
+            .iter()
+            .map(|step| (step.provider(), step.outcome()))
+            .collect()
+    }
+
+    #[test]
+    fn display() {
+        let mut explanation = Explanation::new("region");
+        explanation.push(
+            Step::new("Environment", Outcome::NotLoaded)
+                .with_source(Source::EnvironmentVariable("AWS_REGION".into()))
+                .with_reason("not set"),
+        );
+        explanation.push(
+            Step::new("Profile", Outcome::Success)
+                .with_source(Source::Profile {
+                    profile: "default".into(),
+                    key: Some("region".into()),
+                })
+                .with_reason("`us-east-1`"),
+        );
+        explanation.push(Step::new("Ec2InstanceMetadata", Outcome::Skipped));
+        assert_eq!(
+            "`region`\n  \
+            Environment: not loaded (environment variable `AWS_REGION`): not set\n  \
+            Profile: success (key `region` of profile `default`): `us-east-1`\n  \
+            Ec2InstanceMetadata: skipped",
+            format!("{}", explanation)
+        );
+        assert_eq!(
+            Some("Profile"),
+            explanation.selected().map(|step| step.provider())
+        );
+    }
+
+    #[tokio::test]
+    async fn credentials_chain() {
+        let chain = CredentialsProviderChain::first_try(
+            "Environment",
+            EnvironmentVariableCredentialsProvider::new_with_env(Env::from_slice(&[])),
+        )
+        .or_else(
+            "Static",
+            Credentials::new("AKIDEXAMPLE", "secret", None, None, "test"),
+        )
+        .or_else(
+            "Other",
+            Credentials::new("AKIDOTHER", "secret", None, None, "test"),
+        );
+        let explanation = chain.explain().await;
+        assert_eq!(
+            vec![
+                ("Environment", Outcome::NotLoaded),
+                ("Static", Outcome::Success),
+                ("Other", Outcome::Skipped)
+            ],
+            outcomes(&explanation)
+        );
+        assert_eq!(
+            Some("provided credentials for access key `AKIDEXAMPLE`"),
+            explanation.selected().and_then(|step| step.reason())
+        );
+    }
+
+    #[tokio::test]
+    async fn region_from_profile() {
+        let conf = provider_config(
+            &[("AWS_CONFIG_FILE", "config"), ("AWS_PROFILE", "other")],
+            "[profile other]\nregion = us-west-2",
+        );
+        let explanation = region::Builder::default().configure(&conf).explain().await;
+        assert_eq!(
+            vec![
+                ("Environment", Outcome::NotLoaded),
+                ("Profile", Outcome::Success),
+                ("Ec2InstanceMetadata", Outcome::Skipped)
+            ],
+            outcomes(&explanation)
+        );
+        let selected = explanation.selected().expect("region was resolved");
+        assert_eq!(Some("`us-west-2`"), selected.reason());
+        assert_eq!(
+            Some(&Source::Profile {
+                profile: "other".into(),
+                key: Some("region".into())
+            }),
+            selected.source()
+        );
+    }
+
+    #[tokio::test]
+    async fn retry_config_per_field() {
+        let conf = provider_config(
+            &[("AWS_CONFIG_FILE", "config"), ("AWS_MAX_ATTEMPTS", "5")],
+            "[default]\nmax_attempts = 2\nretry_mode = standard",
+        );
+        let explanation = retry_config::Builder::default()
+            .configure(&conf)
+            .explain()
+            .await;
+        assert_eq!(
+            vec![
+                ("Environment", Outcome::Success),
+                ("Profile", Outcome::Skipped),
+                ("Default", Outcome::Skipped),
+                ("Environment", Outcome::NotLoaded),
+                ("Profile", Outcome::Success),
+                ("Default", Outcome::Skipped),
+            ],
+            outcomes(&explanation)
+        );
+        let max_attempts = explanation
+            .step(Some("max attempts"), "Environment")
+            .expect("step exists");
+        assert_eq!(Outcome::Success, max_attempts.outcome());
+        assert_eq!(Some("`5`"), max_attempts.reason());
+        let retry_mode = explanation
+            .step(Some("retry mode"), "Profile")
+            .expect("step exists");
+        assert_eq!(Outcome::Success, retry_mode.outcome());
+        assert_eq!(Some("`Standard`"), retry_mode.reason());
+        assert_eq!(
+            Some(&Source::Profile {
+                profile: "default".into(),
+                key: Some("retry_mode".into())
+            }),
+            retry_mode.source()
+        );
+        assert_eq!(None, explanation.step(None, "Environment"));
+    }
+
+    #[tokio::test]
+    async fn timeout_config_reports_the_resolved_config() {
+        let explanation = timeout_config::Builder::default().explain().await;
+        let connect_timeout = explanation
+            .step(Some("connect timeout"), "Default")
+            .expect("step exists");
+        assert_eq!(Outcome::Success, connect_timeout.outcome());
+        assert_eq!(Some("3.1s"), connect_timeout.reason());
+        assert_eq!(
+            Some(Outcome::NotLoaded),
+            explanation
+                .step(Some("read timeout"), "Default")
+                .map(|step| step.outcome())
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_retry_config_is_an_error() {
+        let conf = provider_config(&[("AWS_MAX_ATTEMPTS", "0")], "");
+        let explanation = retry_config::Builder::default()
+            .configure(&conf)
+            .explain()
+            .await;
+        assert_eq!(
+            vec![
+                ("Environment", Outcome::Error),
+                ("Profile", Outcome::Skipped),
+                ("Default", Outcome::Skipped),
+            ],
+            outcomes(&explanation)
+        );
+    }
+}
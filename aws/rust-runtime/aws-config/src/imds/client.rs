@@ -61,7 +61,8 @@ fn user_agent() -> AwsUserAgent {
 /// Client for IMDSv2. This client handles fetching tokens, retrying on failure, and token
 /// caching according to the specified token TTL.
 ///
-/// _Note: This client ONLY supports IMDSv2. It will not fallback to IMDSv1. See
+/// _Note: By default, this client ONLY supports IMDSv2. It will not fallback to IMDSv1 unless
+/// [`Builder::imds_v1_fallback`] is enabled. See
 /// [transitioning to IMDSv2](https://docs.aws.amazon.com/AWSEC2/latest/UserGuide/configuring-instance-metadata-service.html#instance-metadata-transition-to-version-2)
 /// for more information._
 ///
@@ -126,6 +127,17 @@ fn user_agent() -> AwsUserAgent {
 ///
 /// 7. The default value of `http://169.254.169.254` will be used.
 ///
+/// ## IMDSv1 fallback
+/// When [`Builder::imds_v1_fallback`] is enabled and the token API responds with a 403, 404 or 405
+/// status code or times out, requests are sent without a session token (IMDSv1). The fallback can
+/// be forbidden regardless of the builder setting by setting the `AWS_EC2_METADATA_V1_DISABLED`
+/// environment variable or the `ec2_metadata_v1_disabled` profile field to `true`:
+/// ```ini
+/// [default]
+/// # ... other configuration
+/// ec2_metadata_v1_disabled = true
+/// ```
+///
 #[derive(Clone, Debug)]
 pub struct Client {
     inner: Arc<ClientInner>,
@@ -430,6 +442,7 @@ pub struct Builder {
     token_ttl: Option<Duration>,
     connect_timeout: Option<Duration>,
     read_timeout: Option<Duration>,
+    v1_fallback: Option<bool>,
     config: Option<ProviderConfig>,
 }
 
@@ -539,6 +552,20 @@ impl Builder {
         self
     }
 
+    /// Allow falling back to IMDSv1 when an IMDSv2 session token can't be loaded
+    ///
+    /// When enabled, requests are sent without a session token if the token API responds with a
+    /// 403, 404 or 405 status code or times out. The token API is tried again once the token TTL
+    /// has elapsed.
+    ///
+    /// This is disabled by default. It has no effect when IMDSv1 is disabled with the
+    /// `AWS_EC2_METADATA_V1_DISABLED` environment variable or the `ec2_metadata_v1_disabled`
+    /// profile field.
+    pub fn imds_v1_fallback(mut self, enabled: bool) -> Self {
+        self.v1_fallback = Some(enabled);
+        self
+    }
+
     /* TODO(https://github.com/awslabs/aws-sdk-rust/issues/339): Support customizing the port explicitly */
     /*
     pub fn port(mut self, port: u32) -> Self {
@@ -567,6 +594,16 @@ impl Builder {
             .unwrap_or_else(|| EndpointSource::Env(config.env(), config.fs()));
         let endpoint = endpoint_source.endpoint(self.mode_override).await?;
         let endpoint = Endpoint::immutable(endpoint);
+        let v1_fallback = if self.v1_fallback.unwrap_or(false) {
+            if v1_disabled(&config.env(), &config.fs()).await? {
+                tracing::debug!("IMDSv1 fallback was requested but IMDSv1 is disabled");
+                false
+            } else {
+                true
+            }
+        } else {
+            false
+        };
         let retry_config = retry::Config::default()
             .with_max_attempts(self.max_attempts.unwrap_or(DEFAULT_ATTEMPTS));
         let token_loader = token::TokenMiddleware::new(
@@ -577,7 +614,8 @@ impl Builder {
             retry_config.clone(),
             timeout_config.clone(),
             config.sleep(),
-        );
+        )
+        .with_v1_fallback(v1_fallback);
         let middleware = ImdsMiddleware { token_loader };
         let mut smithy_builder = aws_smithy_client::Client::builder()
             .connector(connector.clone())
@@ -600,11 +638,27 @@ impl Builder {
 mod env {
     pub(super) const ENDPOINT: &str = "AWS_EC2_METADATA_SERVICE_ENDPOINT";
     pub(super) const ENDPOINT_MODE: &str = "AWS_EC2_METADATA_SERVICE_ENDPOINT_MODE";
+    pub(super) const V1_DISABLED: &str = "AWS_EC2_METADATA_V1_DISABLED";
 }
 
 mod profile_keys {
     pub(super) const ENDPOINT: &str = "ec2_metadata_service_endpoint";
     pub(super) const ENDPOINT_MODE: &str = "ec2_metadata_service_endpoint_mode";
+    pub(super) const V1_DISABLED: &str = "ec2_metadata_v1_disabled";
+}
+
+/// Returns true if IMDSv1 was disabled via the environment or the profile
+async fn v1_disabled(env: &Env, fs: &Fs) -> Result<bool, BuildError> {
+    if let Ok(value) = env.get(env::V1_DISABLED) {
+        return Ok(value.eq_ignore_ascii_case("true"));
+    }
+    let profile = profile::load(fs, env, &Default::default())
+        .await
+        .map_err(BuildError::InvalidProfile)?;
+    Ok(profile
+        .get(profile_keys::V1_DISABLED)
+        .map(|value| value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false))
 }
 
 /// Endpoint Configuration Abstraction
@@ -1031,6 +1085,133 @@ pub(crate) mod test {
         connection.assert_requests_match(&[]);
     }
 
+    async fn make_v1_fallback_client(
+        connector: DynConnector,
+        env: &[(&str, &str)],
+        fs: &[(&str, &str)],
+    ) -> Result<super::Client, super::BuildError> {
+        super::Client::builder()
+            .configure(
+                &ProviderConfig::no_configuration()
+                    .with_sleep(TokioSleep::new())
+                    .with_env(Env::from_slice(env))
+                    .with_fs(Fs::from_slice(fs))
+                    .with_http_connector(connector),
+            )
+            .imds_v1_fallback(true)
+            .build()
+            .await
+    }
+
+    fn imds_v1_request(path: &'static str) -> http::Request<SdkBody> {
+        http::Request::builder()
+            .uri(Uri::from_static(path))
+            .method("GET")
+            .body(SdkBody::empty())
+            .unwrap()
+    }
+
+    /// With IMDSv1 fallback enabled, 403, 404 and 405 token responses lead to requests without a token
+    #[tokio::test]
+    #[traced_test]
+    async fn v1_fallback_on_token_error() {
+        for status in [403u16, 404, 405] {
+            let connection = TestConnection::new(vec![
+                (
+                    token_request("http://169.254.169.254", 21600),
+                    http::Response::builder().status(status).body("").unwrap(),
+                ),
+                (
+                    imds_v1_request("http://169.254.169.254/latest/metadata"),
+                    imds_response("ok"),
+                ),
+                (
+                    imds_v1_request("http://169.254.169.254/latest/metadata2"),
+                    imds_response("ok2"),
+                ),
+            ]);
+            let client = make_v1_fallback_client(DynConnector::new(connection.clone()), &[], &[])
+                .await
+                .expect("valid client");
+            assert_eq!(client.get("/latest/metadata").await.expect("success"), "ok");
+            // the fallback is cached: the token API isn't called again
+            assert_eq!(
+                client.get("/latest/metadata2").await.expect("success"),
+                "ok2"
+            );
+            connection.assert_requests_match(&[]);
+            for request in connection.requests().iter().skip(1) {
+                assert!(request
+                    .actual
+                    .headers()
+                    .get("x-aws-ec2-metadata-token")
+                    .is_none());
+            }
+        }
+        assert!(logs_contain("falling back to IMDSv1"));
+    }
+
+    /// With IMDSv1 fallback enabled, a token request that times out leads to requests without a token
+    #[tokio::test]
+    async fn v1_fallback_on_token_timeout() {
+        use aws_smithy_http::result::ConnectorError;
+
+        let connector = DynConnector::new(tower::service_fn(
+            |request: http::Request<SdkBody>| async move {
+                if request.method() == http::Method::PUT {
+                    return Err(ConnectorError::timeout("token request timed out".into()));
+                }
+                assert!(request.headers().get("x-aws-ec2-metadata-token").is_none());
+                Ok(http::Response::builder()
+                    .status(200)
+                    .body(SdkBody::from("ok"))
+                    .unwrap())
+            },
+        ));
+        let client = make_v1_fallback_client(connector, &[], &[])
+            .await
+            .expect("valid client");
+        assert_eq!(client.get("/latest/metadata").await.expect("success"), "ok");
+    }
+
+    /// Other token failures are not a reason to fall back to IMDSv1
+    #[tokio::test]
+    async fn no_v1_fallback_on_invalid_token() {
+        let connection = TestConnection::new(vec![(
+            token_request("http://169.254.169.254", 21600),
+            token_response(21600, "replaced").map(|_| vec![1, 0]),
+        )]);
+        let client = make_v1_fallback_client(DynConnector::new(connection.clone()), &[], &[])
+            .await
+            .expect("valid client");
+        let err = client.get("/latest/metadata").await.expect_err("no token");
+        assert!(format!("{}", err).contains("Invalid Token"), "{}", err);
+        connection.assert_requests_match(&[]);
+    }
+
+    /// Disabling IMDSv1 via the environment or the profile takes precedence over the builder
+    #[tokio::test]
+    async fn v1_disabled_prevents_fallback() {
+        let env_disabled: (&[(&str, &str)], &[(&str, &str)]) =
+            (&[("AWS_EC2_METADATA_V1_DISABLED", "true")], &[]);
+        let profile_disabled: (&[(&str, &str)], &[(&str, &str)]) = (
+            &[("AWS_CONFIG_FILE", "config")],
+            &[("config", "[default]\nec2_metadata_v1_disabled = TRUE")],
+        );
+        for (env, fs) in [env_disabled, profile_disabled] {
+            let connection = TestConnection::new(vec![(
+                token_request("http://169.254.169.254", 21600),
+                http::Response::builder().status(403).body("").unwrap(),
+            )]);
+            let client = make_v1_fallback_client(DynConnector::new(connection.clone()), env, fs)
+                .await
+                .expect("valid client");
+            let err = client.get("/latest/metadata").await.expect_err("no token");
+            assert!(format!("{}", err).contains("forbidden"), "{}", err);
+            connection.assert_requests_match(&[]);
+        }
+    }
+
     /// Successful responses should classify as `RetryKind::Unnecessary`
     #[test]
     fn successful_response_properly_classified() {
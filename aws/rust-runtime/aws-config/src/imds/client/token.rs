@@ -13,6 +13,7 @@
 //! - Cache the token according to the TTL
 //! - Retry token loading when it fails
 //! - Attach the token to the request in the `x-aws-ec2-metadata-token` header
+//! - If enabled, fall back to IMDSv1 (no token) when the token API is unavailable
 
 use std::fmt::{Debug, Formatter};
 use std::future::Future;
@@ -23,7 +24,7 @@ use std::time::{Duration, SystemTime};
 use aws_http::user_agent::UserAgentStage;
 use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_client::erase::DynConnector;
-use aws_smithy_client::retry;
+use aws_smithy_client::{retry, SdkError};
 use aws_smithy_http::body::SdkBody;
 use aws_smithy_http::endpoint::Endpoint;
 use aws_smithy_http::middleware::AsyncMapRequest;
@@ -39,6 +40,7 @@ use http::{HeaderValue, Uri};
 use crate::cache::ExpiringCache;
 use crate::imds::client::{ImdsError, ImdsResponseRetryClassifier, TokenError};
 use aws_sdk_sso::config::timeout::TimeoutConfig;
+use aws_smithy_types::error::display::DisplayErrorContext;
 
 /// Token Refresh Buffer
 ///
@@ -62,14 +64,17 @@ struct Token {
 /// Token middleware will load/cache a token when required and handle caching/expiry.
 ///
 /// It will attach the token to the incoming request on the `x-aws-ec2-metadata-token` header.
+/// When IMDSv1 fallback is enabled and the token API is unavailable, `None` is cached instead of a
+/// token for the duration of the token TTL and requests are sent without a token.
 #[derive(Clone)]
 pub(super) struct TokenMiddleware {
     client: Arc<aws_smithy_client::Client<DynConnector, MapRequestLayer<UserAgentStage>>>,
     token_parser: GetTokenResponseHandler,
-    token: ExpiringCache<Token, ImdsError>,
+    token: ExpiringCache<Option<Token>, ImdsError>,
     time_source: TimeSource,
     endpoint: Endpoint,
     token_ttl: Duration,
+    v1_fallback: bool,
 }
 
 impl Debug for TokenMiddleware {
@@ -105,8 +110,16 @@ impl TokenMiddleware {
             time_source,
             endpoint,
             token_ttl,
+            v1_fallback: false,
         }
     }
+
+    /// Send requests without a token when the token API is unavailable
+    pub(super) fn with_v1_fallback(mut self, v1_fallback: bool) -> Self {
+        self.v1_fallback = v1_fallback;
+        self
+    }
+
     async fn add_token(&self, request: Request) -> Result<Request, ImdsError> {
         let preloaded_token = self
             .token
@@ -120,15 +133,34 @@ impl TokenMiddleware {
                     .await
             }
         }?;
-        request.augment(|mut request, _| {
-            request
-                .headers_mut()
-                .insert(X_AWS_EC2_METADATA_TOKEN, token.value);
-            Ok(request)
-        })
+        match token {
+            Some(token) => request.augment(|mut request, _| {
+                request
+                    .headers_mut()
+                    .insert(X_AWS_EC2_METADATA_TOKEN, token.value);
+                Ok(request)
+            }),
+            None => Ok(request),
+        }
+    }
+
+    async fn get_token(&self) -> Result<(Option<Token>, SystemTime), ImdsError> {
+        match self.get_token_v2().await {
+            Ok((token, expiry)) => Ok((Some(token), expiry)),
+            Err(ImdsError::FailedToLoadToken(err))
+                if self.v1_fallback && v1_fallback_allowed(&err) =>
+            {
+                tracing::warn!(
+                    err = %DisplayErrorContext(&err),
+                    "failed to load an IMDSv2 session token, falling back to IMDSv1"
+                );
+                Ok((None, self.time_source.now() + self.token_ttl))
+            }
+            Err(err) => Err(err),
+        }
     }
 
-    async fn get_token(&self) -> Result<(Token, SystemTime), ImdsError> {
+    async fn get_token_v2(&self) -> Result<(Token, SystemTime), ImdsError> {
         let mut uri = Uri::from_static("/latest/api/token");
         self.endpoint.set_endpoint(&mut uri, None);
         let request = http::Request::builder()
@@ -156,6 +188,21 @@ impl TokenMiddleware {
     }
 }
 
+/// Token API failures that indicate IMDSv2 is unavailable rather than IMDS as a whole
+///
+/// - 403, 404, 405: the token API is blocked or not supported (e.g. by a proxy in front of IMDS)
+/// - timeouts: the `PUT` request is dropped (e.g. because it exceeds the hop limit)
+fn v1_fallback_allowed(err: &SdkError<TokenError>) -> bool {
+    match err {
+        SdkError::TimeoutError(_) => true,
+        SdkError::DispatchFailure(err) => err.is_timeout(),
+        SdkError::ServiceError { raw, .. } | SdkError::ResponseError { raw, .. } => {
+            matches!(raw.http().status().as_u16(), 403 | 404 | 405)
+        }
+        _ => false,
+    }
+}
+
 impl AsyncMapRequest for TokenMiddleware {
     type Error = ImdsError;
     type Future = Pin<Box<dyn Future<Output = Result<Request, Self::Error>> + Send + 'static>>;
@@ -6,7 +6,9 @@
 //! IMDSv2 Credentials Provider
 //!
 //! # Important
-//! This credential provider will NOT fallback to IMDSv1. Ensure that IMDSv2 is enabled on your instances.
+//! By default, this credential provider will NOT fallback to IMDSv1. Ensure that IMDSv2 is enabled on
+//! your instances, or provide an [`imds::Client`] with [IMDSv1 fallback](crate::imds::client::Builder::imds_v1_fallback)
+//! enabled.
 
 use crate::imds;
 use crate::imds::client::{ImdsError, LazyClient};
@@ -20,7 +22,8 @@ use std::borrow::Cow;
 
 /// IMDSv2 Credentials Provider
 ///
-/// _Note: This credentials provider will NOT fallback to the IMDSv1 flow._
+/// _Note: This credentials provider will NOT fallback to the IMDSv1 flow unless it is configured with an
+/// [`imds::Client`] that enables [IMDSv1 fallback](crate::imds::client::Builder::imds_v1_fallback)._
 #[derive(Debug)]
 pub struct ImdsCredentialsProvider {
     client: LazyClient,
@@ -107,10 +110,7 @@ impl ImdsCredentialsProvider {
     }
 
     fn imds_disabled(&self) -> bool {
-        match self.env.get(super::env::EC2_METADATA_DISABLED) {
-            Ok(value) => value.eq_ignore_ascii_case("true"),
-            _ => false,
-        }
+        super::imds_disabled(&self.env)
     }
 
     /// Load an inner IMDS client from the OnceCell
@@ -148,7 +148,7 @@ impl ImdsCredentialsProvider {
         if self.imds_disabled() {
             tracing::debug!("IMDS disabled because $AWS_EC2_METADATA_DISABLED was set to `true`");
             return Err(CredentialsError::not_loaded(
-                "IMDS disabled by $AWS_ECS_METADATA_DISABLED",
+                "IMDS disabled by $AWS_EC2_METADATA_DISABLED",
             ));
         }
         tracing::debug!("loading credentials from IMDS");
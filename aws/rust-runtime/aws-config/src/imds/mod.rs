@@ -15,5 +15,13 @@ mod env {
     pub(crate) const EC2_METADATA_DISABLED: &str = "AWS_EC2_METADATA_DISABLED";
 }
 
+/// Returns true if IMDS was disabled with `AWS_EC2_METADATA_DISABLED=true`
+fn imds_disabled(env: &aws_types::os_shim_internal::Env) -> bool {
+    match env.get(env::EC2_METADATA_DISABLED) {
+        Ok(value) => value.eq_ignore_ascii_case("true"),
+        _ => false,
+    }
+}
+
 #[doc(inline)]
 pub use client::Client;
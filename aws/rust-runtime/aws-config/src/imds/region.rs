@@ -35,10 +35,7 @@ impl ImdsRegionProvider {
     }
 
     fn imds_disabled(&self) -> bool {
-        match self.env.get(super::env::EC2_METADATA_DISABLED) {
-            Ok(value) => value.eq_ignore_ascii_case("true"),
-            _ => false,
-        }
+        super::imds_disabled(&self.env)
     }
 
     /// Load a region from IMDS
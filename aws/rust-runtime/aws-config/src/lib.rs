@@ -132,6 +132,8 @@ pub mod connector;
 
 pub mod credential_process;
 
+pub mod explain;
+
 // Re-export types from smithy-types
 pub use aws_smithy_types::retry;
 pub use aws_smithy_types::timeout;
@@ -165,20 +167,31 @@ pub async fn load_from_env() -> aws_types::SdkConfig {
 /// Load default sources for all configuration with override support
 pub use loader::ConfigLoader;
 
+/// The TLS configuration loaded by [`ConfigLoader::try_load`] could not be used
+pub use loader::TlsConfigError;
+
 mod loader {
+    use std::error::Error;
+    use std::fmt::{Display, Formatter};
     use std::sync::Arc;
 
-    use crate::connector::default_connector;
+    use crate::connector::{default_connector, try_default_connector};
     use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep};
     use aws_smithy_client::http_connector::{ConnectorSettings, HttpConnector};
+    use aws_smithy_client::tls::{TlsSettings, TlsSettingsError};
+    use aws_smithy_types::error::display::DisplayErrorContext;
     use aws_smithy_types::retry::RetryConfig;
     use aws_smithy_types::timeout::TimeoutConfig;
     use aws_types::app_name::AppName;
     use aws_types::credentials::{ProvideCredentials, SharedCredentialsProvider};
-    use aws_types::endpoint::ResolveAwsEndpoint;
+    use aws_types::endpoint::{EndpointUrlConfig, ResolveAwsEndpoint};
     use aws_types::SdkConfig;
 
-    use crate::default_provider::{app_name, credentials, region, retry_config, timeout_config};
+    use crate::default_provider::ca_bundle::CaBundleError;
+    use crate::default_provider::{
+        app_name, ca_bundle, credentials, endpoint_url, endpoint_variant, region, retry_config,
+        timeout_config,
+    };
     use crate::meta::region::ProvideRegion;
     use crate::provider_config::ProviderConfig;
 
@@ -193,12 +206,16 @@ mod loader {
         app_name: Option<AppName>,
         credentials_provider: Option<SharedCredentialsProvider>,
         endpoint_resolver: Option<Arc<dyn ResolveAwsEndpoint>>,
+        endpoint_url_config: Option<EndpointUrlConfig>,
+        use_fips: Option<bool>,
+        use_dual_stack: Option<bool>,
         region: Option<Box<dyn ProvideRegion>>,
         retry_config: Option<RetryConfig>,
         sleep: Option<Arc<dyn AsyncSleep>>,
         timeout_config: Option<TimeoutConfig>,
         provider_config: Option<ProviderConfig>,
         http_connector: Option<HttpConnector>,
+        tls_settings: Option<TlsSettings>,
     }
 
     impl ConfigLoader {
@@ -322,6 +339,68 @@ mod loader {
             self
         }
 
+        /// Override the endpoint URLs used to build [`SdkConfig`](aws_types::SdkConfig).
+        ///
+        /// By default, endpoint URLs are loaded from the `AWS_ENDPOINT_URL` and
+        /// `AWS_ENDPOINT_URL_<SERVICE>` environment variables and from the active profile. See
+        /// [`default_provider::endpoint_url`](crate::default_provider::endpoint_url).
+        ///
+        /// # Examples
+        ///
+        /// Send DynamoDB requests to a local endpoint
+        /// ```no_run
+        /// # async fn doc() {
+        /// use aws_types::endpoint::EndpointUrlConfig;
+        /// let sdk_config = aws_config::from_env()
+        ///   .endpoint_url_config(
+        ///       EndpointUrlConfig::builder()
+        ///           .service("DynamoDB", "http://localhost:8000")
+        ///           .build()
+        ///   )
+        ///   .load().await;
+        /// # }
+        /// ```
+        pub fn endpoint_url_config(mut self, endpoint_url_config: EndpointUrlConfig) -> Self {
+            self.endpoint_url_config = Some(endpoint_url_config);
+            self
+        }
+
+        /// Override whether requests are sent to FIPS compliant endpoints.
+        ///
+        /// By default, this setting is loaded from the `AWS_USE_FIPS_ENDPOINT` environment
+        /// variable or the `use_fips_endpoint` profile property.
+        ///
+        /// # Examples
+        /// ```no_run
+        /// # async fn create_config() {
+        /// let config = aws_config::from_env()
+        ///     .use_fips(true)
+        ///     .load().await;
+        /// # }
+        /// ```
+        pub fn use_fips(mut self, use_fips: bool) -> Self {
+            self.use_fips = Some(use_fips);
+            self
+        }
+
+        /// Override whether requests are sent to dual-stack (IPv4 and IPv6) endpoints.
+        ///
+        /// By default, this setting is loaded from the `AWS_USE_DUALSTACK_ENDPOINT` environment
+        /// variable or the `use_dualstack_endpoint` profile property.
+        ///
+        /// # Examples
+        /// ```no_run
+        /// # async fn create_config() {
+        /// let config = aws_config::from_env()
+        ///     .use_dual_stack(true)
+        ///     .load().await;
+        /// # }
+        /// ```
+        pub fn use_dual_stack(mut self, use_dual_stack: bool) -> Self {
+            self.use_dual_stack = Some(use_dual_stack);
+            self
+        }
+
         /// Set configuration for all sub-loaders (credentials, region etc.)
         ///
         /// Update the `ProviderConfig` used for all nested loaders. This can be used to override
@@ -346,6 +425,35 @@ mod loader {
             self
         }
 
+        /// Override the TLS settings used for all HTTP connections.
+        ///
+        /// By default, a CA bundle is loaded from the `AWS_CA_BUNDLE` environment variable or the
+        /// `ca_bundle` profile property. The TLS settings apply to the default HTTP connector of the
+        /// [`SdkConfig`](aws_types::SdkConfig) as well as to the connectors used by credentials and
+        /// region providers, e.g. for IMDS, ECS, STS and SSO.
+        ///
+        /// # Examples
+        /// ```no_run
+        /// # async fn create_config() {
+        /// use aws_smithy_client::tls::{ClientCertificate, TlsSettings};
+        ///
+        /// let certificate = std::fs::read("client.pem").expect("readable");
+        /// let private_key = std::fs::read("client-key.pem").expect("readable");
+        /// let config = aws_config::from_env()
+        ///     .tls_settings(
+        ///         TlsSettings::builder()
+        ///             .client_certificate(ClientCertificate::from_pem(certificate, private_key))
+        ///             .build(),
+        ///     )
+        ///     .load()
+        ///     .await;
+        /// # }
+        /// ```
+        pub fn tls_settings(mut self, tls_settings: TlsSettings) -> Self {
+            self.tls_settings = Some(tls_settings);
+            self
+        }
+
         /// Load the default configuration chain
         ///
         /// If fields have been overridden during builder construction, the override values will be used.
@@ -355,8 +463,49 @@ mod loader {
         /// NOTE: When an override is provided, the default implementation is **not** used as a fallback.
         /// This means that if you provide a region provider that does not return a region, no region will
         /// be set in the resulting [`SdkConfig`](aws_types::SdkConfig)
+        ///
+        /// # Panics
+        ///
+        /// Panics if the TLS configuration is invalid, e.g. if the CA bundle set with `AWS_CA_BUNDLE`
+        /// can't be read. Use [`ConfigLoader::try_load`] to handle that error.
         pub async fn load(self) -> SdkConfig {
+            self.try_load()
+                .await
+                .unwrap_or_else(|err| panic!("{}", DisplayErrorContext(&err)))
+        }
+
+        /// Load the default configuration chain, failing if the TLS configuration is invalid
+        ///
+        /// This is the same as [`ConfigLoader::load`], except that a CA bundle that can't be read and
+        /// TLS settings that can't be used, e.g. an invalid client certificate, are returned as errors.
+        pub async fn try_load(self) -> Result<SdkConfig, TlsConfigError> {
             let conf = self.provider_config.unwrap_or_default();
+            // TLS settings must be resolved first: region and credentials providers may make HTTP requests
+            let tls_settings = if let Some(tls_settings) = self.tls_settings {
+                tls_settings
+            } else if !conf.tls_settings().is_empty() {
+                conf.tls_settings().clone()
+            } else {
+                let mut builder = TlsSettings::builder();
+                builder.set_ca_bundle(
+                    ca_bundle::default_provider()
+                        .configure(&conf)
+                        .ca_bundle()
+                        .await
+                        .map_err(TlsConfigError::CaBundle)?,
+                );
+                builder.build()
+            };
+            if !tls_settings.is_empty() {
+                // the connectors created from `conf` would otherwise fail on first use
+                try_default_connector(
+                    &ConnectorSettings::default().with_tls_settings(tls_settings.clone()),
+                    None,
+                )
+                .map_err(TlsConfigError::TlsSettings)?;
+            }
+            let conf = conf.with_tls_settings(tls_settings.clone());
+
             let region = if let Some(provider) = self.region {
                 provider.region().await
             } else {
@@ -385,6 +534,33 @@ mod loader {
                     .await
             };
 
+            let endpoint_url_config = if self.endpoint_url_config.is_some() {
+                self.endpoint_url_config
+            } else {
+                endpoint_url::default_provider()
+                    .configure(&conf)
+                    .endpoint_url_config()
+                    .await
+            };
+
+            let use_fips = if self.use_fips.is_some() {
+                self.use_fips
+            } else {
+                endpoint_variant::default_provider()
+                    .configure(&conf)
+                    .use_fips()
+                    .await
+            };
+
+            let use_dual_stack = if self.use_dual_stack.is_some() {
+                self.use_dual_stack
+            } else {
+                endpoint_variant::default_provider()
+                    .configure(&conf)
+                    .use_dual_stack()
+                    .await
+            };
+
             let sleep_impl = if self.sleep.is_none() {
                 if default_async_sleep().is_none() {
                     tracing::warn!(
@@ -413,7 +589,8 @@ mod loader {
                 http_connector
             } else {
                 HttpConnector::Prebuilt(default_connector(
-                    &ConnectorSettings::from_timeout_config(&timeout_config),
+                    &ConnectorSettings::from_timeout_config(&timeout_config)
+                        .with_tls_settings(tls_settings),
                     sleep_impl.clone(),
                 ))
             };
@@ -436,16 +613,48 @@ mod loader {
                 .http_connector(http_connector);
 
             builder.set_endpoint_resolver(endpoint_resolver);
+            builder.set_endpoint_url_config(endpoint_url_config);
+            builder.set_use_fips(use_fips);
+            builder.set_use_dual_stack(use_dual_stack);
             builder.set_app_name(app_name);
             builder.set_sleep_impl(sleep_impl);
-            builder.build()
+            Ok(builder.build())
+        }
+    }
+
+    /// The TLS configuration loaded by [`ConfigLoader::try_load`] could not be used
+    #[derive(Debug)]
+    pub enum TlsConfigError {
+        /// The configured CA bundle could not be read
+        CaBundle(CaBundleError),
+
+        /// The TLS settings were invalid, e.g. the CA bundle contained no certificates
+        TlsSettings(TlsSettingsError),
+    }
+
+    impl Display for TlsConfigError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "invalid TLS configuration: ")?;
+            match self {
+                TlsConfigError::CaBundle(e) => write!(f, "{}", e),
+                TlsConfigError::TlsSettings(e) => write!(f, "{}", e),
+            }
+        }
+    }
+
+    impl Error for TlsConfigError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                TlsConfigError::CaBundle(e) => Some(e),
+                TlsConfigError::TlsSettings(e) => Some(e),
+            }
         }
     }
 
     #[cfg(test)]
     mod test {
-        use crate::from_env;
         use crate::provider_config::ProviderConfig;
+        use crate::{from_env, TlsConfigError};
         use aws_smithy_async::rt::sleep::TokioSleep;
         use aws_smithy_client::erase::DynConnector;
         use aws_smithy_client::never::NeverConnector;
@@ -482,5 +691,24 @@ mod loader {
                 "akid"
             );
         }
+
+        #[tokio::test]
+        async fn unreadable_ca_bundle_is_an_error() {
+            let env = Env::from_slice(&[("AWS_CA_BUNDLE", "/missing.pem")]);
+            let err = from_env()
+                .configure(
+                    ProviderConfig::no_configuration()
+                        .with_env(env)
+                        .with_http_connector(DynConnector::new(NeverConnector::new())),
+                )
+                .try_load()
+                .await
+                .expect_err("the CA bundle doesn't exist");
+            assert!(
+                matches!(err, TlsConfigError::CaBundle(_)),
+                "unexpected error: {}",
+                err
+            );
+        }
     }
 }
@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::explain::{Explanation, Outcome, Step};
 use aws_smithy_types::error::display::DisplayErrorContext;
 use aws_types::credentials::{self, future, CredentialsError, ProvideCredentials};
 use std::borrow::Cow;
@@ -74,6 +75,41 @@ impl CredentialsProviderChain {
         )
     }
 
+    /// Explain which provider of the chain provides credentials and why the others do not
+    ///
+    /// Every provider is tried until one provides credentials or fails, like when loading credentials.
+    /// The remaining providers are skipped.
+    pub async fn explain(&self) -> Explanation {
+        let mut explanation = Explanation::new("credentials");
+        let mut skip_reason = None;
+        for (name, provider) in &self.providers {
+            if let Some(reason) = skip_reason {
+                explanation.push(Step::new(name.clone(), Outcome::Skipped).with_reason(reason));
+                continue;
+            }
+            let span = tracing::debug_span!("explain_credentials", provider = %name);
+            let step = match provider.provide_credentials().instrument(span).await {
+                Ok(credentials) => {
+                    skip_reason = Some("a previous provider provided credentials");
+                    Step::new(name.clone(), Outcome::Success).with_reason(format!(
+                        "provided credentials for access key `{}`",
+                        credentials.access_key_id()
+                    ))
+                }
+                Err(CredentialsError::CredentialsNotLoaded { context, .. }) => {
+                    Step::new(name.clone(), Outcome::NotLoaded).with_reason(context.to_string())
+                }
+                Err(err) => {
+                    skip_reason = Some("a previous provider failed");
+                    Step::new(name.clone(), Outcome::Error)
+                        .with_reason(DisplayErrorContext(&err).to_string())
+                }
+            };
+            explanation.push(step);
+        }
+        explanation
+    }
+
     async fn credentials(&self) -> credentials::Result {
         for (name, provider) in &self.providers {
             let span = tracing::debug_span!("load_credentials", provider = %name);
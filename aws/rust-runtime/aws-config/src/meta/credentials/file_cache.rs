@@ -0,0 +1,488 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! File-backed credentials cache shared across processes
+//!
+//! In-memory caches like [`LazyCachingCredentialsProvider`](super::LazyCachingCredentialsProvider)
+//! are lost when a process exits, so short-lived processes like command line tools assume the same
+//! role again on every invocation. [`FileCachingCredentialsProvider`] stores temporary credentials
+//! in `~/.aws/rust-sdk/cache/<hash of the cache key>.json`, similar to the `~/.aws/cli/cache` of
+//! the AWS CLI, so that they are reused until they expire.
+//!
+//! Cache files are written atomically and are only readable and writable by their owner. Files
+//! that other users could read or modify are ignored and replaced.
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use aws_smithy_json::serialize::{JsonArrayWriter, JsonObjectWriter};
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use aws_smithy_types::DateTime;
+use aws_types::credentials::{self, future, ProvideCredentials, SharedCredentialsProvider};
+use aws_types::os_shim_internal::{Env, Fs, TimeSource};
+use aws_types::Credentials;
+use ring::digest;
+use zeroize::Zeroizing;
+
+use crate::fs_util::{home_dir, Os};
+use crate::json_credentials::{parse_json_credentials, JsonCredentials, RefreshableCredentials};
+use crate::provider_config::ProviderConfig;
+
+const DEFAULT_BUFFER_TIME: Duration = Duration::from_secs(5 * 60);
+const PROVIDER_NAME: &str = "FileCache";
+
+/// Credentials provider that caches the credentials of another provider in a file
+///
+/// Only temporary credentials, i.e. credentials with a session token and an expiration time, are
+/// cached. Errors reading or writing the cache are logged and the underlying provider is used
+/// instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_config::meta::credentials::FileCachingCredentialsProvider;
+/// use aws_config::sts::AssumeRoleProvider;
+/// use aws_types::credentials::SharedCredentialsProvider;
+/// use aws_types::Credentials;
+///
+/// let role_arn = "arn:aws:iam::123456789012:role/demo";
+/// let provider = FileCachingCredentialsProvider::builder()
+///     // the key must identify the credentials, e.g. the role and session name
+///     .cache_key(format!("{}/my-session", role_arn))
+///     .load(
+///         AssumeRoleProvider::builder(role_arn)
+///             .session_name("my-session")
+///             .build(SharedCredentialsProvider::new(Credentials::new(
+///                 "akid", "secret", None, None, "example",
+///             ))),
+///     )
+///     .build();
+/// ```
+#[derive(Debug)]
+pub struct FileCachingCredentialsProvider {
+    cache: FileCache,
+    cache_key: String,
+    provider: SharedCredentialsProvider,
+}
+
+impl FileCachingCredentialsProvider {
+    /// Builder for [`FileCachingCredentialsProvider`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    async fn credentials(&self) -> credentials::Result {
+        if let Some(credentials) = self.cache.load(&self.cache_key).await {
+            tracing::debug!("loaded credentials from the file cache");
+            return Ok(credentials);
+        }
+        let credentials = self.provider.provide_credentials().await?;
+        self.cache.store(&self.cache_key, &credentials).await;
+        Ok(credentials)
+    }
+}
+
+impl ProvideCredentials for FileCachingCredentialsProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(self.credentials())
+    }
+}
+
+/// Builder for [`FileCachingCredentialsProvider`]
+#[derive(Debug, Default)]
+pub struct Builder {
+    provider_config: Option<ProviderConfig>,
+    cache_key: Option<String>,
+    directory: Option<PathBuf>,
+    buffer_time: Option<Duration>,
+    load: Option<SharedCredentialsProvider>,
+}
+
+impl Builder {
+    /// Override the configuration used for this provider
+    ///
+    /// The file system, environment and time source of the configuration are used to locate,
+    /// read and write cache files.
+    pub fn configure(mut self, provider_config: &ProviderConfig) -> Self {
+        self.provider_config = Some(provider_config.clone());
+        self
+    }
+
+    /// Key identifying the cached credentials (required)
+    ///
+    /// Providers sharing a key share their cached credentials, so the key must include
+    /// everything that determines the credentials, e.g. the role ARN, session name and policies.
+    /// The name of the cache file is a hash of the key.
+    pub fn cache_key(mut self, cache_key: impl Into<String>) -> Self {
+        self.cache_key = Some(cache_key.into());
+        self
+    }
+
+    /// Directory of the cache files
+    ///
+    /// Defaults to `~/.aws/rust-sdk/cache`.
+    pub fn directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Amount of time before the expiration of cached credentials when they are no longer used
+    ///
+    /// This should be longer than the buffer time of in-memory caches of the credentials, so that
+    /// they don't reload credentials that are about to expire from the file.
+    ///
+    /// Defaults to 5 minutes.
+    pub fn buffer_time(mut self, buffer_time: Duration) -> Self {
+        self.buffer_time = Some(buffer_time);
+        self
+    }
+
+    /// Provider of the credentials to cache (required)
+    pub fn load(mut self, provider: impl ProvideCredentials + 'static) -> Self {
+        self.load = Some(SharedCredentialsProvider::new(provider));
+        self
+    }
+
+    /// Creates the [`FileCachingCredentialsProvider`]
+    ///
+    /// # Panics
+    /// This will panic if no `cache_key` or `load` implementation is given.
+    pub fn build(self) -> FileCachingCredentialsProvider {
+        let mut cache = FileCache::new(&self.provider_config.unwrap_or_default());
+        cache.directory = self.directory;
+        cache.buffer_time = self.buffer_time.unwrap_or(DEFAULT_BUFFER_TIME);
+        FileCachingCredentialsProvider {
+            cache,
+            cache_key: self.cache_key.expect("cache key is required"),
+            provider: self.load.expect("load implementation is required"),
+        }
+    }
+}
+
+/// Cache of temporary credentials stored in files, keyed by a hash of the cache key
+#[derive(Clone, Debug)]
+pub(crate) struct FileCache {
+    directory: Option<PathBuf>,
+    buffer_time: Duration,
+    env: Env,
+    fs: Fs,
+    time_source: TimeSource,
+}
+
+impl FileCache {
+    pub(crate) fn new(provider_config: &ProviderConfig) -> Self {
+        Self {
+            directory: None,
+            buffer_time: DEFAULT_BUFFER_TIME,
+            env: provider_config.env(),
+            fs: provider_config.fs(),
+            time_source: provider_config.time_source(),
+        }
+    }
+
+    fn path(&self, cache_key: &str) -> Option<PathBuf> {
+        let mut path = match &self.directory {
+            Some(directory) => directory.clone(),
+            None => {
+                let mut path = PathBuf::from(home_dir(&self.env, Os::real())?);
+                path.push(".aws/rust-sdk/cache");
+                path
+            }
+        };
+        // hex::encode returns a lowercase string
+        path.push(hex::encode(digest::digest(
+            &digest::SHA256,
+            cache_key.as_bytes(),
+        )));
+        path.set_extension("json");
+        Some(path)
+    }
+
+    /// Load unexpired credentials cached under `cache_key`
+    pub(crate) async fn load(&self, cache_key: &str) -> Option<Credentials> {
+        let path = match self.path(cache_key) {
+            Some(path) => path,
+            None => {
+                tracing::debug!("could not resolve a home directory, not using the file cache");
+                return None;
+            }
+        };
+        let data = match self.fs.read_private_to_end(&path).await {
+            Ok(Some(data)) => Zeroizing::new(data),
+            Ok(None) => {
+                tracing::warn!(path = %path.display(), "ignoring cached credentials because the file is readable or writable by other users");
+                return None;
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                tracing::debug!(path = %path.display(), "no cached credentials");
+                return None;
+            }
+            Err(err) => {
+                tracing::warn!(path = %path.display(), err = %DisplayErrorContext(&err), "failed to read cached credentials");
+                return None;
+            }
+        };
+        let credentials = std::str::from_utf8(&data)
+            .map_err(|err| err.to_string())
+            .and_then(|data| parse_json_credentials(data).map_err(|err| err.to_string()));
+        let (credentials, expiration) = match credentials {
+            Ok(JsonCredentials::RefreshableCredentials(RefreshableCredentials {
+                access_key_id,
+                secret_access_key,
+                session_token,
+                expiration,
+            })) => (
+                Credentials::new(
+                    access_key_id,
+                    secret_access_key,
+                    Some(session_token.to_string()),
+                    Some(expiration),
+                    PROVIDER_NAME,
+                ),
+                expiration,
+            ),
+            Ok(JsonCredentials::Error { .. }) => {
+                tracing::warn!(path = %path.display(), "ignoring invalid cached credentials");
+                return None;
+            }
+            Err(err) => {
+                tracing::warn!(path = %path.display(), err = %err, "ignoring invalid cached credentials");
+                return None;
+            }
+        };
+        if expired(expiration, self.buffer_time, self.time_source.now()) {
+            tracing::debug!(path = %path.display(), "cached credentials are expired");
+            return None;
+        }
+        Some(credentials)
+    }
+
+    /// Atomically replace the credentials cached under `cache_key`
+    ///
+    /// Credentials without a session token or expiration time are long-term credentials and are
+    /// not cached.
+    pub(crate) async fn store(&self, cache_key: &str, credentials: &Credentials) {
+        let (session_token, expiration) = match (credentials.session_token(), credentials.expiry())
+        {
+            (Some(session_token), Some(expiration)) => (session_token, expiration),
+            _ => {
+                tracing::debug!("not caching credentials without a session token and expiration");
+                return;
+            }
+        };
+        let path = match self.path(cache_key) {
+            Some(path) => path,
+            None => return,
+        };
+        let mut data = Zeroizing::new(String::new());
+        let mut object = JsonObjectWriter::new(&mut data);
+        object.key("Code").string("Success");
+        object
+            .key("AccessKeyId")
+            .string(credentials.access_key_id());
+        object
+            .key("SecretAccessKey")
+            .string(credentials.secret_access_key());
+        object.key("Token").string(session_token);
+        if let Err(err) = object
+            .key("Expiration")
+            .date_time(&DateTime::from(expiration), Format::DateTime)
+        {
+            tracing::warn!(err = %DisplayErrorContext(&err), "not caching credentials with an invalid expiration");
+            return;
+        }
+        object.finish();
+        match self.fs.write(&path, data.as_bytes()).await {
+            Ok(()) => tracing::debug!(path = %path.display(), "cached credentials"),
+            Err(err) => {
+                tracing::warn!(path = %path.display(), err = %DisplayErrorContext(&err), "failed to cache credentials")
+            }
+        }
+    }
+}
+
+fn expired(expiration: SystemTime, buffer_time: Duration, now: SystemTime) -> bool {
+    now + buffer_time >= expiration
+}
+
+/// Build an unambiguous cache key from a list of settings
+///
+/// Settings are serialized in order, so settings with the same name may be repeated, e.g. for each
+/// role of a role chain.
+pub(crate) fn cache_key<'a>(settings: impl IntoIterator<Item = (&'a str, &'a str)>) -> String {
+    let mut out = String::new();
+    let mut array = JsonArrayWriter::new(&mut out);
+    for (name, value) in settings {
+        let mut setting = array.value().start_array();
+        setting.value().string(name);
+        setting.value().string(value);
+        setting.finish();
+    }
+    array.finish();
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use aws_types::credentials::ProvideCredentials;
+    use aws_types::os_shim_internal::{Env, Fs, ManualTimeSource, TimeSource};
+    use aws_types::Credentials;
+    use tracing_test::traced_test;
+
+    use crate::meta::credentials::file_cache::{cache_key, FileCache};
+    use crate::meta::credentials::{provide_credentials_fn, FileCachingCredentialsProvider};
+    use crate::provider_config::ProviderConfig;
+
+    fn epoch_secs(secs: u64) -> std::time::SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn temporary_credentials(expiration: u64) -> Credentials {
+        Credentials::new(
+            "ASIARTEST",
+            "secret",
+            Some("token".into()),
+            Some(epoch_secs(expiration)),
+            "test",
+        )
+    }
+
+    fn file_cache(fs: &Fs, time: &ManualTimeSource) -> FileCache {
+        FileCache::new(
+            &ProviderConfig::no_configuration()
+                .with_env(Env::from_slice(&[("HOME", "/home/user")]))
+                .with_fs(fs.clone())
+                .with_time_source(TimeSource::manual(time)),
+        )
+    }
+
+    #[test]
+    fn cache_key_is_unambiguous() {
+        assert_eq!(
+            r#"[["role_arn","arn"],["session_name","a\",\"b"]]"#,
+            cache_key([("role_arn", "arn"), ("session_name", "a\",\"b")])
+        );
+        assert_ne!(
+            cache_key([("a", "b,c")]),
+            cache_key([("a", "b"), ("c", "")])
+        );
+    }
+
+    #[tokio::test]
+    async fn store_and_load() {
+        let fs = Fs::from_slice(&[]);
+        let mut time = ManualTimeSource::new(epoch_secs(1000));
+        let cache = file_cache(&fs, &time);
+
+        assert_eq!(None, cache.load("key").await);
+        cache.store("key", &temporary_credentials(5000)).await;
+        assert!(fs
+            .read_to_end(
+                "/home/user/.aws/rust-sdk/cache/2c70e12b7a0646f92279f427c7b38e7334d8e5389cff167a1dc30e73f826b683.json"
+            )
+            .await
+            .is_ok());
+
+        let loaded = cache.load("key").await.expect("cached credentials");
+        assert_eq!("ASIARTEST", loaded.access_key_id());
+        assert_eq!("secret", loaded.secret_access_key());
+        assert_eq!(Some("token"), loaded.session_token());
+        assert_eq!(Some(epoch_secs(5000)), loaded.expiry());
+        assert_eq!(None, cache.load("other key").await);
+
+        // credentials are not used within the buffer time before they expire
+        time.set_time(epoch_secs(4800));
+        assert_eq!(None, cache.load("key").await);
+    }
+
+    #[tokio::test]
+    async fn long_term_credentials_are_not_cached() {
+        let fs = Fs::from_slice(&[]);
+        let time = ManualTimeSource::new(epoch_secs(1000));
+        let cache = file_cache(&fs, &time);
+        cache
+            .store(
+                "key",
+                &Credentials::new("AKIDTEST", "secret", None, None, "test"),
+            )
+            .await;
+        assert_eq!(None, cache.load("key").await);
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn invalid_cache_files_are_ignored() {
+        let cache = FileCache::new(
+            &ProviderConfig::no_configuration()
+                .with_env(Env::from_slice(&[("HOME", "/home/user")]))
+                .with_fs(Fs::from_slice(&[(
+                    "/home/user/.aws/rust-sdk/cache/2c70e12b7a0646f92279f427c7b38e7334d8e5389cff167a1dc30e73f826b683.json",
+                    "{\"AccessKeyId\": \"ASIARTEST\"}",
+                )])),
+        );
+        assert_eq!(None, cache.load("key").await);
+        assert!(logs_contain("ignoring invalid cached credentials"));
+    }
+
+    #[cfg(unix)]
+    #[traced_test]
+    #[tokio::test]
+    async fn cache_files_readable_by_other_users_are_ignored() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("aws-config-file-cache-{}", std::process::id()));
+        let time = ManualTimeSource::new(epoch_secs(1000));
+        let cache = file_cache(&Fs::from_test_dir(&dir, "/home/user"), &time);
+        cache.store("key", &temporary_credentials(5000)).await;
+        assert!(cache.load("key").await.is_some());
+
+        std::fs::set_permissions(
+            dir.join(".aws/rust-sdk/cache/2c70e12b7a0646f92279f427c7b38e7334d8e5389cff167a1dc30e73f826b683.json"),
+            std::fs::Permissions::from_mode(0o644),
+        )
+        .unwrap();
+        assert_eq!(None, cache.load("key").await);
+        assert!(logs_contain("readable or writable by other users"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn providers_share_cached_credentials() {
+        let time = ManualTimeSource::new(epoch_secs(1000));
+        let conf = ProviderConfig::no_configuration()
+            .with_env(Env::from_slice(&[]))
+            .with_fs(Fs::from_slice(&[]))
+            .with_time_source(TimeSource::manual(&time));
+        let first = FileCachingCredentialsProvider::builder()
+            .configure(&conf)
+            .directory("/cache")
+            .cache_key("key")
+            .load(provide_credentials_fn(|| async {
+                Ok(temporary_credentials(5000))
+            }))
+            .build();
+        // e.g. a provider of another process using the same file system
+        let second = FileCachingCredentialsProvider::builder()
+            .configure(&conf)
+            .directory("/cache")
+            .cache_key("key")
+            .load(provide_credentials_fn(|| async {
+                panic!("credentials should be loaded from the cache")
+            }))
+            .build();
+
+        let loaded = first.provide_credentials().await.unwrap();
+        let cached = second.provide_credentials().await.unwrap();
+        assert_eq!(loaded.access_key_id(), cached.access_key_id());
+        assert_eq!(loaded.expiry(), cached.expiry());
+    }
+}
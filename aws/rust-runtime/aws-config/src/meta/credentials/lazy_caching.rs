@@ -5,11 +5,14 @@
 
 //! Lazy, caching, credentials provider implementation
 
-use std::sync::Arc;
-use std::time::Duration;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, SystemTime};
 
 use aws_smithy_async::future::timeout::Timeout;
 use aws_smithy_async::rt::sleep::AsyncSleep;
+use aws_smithy_types::error::display::DisplayErrorContext;
 use tracing::{trace_span, Instrument};
 
 use aws_types::credentials::{future, CredentialsError, ProvideCredentials};
@@ -20,6 +23,12 @@ use crate::cache::ExpiringCache;
 const DEFAULT_LOAD_TIMEOUT: Duration = Duration::from_secs(5);
 const DEFAULT_CREDENTIAL_EXPIRATION: Duration = Duration::from_secs(15 * 60);
 const DEFAULT_BUFFER_TIME: Duration = Duration::from_secs(10);
+/// Background refreshes happen at a random time within this window before the buffer time
+const BACKGROUND_REFRESH_WINDOW: Duration = Duration::from_secs(5 * 60);
+/// Shortest delay between two background refreshes
+const MIN_REFRESH_DELAY: Duration = Duration::from_secs(1);
+/// Delay before retrying a failed background refresh, or a load that served expired credentials
+const REFRESH_RETRY_DELAY: Duration = Duration::from_secs(30);
 
 /// `LazyCachingCredentialsProvider` implements [`ProvideCredentials`] by caching
 /// credentials that it loads by calling a user-provided [`ProvideCredentials`] implementation.
@@ -27,14 +36,29 @@ const DEFAULT_BUFFER_TIME: Duration = Duration::from_secs(10);
 /// For example, you can provide an [`ProvideCredentials`] implementation that calls
 /// AWS STS's AssumeRole operation to get temporary credentials, and `LazyCachingCredentialsProvider`
 /// will cache those credentials until they expire.
+///
+/// By default, credentials are reloaded by the first caller that finds them expired. Two options
+/// keep loads off the request path and make the provider resilient to outages of the credentials
+/// source:
+/// - [`background_refresh`](Builder::background_refresh) refreshes credentials in a background
+///   task shortly before they expire.
+/// - [`expired_credentials_grace_period`](Builder::expired_credentials_grace_period) keeps
+///   serving the last loaded credentials for some time after they expired when loading fails.
+///
+/// Both are counted in the provider's [`refresh_metrics`](LazyCachingCredentialsProvider::refresh_metrics),
+/// which are also pushed to the [`refresh_metrics_listener`](Builder::refresh_metrics_listener)
+/// after every change, e.g. to export them as gauges.
 #[derive(Debug)]
 pub struct LazyCachingCredentialsProvider {
     time: TimeSource,
     sleeper: Arc<dyn AsyncSleep>,
     cache: ExpiringCache<Credentials, CredentialsError>,
-    loader: Arc<dyn ProvideCredentials>,
-    load_timeout: Duration,
-    default_credential_expiration: Duration,
+    loader: Loader,
+    buffer_time: Duration,
+    background_refresh: bool,
+    background_refresh_started: AtomicBool,
+    // Background refresh tasks stop once the provider, and with it this `Arc`, is dropped
+    alive: Arc<()>,
 }
 
 impl LazyCachingCredentialsProvider {
@@ -48,11 +72,20 @@ impl LazyCachingCredentialsProvider {
     ) -> Self {
         LazyCachingCredentialsProvider {
             time,
-            sleeper,
+            sleeper: sleeper.clone(),
             cache: ExpiringCache::new(buffer_time),
-            loader,
-            load_timeout,
-            default_credential_expiration,
+            loader: Loader {
+                provider: loader,
+                sleeper,
+                load_timeout,
+                default_credential_expiration,
+                last_loaded: Default::default(),
+                metrics: Default::default(),
+            },
+            buffer_time,
+            background_refresh: false,
+            background_refresh_started: AtomicBool::new(false),
+            alive: Arc::new(()),
         }
     }
 
@@ -60,6 +93,26 @@ impl LazyCachingCredentialsProvider {
     pub fn builder() -> builder::Builder {
         builder::Builder::new()
     }
+
+    /// Returns counters of the credential loads made by this provider
+    pub fn refresh_metrics(&self) -> RefreshMetrics {
+        self.loader.metrics.snapshot()
+    }
+
+    fn start_background_refresh(&self) {
+        if !self.background_refresh || self.background_refresh_started.swap(true, Ordering::SeqCst)
+        {
+            return;
+        }
+        spawn_background_refresh(BackgroundRefresh {
+            alive: Arc::downgrade(&self.alive),
+            time: self.time.clone(),
+            sleeper: self.sleeper.clone(),
+            cache: self.cache.clone(),
+            loader: self.loader.clone(),
+            buffer_time: self.buffer_time,
+        });
+    }
 }
 
 impl ProvideCredentials for LazyCachingCredentialsProvider {
@@ -69,45 +122,315 @@ impl ProvideCredentials for LazyCachingCredentialsProvider {
     {
         let now = self.time.now();
         let loader = self.loader.clone();
-        let timeout_future = self.sleeper.sleep(self.load_timeout);
-        let load_timeout = self.load_timeout;
-        let cache = self.cache.clone();
-        let default_credential_expiration = self.default_credential_expiration;
+        let buffer_time = self.buffer_time;
 
         future::ProvideCredentials::new(async move {
             // Attempt to get cached credentials, or clear the cache if they're expired
-            if let Some(credentials) = cache.yield_or_clear_if_expired(now).await {
+            if let Some(credentials) = self.cache.yield_or_clear_if_expired(now).await {
                 tracing::debug!("loaded credentials from cache");
-                Ok(credentials)
-            } else {
-                // If we didn't get credentials from the cache, then we need to try and load.
-                // There may be other threads also loading simultaneously, but this is OK
-                // since the futures are not eagerly executed, and the cache will only run one
-                // of them.
-                let span = trace_span!("lazy_load_credentials");
-                let future = Timeout::new(loader.provide_credentials(), timeout_future);
-                cache
-                    .get_or_load(|| {
-                        async move {
-                            let credentials = future.await.map_err(|_err| {
-                                CredentialsError::provider_timed_out(load_timeout)
-                            })??;
-                            // If the credentials don't have an expiration time, then create a default one
-                            let expiry = credentials
-                                .expiry()
-                                .unwrap_or(now + default_credential_expiration);
-                            Ok((credentials, expiry))
+                return Ok(credentials);
+            }
+            // If we didn't get credentials from the cache, then we need to try and load.
+            // There may be other threads also loading simultaneously, but this is OK
+            // since the futures are not eagerly executed, and the cache will only run one
+            // of them.
+            let span = trace_span!("lazy_load_credentials");
+            let result = self
+                .cache
+                .get_or_load(|| {
+                    async move {
+                        match loader.load(now).await {
+                            Ok(loaded) => Ok(loaded),
+                            Err(err) => loader.serve_expired(now, buffer_time, err),
                         }
-                        // Only instrument the the actual load future so that no span
-                        // is opened if the cache decides not to execute it.
-                        .instrument(span)
-                    })
-                    .await
+                    }
+                    // Only instrument the the actual load future so that no span
+                    // is opened if the cache decides not to execute it.
+                    .instrument(span)
+                })
+                .await;
+            if result.is_ok() {
+                self.start_background_refresh();
             }
+            result
         })
     }
 }
 
+/// Counters of the credential loads made by a [`LazyCachingCredentialsProvider`]
+///
+/// Loads made by callers that found the cached credentials expired and background refreshes are
+/// both counted.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RefreshMetrics {
+    refreshes: u64,
+    refresh_failures: u64,
+    consecutive_refresh_failures: u64,
+    expired_credentials_served: u64,
+}
+
+impl RefreshMetrics {
+    /// Number of successful loads
+    pub fn refreshes(&self) -> u64 {
+        self.refreshes
+    }
+
+    /// Number of failed loads, including timeouts
+    pub fn refresh_failures(&self) -> u64 {
+        self.refresh_failures
+    }
+
+    /// Number of failed loads since the last successful load
+    pub fn consecutive_refresh_failures(&self) -> u64 {
+        self.consecutive_refresh_failures
+    }
+
+    /// Number of times expired credentials were served because loading failed within the
+    /// [grace period](Builder::expired_credentials_grace_period)
+    pub fn expired_credentials_served(&self) -> u64 {
+        self.expired_credentials_served
+    }
+}
+
+/// Callback receiving the [`RefreshMetrics`] of a provider after every change
+#[derive(Clone)]
+struct MetricsListener(Arc<dyn Fn(&RefreshMetrics) + Send + Sync>);
+
+impl fmt::Debug for MetricsListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MetricsListener")
+    }
+}
+
+#[derive(Debug, Default)]
+struct Metrics {
+    refreshes: AtomicU64,
+    refresh_failures: AtomicU64,
+    consecutive_refresh_failures: AtomicU64,
+    expired_credentials_served: AtomicU64,
+    listener: Option<MetricsListener>,
+}
+
+impl Metrics {
+    fn with_listener(listener: Option<MetricsListener>) -> Self {
+        Self {
+            listener,
+            ..Default::default()
+        }
+    }
+
+    fn record<T>(&self, result: &Result<T, CredentialsError>) {
+        if result.is_ok() {
+            self.refreshes.fetch_add(1, Ordering::Relaxed);
+            self.consecutive_refresh_failures
+                .store(0, Ordering::Relaxed);
+        } else {
+            self.refresh_failures.fetch_add(1, Ordering::Relaxed);
+            self.consecutive_refresh_failures
+                .fetch_add(1, Ordering::Relaxed);
+        }
+        self.notify();
+    }
+
+    fn record_expired_credentials_served(&self) {
+        self.expired_credentials_served
+            .fetch_add(1, Ordering::Relaxed);
+        self.notify();
+    }
+
+    fn notify(&self) {
+        if let Some(listener) = &self.listener {
+            (listener.0)(&self.snapshot());
+        }
+    }
+
+    fn snapshot(&self) -> RefreshMetrics {
+        RefreshMetrics {
+            refreshes: self.refreshes.load(Ordering::Relaxed),
+            refresh_failures: self.refresh_failures.load(Ordering::Relaxed),
+            consecutive_refresh_failures: self.consecutive_refresh_failures.load(Ordering::Relaxed),
+            expired_credentials_served: self.expired_credentials_served.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The last loaded credentials, kept to be served when loading fails within the grace period
+#[derive(Clone, Debug, Default)]
+struct LastLoaded {
+    value: Arc<Mutex<Option<(Credentials, SystemTime)>>>,
+    grace_period: Option<Duration>,
+}
+
+impl LastLoaded {
+    fn store(&self, credentials: &Credentials, expiry: SystemTime) {
+        if self.grace_period.is_some() {
+            *self.value.lock().unwrap() = Some((credentials.clone(), expiry));
+        }
+    }
+
+    /// The last loaded credentials, if they expired less than the grace period before `now`
+    fn within_grace_period(&self, now: SystemTime) -> Option<Credentials> {
+        let grace_period = self.grace_period?;
+        let value = self.value.lock().unwrap();
+        let (credentials, expiry) = value.as_ref()?;
+        if now < *expiry + grace_period {
+            Some(credentials.clone())
+        } else {
+            None
+        }
+    }
+}
+
+/// Loads credentials from the underlying provider, shared with the background refresh task
+#[derive(Clone, Debug)]
+struct Loader {
+    provider: Arc<dyn ProvideCredentials>,
+    sleeper: Arc<dyn AsyncSleep>,
+    load_timeout: Duration,
+    default_credential_expiration: Duration,
+    last_loaded: LastLoaded,
+    metrics: Arc<Metrics>,
+}
+
+impl Loader {
+    async fn load(&self, now: SystemTime) -> Result<(Credentials, SystemTime), CredentialsError> {
+        let future = Timeout::new(
+            self.provider.provide_credentials(),
+            self.sleeper.sleep(self.load_timeout),
+        );
+        let result = match future.await {
+            Ok(result) => result,
+            Err(_err) => Err(CredentialsError::provider_timed_out(self.load_timeout)),
+        };
+        self.metrics.record(&result);
+        let credentials = result?;
+        // If the credentials don't have an expiration time, then create a default one
+        let expiry = credentials
+            .expiry()
+            .unwrap_or(now + self.default_credential_expiration);
+        self.last_loaded.store(&credentials, expiry);
+        Ok((credentials, expiry))
+    }
+
+    /// Serve the last loaded credentials instead of failing if they are within the grace period
+    ///
+    /// They are cached for a short time so that callers don't wait for the failing source on
+    /// every request.
+    fn serve_expired(
+        &self,
+        now: SystemTime,
+        buffer_time: Duration,
+        err: CredentialsError,
+    ) -> Result<(Credentials, SystemTime), CredentialsError> {
+        match self.last_loaded.within_grace_period(now) {
+            Some(credentials) => {
+                tracing::warn!(
+                    err = %DisplayErrorContext(&err),
+                    "failed to load credentials, serving the last loaded credentials within the grace period"
+                );
+                self.metrics.record_expired_credentials_served();
+                Ok((credentials, now + buffer_time + retry_delay()))
+            }
+            None => Err(err),
+        }
+    }
+}
+
+/// Task that refreshes credentials before they expire so that callers never wait for a load
+#[derive(Debug)]
+struct BackgroundRefresh {
+    alive: Weak<()>,
+    time: TimeSource,
+    sleeper: Arc<dyn AsyncSleep>,
+    cache: ExpiringCache<Credentials, CredentialsError>,
+    loader: Loader,
+    buffer_time: Duration,
+}
+
+#[cfg_attr(not(feature = "rt-tokio"), allow(dead_code))]
+impl BackgroundRefresh {
+    async fn run(self) {
+        let mut delay = self.next_delay().await;
+        loop {
+            self.sleeper.sleep(delay).await;
+            if self.alive.upgrade().is_none() {
+                tracing::debug!(
+                    "the credentials provider was dropped, stopping background refresh"
+                );
+                return;
+            }
+            delay = match self.loader.load(self.time.now()).await {
+                Ok((credentials, expiry)) => {
+                    tracing::debug!("refreshed credentials in the background");
+                    self.cache.set(credentials, expiry).await;
+                    self.next_delay().await
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        err = %DisplayErrorContext(&err),
+                        "failed to refresh credentials in the background, cached credentials are used until they expire"
+                    );
+                    retry_delay()
+                }
+            };
+        }
+    }
+
+    /// Delay until a random time of the refresh window before the cached credentials expire
+    async fn next_delay(&self) -> Duration {
+        let now = self.time.now();
+        let remaining = self
+            .cache
+            .expiry()
+            .await
+            .and_then(|expiry| expiry.duration_since(now).ok())
+            .unwrap_or_default()
+            .saturating_sub(self.buffer_time);
+        if remaining.is_zero() {
+            // Credentials that expire within the buffer time are loaded by the next caller
+            // anyway: back off instead of refreshing them continuously
+            return retry_delay();
+        }
+        // The refresh window is at most half of the remaining lifetime, so that short-lived
+        // credentials are refreshed in proportion to their lifetime
+        let lead = jitter(BACKGROUND_REFRESH_WINDOW.min(remaining / 2));
+        (remaining - lead).max(MIN_REFRESH_DELAY)
+    }
+}
+
+#[cfg(feature = "rt-tokio")]
+fn spawn_background_refresh(task: BackgroundRefresh) {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => {
+            handle.spawn(
+                task.run()
+                    .instrument(tracing::debug_span!("background_refresh_credentials")),
+            );
+        }
+        Err(_) => tracing::warn!(
+            "background credentials refresh is disabled because the provider is not used from a Tokio runtime"
+        ),
+    }
+}
+
+#[cfg(not(feature = "rt-tokio"))]
+fn spawn_background_refresh(_task: BackgroundRefresh) {
+    tracing::warn!(
+        "background credentials refresh is disabled because it requires the `rt-tokio` feature"
+    );
+}
+
+/// [`REFRESH_RETRY_DELAY`], with up to 50% of jitter so that retries of many processes are spread out
+fn retry_delay() -> Duration {
+    REFRESH_RETRY_DELAY / 2 + jitter(REFRESH_RETRY_DELAY / 2)
+}
+
+/// A random duration between zero and `max`
+fn jitter(max: Duration) -> Duration {
+    max.mul_f64(fastrand::f64())
+}
+
 use aws_types::Credentials;
 pub use builder::Builder;
 
@@ -119,8 +442,8 @@ mod builder {
     use aws_types::credentials::ProvideCredentials;
 
     use super::{
-        LazyCachingCredentialsProvider, DEFAULT_BUFFER_TIME, DEFAULT_CREDENTIAL_EXPIRATION,
-        DEFAULT_LOAD_TIMEOUT,
+        LazyCachingCredentialsProvider, Metrics, MetricsListener, RefreshMetrics,
+        DEFAULT_BUFFER_TIME, DEFAULT_CREDENTIAL_EXPIRATION, DEFAULT_LOAD_TIMEOUT,
     };
     use crate::provider_config::ProviderConfig;
     use aws_types::os_shim_internal::TimeSource;
@@ -149,6 +472,9 @@ mod builder {
         load_timeout: Option<Duration>,
         buffer_time: Option<Duration>,
         default_credential_expiration: Option<Duration>,
+        background_refresh: Option<bool>,
+        expired_credentials_grace_period: Option<Duration>,
+        refresh_metrics_listener: Option<MetricsListener>,
     }
 
     impl Builder {
@@ -265,6 +591,99 @@ mod builder {
             self
         }
 
+        /// Refresh credentials in a background task before they expire.
+        ///
+        /// Without background refresh, the first caller that finds the cached credentials expired
+        /// waits for new credentials to load. With background refresh, credentials are reloaded at
+        /// a random time within five minutes before the [buffer time](Self::buffer_time) so that
+        /// callers get cached credentials while the credentials source is reachable. Failed
+        /// refreshes are retried after about 30 seconds.
+        ///
+        /// The task is started from the first successful load and stops once the provider is
+        /// dropped. It requires the `rt-tokio` feature and a Tokio runtime; otherwise, a warning
+        /// is logged and credentials are only loaded when they expire.
+        ///
+        /// Defaults to `false`.
+        pub fn background_refresh(mut self, enabled: bool) -> Self {
+            self.set_background_refresh(Some(enabled));
+            self
+        }
+
+        /// Refresh credentials in a background task before they expire.
+        ///
+        /// See [`background_refresh`](Self::background_refresh) for more information.
+        pub fn set_background_refresh(&mut self, enabled: Option<bool>) -> &mut Self {
+            self.background_refresh = enabled;
+            self
+        }
+
+        /// Amount of time after their expiration during which the last loaded credentials are
+        /// served when loading new credentials fails.
+        ///
+        /// This keeps requests working during short outages of the credentials source, as long as
+        /// the service still accepts the credentials. Loading is retried about every 30 seconds
+        /// while expired credentials are served.
+        ///
+        /// By default, loading errors are returned to the caller as soon as credentials expire.
+        pub fn expired_credentials_grace_period(mut self, grace_period: Duration) -> Self {
+            self.set_expired_credentials_grace_period(Some(grace_period));
+            self
+        }
+
+        /// Amount of time after their expiration during which the last loaded credentials are
+        /// served when loading new credentials fails.
+        ///
+        /// See [`expired_credentials_grace_period`](Self::expired_credentials_grace_period) for
+        /// more information.
+        pub fn set_expired_credentials_grace_period(
+            &mut self,
+            grace_period: Option<Duration>,
+        ) -> &mut Self {
+            self.expired_credentials_grace_period = grace_period;
+            self
+        }
+
+        /// Function called with the updated [`RefreshMetrics`] after every credential load.
+        ///
+        /// The function is called from the task loading credentials, so it must return quickly,
+        /// e.g. after updating gauges of a metrics library.
+        ///
+        /// # Examples
+        ///
+        /// ```no_run
+        /// use aws_config::meta::credentials::LazyCachingCredentialsProvider;
+        /// use aws_types::Credentials;
+        ///
+        /// let provider = LazyCachingCredentialsProvider::builder()
+        ///     .load(Credentials::new("example", "example", None, None, "example"))
+        ///     .refresh_metrics_listener(|metrics| {
+        ///         println!(
+        ///             "credential refresh failures: {}",
+        ///             metrics.consecutive_refresh_failures()
+        ///         )
+        ///     })
+        ///     .build();
+        /// ```
+        pub fn refresh_metrics_listener(
+            mut self,
+            listener: impl Fn(&RefreshMetrics) + Send + Sync + 'static,
+        ) -> Self {
+            self.refresh_metrics_listener = Some(MetricsListener(Arc::new(listener)));
+            self
+        }
+
+        /// Function called with the updated [`RefreshMetrics`] after every credential load.
+        ///
+        /// See [`refresh_metrics_listener`](Self::refresh_metrics_listener).
+        pub fn set_refresh_metrics_listener(
+            &mut self,
+            listener: Option<impl Fn(&RefreshMetrics) + Send + Sync + 'static>,
+        ) -> &mut Self {
+            self.refresh_metrics_listener =
+                listener.map(|listener| MetricsListener(Arc::new(listener)));
+            self
+        }
+
         /// Creates the [`LazyCachingCredentialsProvider`].
         ///
         /// # Panics
@@ -279,7 +698,7 @@ mod builder {
                 default_credential_expiration >= DEFAULT_CREDENTIAL_EXPIRATION,
                 "default_credential_expiration must be at least 15 minutes"
             );
-            LazyCachingCredentialsProvider::new(
+            let mut provider = LazyCachingCredentialsProvider::new(
                 self.time_source.unwrap_or_default(),
                 self.sleep.unwrap_or_else(|| {
                     default_async_sleep().expect("no default sleep implementation available")
@@ -288,13 +707,19 @@ mod builder {
                 self.load_timeout.unwrap_or(DEFAULT_LOAD_TIMEOUT),
                 default_credential_expiration,
                 self.buffer_time.unwrap_or(DEFAULT_BUFFER_TIME),
-            )
+            );
+            provider.background_refresh = self.background_refresh.unwrap_or(false);
+            provider.loader.last_loaded.grace_period = self.expired_credentials_grace_period;
+            provider.loader.metrics =
+                Arc::new(Metrics::with_listener(self.refresh_metrics_listener));
+            provider
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
     use std::sync::{Arc, Mutex};
     use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -307,7 +732,7 @@ mod tests {
     use crate::meta::credentials::credential_fn::provide_credentials_fn;
 
     use super::{
-        LazyCachingCredentialsProvider, TimeSource, DEFAULT_BUFFER_TIME,
+        LazyCachingCredentialsProvider, RefreshMetrics, TimeSource, DEFAULT_BUFFER_TIME,
         DEFAULT_CREDENTIAL_EXPIRATION, DEFAULT_LOAD_TIMEOUT,
     };
     use aws_types::os_shim_internal::ManualTimeSource;
@@ -485,4 +910,144 @@ mod tests {
             Err(CredentialsError::ProviderTimedOut { .. })
         ));
     }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn serve_expired_credentials_within_grace_period() {
+        let mut time = ManualTimeSource::new(epoch_secs(100));
+        let mut provider = test_provider(
+            TimeSource::manual(&time),
+            vec![
+                Ok(credentials(1000)),
+                Err(CredentialsError::not_loaded("failed")),
+                Err(CredentialsError::not_loaded("failed")),
+            ],
+        );
+        provider.loader.last_loaded.grace_period = Some(Duration::from_secs(3600));
+
+        expect_creds(1000, &provider).await;
+        time.set_time(epoch_secs(1500));
+        expect_creds(1000, &provider).await;
+        assert!(logs_contain("serving the last loaded credentials"));
+        // expired credentials are cached for a short time while the source is down
+        expect_creds(1000, &provider).await;
+
+        time.set_time(epoch_secs(5000));
+        assert!(provider.provide_credentials().await.is_err());
+
+        let metrics = provider.refresh_metrics();
+        assert_eq!(1, metrics.refreshes());
+        assert_eq!(2, metrics.refresh_failures());
+        assert_eq!(2, metrics.consecutive_refresh_failures());
+        assert_eq!(1, metrics.expired_credentials_served());
+    }
+
+    fn counting_provider(
+        time: TimeSource,
+        load: impl Fn(u64) -> credentials::Result + Send + Sync + 'static,
+    ) -> (LazyCachingCredentialsProvider, Arc<AtomicU64>) {
+        let loads = Arc::new(AtomicU64::new(0));
+        let loader_loads = loads.clone();
+        let mut provider = LazyCachingCredentialsProvider::new(
+            time,
+            Arc::new(TokioSleep::new()),
+            Arc::new(provide_credentials_fn(move || {
+                let result = load(loader_loads.fetch_add(1, Ordering::SeqCst) + 1);
+                async move { result }
+            })),
+            DEFAULT_LOAD_TIMEOUT,
+            DEFAULT_CREDENTIAL_EXPIRATION,
+            DEFAULT_BUFFER_TIME,
+        );
+        provider.background_refresh = true;
+        (provider, loads)
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn background_refresh() {
+        tokio::time::pause();
+        let time = ManualTimeSource::new(epoch_secs(100));
+        let (provider, loads) = counting_provider(TimeSource::manual(&time), |load| {
+            Ok(credentials(1000 * load))
+        });
+
+        expect_creds(1000, &provider).await;
+        // the refresh happens up to five minutes before the credentials expire at 1000s
+        tokio::time::sleep(Duration::from_secs(1000)).await;
+        assert_eq!(2, loads.load(Ordering::SeqCst));
+
+        // callers get the refreshed credentials from the cache
+        expect_creds(2000, &provider).await;
+        assert_eq!(2, loads.load(Ordering::SeqCst));
+        assert_eq!(2, provider.refresh_metrics().refreshes());
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn background_refresh_failure() {
+        tokio::time::pause();
+        let time = ManualTimeSource::new(epoch_secs(100));
+        let (provider, loads) = counting_provider(TimeSource::manual(&time), |load| {
+            if load == 1 {
+                Ok(credentials(1000))
+            } else {
+                Err(CredentialsError::not_loaded("failed"))
+            }
+        });
+
+        expect_creds(1000, &provider).await;
+        tokio::time::sleep(Duration::from_secs(1000)).await;
+        assert!(logs_contain(
+            "failed to refresh credentials in the background"
+        ));
+        let metrics = provider.refresh_metrics();
+        assert!(metrics.refresh_failures() > 1, "{:?}", metrics);
+        assert_eq!(
+            metrics.refresh_failures(),
+            metrics.consecutive_refresh_failures()
+        );
+
+        // the cached credentials are still valid
+        let loads_before = loads.load(Ordering::SeqCst);
+        expect_creds(1000, &provider).await;
+        assert_eq!(loads_before, loads.load(Ordering::SeqCst));
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn background_refresh_backs_off_for_short_lived_credentials() {
+        tokio::time::pause();
+        let time = ManualTimeSource::new(epoch_secs(100));
+        // the credentials always expire within the buffer time
+        let (provider, loads) =
+            counting_provider(TimeSource::manual(&time), |_| Ok(credentials(105)));
+
+        provider.provide_credentials().await.expect("credentials");
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        // refreshes are at least 15 seconds apart instead of one second
+        assert!(loads.load(Ordering::SeqCst) <= 5, "{:?}", loads);
+    }
+
+    #[tokio::test]
+    async fn refresh_metrics_are_pushed_to_the_listener() {
+        let updates = Arc::new(Mutex::new(Vec::<RefreshMetrics>::new()));
+        let listener_updates = updates.clone();
+        let provider = LazyCachingCredentialsProvider::builder()
+            .sleep(TokioSleep::new())
+            .load(provide_credentials_fn(|| async {
+                Err(CredentialsError::not_loaded("failed"))
+            }))
+            .refresh_metrics_listener(move |metrics| {
+                listener_updates.lock().unwrap().push(metrics.clone())
+            })
+            .build();
+
+        assert!(provider.provide_credentials().await.is_err());
+        assert!(provider.provide_credentials().await.is_err());
+        let updates = updates.lock().unwrap();
+        assert_eq!(2, updates.len());
+        assert_eq!(1, updates[0].consecutive_refresh_failures());
+        assert_eq!(2, updates[1].consecutive_refresh_failures());
+    }
 }
@@ -13,3 +13,6 @@ pub use credential_fn::provide_credentials_fn;
 
 pub mod lazy_caching;
 pub use lazy_caching::LazyCachingCredentialsProvider;
+
+pub mod file_cache;
+pub use file_cache::FileCachingCredentialsProvider;
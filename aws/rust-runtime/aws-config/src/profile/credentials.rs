@@ -22,12 +22,14 @@
 //! - `exec` which contains a chain representation of providers to implement passing bootstrapped credentials
 //! through a series of providers.
 
+use crate::meta::credentials::file_cache::{self, FileCache};
 use crate::profile::credentials::exec::named::NamedProviderFactory;
 use crate::profile::credentials::exec::{ClientConfiguration, ProviderChain};
 use crate::profile::parser::ProfileParseError;
 use crate::profile::profile_file::ProfileFiles;
 use crate::profile::Profile;
 use crate::provider_config::ProviderConfig;
+use crate::sts::mfa::{ProvideMfaTokenCode, SharedMfaTokenCodeProvider};
 use aws_smithy_types::error::display::DisplayErrorContext;
 use aws_types::credentials::{self, future, CredentialsError, ProvideCredentials};
 use std::borrow::Cow;
@@ -120,6 +122,28 @@ impl ProvideCredentials for ProfileFileCredentialsProvider {
 /// aws_secret_access_key = 456
 /// ```
 ///
+/// Roles may require MFA, set a session duration, a source identity or session tags. MFA token codes
+/// are loaded from the [`mfa_token_code_provider`](Builder::mfa_token_code_provider) of the builder:
+/// ```ini
+/// [default]
+/// role_arn = arn:aws:iam::123456789:role/RoleA
+/// source_profile = base
+/// mfa_serial = arn:aws:iam::123456789:mfa/user
+/// duration_seconds = 7200
+/// source_identity = alice
+/// role_session_tags =
+///   Project = Blue
+///   Team = Ops
+/// transitive_tag_keys = Project,Team
+/// ```
+///
+/// `duration_seconds` must be between 900 (15 minutes) and 43200 (12 hours). The shared config
+/// file has no standard properties for session tags, so they are read from two properties that are
+/// specific to this SDK and ignored by other tools, such as the AWS CLI:
+/// - `role_session_tags`: the session tags, as `key = value` sub-properties
+/// - `transitive_tag_keys`: a comma-separated list of the keys of the session tags that are passed
+///   on to roles assumed with the session
+///
 /// Other more complex configurations are possible, consult `test-data/assume-role-tests.json`.
 ///
 /// ### Credentials loaded from an external process
@@ -140,6 +164,20 @@ impl ProvideCredentials for ProfileFileCredentialsProvider {
 /// region = us-west-2
 /// ```
 ///
+/// SSO settings can also be shared by multiple profiles with an `[sso-session]` section. Tokens
+/// created with `aws sso login --sso-session my-sso` are refreshed automatically:
+/// ```ini
+/// [default]
+/// sso_session = my-sso
+/// sso_account_id = 123456789011
+/// sso_role_name = readOnly
+/// region = us-west-2
+///
+/// [sso-session my-sso]
+/// sso_start_url = https://example.com/start
+/// sso_region = us-east-2
+/// ```
+///
 /// SSO can also be used as a source profile for assume role chains.
 ///
 #[doc = include_str!("location_of_profile_files.md")]
@@ -150,6 +188,7 @@ pub struct ProfileFileCredentialsProvider {
     provider_config: ProviderConfig,
     profile_override: Option<String>,
     profile_files: ProfileFiles,
+    file_cache: Option<FileCache>,
 }
 
 impl ProfileFileCredentialsProvider {
@@ -176,6 +215,11 @@ impl ProfileFileCredentialsProvider {
                 &err
             )),
         })?;
+        // Credentials of role chains are cached, base credentials are cheap to load
+        let file_cache = self
+            .file_cache
+            .as_ref()
+            .filter(|_| !inner_provider.chain().is_empty());
         let mut creds = match inner_provider
             .base()
             .provide_credentials()
@@ -191,6 +235,20 @@ impl ProfileFileCredentialsProvider {
                 return Err(CredentialsError::provider_error(e));
             }
         };
+        // The identity of the base credentials is part of the cache key, so that role credentials
+        // assumed by one identity, e.g. from environment variables, are never used by another
+        let cache_key = file_cache.map(|_| {
+            file_cache::cache_key([
+                ("chain", inner_provider.cache_key()),
+                ("source_access_key_id", creds.access_key_id()),
+            ])
+        });
+        if let (Some(file_cache), Some(cache_key)) = (file_cache, &cache_key) {
+            if let Some(creds) = file_cache.load(cache_key).await {
+                tracing::info!(creds = ?creds, "loaded assume role credentials from the file cache");
+                return Ok(creds);
+            }
+        }
         for provider in inner_provider.chain().iter() {
             let next_creds = provider
                 .credentials(creds, &self.client_config)
@@ -207,6 +265,9 @@ impl ProfileFileCredentialsProvider {
                 }
             }
         }
+        if let (Some(file_cache), Some(cache_key)) = (file_cache, &cache_key) {
+            file_cache.store(cache_key, &creds).await;
+        }
         Ok(creds)
     }
 }
@@ -262,6 +323,16 @@ pub enum ProfileFileError {
         /// Error message
         message: Cow<'static, str>,
     },
+    /// A property of the profile had an invalid value
+    #[non_exhaustive]
+    InvalidProperty {
+        /// The name of the profile
+        profile: String,
+        /// The name of the property
+        property: String,
+        /// Error message
+        message: Cow<'static, str>,
+    },
     /// The profile referred to a another profile by name that was not defined
     #[non_exhaustive]
     MissingProfile {
@@ -309,6 +380,15 @@ impl Display for ProfileFileError {
             ProfileFileError::InvalidCredentialSource { profile, message } => {
                 write!(f, "invalid credential source in `{}`: {}", profile, message)
             }
+            ProfileFileError::InvalidProperty {
+                profile,
+                property,
+                message,
+            } => write!(
+                f,
+                "invalid `{}` in profile `{}`: {}",
+                property, profile, message
+            ),
             ProfileFileError::MissingProfile { profile, message } => {
                 write!(f, "profile `{}` was not defined: {}", profile, message)
             }
@@ -357,6 +437,8 @@ pub struct Builder {
     profile_override: Option<String>,
     profile_files: Option<ProfileFiles>,
     custom_providers: HashMap<Cow<'static, str>, Arc<dyn ProvideCredentials>>,
+    mfa_token_code_provider: Option<SharedMfaTokenCodeProvider>,
+    file_cache: bool,
 }
 
 impl Builder {
@@ -415,6 +497,46 @@ impl Builder {
         self
     }
 
+    /// Set the provider of MFA token codes for profiles that define an `mfa_serial`
+    ///
+    /// Without a token code provider, assuming a role from a profile with an `mfa_serial` fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use aws_config::profile::ProfileFileCredentialsProvider;
+    /// use aws_config::sts::mfa::provide_mfa_token_code_fn;
+    ///
+    /// # if cfg!(any(feature = "rustls", feature = "native-tls")) {
+    /// let provider = ProfileFileCredentialsProvider::builder()
+    ///     .mfa_token_code_provider(provide_mfa_token_code_fn(|_mfa_serial| async {
+    ///         // prompt for the current token code
+    ///         Ok("123456".to_string())
+    ///     }))
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn mfa_token_code_provider(mut self, provider: impl ProvideMfaTokenCode + 'static) -> Self {
+        self.mfa_token_code_provider = Some(SharedMfaTokenCodeProvider::new(provider));
+        self
+    }
+
+    /// Cache the credentials of assume role chains in a file shared with other processes
+    ///
+    /// Processes that use the same profile reuse the cached credentials until they expire instead
+    /// of assuming the roles of the chain again, e.g. successive invocations of a command line
+    /// tool. Credentials loaded without assuming a role are not cached. The base credentials of the
+    /// chain are still loaded and their access key ID is part of the cache key, so that credentials
+    /// assumed by one identity are never used by another.
+    ///
+    /// See [`file_cache`](crate::meta::credentials::file_cache) for more information.
+    ///
+    /// Defaults to `false`.
+    pub fn file_cache(mut self, enabled: bool) -> Self {
+        self.file_cache = enabled;
+        self
+    }
+
     /// Override the profile name used by the [`ProfileFileCredentialsProvider`]
     pub fn profile_name(mut self, profile_name: impl Into<String>) -> Self {
         self.profile_override = Some(profile_name.into());
@@ -468,7 +590,9 @@ impl Builder {
             client_config: ClientConfiguration {
                 sts_client: core_client,
                 region: conf.region(),
+                mfa_token_code_provider: self.mfa_token_code_provider,
             },
+            file_cache: self.file_cache.then(|| FileCache::new(&conf)),
             provider_config: conf,
             profile_override: self.profile_override,
             profile_files: self.profile_files.unwrap_or_default(),
@@ -5,6 +5,7 @@
 
 use std::sync::Arc;
 
+use aws_sdk_sts::model::Tag;
 use aws_sdk_sts::operation::AssumeRole;
 use aws_sdk_sts::{Config, Credentials};
 use aws_types::region::Region;
@@ -16,6 +17,7 @@ use crate::profile::credentials::ProfileFileError;
 use crate::provider_config::ProviderConfig;
 use crate::sso::{SsoConfig, SsoCredentialsProvider};
 use crate::sts;
+use crate::sts::mfa::{self, SharedMfaTokenCodeProvider};
 use crate::web_identity_token::{StaticConfiguration, WebIdentityTokenCredentialsProvider};
 use aws_sdk_sts::middleware::DefaultMiddleware;
 use aws_smithy_client::erase::DynConnector;
@@ -28,12 +30,18 @@ pub(super) struct AssumeRoleProvider {
     role_arn: String,
     external_id: Option<String>,
     session_name: Option<String>,
+    mfa_serial: Option<String>,
+    duration_seconds: Option<i32>,
+    source_identity: Option<String>,
+    tags: Vec<Tag>,
+    transitive_tag_keys: Vec<String>,
 }
 
 #[derive(Debug)]
 pub(super) struct ClientConfiguration {
     pub(super) sts_client: aws_smithy_client::Client<DynConnector, DefaultMiddleware>,
     pub(super) region: Option<Region>,
+    pub(super) mfa_token_code_provider: Option<SharedMfaTokenCodeProvider>,
 }
 
 impl AssumeRoleProvider {
@@ -51,10 +59,25 @@ impl AssumeRoleProvider {
             .as_ref()
             .cloned()
             .unwrap_or_else(|| sts::util::default_session_name("assume-role-from-profile"));
-        let operation = AssumeRole::builder()
+        let mut operation = AssumeRole::builder()
             .role_arn(&self.role_arn)
             .set_external_id(self.external_id.clone())
             .role_session_name(session_name)
+            .set_duration_seconds(self.duration_seconds)
+            .set_source_identity(self.source_identity.clone());
+        if !self.tags.is_empty() {
+            operation = operation.set_tags(Some(self.tags.clone()));
+        }
+        if !self.transitive_tag_keys.is_empty() {
+            operation = operation.set_transitive_tag_keys(Some(self.transitive_tag_keys.clone()));
+        }
+        if let Some(mfa_serial) = &self.mfa_serial {
+            let token_code =
+                mfa::load_token_code(mfa_serial, client_config.mfa_token_code_provider.as_ref())
+                    .await?;
+            operation = operation.serial_number(mfa_serial).token_code(token_code);
+        }
+        let operation = operation
             .build()
             .expect("operation is valid")
             .make_operation(&config)
@@ -74,6 +97,7 @@ impl AssumeRoleProvider {
 pub(super) struct ProviderChain {
     base: Arc<dyn ProvideCredentials>,
     chain: Vec<AssumeRoleProvider>,
+    cache_key: String,
 }
 
 impl ProviderChain {
@@ -84,6 +108,11 @@ impl ProviderChain {
     pub(crate) fn chain(&self) -> &[AssumeRoleProvider] {
         self.chain.as_slice()
     }
+
+    /// Key identifying the credentials produced by this chain in the file cache
+    pub(crate) fn cache_key(&self) -> &str {
+        &self.cache_key
+    }
 }
 
 impl ProviderChain {
@@ -102,7 +131,9 @@ impl ProviderChain {
             }
             BaseProvider::AccessKey(key) => Arc::new(key.clone()),
             BaseProvider::CredentialProcess(credential_process) => Arc::new(
-                CredentialProcessProvider::new(credential_process.unredacted().into()),
+                CredentialProcessProvider::builder(credential_process.unredacted())
+                    .configure(provider_config)
+                    .build(),
             ),
             BaseProvider::WebIdentityTokenRole {
                 role_arn,
@@ -126,12 +157,14 @@ impl ProviderChain {
                 sso_region,
                 sso_role_name,
                 sso_start_url,
+                sso_session_name,
             } => {
                 let sso_config = SsoConfig {
                     account_id: sso_account_id.to_string(),
                     role_name: sso_role_name.to_string(),
                     start_url: sso_start_url.to_string(),
                     region: Region::new(sso_region.to_string()),
+                    session_name: sso_session_name.map(|name| name.to_string()),
                 };
                 Arc::new(SsoCredentialsProvider::new(provider_config, sso_config))
             }
@@ -146,10 +179,27 @@ impl ProviderChain {
                     role_arn: role_arn.role_arn.into(),
                     external_id: role_arn.external_id.map(|id| id.into()),
                     session_name: role_arn.session_name.map(|id| id.into()),
+                    mfa_serial: role_arn.mfa_serial.map(|serial| serial.into()),
+                    duration_seconds: role_arn.duration_seconds,
+                    source_identity: role_arn.source_identity.map(|id| id.into()),
+                    tags: role_arn
+                        .tags
+                        .iter()
+                        .map(|(key, value)| Tag::builder().key(*key).value(*value).build())
+                        .collect(),
+                    transitive_tag_keys: role_arn
+                        .transitive_tag_keys
+                        .iter()
+                        .map(|key| key.to_string())
+                        .collect(),
                 }
             })
             .collect();
-        Ok(ProviderChain { base, chain })
+        Ok(ProviderChain {
+            base,
+            chain,
+            cache_key: repr.cache_key(),
+        })
     }
 }
 
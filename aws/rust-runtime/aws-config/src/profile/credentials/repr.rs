@@ -16,6 +16,7 @@ use crate::credential_process::CommandWithSensitiveArgs;
 use crate::profile::credentials::ProfileFileError;
 use crate::profile::{Profile, ProfileSet};
 use aws_types::Credentials;
+use std::borrow::Cow;
 
 /// Chain of Profile Providers
 ///
@@ -38,6 +39,73 @@ impl<'a> ProfileChain<'a> {
     pub(super) fn chain(&self) -> &[RoleArn<'a>] {
         self.chain.as_slice()
     }
+
+    /// Key identifying the credentials produced by this chain in the file cache
+    pub(super) fn cache_key(&self) -> String {
+        let mut settings: Vec<(&str, Cow<'_, str>)> = Vec::new();
+        match &self.base {
+            BaseProvider::NamedSource(name) => settings.push(("credential_source", (*name).into())),
+            BaseProvider::AccessKey(credentials) => {
+                settings.push(("aws_access_key_id", credentials.access_key_id().into()))
+            }
+            BaseProvider::WebIdentityTokenRole {
+                role_arn,
+                web_identity_token_file,
+                session_name,
+            } => {
+                settings.push(("web_identity_role_arn", (*role_arn).into()));
+                settings.push(("web_identity_token_file", (*web_identity_token_file).into()));
+                if let Some(session_name) = session_name {
+                    settings.push(("web_identity_session_name", (*session_name).into()));
+                }
+            }
+            BaseProvider::Sso {
+                sso_account_id,
+                sso_region,
+                sso_role_name,
+                sso_start_url,
+                sso_session_name,
+            } => {
+                settings.push(("sso_account_id", (*sso_account_id).into()));
+                settings.push(("sso_region", (*sso_region).into()));
+                settings.push(("sso_role_name", (*sso_role_name).into()));
+                settings.push(("sso_start_url", (*sso_start_url).into()));
+                if let Some(sso_session_name) = sso_session_name {
+                    settings.push(("sso_session", (*sso_session_name).into()));
+                }
+            }
+            BaseProvider::CredentialProcess(command) => {
+                settings.push(("credential_process", command.unredacted().into()))
+            }
+        }
+        for role in &self.chain {
+            settings.push(("role_arn", role.role_arn.into()));
+            let optional = [
+                ("external_id", role.external_id),
+                ("role_session_name", role.session_name),
+                ("mfa_serial", role.mfa_serial),
+                ("source_identity", role.source_identity),
+            ];
+            for (name, value) in optional {
+                if let Some(value) = value {
+                    settings.push((name, value.into()));
+                }
+            }
+            if let Some(duration_seconds) = role.duration_seconds {
+                settings.push(("duration_seconds", duration_seconds.to_string().into()));
+            }
+            for (key, value) in &role.tags {
+                settings.push(("tag_key", (*key).into()));
+                settings.push(("tag_value", (*value).into()));
+            }
+            for key in &role.transitive_tag_keys {
+                settings.push(("transitive_tag_key", (*key).into()));
+            }
+        }
+        crate::meta::credentials::file_cache::cache_key(
+            settings.iter().map(|(name, value)| (*name, value.as_ref())),
+        )
+    }
 }
 
 /// A base member of the profile chain
@@ -77,11 +145,15 @@ pub(super) enum BaseProvider<'a> {
     },
 
     /// An SSO Provider
+    ///
+    /// When the profile references an `[sso-session]` section, `sso_start_url` and `sso_region`
+    /// are read from it and the access token is refreshed using that session.
     Sso {
         sso_account_id: &'a str,
         sso_region: &'a str,
         sso_role_name: &'a str,
         sso_start_url: &'a str,
+        sso_session_name: Option<&'a str>,
     },
 
     /// A profile that specifies a `credential_process`
@@ -105,6 +177,21 @@ pub(super) struct RoleArn<'a> {
 
     /// session name parameter to pass to the assume role provider
     pub(super) session_name: Option<&'a str>,
+
+    /// serial number (or ARN) of the MFA device required to assume the role
+    pub(super) mfa_serial: Option<&'a str>,
+
+    /// duration of the role session, in seconds
+    pub(super) duration_seconds: Option<i32>,
+
+    /// source identity to set on the role session
+    pub(super) source_identity: Option<&'a str>,
+
+    /// session tags as key/value pairs, in the order they were defined
+    pub(super) tags: Vec<(&'a str, &'a str)>,
+
+    /// keys of the session tags that are transitive
+    pub(super) transitive_tag_keys: Vec<&'a str>,
 }
 
 /// Resolve a ProfileChain from a ProfileSet or return an error
@@ -173,11 +260,12 @@ pub(super) fn resolve_chain<'a>(
             // We check for one here and then process the profile accordingly as either a "chain provider"
             // or a "base provider"
             if let Some(role_provider) = role_arn_from_profile(profile) {
+                let role_provider = role_provider?;
                 let next = chain_provider(profile)?;
                 chain.push(role_provider);
                 next
             } else {
-                break base_provider(profile).map_err(|err| {
+                break base_provider(profile_set, profile).map_err(|err| {
                     // It's possible for base_provider to return a `ProfileFileError::ProfileDidNotContainCredentials`
                     // if we're still looking at the first provider we want to surface it. However,
                     // if we're looking at any provider after the first we want to instead return a `ProfileFileError::InvalidCredentialSource`
@@ -198,7 +286,7 @@ pub(super) fn resolve_chain<'a>(
                 // self referential profile, don't go through the loop because it will error
                 // on the infinite loop check. Instead, reload this profile as a base profile
                 // and exit.
-                break base_provider(profile)?;
+                break base_provider(profile_set, profile)?;
             }
             NextProfile::Named(name) => source_profile_name = name,
         }
@@ -211,6 +299,13 @@ mod role {
     pub(super) const ROLE_ARN: &str = "role_arn";
     pub(super) const EXTERNAL_ID: &str = "external_id";
     pub(super) const SESSION_NAME: &str = "role_session_name";
+    pub(super) const MFA_SERIAL: &str = "mfa_serial";
+    pub(super) const DURATION_SECONDS: &str = "duration_seconds";
+    /// Session durations accepted by STS, from 15 minutes to 12 hours
+    pub(super) const DURATION_SECONDS_RANGE: std::ops::RangeInclusive<i32> = 900..=43200;
+    pub(super) const SOURCE_IDENTITY: &str = "source_identity";
+    pub(super) const SESSION_TAGS: &str = "role_session_tags";
+    pub(super) const TRANSITIVE_TAG_KEYS: &str = "transitive_tag_keys";
 
     pub(super) const CREDENTIAL_SOURCE: &str = "credential_source";
     pub(super) const SOURCE_PROFILE: &str = "source_profile";
@@ -221,6 +316,7 @@ mod sso {
     pub(super) const REGION: &str = "sso_region";
     pub(super) const ROLE_NAME: &str = "sso_role_name";
     pub(super) const START_URL: &str = "sso_start_url";
+    pub(super) const SESSION_NAME: &str = "sso_session";
 }
 
 mod web_identity_token {
@@ -239,12 +335,15 @@ mod credential_process {
 
 const PROVIDER_NAME: &str = "ProfileFile";
 
-fn base_provider(profile: &Profile) -> Result<BaseProvider<'_>, ProfileFileError> {
+fn base_provider<'a>(
+    profile_set: &'a ProfileSet,
+    profile: &'a Profile,
+) -> Result<BaseProvider<'a>, ProfileFileError> {
     // the profile must define either a `CredentialsSource` or a concrete set of access keys
     match profile.get(role::CREDENTIAL_SOURCE) {
         Some(source) => Ok(BaseProvider::NamedSource(source)),
         None => web_identity_token_from_profile(profile)
-            .or_else(|| sso_from_profile(profile))
+            .or_else(|| sso_from_profile(profile_set, profile))
             .or_else(|| credential_process_from_profile(profile))
             .unwrap_or_else(|| Ok(BaseProvider::AccessKey(static_creds_from_profile(profile)?))),
     }
@@ -282,22 +381,95 @@ fn chain_provider(profile: &Profile) -> Result<NextProfile<'_>, ProfileFileError
     }
 }
 
-fn role_arn_from_profile(profile: &Profile) -> Option<RoleArn<'_>> {
+/// Load the role to assume from a profile
+///
+/// Example:
+/// ```ini
+/// [profile A]
+/// role_arn = arn:aws:iam::123456789:role/RoleA
+/// source_profile = B
+/// mfa_serial = arn:aws:iam::123456789:mfa/user
+/// duration_seconds = 7200
+/// source_identity = alice
+/// role_session_tags =
+///   Project = Blue
+///   Team = Ops
+/// transitive_tag_keys = Project,Team
+/// ```
+fn role_arn_from_profile(profile: &Profile) -> Option<Result<RoleArn<'_>, ProfileFileError>> {
     // Web Identity Tokens are root providers, not chained roles
     if profile.get(web_identity_token::TOKEN_FILE).is_some() {
         return None;
     }
     let role_arn = profile.get(role::ROLE_ARN)?;
-    let session_name = profile.get(role::SESSION_NAME);
-    let external_id = profile.get(role::EXTERNAL_ID);
-    Some(RoleArn {
-        role_arn,
-        external_id,
-        session_name,
-    })
+    let invalid = |property: &'static str, message: String| ProfileFileError::InvalidProperty {
+        profile: profile.name().to_string(),
+        property: property.into(),
+        message: message.into(),
+    };
+    let parse_profile = || {
+        let duration_seconds = profile
+            .get(role::DURATION_SECONDS)
+            .map(|duration| match duration.trim().parse::<i32>() {
+                Ok(secs) if role::DURATION_SECONDS_RANGE.contains(&secs) => Ok(secs),
+                _ => Err(invalid(
+                    role::DURATION_SECONDS,
+                    format!(
+                        "must be a number of seconds between {} and {} but was `{}`",
+                        role::DURATION_SECONDS_RANGE.start(),
+                        role::DURATION_SECONDS_RANGE.end(),
+                        duration
+                    ),
+                )),
+            })
+            .transpose()?;
+        let tags = profile
+            .get(role::SESSION_TAGS)
+            .map(|tags| {
+                tags.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(|line| match line.split_once('=') {
+                        Some((key, value)) => Ok((key.trim(), value.trim())),
+                        None => Err(invalid(
+                            role::SESSION_TAGS,
+                            format!(
+                                "must contain `key = value` sub-properties but contained `{}`",
+                                line
+                            ),
+                        )),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+        let transitive_tag_keys = profile
+            .get(role::TRANSITIVE_TAG_KEYS)
+            .map(|keys| {
+                keys.split(',')
+                    .map(str::trim)
+                    .filter(|key| !key.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(RoleArn {
+            role_arn,
+            external_id: profile.get(role::EXTERNAL_ID),
+            session_name: profile.get(role::SESSION_NAME),
+            mfa_serial: profile.get(role::MFA_SERIAL),
+            duration_seconds,
+            source_identity: profile.get(role::SOURCE_IDENTITY),
+            tags,
+            transitive_tag_keys,
+        })
+    };
+    Some(parse_profile())
 }
 
-fn sso_from_profile(profile: &Profile) -> Option<Result<BaseProvider<'_>, ProfileFileError>> {
+fn sso_from_profile<'a>(
+    profile_set: &'a ProfileSet,
+    profile: &'a Profile,
+) -> Option<Result<BaseProvider<'a>, ProfileFileError>> {
     /*
     Sample:
     [profile sample-profile]
@@ -305,11 +477,24 @@ fn sso_from_profile(profile: &Profile) -> Option<Result<BaseProvider<'_>, Profil
     sso_region = us-east-1
     sso_role_name = SampleRole
     sso_start_url = https://d-abc123.awsapps.com/start-beta
+
+    or, with an sso-session:
+    [profile sample-profile]
+    sso_session = my-sso
+    sso_account_id = 012345678901
+    sso_role_name = SampleRole
+
+    [sso-session my-sso]
+    sso_region = us-east-1
+    sso_start_url = https://d-abc123.awsapps.com/start-beta
     */
     let account_id = profile.get(sso::ACCOUNT_ID);
     let region = profile.get(sso::REGION);
     let role_name = profile.get(sso::ROLE_NAME);
     let start_url = profile.get(sso::START_URL);
+    let session_name = profile.get(sso::SESSION_NAME);
+    // `sso_session` on its own doesn't make this an SSO profile, so that profiles that only
+    // reference an sso-session keep resolving through the other credential sources
     if [account_id, region, role_name, start_url]
         .iter()
         .all(|field| field.is_none())
@@ -318,6 +503,10 @@ fn sso_from_profile(profile: &Profile) -> Option<Result<BaseProvider<'_>, Profil
     }
     let missing_field = |s| move || ProfileFileError::missing_field(profile, s);
     let parse_profile = || {
+        let (region, start_url) = match session_name {
+            Some(session_name) => sso_session_settings(profile_set, profile, session_name)?,
+            None => (region, start_url),
+        };
         let sso_account_id = account_id.ok_or_else(missing_field(sso::ACCOUNT_ID))?;
         let sso_region = region.ok_or_else(missing_field(sso::REGION))?;
         let sso_role_name = role_name.ok_or_else(missing_field(sso::ROLE_NAME))?;
@@ -327,11 +516,54 @@ fn sso_from_profile(profile: &Profile) -> Option<Result<BaseProvider<'_>, Profil
             sso_region,
             sso_role_name,
             sso_start_url,
+            sso_session_name: session_name,
         })
     };
     Some(parse_profile())
 }
 
+/// Resolve the `sso_region` and `sso_start_url` of a profile referencing an `[sso-session]`
+///
+/// Settings may be repeated in the profile but must then match the ones of the sso-session.
+fn sso_session_settings<'a>(
+    profile_set: &'a ProfileSet,
+    profile: &'a Profile,
+    session_name: &str,
+) -> Result<(Option<&'a str>, Option<&'a str>), ProfileFileError> {
+    let session =
+        profile_set
+            .sso_session(session_name)
+            .ok_or_else(|| ProfileFileError::MissingProfile {
+                profile: profile.name().to_string(),
+                message: format!(
+                    "could not find sso-session `{}` referenced from profile `{}`",
+                    session_name,
+                    profile.name()
+                )
+                .into(),
+            })?;
+    let setting = |key: &'static str| match (profile.get(key), session.get(key)) {
+        (Some(profile_value), Some(session_value)) if profile_value != session_value => {
+            Err(ProfileFileError::InvalidCredentialSource {
+                profile: profile.name().to_string(),
+                message: format!(
+                    "`{}` of profile `{}` ({}) does not match the one of sso-session `{}` ({})",
+                    key,
+                    profile.name(),
+                    profile_value,
+                    session_name,
+                    session_value
+                )
+                .into(),
+            })
+        }
+        (profile_value, session_value) => Ok(session_value.or(profile_value)),
+    };
+    let region = setting(sso::REGION)?;
+    let start_url = setting(sso::START_URL)?;
+    Ok((region, start_url))
+}
+
 fn web_identity_token_from_profile(
     profile: &Profile,
 ) -> Option<Result<BaseProvider<'_>, ProfileFileError>> {
@@ -434,7 +666,10 @@ mod tests {
     }
 
     fn check(test_case: TestCase) {
-        let source = ProfileSet::new(test_case.input.profile, test_case.input.selected_profile);
+        let mut source = ProfileSet::new(test_case.input.profile, test_case.input.selected_profile);
+        for (name, properties) in test_case.input.sso_session {
+            source.insert_sso_session(name, properties);
+        }
         let actual = resolve_chain(&source, None);
         let expected = test_case.output;
         match (expected, actual) {
@@ -465,6 +700,8 @@ mod tests {
     struct TestInput {
         profile: HashMap<String, HashMap<String, String>>,
         selected_profile: String,
+        #[serde(default)]
+        sso_session: HashMap<String, HashMap<String, String>>,
     }
 
     fn to_test_output(profile_chain: ProfileChain<'_>) -> Vec<Provider> {
@@ -493,11 +730,13 @@ mod tests {
                 sso_region,
                 sso_role_name,
                 sso_start_url,
+                sso_session_name,
             } => output.push(Provider::Sso {
                 sso_account_id: sso_account_id.into(),
                 sso_region: sso_region.into(),
                 sso_role_name: sso_role_name.into(),
                 sso_start_url: sso_start_url.into(),
+                sso_session_name: sso_session_name.map(ToString::to_string),
             }),
         };
         for role in profile_chain.chain {
@@ -505,6 +744,19 @@ mod tests {
                 role_arn: role.role_arn.into(),
                 external_id: role.external_id.map(ToString::to_string),
                 role_session_name: role.session_name.map(ToString::to_string),
+                mfa_serial: role.mfa_serial.map(ToString::to_string),
+                duration_seconds: role.duration_seconds,
+                source_identity: role.source_identity.map(ToString::to_string),
+                tags: role
+                    .tags
+                    .into_iter()
+                    .map(|(key, value)| vec![key.to_string(), value.to_string()])
+                    .collect(),
+                transitive_tag_keys: role
+                    .transitive_tag_keys
+                    .into_iter()
+                    .map(ToString::to_string)
+                    .collect(),
             })
         }
         output
@@ -522,6 +774,13 @@ mod tests {
             role_arn: String,
             external_id: Option<String>,
             role_session_name: Option<String>,
+            mfa_serial: Option<String>,
+            duration_seconds: Option<i32>,
+            source_identity: Option<String>,
+            #[serde(default)]
+            tags: Vec<Vec<String>>,
+            #[serde(default)]
+            transitive_tag_keys: Vec<String>,
         },
         AccessKey {
             access_key_id: String,
@@ -540,6 +799,7 @@ mod tests {
             sso_region: String,
             sso_role_name: String,
             sso_start_url: String,
+            sso_session_name: Option<String>,
         },
     }
 
@@ -569,4 +829,57 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn cache_key_identifies_the_role_chain() {
+        fn cache_key(profiles: &[(&str, &[(&str, &str)])]) -> String {
+            let profiles = profiles
+                .iter()
+                .map(|(name, properties)| {
+                    let properties = properties
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect::<HashMap<_, _>>();
+                    (name.to_string(), properties)
+                })
+                .collect();
+            let profile_set = ProfileSet::new(profiles, "default");
+            resolve_chain(&profile_set, None).unwrap().cache_key()
+        }
+        let base: &[(&str, &str)] = &[
+            ("aws_access_key_id", "AKIDBASE"),
+            ("aws_secret_access_key", "secret"),
+        ];
+        let role_a = cache_key(&[
+            (
+                "default",
+                &[("role_arn", "arn:a"), ("source_profile", "base")],
+            ),
+            ("base", base),
+        ]);
+        assert_eq!(
+            r#"[["aws_access_key_id","AKIDBASE"],["role_arn","arn:a"]]"#,
+            role_a
+        );
+        let role_b = cache_key(&[
+            (
+                "default",
+                &[("role_arn", "arn:b"), ("source_profile", "base")],
+            ),
+            ("base", base),
+        ]);
+        let role_a_mfa = cache_key(&[
+            (
+                "default",
+                &[
+                    ("role_arn", "arn:a"),
+                    ("source_profile", "base"),
+                    ("mfa_serial", "GAHT12345678"),
+                ],
+            ),
+            ("base", base),
+        ]);
+        assert_ne!(role_a, role_b);
+        assert_ne!(role_a, role_a_mfa);
+    }
 }
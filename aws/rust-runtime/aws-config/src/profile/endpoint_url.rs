@@ -0,0 +1,232 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Load endpoint URLs from an AWS profile
+
+use super::profile_file::ProfileFiles;
+use crate::provider_config::ProviderConfig;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use aws_types::endpoint::EndpointUrlConfig;
+use aws_types::os_shim_internal::{Env, Fs};
+
+const ENDPOINT_URL: &str = "endpoint_url";
+const SERVICES: &str = "services";
+
+/// Loads endpoint URLs from a profile file
+///
+/// This provider will attempt to load AWS shared configuration and then read the `endpoint_url`
+/// property of the active profile, along with the per-service `endpoint_url` sub-properties of the
+/// `[services]` section referenced by its `services` property. A service-specific URL takes
+/// precedence over the `endpoint_url` of the profile.
+///
+#[doc = include_str!("location_of_profile_files.md")]
+///
+/// # Examples
+///
+/// **Loads `http://localhost:8000` for DynamoDB and `http://localhost:4566` for other services**
+/// ```ini
+/// [default]
+/// endpoint_url = http://localhost:4566
+/// services = local
+///
+/// [services local]
+/// dynamodb =
+///   endpoint_url = http://localhost:8000
+/// ```
+///
+/// This provider is part of the [default endpoint URL provider chain](crate::default_provider::endpoint_url).
+#[derive(Debug, Default)]
+pub struct ProfileFileEndpointUrlProvider {
+    fs: Fs,
+    env: Env,
+    profile_override: Option<String>,
+    profile_files: ProfileFiles,
+}
+
+impl ProfileFileEndpointUrlProvider {
+    /// Create a new [ProfileFileEndpointUrlProvider]
+    ///
+    /// To override the selected profile, set the `AWS_PROFILE` environment variable or use the [`Builder`].
+    pub fn new() -> Self {
+        Self {
+            fs: Fs::real(),
+            env: Env::real(),
+            profile_override: None,
+            profile_files: Default::default(),
+        }
+    }
+
+    /// [`Builder`] to construct a [`ProfileFileEndpointUrlProvider`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Parses the profile config and attempts to find endpoint URLs.
+    ///
+    /// Returns `None` when the active profile configures no endpoint URL.
+    pub async fn endpoint_url_config(&self) -> Option<EndpointUrlConfig> {
+        let profile_set = super::parser::load(&self.fs, &self.env, &self.profile_files)
+            .await
+            .map_err(
+                |err| tracing::warn!(err = %DisplayErrorContext(&err), "failed to parse profile"),
+            )
+            .ok()?;
+        let selected_profile_name = self
+            .profile_override
+            .as_deref()
+            .unwrap_or_else(|| profile_set.selected_profile());
+        let selected_profile = profile_set.get_profile(selected_profile_name)?;
+
+        let mut builder = EndpointUrlConfig::builder();
+        builder.set_global(selected_profile.get(ENDPOINT_URL).map(str::to_owned));
+        if let Some(services_name) = selected_profile.get(SERVICES) {
+            match profile_set.services_section(services_name) {
+                Some(services) => {
+                    for service_id in services.service_ids() {
+                        if let Some(url) = services.get(service_id, ENDPOINT_URL) {
+                            builder.set_service(service_id, url);
+                        }
+                    }
+                }
+                None => tracing::warn!(
+                    "`services` property in profile `{}` references the missing services section `{}`",
+                    selected_profile_name,
+                    services_name
+                ),
+            }
+        }
+        let config = builder.build();
+        (!config.is_empty()).then(|| config)
+    }
+}
+
+/// Builder for [ProfileFileEndpointUrlProvider]
+#[derive(Debug, Default)]
+pub struct Builder {
+    config: Option<ProviderConfig>,
+    profile_override: Option<String>,
+    profile_files: Option<ProfileFiles>,
+}
+
+impl Builder {
+    /// Override the configuration for this provider
+    pub fn configure(mut self, config: &ProviderConfig) -> Self {
+        self.config = Some(config.clone());
+        self
+    }
+
+    /// Override the profile name used by the [ProfileFileEndpointUrlProvider]
+    pub fn profile_name(mut self, profile_name: impl Into<String>) -> Self {
+        self.profile_override = Some(profile_name.into());
+        self
+    }
+
+    /// Build a [ProfileFileEndpointUrlProvider] from this builder
+    pub fn build(self) -> ProfileFileEndpointUrlProvider {
+        let conf = self.config.unwrap_or_default();
+        ProfileFileEndpointUrlProvider {
+            env: conf.env(),
+            fs: conf.fs(),
+            profile_override: self.profile_override,
+            profile_files: self.profile_files.unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProfileFileEndpointUrlProvider;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::os_shim_internal::{Env, Fs};
+    use tracing_test::traced_test;
+
+    fn provider_config(config_contents: &str) -> ProviderConfig {
+        let fs = Fs::from_slice(&[("test_config", config_contents)]);
+        let env = Env::from_slice(&[("AWS_CONFIG_FILE", "test_config")]);
+        ProviderConfig::empty()
+            .with_fs(fs)
+            .with_env(env)
+            .with_http_connector(no_traffic_connector())
+    }
+
+    fn default_provider(config_contents: &str) -> ProfileFileEndpointUrlProvider {
+        ProfileFileEndpointUrlProvider::builder()
+            .configure(&provider_config(config_contents))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn no_endpoint_url() {
+        assert_eq!(
+            None,
+            default_provider("[default]\n").endpoint_url_config().await
+        );
+    }
+
+    #[tokio::test]
+    async fn global_and_service_endpoint_urls() {
+        let config = "\
+            [default]\n\
+            endpoint_url = http://localhost:4566\n\
+            services = local\n\
+            \n\
+            [services local]\n\
+            dynamodb =\n  endpoint_url = http://localhost:8000\n\
+            secrets_manager =\n  endpoint_url = http://localhost:9000\n\
+            s3 =\n  addressing_style = path\n\
+        ";
+        let config = default_provider(config)
+            .endpoint_url_config()
+            .await
+            .expect("endpoint URLs set");
+        assert_eq!(
+            config.endpoint_url("DynamoDB"),
+            Some("http://localhost:8000")
+        );
+        assert_eq!(
+            config.endpoint_url("Secrets Manager"),
+            Some("http://localhost:9000")
+        );
+        assert_eq!(config.endpoint_url("S3"), Some("http://localhost:4566"));
+    }
+
+    #[tokio::test]
+    async fn endpoint_url_other_profile() {
+        let config = "\
+            [default]\n\
+            endpoint_url = http://wrong\n\
+            \n\
+            [profile other]\n\
+            services = local\n\
+            \n\
+            [services local]\n\
+            sts =\n  endpoint_url = http://localhost:8000\n\
+        ";
+        let config = ProfileFileEndpointUrlProvider::builder()
+            .profile_name("other")
+            .configure(&provider_config(config))
+            .build()
+            .endpoint_url_config()
+            .await
+            .expect("endpoint URLs set");
+        assert_eq!(config.endpoint_url("sts"), Some("http://localhost:8000"));
+        assert_eq!(config.endpoint_url("s3"), None);
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn missing_services_section() {
+        assert_eq!(
+            None,
+            default_provider("[default]\nservices = missing")
+                .endpoint_url_config()
+                .await
+        );
+        assert!(logs_contain(
+            "`services` property in profile `default` references the missing services section `missing`"
+        ));
+    }
+}
@@ -0,0 +1,189 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Load the FIPS and dual-stack endpoint settings from an AWS profile
+
+use super::profile_file::ProfileFiles;
+use crate::environment::endpoint_variant::parse_bool;
+use crate::provider_config::ProviderConfig;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use aws_types::os_shim_internal::{Env, Fs};
+
+const USE_FIPS: &str = "use_fips_endpoint";
+const USE_DUAL_STACK: &str = "use_dualstack_endpoint";
+
+/// Loads the FIPS and dual-stack endpoint settings from a profile file
+///
+/// This provider will attempt to load AWS shared configuration and then read the
+/// `use_fips_endpoint` and `use_dualstack_endpoint` properties from the active profile.
+///
+#[doc = include_str!("location_of_profile_files.md")]
+///
+/// # Examples
+///
+/// **Use FIPS endpoints for all requests**
+/// ```ini
+/// [default]
+/// use_fips_endpoint = true
+/// ```
+///
+/// This provider is part of the [default endpoint variant provider chain](crate::default_provider::endpoint_variant).
+#[derive(Debug, Default)]
+pub struct ProfileFileEndpointVariantProvider {
+    fs: Fs,
+    env: Env,
+    profile_override: Option<String>,
+    profile_files: ProfileFiles,
+}
+
+impl ProfileFileEndpointVariantProvider {
+    /// Create a new [ProfileFileEndpointVariantProvider]
+    ///
+    /// To override the selected profile, set the `AWS_PROFILE` environment variable or use the [`Builder`].
+    pub fn new() -> Self {
+        Self {
+            fs: Fs::real(),
+            env: Env::real(),
+            profile_override: None,
+            profile_files: Default::default(),
+        }
+    }
+
+    /// [`Builder`] to construct a [`ProfileFileEndpointVariantProvider`]
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Parses the profile config and attempts to find the FIPS setting.
+    pub async fn use_fips(&self) -> Option<bool> {
+        self.load_bool(USE_FIPS).await
+    }
+
+    /// Parses the profile config and attempts to find the dual-stack setting.
+    pub async fn use_dual_stack(&self) -> Option<bool> {
+        self.load_bool(USE_DUAL_STACK).await
+    }
+
+    async fn load_bool(&self, key: &str) -> Option<bool> {
+        let profile = super::parser::load(&self.fs, &self.env, &self.profile_files)
+            .await
+            .map_err(
+                |err| tracing::warn!(err = %DisplayErrorContext(&err), "failed to parse profile"),
+            )
+            .ok()?;
+        let selected_profile_name = self
+            .profile_override
+            .as_deref()
+            .unwrap_or_else(|| profile.selected_profile());
+        let value = profile.get_profile(selected_profile_name)?.get(key)?;
+        match parse_bool(value) {
+            Some(value) => Some(value),
+            None => {
+                tracing::warn!(
+                    "`{}` property in profile `{}` was invalid, expected `true` or `false`",
+                    key,
+                    selected_profile_name
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Builder for [ProfileFileEndpointVariantProvider]
+#[derive(Debug, Default)]
+pub struct Builder {
+    config: Option<ProviderConfig>,
+    profile_override: Option<String>,
+    profile_files: Option<ProfileFiles>,
+}
+
+impl Builder {
+    /// Override the configuration for this provider
+    pub fn configure(mut self, config: &ProviderConfig) -> Self {
+        self.config = Some(config.clone());
+        self
+    }
+
+    /// Override the profile name used by the [ProfileFileEndpointVariantProvider]
+    pub fn profile_name(mut self, profile_name: impl Into<String>) -> Self {
+        self.profile_override = Some(profile_name.into());
+        self
+    }
+
+    /// Build a [ProfileFileEndpointVariantProvider] from this builder
+    pub fn build(self) -> ProfileFileEndpointVariantProvider {
+        let conf = self.config.unwrap_or_default();
+        ProfileFileEndpointVariantProvider {
+            env: conf.env(),
+            fs: conf.fs(),
+            profile_override: self.profile_override,
+            profile_files: self.profile_files.unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProfileFileEndpointVariantProvider;
+    use crate::provider_config::ProviderConfig;
+    use crate::test_case::no_traffic_connector;
+    use aws_types::os_shim_internal::{Env, Fs};
+    use tracing_test::traced_test;
+
+    fn provider_config(config_contents: &str) -> ProviderConfig {
+        let fs = Fs::from_slice(&[("test_config", config_contents)]);
+        let env = Env::from_slice(&[("AWS_CONFIG_FILE", "test_config")]);
+        ProviderConfig::empty()
+            .with_fs(fs)
+            .with_env(env)
+            .with_http_connector(no_traffic_connector())
+    }
+
+    fn default_provider(config_contents: &str) -> ProfileFileEndpointVariantProvider {
+        ProfileFileEndpointVariantProvider::builder()
+            .configure(&provider_config(config_contents))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn no_settings() {
+        let provider = default_provider("[default]\n");
+        assert_eq!(None, provider.use_fips().await);
+        assert_eq!(None, provider.use_dual_stack().await);
+    }
+
+    #[tokio::test]
+    async fn settings_from_other_profile() {
+        let config = "\
+            [default]\n\
+            use_fips_endpoint = false\n\
+            \n\
+            [profile other]\n\
+            use_fips_endpoint = true\n\
+            use_dualstack_endpoint = true\n\
+        ";
+        let provider = ProfileFileEndpointVariantProvider::builder()
+            .profile_name("other")
+            .configure(&provider_config(config))
+            .build();
+        assert_eq!(Some(true), provider.use_fips().await);
+        assert_eq!(Some(true), provider.use_dual_stack().await);
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn invalid_setting() {
+        assert_eq!(
+            None,
+            default_provider("[default]\nuse_dualstack_endpoint = 1")
+                .use_dual_stack()
+                .await
+        );
+        assert!(logs_contain(
+            "`use_dualstack_endpoint` property in profile `default` was invalid"
+        ));
+    }
+}
@@ -16,10 +16,13 @@ mod parser;
 #[doc(inline)]
 pub use parser::ProfileParseError;
 #[doc(inline)]
-pub use parser::{load, Profile, ProfileSet, Property};
+pub use parser::{load, Profile, ProfileSet, Property, ServicesSection, SsoSession};
 
 pub mod app_name;
+pub mod ca_bundle;
 pub mod credentials;
+pub mod endpoint_url;
+pub mod endpoint_variant;
 pub mod profile_file;
 pub mod region;
 pub mod retry_config;
@@ -6,6 +6,7 @@
 use crate::profile::parser::parse::parse_profile_file;
 use crate::profile::parser::source::Source;
 use crate::profile::profile_file::ProfileFiles;
+use aws_types::endpoint::normalize_service_id;
 use aws_types::os_shim_internal::{Env, Fs};
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -41,6 +42,17 @@ mod source;
 /// # profiles must begin with `profile`
 /// [profile other]
 /// key = value2
+///
+///
+/// # sso-session sections hold settings shared by the profiles referencing them
+/// [sso-session my-sso]
+/// sso_start_url = https://d-abc123.awsapps.com/start
+/// sso_region = us-east-1
+///
+/// # services sections hold service-specific settings for the profiles referencing them
+/// [services local]
+/// dynamodb =
+///   endpoint_url = http://localhost:8000
 /// ```
 ///
 /// ### The credentials file `~/.aws/credentials`
@@ -66,6 +78,8 @@ pub async fn load(
 pub struct ProfileSet {
     profiles: HashMap<String, Profile>,
     selected_profile: Cow<'static, str>,
+    sso_sessions: HashMap<String, SsoSession>,
+    services: HashMap<String, ServicesSection>,
 }
 
 impl ProfileSet {
@@ -100,6 +114,16 @@ impl ProfileSet {
         base
     }
 
+    #[cfg(test)]
+    pub(crate) fn insert_sso_session(&mut self, name: String, properties: HashMap<String, String>) {
+        let properties = properties
+            .into_iter()
+            .map(|(k, v)| (k.clone(), Property::new(k, v)))
+            .collect();
+        self.sso_sessions
+            .insert(name.clone(), SsoSession::new(name, properties));
+    }
+
     /// Retrieves a key-value pair from the currently selected profile
     pub fn get(&self, key: &str) -> Option<&str> {
         self.profiles
@@ -117,6 +141,16 @@ impl ProfileSet {
         self.selected_profile.as_ref()
     }
 
+    /// Retrieves a named `[sso-session]` section from the profile set
+    pub fn sso_session(&self, sso_session_name: &str) -> Option<&SsoSession> {
+        self.sso_sessions.get(sso_session_name)
+    }
+
+    /// Retrieves a named `[services]` section from the profile set
+    pub fn services_section(&self, services_name: &str) -> Option<&ServicesSection> {
+        self.services.get(services_name)
+    }
+
     /// Returns true if no profiles are contained in this profile set
     pub fn is_empty(&self) -> bool {
         self.profiles.is_empty()
@@ -141,6 +175,8 @@ impl ProfileSet {
         Self {
             profiles: Default::default(),
             selected_profile: "default".into(),
+            sso_sessions: Default::default(),
+            services: Default::default(),
         }
     }
 }
@@ -171,6 +207,82 @@ impl Profile {
     }
 }
 
+/// An `[sso-session name]` section of the config file
+///
+/// SSO sessions hold the `sso_start_url`, `sso_region` and `sso_registration_scopes` settings
+/// shared by all the profiles referencing them with `sso_session = name`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SsoSession {
+    name: String,
+    properties: HashMap<String, Property>,
+}
+
+impl SsoSession {
+    /// Create a new SSO session
+    pub fn new(name: String, properties: HashMap<String, Property>) -> Self {
+        Self { name, properties }
+    }
+
+    /// The name of this SSO session
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns a reference to the property named `name`
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.properties.get(name).map(|prop| prop.value())
+    }
+}
+
+/// A `[services name]` section of the config file
+///
+/// Services sections hold service-specific settings for the profiles referencing them with
+/// `services = name`. Each key is a service ID and its value is a list of sub-properties:
+/// ```ini
+/// [services local]
+/// dynamodb =
+///   endpoint_url = http://localhost:8000
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ServicesSection {
+    name: String,
+    properties: HashMap<String, Property>,
+}
+
+impl ServicesSection {
+    /// Create a new services section
+    pub fn new(name: String, properties: HashMap<String, Property>) -> Self {
+        Self { name, properties }
+    }
+
+    /// The name of this services section
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the service IDs configured in this services section
+    pub fn service_ids(&self) -> impl Iterator<Item = &str> {
+        self.properties.keys().map(String::as_ref)
+    }
+
+    /// Returns the sub-property `name` of the service with the given service ID
+    ///
+    /// Service IDs are compared after normalization, so `Secrets Manager` matches the
+    /// `secrets_manager` key.
+    pub fn get(&self, service_id: &str, name: &str) -> Option<&str> {
+        let service_id = normalize_service_id(service_id);
+        let service = self
+            .properties
+            .iter()
+            .find(|(key, _)| normalize_service_id(key) == service_id)?
+            .1;
+        service.value().lines().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key.trim() == name).then(|| value.trim())
+        })
+    }
+}
+
 /// Key-Value property pair
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Property {
@@ -247,6 +359,57 @@ mod test {
         assert_eq!(profile_names, vec!["bar", "foo"]);
     }
 
+    #[test]
+    fn sso_sessions_are_exposed() {
+        let source = make_source(ParserInput {
+            config_file: Some(
+                "[profile foo]\nsso_session = my-sso\n\
+                 [sso-session my-sso]\nsso_start_url = https://d-abc123.awsapps.com/start\nsso_region = us-east-1"
+                    .to_string(),
+            ),
+            credentials_file: Some("[sso-session ignored]\nsso_region = us-west-2".to_string()),
+        });
+
+        let profile_set = ProfileSet::parse(source).expect("profiles loaded");
+        let profile_names: Vec<_> = profile_set.profiles().collect();
+        assert_eq!(profile_names, vec!["foo"]);
+        let sso_session = profile_set
+            .sso_session("my-sso")
+            .expect("sso-session loaded");
+        assert_eq!(sso_session.name(), "my-sso");
+        assert_eq!(
+            sso_session.get("sso_start_url"),
+            Some("https://d-abc123.awsapps.com/start")
+        );
+        assert_eq!(sso_session.get("sso_region"), Some("us-east-1"));
+        assert_eq!(profile_set.sso_session("ignored"), None);
+    }
+
+    #[test]
+    fn services_sections_are_exposed() {
+        let source = make_source(ParserInput {
+            config_file: Some(
+                "[profile foo]\nservices = local\n\
+                 [services local]\nsecrets_manager =\n  endpoint_url = http://localhost:4566\n  other = value"
+                    .to_string(),
+            ),
+            credentials_file: Some("".to_string()),
+        });
+
+        let profile_set = ProfileSet::parse(source).expect("profiles loaded");
+        let services = profile_set
+            .services_section("local")
+            .expect("services loaded");
+        assert_eq!(services.name(), "local");
+        assert_eq!(
+            services.get("Secrets Manager", "endpoint_url"),
+            Some("http://localhost:4566")
+        );
+        assert_eq!(services.get("secrets_manager", "other"), Some("value"));
+        assert_eq!(services.get("secrets_manager", "missing"), None);
+        assert_eq!(services.get("s3", "endpoint_url"), None);
+    }
+
     /// Run all tests from the fuzzing corpus to validate coverage
     #[test]
     #[ignore]
@@ -5,12 +5,14 @@
 
 use crate::profile::parser::parse::{RawProfileSet, WHITESPACE};
 use crate::profile::profile_file::ProfileFileKind;
-use crate::profile::{Profile, ProfileSet, Property};
+use crate::profile::{Profile, ProfileSet, Property, ServicesSection, SsoSession};
 use std::borrow::Cow;
 use std::collections::HashMap;
 
 const DEFAULT: &str = "default";
 const PROFILE_PREFIX: &str = "profile";
+const SSO_SESSION_PREFIX: &str = "sso-session";
+const SERVICES_PREFIX: &str = "services";
 
 #[derive(Eq, PartialEq, Hash, Debug)]
 struct ProfileName<'a> {
@@ -60,6 +62,25 @@ impl ProfileName<'_> {
     }
 }
 
+/// Parse the name of an `[sso-session name]` section, returning `None` for any other section
+fn sso_session_name(input: &str) -> Option<&str> {
+    prefixed_section_name(input, SSO_SESSION_PREFIX)
+}
+
+/// Parse the name of a `[services name]` section, returning `None` for any other section
+fn services_section_name(input: &str) -> Option<&str> {
+    prefixed_section_name(input, SERVICES_PREFIX)
+}
+
+fn prefixed_section_name<'a>(input: &'a str, prefix: &str) -> Option<&'a str> {
+    let input = input.trim_matches(WHITESPACE);
+    match input.strip_prefix(prefix) {
+        // sso-sessionfoo is a profile name, not an sso-session
+        Some(stripped) if stripped.starts_with(WHITESPACE) => Some(stripped.trim()),
+        _ => None,
+    }
+}
+
 /// Normalize a raw profile into a `MergedProfile`
 ///
 /// This function follows the following rules, codified in the tests & the reference Java implementation
@@ -67,13 +88,26 @@ impl ProfileName<'_> {
 /// - Profile names are validated (see `validate_profile_name`)
 /// - A profile named `profile default` takes priority over a profile named `default`.
 /// - Profiles with identical names are merged
+/// - `[sso-session name]` and `[services name]` sections are collected separately and are only
+///   valid in config files
 pub(super) fn merge_in(
     base: &mut ProfileSet,
     raw_profile_set: RawProfileSet<'_>,
     kind: ProfileFileKind,
 ) {
+    let mut raw_profiles = Vec::new();
+    for (name, raw_section) in raw_profile_set {
+        if let Some(name) = sso_session_name(name) {
+            merge_sso_session(base, name, raw_section, kind);
+        } else if let Some(name) = services_section_name(name) {
+            merge_services_section(base, name, raw_section, kind);
+        } else {
+            raw_profiles.push((name, raw_section));
+        }
+    }
+
     // parse / validate profile names
-    let validated_profiles = raw_profile_set
+    let validated_profiles = raw_profiles
         .into_iter()
         .map(|(name, profile)| (ProfileName::parse(name).valid_for(kind), profile));
 
@@ -109,20 +143,81 @@ pub(super) fn merge_in(
             .profiles
             .entry(profile_name.name.to_string())
             .or_insert_with(|| Profile::new(profile_name.name.to_string(), Default::default()));
-        merge_into_base(profile, raw_profile)
+        merge_into_base(&profile.name, &mut profile.properties, raw_profile)
+    }
+}
+
+fn merge_sso_session(
+    base: &mut ProfileSet,
+    name: &str,
+    raw_sso_session: HashMap<&str, Cow<'_, str>>,
+    kind: ProfileFileKind,
+) {
+    if !valid_config_section(SSO_SESSION_PREFIX, name, kind) {
+        return;
     }
+    let sso_session = base
+        .sso_sessions
+        .entry(name.to_string())
+        .or_insert_with(|| SsoSession::new(name.to_string(), Default::default()));
+    merge_into_base(
+        &sso_session.name,
+        &mut sso_session.properties,
+        raw_sso_session,
+    )
 }
 
-fn merge_into_base<'a>(target: &mut Profile, profile: HashMap<&str, Cow<'a, str>>) {
+fn merge_services_section(
+    base: &mut ProfileSet,
+    name: &str,
+    raw_services: HashMap<&str, Cow<'_, str>>,
+    kind: ProfileFileKind,
+) {
+    if !valid_config_section(SERVICES_PREFIX, name, kind) {
+        return;
+    }
+    let services = base
+        .services
+        .entry(name.to_string())
+        .or_insert_with(|| ServicesSection::new(name.to_string(), Default::default()));
+    merge_into_base(&services.name, &mut services.properties, raw_services)
+}
+
+/// Check that a `[<section_type> name]` section is allowed in this file, emitting a warning if not
+fn valid_config_section(section_type: &str, name: &str, kind: ProfileFileKind) -> bool {
+    if matches!(kind, ProfileFileKind::Credentials) {
+        tracing::warn!(
+            "{} `{}` ignored because {} sections are only allowed in the config file",
+            section_type,
+            name,
+            section_type
+        );
+        return false;
+    }
+    if validate_identifier(name).is_err() {
+        tracing::warn!(
+            "{} `{}` ignored because `{}` was not a valid identifier",
+            section_type,
+            name,
+            name
+        );
+        return false;
+    }
+    true
+}
+
+fn merge_into_base<'a>(
+    section_name: &str,
+    target: &mut HashMap<String, Property>,
+    profile: HashMap<&str, Cow<'a, str>>,
+) {
     for (k, v) in profile {
         match validate_identifier(k) {
             Ok(k) => {
-                target
-                    .properties
-                    .insert(k.to_owned(), Property::new(k.to_owned(), v.into()));
+                target.insert(k.to_owned(), Property::new(k.to_owned(), v.into()));
             }
             Err(_) => {
-                tracing::warn!(profile = %&target.name, key = ?k, "key ignored because `{}` was not a valid identifier", k);
+                tracing::warn!(profile = %section_name, key = ?k, "key ignored because `{}` was not a valid identifier", k);
             }
         }
     }
@@ -153,7 +248,7 @@ mod tests {
     use crate::profile::parser::parse::RawProfileSet;
     use crate::profile::ProfileSet;
 
-    use super::{merge_in, ProfileName};
+    use super::{merge_in, services_section_name, sso_session_name, ProfileName};
     use crate::profile::parser::normalize::validate_identifier;
     use crate::profile::profile_file::ProfileFileKind;
 
@@ -203,6 +298,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sso_session_name_parsing() {
+        assert_eq!(sso_session_name("sso-session my-sso"), Some("my-sso"));
+        assert_eq!(sso_session_name("  sso-session\tmy-sso  "), Some("my-sso"));
+        assert_eq!(sso_session_name("sso-sessionfoo"), None);
+        assert_eq!(sso_session_name("profile sso-session"), None);
+    }
+
+    #[test]
+    #[traced_test]
+    fn sso_session_in_credentials_file_generates_warning() {
+        let mut profile: RawProfileSet<'_> = HashMap::new();
+        profile.insert("sso-session my-sso", HashMap::new());
+        let mut base = ProfileSet::empty();
+        merge_in(&mut base, profile, ProfileFileKind::Credentials);
+        assert!(base.sso_session("my-sso").is_none());
+        assert!(logs_contain("sso-session `my-sso` ignored"));
+    }
+
+    #[test]
+    fn services_section_name_parsing() {
+        assert_eq!(services_section_name("services local"), Some("local"));
+        assert_eq!(services_section_name("servicesfoo"), None);
+        assert_eq!(services_section_name("profile services"), None);
+    }
+
+    #[test]
+    #[traced_test]
+    fn services_section_in_credentials_file_generates_warning() {
+        let mut profile: RawProfileSet<'_> = HashMap::new();
+        profile.insert("services local", HashMap::new());
+        let mut base = ProfileSet::empty();
+        merge_in(&mut base, profile, ProfileFileKind::Credentials);
+        assert!(base.services_section("local").is_none());
+        assert!(logs_contain(
+            "services `local` ignored because services sections are only allowed in the config file"
+        ));
+    }
+
     #[test]
     fn test_validate_identifier() {
         assert_eq!(
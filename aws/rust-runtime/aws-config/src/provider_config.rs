@@ -7,6 +7,7 @@
 
 use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep};
 use aws_smithy_client::erase::DynConnector;
+use aws_smithy_client::tls::TlsSettings;
 use aws_types::os_shim_internal::{Env, Fs, TimeSource};
 use aws_types::{
     http_connector::{ConnectorSettings, HttpConnector},
@@ -34,6 +35,7 @@ pub struct ProviderConfig {
     connector: HttpConnector,
     sleep: Option<Arc<dyn AsyncSleep>>,
     region: Option<Region>,
+    tls_settings: TlsSettings,
 }
 
 impl Debug for ProviderConfig {
@@ -43,6 +45,7 @@ impl Debug for ProviderConfig {
             .field("fs", &self.fs)
             .field("sleep", &self.sleep)
             .field("region", &self.region)
+            .field("tls_settings", &self.tls_settings)
             .finish()
     }
 }
@@ -62,6 +65,7 @@ impl Default for ProviderConfig {
             connector,
             sleep: default_async_sleep(),
             region: None,
+            tls_settings: TlsSettings::default(),
         }
     }
 }
@@ -83,6 +87,7 @@ impl ProviderConfig {
             connector: HttpConnector::Prebuilt(None),
             sleep: None,
             region: None,
+            tls_settings: TlsSettings::default(),
         }
     }
 }
@@ -122,6 +127,7 @@ impl ProviderConfig {
             connector: HttpConnector::Prebuilt(None),
             sleep: None,
             region: None,
+            tls_settings: TlsSettings::default(),
         }
     }
 
@@ -160,13 +166,27 @@ impl ProviderConfig {
 
     #[allow(dead_code)]
     pub(crate) fn default_connector(&self) -> Option<DynConnector> {
-        self.connector
-            .connector(&Default::default(), self.sleep.clone())
+        self.connector(&Default::default())
     }
 
+    /// Create a connector for `settings`
+    ///
+    /// Unless `settings` carry their own TLS settings, the TLS settings of this configuration are applied.
     #[allow(dead_code)]
     pub(crate) fn connector(&self, settings: &ConnectorSettings) -> Option<DynConnector> {
-        self.connector.connector(settings, self.sleep.clone())
+        if settings.tls_settings().is_empty() && !self.tls_settings.is_empty() {
+            let settings = settings
+                .clone()
+                .with_tls_settings(self.tls_settings.clone());
+            self.connector.connector(&settings, self.sleep.clone())
+        } else {
+            self.connector.connector(settings, self.sleep.clone())
+        }
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn tls_settings(&self) -> &TlsSettings {
+        &self.tls_settings
     }
 
     #[allow(dead_code)]
@@ -264,6 +284,33 @@ impl ProviderConfig {
         }
     }
 
+    /// Override the TLS settings used by the connectors of this configuration
+    ///
+    /// TLS settings configure additional trusted root certificates and a client certificate. They
+    /// apply to every connector created from this configuration, e.g. the connectors used for IMDS,
+    /// ECS, STS and SSO. They are ignored by connectors set with [`ProviderConfig::with_http_connector`]
+    /// or [`ProviderConfig::with_tcp_connector`].
+    ///
+    /// The default connectors panic when the TLS settings are invalid, e.g. when the CA bundle
+    /// contains no certificates. Use [`try_default_connector`](crate::connector::try_default_connector)
+    /// to check them first.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use aws_config::provider_config::ProviderConfig;
+    /// use aws_smithy_client::tls::TlsSettings;
+    ///
+    /// let ca_bundle = std::fs::read("/etc/ssl/certs/corporate-proxy.pem").expect("readable");
+    /// let conf = ProviderConfig::without_region()
+    ///     .with_tls_settings(TlsSettings::builder().ca_bundle(ca_bundle).build());
+    /// ```
+    pub fn with_tls_settings(self, tls_settings: TlsSettings) -> Self {
+        ProviderConfig {
+            tls_settings,
+            ..self
+        }
+    }
+
     /// Override the sleep implementation for this configuration
     pub fn with_sleep(self, sleep: impl AsyncSleep + 'static) -> Self {
         ProviderConfig {
@@ -0,0 +1,513 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! SSO token cache stored in `~/.aws/sso/cache`
+//!
+//! Tokens created for a legacy SSO profile are keyed by their `sso_start_url` while tokens
+//! created for an `[sso-session]` are keyed by the name of the session.
+
+use crate::fs_util::{home_dir, Os};
+use crate::json_credentials::{json_parse_loop, InvalidJsonCredentials};
+
+use aws_smithy_json::deserialize::Token;
+use aws_smithy_json::serialize::JsonObjectWriter;
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::DateTime;
+use aws_types::os_shim_internal::{Env, Fs};
+use aws_types::region::Region;
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ring::digest;
+use zeroize::Zeroizing;
+
+#[derive(Debug)]
+pub(crate) enum LoadTokenError {
+    InvalidCredentials(InvalidJsonCredentials),
+    NoHomeDirectory,
+    IoError { err: io::Error, path: PathBuf },
+    InsecureFile { path: PathBuf },
+    WriteError { err: io::Error, path: PathBuf },
+}
+
+impl Display for LoadTokenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadTokenError::InvalidCredentials(err) => {
+                write!(f, "SSO Token was invalid (expected JSON): {}", err)
+            }
+            LoadTokenError::NoHomeDirectory => write!(f, "Could not resolve a home directory"),
+            LoadTokenError::IoError { err, path } => {
+                write!(f, "failed to read `{}`: {}", path.display(), err)
+            }
+            LoadTokenError::InsecureFile { path } => write!(
+                f,
+                "refusing to read `{}` because it is readable or writable by other users",
+                path.display()
+            ),
+            LoadTokenError::WriteError { err, path } => {
+                write!(f, "failed to write `{}`: {}", path.display(), err)
+            }
+        }
+    }
+}
+
+impl Error for LoadTokenError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadTokenError::InvalidCredentials(err) => Some(err as _),
+            LoadTokenError::NoHomeDirectory | LoadTokenError::InsecureFile { .. } => None,
+            LoadTokenError::IoError { err, .. } | LoadTokenError::WriteError { err, .. } => {
+                Some(err as _)
+            }
+        }
+    }
+}
+
+/// A token read from (or written to) the SSO token cache
+///
+/// The refresh token and the client registration are only present in tokens created for an
+/// `[sso-session]`. They allow the access token to be refreshed without logging in again.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SsoToken {
+    pub(crate) access_token: Zeroizing<String>,
+    pub(crate) expires_at: DateTime,
+    pub(crate) region: Option<Region>,
+    pub(crate) start_url: Option<String>,
+    pub(crate) refresh_token: Option<Zeroizing<String>>,
+    pub(crate) client_id: Option<String>,
+    pub(crate) client_secret: Option<Zeroizing<String>>,
+    pub(crate) registration_expires_at: Option<DateTime>,
+}
+
+/// Load the token cached under `identifier` from `~/.aws/sso/cache/<hashofidentifier>.json`
+///
+/// This is used for the legacy cache keyed by start URL, which is written by the AWS CLI, so
+/// the permissions of the cache file aren't checked.
+pub(crate) async fn load_token(
+    identifier: &str,
+    env: &Env,
+    fs: &Fs,
+) -> Result<SsoToken, LoadTokenError> {
+    let home = home_dir(env, Os::real()).ok_or(LoadTokenError::NoHomeDirectory)?;
+    let path = sso_token_path(identifier, &home);
+    read_token(&path, fs).await
+}
+
+/// Load the token cached for the `[sso-session]` named `session_name`
+///
+/// Unlike [`load_token`], cache files that other users can read or write are rejected since
+/// they hold a refresh token and the client secret.
+pub(crate) async fn load_session_token(
+    session_name: &str,
+    env: &Env,
+    fs: &Fs,
+) -> Result<SsoToken, LoadTokenError> {
+    let home = home_dir(env, Os::real()).ok_or(LoadTokenError::NoHomeDirectory)?;
+    let path = sso_token_path(session_name, &home);
+    let data = fs
+        .read_private_to_end(&path)
+        .await
+        .map_err(|err| LoadTokenError::IoError {
+            err,
+            path: path.to_path_buf(),
+        })?
+        .map(Zeroizing::new)
+        .ok_or(LoadTokenError::InsecureFile { path })?;
+    parse_token_json(&data).map_err(LoadTokenError::InvalidCredentials)
+}
+
+async fn read_token(path: &Path, fs: &Fs) -> Result<SsoToken, LoadTokenError> {
+    let data =
+        Zeroizing::new(
+            fs.read_to_end(path)
+                .await
+                .map_err(|err| LoadTokenError::IoError {
+                    err,
+                    path: path.to_path_buf(),
+                })?,
+        );
+    let token = parse_token_json(&data).map_err(LoadTokenError::InvalidCredentials)?;
+    Ok(token)
+}
+
+/// Atomically replace the token cached under `identifier`
+pub(crate) async fn save_token(
+    identifier: &str,
+    token: &SsoToken,
+    env: &Env,
+    fs: &Fs,
+) -> Result<(), LoadTokenError> {
+    let home = home_dir(env, Os::real()).ok_or(LoadTokenError::NoHomeDirectory)?;
+    let path = sso_token_path(identifier, &home);
+    let data = token_json(token)
+        .map_err(|err| LoadTokenError::InvalidCredentials(InvalidJsonCredentials::Other(err)))?;
+    fs.write(&path, data.as_bytes())
+        .await
+        .map_err(|err| LoadTokenError::WriteError { err, path })
+}
+
+/// Parse SSO token JSON from input
+fn parse_token_json(input: &[u8]) -> Result<SsoToken, InvalidJsonCredentials> {
+    /*
+      Example:
+      {
+        "accessToken": "base64string",
+        "expiresAt": "2019-11-14T04:05:45Z",
+        "region": "us-west-2",
+        "startUrl": "https://d-abc123.awsapps.com/start",
+        "refreshToken": "<refresh token>",
+        "clientId": "<client id>",
+        "clientSecret": "<client secret>",
+        "registrationExpiresAt": "2019-12-14T04:05:45Z"
+    }*/
+    let mut acccess_token = None;
+    let mut expires_at = None;
+    let mut region = None;
+    let mut start_url = None;
+    let mut refresh_token = None;
+    let mut client_id = None;
+    let mut client_secret = None;
+    let mut registration_expires_at = None;
+    json_parse_loop(input, |key, value| {
+        match (key, value) {
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("accessToken") => {
+                acccess_token = Some(value.to_unescaped()?.to_string())
+            }
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("expiresAt") => {
+                expires_at = Some(value.to_unescaped()?)
+            }
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("region") => {
+                region = Some(value.to_unescaped()?.to_string())
+            }
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("startUrl") => {
+                start_url = Some(value.to_unescaped()?.to_string())
+            }
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("refreshToken") => {
+                refresh_token = Some(Zeroizing::new(value.to_unescaped()?.to_string()))
+            }
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("clientId") => {
+                client_id = Some(value.to_unescaped()?.to_string())
+            }
+            (key, Token::ValueString { value, .. }) if key.eq_ignore_ascii_case("clientSecret") => {
+                client_secret = Some(Zeroizing::new(value.to_unescaped()?.to_string()))
+            }
+            (key, Token::ValueString { value, .. })
+                if key.eq_ignore_ascii_case("registrationExpiresAt") =>
+            {
+                registration_expires_at = Some(value.to_unescaped()?)
+            }
+            _other => {} // ignored
+        };
+        Ok(())
+    })?;
+    let access_token =
+        Zeroizing::new(acccess_token.ok_or(InvalidJsonCredentials::MissingField("accessToken"))?);
+    let expires_at = expires_at.ok_or(InvalidJsonCredentials::MissingField("expiresAt"))?;
+    let expires_at = DateTime::from_str(expires_at.as_ref(), Format::DateTime).map_err(|e| {
+        InvalidJsonCredentials::InvalidField {
+            field: "expiresAt",
+            err: e.into(),
+        }
+    })?;
+    let registration_expires_at = registration_expires_at
+        .map(|expires_at| DateTime::from_str(expires_at.as_ref(), Format::DateTime))
+        .transpose()
+        .map_err(|e| InvalidJsonCredentials::InvalidField {
+            field: "registrationExpiresAt",
+            err: e.into(),
+        })?;
+    let region = region.map(Region::new);
+    Ok(SsoToken {
+        access_token,
+        expires_at,
+        region,
+        start_url,
+        refresh_token,
+        client_id,
+        client_secret,
+        registration_expires_at,
+    })
+}
+
+/// Serialize an SSO token into the JSON format of the token cache
+fn token_json(token: &SsoToken) -> Result<Zeroizing<String>, std::borrow::Cow<'static, str>> {
+    let mut out = Zeroizing::new(String::new());
+    let mut object = JsonObjectWriter::new(&mut *out);
+    object.key("accessToken").string(&token.access_token);
+    object
+        .key("expiresAt")
+        .date_time(&token.expires_at, Format::DateTime)
+        .map_err(|err| format!("invalid `expiresAt`: {}", err))?;
+    if let Some(region) = &token.region {
+        object.key("region").string(region.as_ref());
+    }
+    if let Some(start_url) = &token.start_url {
+        object.key("startUrl").string(start_url);
+    }
+    if let Some(refresh_token) = &token.refresh_token {
+        object.key("refreshToken").string(refresh_token);
+    }
+    if let Some(client_id) = &token.client_id {
+        object.key("clientId").string(client_id);
+    }
+    if let Some(client_secret) = &token.client_secret {
+        object.key("clientSecret").string(client_secret);
+    }
+    if let Some(registration_expires_at) = &token.registration_expires_at {
+        object
+            .key("registrationExpiresAt")
+            .date_time(registration_expires_at, Format::DateTime)
+            .map_err(|err| format!("invalid `registrationExpiresAt`: {}", err))?;
+    }
+    object.finish();
+    Ok(out)
+}
+
+/// Determine the SSO token path for a given start_url or sso-session name
+fn sso_token_path(identifier: &str, home: &str) -> PathBuf {
+    // hex::encode returns a lowercase string
+    let mut out = PathBuf::with_capacity(home.len() + "/.aws/sso/cache".len() + ".json".len() + 40);
+    out.push(home);
+    out.push(".aws/sso/cache");
+    out.push(&hex::encode(digest::digest(
+        &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        identifier.as_bytes(),
+    )));
+    out.set_extension("json");
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use crate::json_credentials::InvalidJsonCredentials;
+    use crate::sso::cache::{
+        load_session_token, load_token, parse_token_json, save_token, sso_token_path,
+        LoadTokenError, SsoToken,
+    };
+    use aws_smithy_types::DateTime;
+    use aws_types::os_shim_internal::{Env, Fs};
+    use aws_types::region::Region;
+    use zeroize::Zeroizing;
+
+    #[test]
+    fn deserialize_valid_tokens() {
+        let token = br#"
+        {
+            "accessToken": "base64string",
+            "expiresAt": "2009-02-13T23:31:30Z",
+            "region": "us-west-2",
+            "startUrl": "https://d-abc123.awsapps.com/start"
+        }"#;
+        assert_eq!(
+            parse_token_json(token).expect("valid"),
+            SsoToken {
+                access_token: Zeroizing::new("base64string".into()),
+                expires_at: DateTime::from_secs(1234567890),
+                region: Some(Region::from_static("us-west-2")),
+                start_url: Some("https://d-abc123.awsapps.com/start".into()),
+                refresh_token: None,
+                client_id: None,
+                client_secret: None,
+                registration_expires_at: None,
+            }
+        );
+
+        let no_region = br#"{
+            "accessToken": "base64string",
+            "expiresAt": "2009-02-13T23:31:30Z"
+        }"#;
+        assert_eq!(
+            parse_token_json(no_region).expect("valid"),
+            SsoToken {
+                access_token: Zeroizing::new("base64string".into()),
+                expires_at: DateTime::from_secs(1234567890),
+                region: None,
+                start_url: None,
+                refresh_token: None,
+                client_id: None,
+                client_secret: None,
+                registration_expires_at: None,
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_refreshable_token() {
+        let token = br#"
+        {
+            "accessToken": "base64string",
+            "expiresAt": "2009-02-13T23:31:30Z",
+            "region": "us-west-2",
+            "startUrl": "https://d-abc123.awsapps.com/start",
+            "refreshToken": "refresh",
+            "clientId": "client",
+            "clientSecret": "secret",
+            "registrationExpiresAt": "2009-02-14T23:31:30Z"
+        }"#;
+        let token = parse_token_json(token).expect("valid");
+        assert_eq!(token.refresh_token, Some(Zeroizing::new("refresh".into())));
+        assert_eq!(token.client_id.as_deref(), Some("client"));
+        assert_eq!(token.client_secret, Some(Zeroizing::new("secret".into())));
+        assert_eq!(
+            token.registration_expires_at,
+            Some(DateTime::from_secs(1234567890 + 24 * 3600))
+        );
+    }
+
+    #[test]
+    fn invalid_timestamp() {
+        let token = br#"
+        {
+            "accessToken": "base64string",
+            "expiresAt": "notatimestamp",
+            "region": "us-west-2",
+            "startUrl": "https://d-abc123.awsapps.com/start"
+        }"#;
+        let err = parse_token_json(token).expect_err("invalid timestamp");
+        assert!(
+            format!("{}", err).contains("Invalid field in response: `expiresAt`."),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn missing_fields() {
+        let token = br#"
+        {
+            "expiresAt": "notatimestamp",
+            "region": "us-west-2",
+            "startUrl": "https://d-abc123.awsapps.com/start"
+        }"#;
+        let err = parse_token_json(token).expect_err("missing akid");
+        assert!(
+            matches!(err, InvalidJsonCredentials::MissingField("accessToken")),
+            "incorrect error: {:?}",
+            err
+        );
+
+        let token = br#"
+        {
+            "accessToken": "akid",
+            "region": "us-west-2",
+            "startUrl": "https://d-abc123.awsapps.com/start"
+        }"#;
+        let err = parse_token_json(token).expect_err("missing expiry");
+        assert!(
+            matches!(err, InvalidJsonCredentials::MissingField("expiresAt")),
+            "incorrect error: {:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn determine_correct_cache_filenames() {
+        assert_eq!(
+            sso_token_path("https://d-92671207e4.awsapps.com/start", "/home/me").as_os_str(),
+            "/home/me/.aws/sso/cache/13f9d35043871d073ab260e020f0ffde092cb14b.json"
+        );
+        assert_eq!(
+            sso_token_path("https://d-92671207e4.awsapps.com/start", "/home/me/").as_os_str(),
+            "/home/me/.aws/sso/cache/13f9d35043871d073ab260e020f0ffde092cb14b.json"
+        );
+    }
+
+    #[tokio::test]
+    async fn gracefully_handle_missing_files() {
+        let err = load_token(
+            "asdf",
+            &Env::from_slice(&[("HOME", "/home")]),
+            &Fs::from_slice(&[]),
+        )
+        .await
+        .expect_err("should fail, file is missing");
+        assert!(
+            matches!(err, LoadTokenError::IoError { .. }),
+            "should be io error, got {}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn saved_tokens_can_be_loaded() {
+        let env = Env::from_slice(&[("HOME", "/home")]);
+        let fs = Fs::from_slice(&[]);
+        let token = SsoToken {
+            access_token: Zeroizing::new("access".into()),
+            expires_at: DateTime::from_secs(1234567890),
+            region: Some(Region::from_static("us-west-2")),
+            start_url: Some("https://d-abc123.awsapps.com/start".into()),
+            refresh_token: Some(Zeroizing::new("refresh".into())),
+            client_id: Some("client".into()),
+            client_secret: Some(Zeroizing::new("secret".into())),
+            registration_expires_at: Some(DateTime::from_secs(1234567890 + 24 * 3600)),
+        };
+        save_token("my-sso", &token, &env, &fs)
+            .await
+            .expect("token saved");
+        assert_eq!(
+            load_token("my-sso", &env, &fs).await.expect("token loaded"),
+            token
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn refuse_tokens_readable_by_other_users() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("aws-config-sso-{}", std::process::id()));
+        let env = Env::from_slice(&[("HOME", "/home")]);
+        let fs = Fs::from_test_dir(&dir, "/home");
+        let token = SsoToken {
+            access_token: Zeroizing::new("access".into()),
+            expires_at: DateTime::from_secs(1234567890),
+            region: None,
+            start_url: None,
+            refresh_token: None,
+            client_id: None,
+            client_secret: None,
+            registration_expires_at: None,
+        };
+        save_token("my-sso", &token, &env, &fs)
+            .await
+            .expect("token saved");
+        let path = dir.join(
+            sso_token_path("my-sso", "/home")
+                .strip_prefix("/home")
+                .unwrap(),
+        );
+        assert_eq!(
+            0o600,
+            std::fs::metadata(&path).unwrap().permissions().mode() & 0o777
+        );
+        assert_eq!(
+            load_session_token("my-sso", &env, &fs)
+                .await
+                .expect("token loaded"),
+            token
+        );
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        // Legacy caches are written by the AWS CLI, which may use other permissions
+        assert_eq!(
+            load_token("my-sso", &env, &fs).await.expect("token loaded"),
+            token
+        );
+        let err = load_session_token("my-sso", &env, &fs)
+            .await
+            .expect_err("cache file is readable by other users");
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(
+            matches!(err, LoadTokenError::InsecureFile { .. }),
+            "should be an insecure file error, got {}",
+            err
+        );
+    }
+}
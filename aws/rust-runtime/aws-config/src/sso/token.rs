@@ -0,0 +1,479 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! SSO token provider for `[sso-session]`s
+//!
+//! Tokens created by `aws sso login --sso-session <name>` are cached with a refresh token and the
+//! client registration used to create them. When the cached access token is about to expire,
+//! it is refreshed with the SSO OIDC `CreateToken` API and the cache is updated, so that
+//! credentials keep being available without logging in again.
+
+use crate::connector::expect_connector;
+use crate::json_credentials::{json_parse_loop, InvalidJsonCredentials};
+use crate::provider_config::ProviderConfig;
+use crate::sso::cache::{load_session_token, save_token, SsoToken};
+
+use aws_smithy_client::erase::DynConnector;
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http::operation::{Operation, Request};
+use aws_smithy_http::response::ParseStrictResponse;
+use aws_smithy_http::result::{SdkError, SdkSuccess};
+use aws_smithy_http::retry::ClassifyRetry;
+use aws_smithy_json::deserialize::Token;
+use aws_smithy_json::serialize::JsonObjectWriter;
+use aws_smithy_types::retry::{ErrorKind, RetryKind};
+use aws_smithy_types::{DateTime, Number};
+use aws_types::credentials::CredentialsError;
+use aws_types::os_shim_internal::{Env, Fs, TimeSource};
+use aws_types::region::Region;
+
+use bytes::Bytes;
+use http::header::CONTENT_TYPE;
+use http::Response;
+use std::time::{Duration, SystemTime};
+use tokio::sync::Mutex;
+use tower::layer::util::Identity;
+use zeroize::Zeroizing;
+
+/// Tokens expiring within this window are refreshed
+const REFRESH_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+/// Refreshes are attempted at most once in this interval, even if they fail
+const MIN_TIME_BETWEEN_REFRESH: Duration = Duration::from_secs(30);
+
+/// Token provider for an `[sso-session]`
+///
+/// Loads the access token cached in `~/.aws/sso/cache/<hash of session name>.json` and refreshes
+/// it when it is about to expire.
+#[derive(Debug)]
+pub(crate) struct SsoTokenProvider {
+    env: Env,
+    fs: Fs,
+    time_source: TimeSource,
+    session_name: String,
+    start_url: String,
+    region: Region,
+    client: aws_smithy_client::Client<DynConnector, Identity>,
+    last_refresh_attempt: Mutex<Option<SystemTime>>,
+}
+
+impl SsoTokenProvider {
+    pub(crate) fn new(
+        provider_config: &ProviderConfig,
+        session_name: String,
+        start_url: String,
+        region: Region,
+    ) -> Self {
+        let mut client_builder = aws_smithy_client::Client::builder()
+            .connector(expect_connector(
+                provider_config.connector(&Default::default()),
+            ))
+            .middleware(Identity::new());
+        client_builder.set_sleep_impl(provider_config.sleep());
+        SsoTokenProvider {
+            env: provider_config.env(),
+            fs: provider_config.fs(),
+            time_source: provider_config.time_source(),
+            session_name,
+            start_url,
+            region,
+            client: client_builder.build(),
+            last_refresh_attempt: Mutex::new(None),
+        }
+    }
+
+    /// Load the cached token, refreshing it first if it is about to expire
+    ///
+    /// If the refresh fails, the cached token is returned as long as it did not expire yet.
+    pub(crate) async fn resolve_token(&self) -> Result<SsoToken, CredentialsError> {
+        // Hold the lock while refreshing so concurrent requests don't refresh the token twice
+        let mut last_refresh_attempt = self.last_refresh_attempt.lock().await;
+        let token = load_session_token(&self.session_name, &self.env, &self.fs)
+            .await
+            .map_err(CredentialsError::provider_error)?;
+        if let Some(start_url) = &token.start_url {
+            if start_url != &self.start_url {
+                return Err(CredentialsError::invalid_configuration(format!(
+                    "the cached SSO token for sso-session `{}` was created for `{}` instead of `{}`. \
+                     Run `aws sso login --sso-session {}` to create a new token",
+                    self.session_name, start_url, self.start_url, self.session_name
+                )));
+            }
+        }
+
+        let now = self.time_source.now();
+        let expires_at = to_system_time(token.expires_at)?;
+        let can_refresh_again = match *last_refresh_attempt {
+            Some(last_attempt) => now >= last_attempt + MIN_TIME_BETWEEN_REFRESH,
+            None => true,
+        };
+        if now + REFRESH_WINDOW >= expires_at && can_refresh_again && self.can_refresh(&token)? {
+            *last_refresh_attempt = Some(now);
+            match self.refresh(&token, now).await {
+                Ok(refreshed) => {
+                    if let Err(err) =
+                        save_token(&self.session_name, &refreshed, &self.env, &self.fs).await
+                    {
+                        tracing::warn!(error = %err, "failed to update the SSO token cache");
+                    }
+                    tracing::debug!(sso_session = %self.session_name, "refreshed SSO token");
+                    return Ok(refreshed);
+                }
+                Err(err) if now < expires_at => {
+                    tracing::warn!(error = %err, "failed to refresh SSO token, using the cached token");
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        if now >= expires_at {
+            return Err(CredentialsError::provider_error(format!(
+                "the SSO token for sso-session `{}` expired. \
+                 Run `aws sso login --sso-session {}` to create a new token",
+                self.session_name, self.session_name
+            )));
+        }
+        Ok(token)
+    }
+
+    /// A token can be refreshed if it has a refresh token and an unexpired client registration
+    fn can_refresh(&self, token: &SsoToken) -> Result<bool, CredentialsError> {
+        let registration_expires_at = match (
+            &token.refresh_token,
+            &token.client_id,
+            &token.client_secret,
+            token.registration_expires_at,
+        ) {
+            (Some(_), Some(_), Some(_), Some(registration_expires_at)) => registration_expires_at,
+            _ => return Ok(false),
+        };
+        Ok(self.time_source.now() < to_system_time(registration_expires_at)?)
+    }
+
+    async fn refresh(
+        &self,
+        token: &SsoToken,
+        now: SystemTime,
+    ) -> Result<SsoToken, CredentialsError> {
+        let output = match self.client.call(self.operation(token)?).await {
+            Ok(output) => output,
+            Err(SdkError::ServiceError { err, .. }) => return Err(err),
+            Err(other) => return Err(CredentialsError::provider_error(other)),
+        };
+        Ok(SsoToken {
+            access_token: output.access_token,
+            expires_at: DateTime::from(now + output.expires_in),
+            refresh_token: output.refresh_token.or_else(|| token.refresh_token.clone()),
+            ..token.clone()
+        })
+    }
+
+    fn operation(
+        &self,
+        token: &SsoToken,
+    ) -> Result<Operation<CreateTokenResponseParser, CreateTokenRetryClassifier>, CredentialsError>
+    {
+        let mut body = Zeroizing::new(String::new());
+        let mut object = JsonObjectWriter::new(&mut *body);
+        let fields = [
+            ("clientId", token.client_id.as_deref()),
+            (
+                "clientSecret",
+                token.client_secret.as_deref().map(String::as_str),
+            ),
+            ("grantType", Some("refresh_token")),
+            (
+                "refreshToken",
+                token.refresh_token.as_deref().map(String::as_str),
+            ),
+        ];
+        for (key, value) in fields {
+            let value = value.ok_or_else(|| {
+                CredentialsError::unhandled(format!("`{}` missing from the SSO token", key))
+            })?;
+            object.key(key).string(value);
+        }
+        object.finish();
+
+        let http_req = http::Request::builder()
+            .method("POST")
+            .uri(format!("https://oidc.{}.amazonaws.com/token", self.region))
+            .header(CONTENT_TYPE, "application/json")
+            .body(SdkBody::from(body.as_str()))
+            .map_err(CredentialsError::unhandled)?;
+        Ok(
+            Operation::new(Request::new(http_req), CreateTokenResponseParser)
+                .with_retry_classifier(CreateTokenRetryClassifier),
+        )
+    }
+}
+
+fn to_system_time(date_time: DateTime) -> Result<SystemTime, CredentialsError> {
+    SystemTime::try_from(date_time).map_err(|err| {
+        CredentialsError::unhandled(format!(
+            "SSO token expiration could not be converted into a system time: {}",
+            err
+        ))
+    })
+}
+
+/// Output of the SSO OIDC `CreateToken` API
+#[derive(Debug)]
+struct CreateTokenOutput {
+    access_token: Zeroizing<String>,
+    expires_in: Duration,
+    refresh_token: Option<Zeroizing<String>>,
+}
+
+#[derive(Clone, Debug)]
+struct CreateTokenResponseParser;
+
+impl ParseStrictResponse for CreateTokenResponseParser {
+    type Output = Result<CreateTokenOutput, CredentialsError>;
+
+    fn parse(&self, response: &Response<Bytes>) -> Self::Output {
+        let body = response.body().as_ref();
+        if !response.status().is_success() {
+            let mut error = None;
+            let mut description = None;
+            // error responses are best effort, don't fail if they aren't valid JSON
+            let _ = json_parse_loop(body, |key, value| {
+                match (key, value) {
+                    (key, Token::ValueString { value, .. }) if key == "error" => {
+                        error = Some(value.to_unescaped()?.to_string())
+                    }
+                    (key, Token::ValueString { value, .. }) if key == "error_description" => {
+                        description = Some(value.to_unescaped()?.to_string())
+                    }
+                    _other => {} // ignored
+                };
+                Ok(())
+            });
+            return Err(CredentialsError::provider_error(format!(
+                "failed to refresh SSO token ({}): {} {}",
+                response.status(),
+                error.as_deref().unwrap_or("unknown error"),
+                description.as_deref().unwrap_or_default()
+            )));
+        }
+        parse_create_token_output(body).map_err(CredentialsError::unhandled)
+    }
+}
+
+fn parse_create_token_output(input: &[u8]) -> Result<CreateTokenOutput, InvalidJsonCredentials> {
+    let mut access_token = None;
+    let mut expires_in = None;
+    let mut refresh_token = None;
+    json_parse_loop(input, |key, value| {
+        match (key, value) {
+            (key, Token::ValueString { value, .. }) if key == "accessToken" => {
+                access_token = Some(Zeroizing::new(value.to_unescaped()?.to_string()))
+            }
+            (key, Token::ValueNumber { value, .. }) if key == "expiresIn" => {
+                expires_in = Some(match value {
+                    Number::PosInt(secs) => Duration::from_secs(*secs),
+                    _ => {
+                        return Err(InvalidJsonCredentials::InvalidField {
+                            field: "expiresIn",
+                            err: format!("expected a positive integer, found {:?}", value).into(),
+                        })
+                    }
+                })
+            }
+            (key, Token::ValueString { value, .. }) if key == "refreshToken" => {
+                refresh_token = Some(Zeroizing::new(value.to_unescaped()?.to_string()))
+            }
+            _other => {} // ignored
+        };
+        Ok(())
+    })?;
+    Ok(CreateTokenOutput {
+        access_token: access_token.ok_or(InvalidJsonCredentials::MissingField("accessToken"))?,
+        expires_in: expires_in.ok_or(InvalidJsonCredentials::MissingField("expiresIn"))?,
+        refresh_token,
+    })
+}
+
+#[derive(Clone, Debug)]
+struct CreateTokenRetryClassifier;
+
+impl ClassifyRetry<SdkSuccess<CreateTokenOutput>, SdkError<CredentialsError>>
+    for CreateTokenRetryClassifier
+{
+    fn classify_retry(
+        &self,
+        response: Result<&SdkSuccess<CreateTokenOutput>, &SdkError<CredentialsError>>,
+    ) -> RetryKind {
+        match response {
+            Ok(_) => RetryKind::Unnecessary,
+            // socket errors, networking timeouts
+            Err(SdkError::DispatchFailure(client_err))
+                if client_err.is_timeout() || client_err.is_io() =>
+            {
+                RetryKind::Error(ErrorKind::TransientError)
+            }
+            // 5xx errors
+            Err(SdkError::ServiceError { raw, .. } | SdkError::ResponseError { raw, .. })
+                if raw.http().status().is_server_error() =>
+            {
+                RetryKind::Error(ErrorKind::ServerError)
+            }
+            Err(_) => RetryKind::UnretryableFailure,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::provider_config::ProviderConfig;
+    use crate::sso::cache::{load_session_token, save_token, SsoToken};
+    use crate::sso::token::SsoTokenProvider;
+    use aws_smithy_client::erase::DynConnector;
+    use aws_smithy_client::test_connection::TestConnection;
+    use aws_smithy_http::body::SdkBody;
+    use aws_smithy_types::DateTime;
+    use aws_types::os_shim_internal::{Env, Fs, ManualTimeSource, TimeSource};
+    use aws_types::region::Region;
+    use std::time::{Duration, UNIX_EPOCH};
+    use zeroize::Zeroizing;
+
+    const NOW: u64 = 1234567890;
+
+    fn cached_token(expires_in: Duration) -> SsoToken {
+        SsoToken {
+            access_token: Zeroizing::new("cached-access-token".into()),
+            expires_at: DateTime::from(UNIX_EPOCH + Duration::from_secs(NOW) + expires_in),
+            region: Some(Region::from_static("us-east-1")),
+            start_url: Some("https://d-abc123.awsapps.com/start".into()),
+            refresh_token: Some(Zeroizing::new("cached-refresh-token".into())),
+            client_id: Some("client-id".into()),
+            client_secret: Some(Zeroizing::new("client-secret".into())),
+            registration_expires_at: Some(DateTime::from_secs(NOW as i64 + 24 * 3600)),
+        }
+    }
+
+    async fn provider(
+        token: &SsoToken,
+        connector: TestConnection<&'static str>,
+    ) -> (SsoTokenProvider, Env, Fs) {
+        let env = Env::from_slice(&[("HOME", "/home/me")]);
+        let fs = Fs::from_slice(&[]);
+        save_token("my-sso", token, &env, &fs).await.unwrap();
+        let provider_config = ProviderConfig::empty()
+            .with_env(env.clone())
+            .with_fs(fs.clone())
+            .with_time_source(TimeSource::manual(&ManualTimeSource::new(
+                UNIX_EPOCH + Duration::from_secs(NOW),
+            )))
+            .with_http_connector(DynConnector::new(connector));
+        let provider = SsoTokenProvider::new(
+            &provider_config,
+            "my-sso".into(),
+            "https://d-abc123.awsapps.com/start".into(),
+            Region::from_static("us-east-1"),
+        );
+        (provider, env, fs)
+    }
+
+    fn create_token_request() -> http::Request<SdkBody> {
+        http::Request::builder()
+            .method("POST")
+            .uri("https://oidc.us-east-1.amazonaws.com/token")
+            .body(SdkBody::from(
+                r#"{"clientId":"client-id","clientSecret":"client-secret","grantType":"refresh_token","refreshToken":"cached-refresh-token"}"#,
+            ))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn valid_tokens_are_not_refreshed() {
+        let token = cached_token(Duration::from_secs(3600));
+        let connector = TestConnection::new(vec![]);
+        let (provider, _, _) = provider(&token, connector.clone()).await;
+        assert_eq!(provider.resolve_token().await.expect("valid token"), token);
+        assert!(connector.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn expiring_tokens_are_refreshed_and_cached() {
+        let token = cached_token(Duration::from_secs(60));
+        let connector = TestConnection::new(vec![(
+            create_token_request(),
+            http::Response::builder()
+                .status(200)
+                .body(r#"{"accessToken":"new-access-token","expiresIn":3600,"refreshToken":"new-refresh-token","tokenType":"Bearer"}"#)
+                .unwrap(),
+        )]);
+        let (provider, env, fs) = provider(&token, connector.clone()).await;
+        let refreshed = provider.resolve_token().await.expect("refreshed token");
+        connector.assert_requests_match(&[]);
+
+        assert_eq!(refreshed.access_token.as_str(), "new-access-token");
+        assert_eq!(refreshed.expires_at, DateTime::from_secs(NOW as i64 + 3600));
+        assert_eq!(
+            refreshed.refresh_token.as_deref().map(String::as_str),
+            Some("new-refresh-token")
+        );
+        assert_eq!(refreshed.client_id, token.client_id);
+        assert_eq!(
+            load_session_token("my-sso", &env, &fs)
+                .await
+                .expect("token cached"),
+            refreshed
+        );
+    }
+
+    #[tokio::test]
+    async fn cached_token_used_when_refresh_fails() {
+        let token = cached_token(Duration::from_secs(60));
+        let connector = TestConnection::new(vec![(
+            create_token_request(),
+            http::Response::builder()
+                .status(400)
+                .body(r#"{"error":"invalid_grant","error_description":"refresh token expired"}"#)
+                .unwrap(),
+        )]);
+        let (provider, _, _) = provider(&token, connector.clone()).await;
+        assert_eq!(provider.resolve_token().await.expect("cached token"), token);
+        // a refresh was already attempted recently
+        assert_eq!(provider.resolve_token().await.expect("cached token"), token);
+        assert_eq!(connector.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn expired_tokens_fail_when_refresh_fails() {
+        let token = cached_token(Duration::ZERO);
+        let connector = TestConnection::new(vec![(
+            create_token_request(),
+            http::Response::builder()
+                .status(400)
+                .body(r#"{"error":"invalid_grant","error_description":"refresh token expired"}"#)
+                .unwrap(),
+        )]);
+        let (provider, _, _) = provider(&token, connector).await;
+        let err = provider.resolve_token().await.expect_err("token expired");
+        assert!(
+            format!("{}", err).contains("invalid_grant"),
+            "unexpected error: {}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn tokens_without_refresh_token_expire() {
+        let token = SsoToken {
+            refresh_token: None,
+            ..cached_token(Duration::ZERO)
+        };
+        let connector = TestConnection::new(vec![]);
+        let (provider, _, _) = provider(&token, connector.clone()).await;
+        let err = provider.resolve_token().await.expect_err("token expired");
+        assert!(
+            format!("{}", err).contains("aws sso login --sso-session my-sso"),
+            "unexpected error: {}",
+            err
+        );
+        assert!(connector.requests().is_empty());
+    }
+}
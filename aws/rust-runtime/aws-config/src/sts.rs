@@ -10,6 +10,7 @@ use aws_sdk_sts::middleware::DefaultMiddleware;
 use aws_smithy_client::erase::DynConnector;
 use aws_smithy_client::Client;
 
+pub mod mfa;
 pub(crate) mod util;
 
 pub use assume_role::{AssumeRoleProvider, AssumeRoleProviderBuilder};
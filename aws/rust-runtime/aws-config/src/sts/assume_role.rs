@@ -6,8 +6,9 @@
 //! Assume credentials for a role through the AWS Security Token Service (STS).
 
 use aws_sdk_sts::error::AssumeRoleErrorKind;
+use aws_sdk_sts::input::assume_role_input;
 use aws_sdk_sts::middleware::DefaultMiddleware;
-use aws_sdk_sts::model::PolicyDescriptorType;
+use aws_sdk_sts::model::{PolicyDescriptorType, Tag};
 use aws_sdk_sts::operation::AssumeRole;
 use aws_smithy_client::erase::DynConnector;
 use aws_smithy_http::result::SdkError;
@@ -17,8 +18,10 @@ use aws_types::credentials::{
 use aws_types::region::Region;
 use std::time::Duration;
 
+use crate::meta::credentials::file_cache::{self, FileCache};
 use crate::meta::credentials::LazyCachingCredentialsProvider;
 use crate::provider_config::ProviderConfig;
+use crate::sts::mfa::{self, ProvideMfaTokenCode, SharedMfaTokenCodeProvider};
 use tracing::Instrument;
 
 /// Credentials provider that uses credentials provided by another provider to assume a role
@@ -50,7 +53,20 @@ pub struct AssumeRoleProvider {
 struct Inner {
     sts: aws_smithy_client::Client<DynConnector, DefaultMiddleware>,
     conf: aws_sdk_sts::Config,
-    op: aws_sdk_sts::input::AssumeRoleInput,
+    op: assume_role_input::Builder,
+    mfa_serial: Option<String>,
+    mfa_token_code_provider: Option<SharedMfaTokenCodeProvider>,
+    file_cache: Option<RoleFileCache>,
+}
+
+/// File cache of the assumed credentials, keyed by the settings of the role session and the
+/// identity of the source credentials
+#[derive(Debug)]
+struct RoleFileCache {
+    cache: FileCache,
+    settings: Vec<(&'static str, String)>,
+    source: SharedCredentialsProvider,
+    region: Option<Region>,
 }
 
 impl AssumeRoleProvider {
@@ -79,6 +95,12 @@ pub struct AssumeRoleProviderBuilder {
     session_length: Option<Duration>,
     policy: Option<String>,
     policy_arns: Option<Vec<PolicyDescriptorType>>,
+    tags: Option<Vec<Tag>>,
+    transitive_tag_keys: Option<Vec<String>>,
+    source_identity: Option<String>,
+    mfa_serial: Option<String>,
+    mfa_token_code_provider: Option<SharedMfaTokenCodeProvider>,
+    file_cache: bool,
 }
 
 impl AssumeRoleProviderBuilder {
@@ -99,6 +121,12 @@ impl AssumeRoleProviderBuilder {
             conf: None,
             policy: None,
             policy_arns: None,
+            tags: None,
+            transitive_tag_keys: None,
+            source_identity: None,
+            mfa_serial: None,
+            mfa_token_code_provider: None,
+            file_cache: false,
         }
     }
 
@@ -143,6 +171,65 @@ impl AssumeRoleProviderBuilder {
         self
     }
 
+    /// Set the session tags to pass to the role session.
+    ///
+    /// This parameter is optional.
+    /// For more information, see
+    /// [tags](aws_sdk_sts::input::assume_role_input::Builder::tags)
+    pub fn tags(mut self, tags: Vec<Tag>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+
+    /// Set the keys of the session tags that persist when this role session is used to assume
+    /// another role.
+    ///
+    /// This parameter is optional.
+    /// For more information, see
+    /// [transitive_tag_keys](aws_sdk_sts::input::assume_role_input::Builder::transitive_tag_keys)
+    pub fn transitive_tag_keys(mut self, keys: Vec<String>) -> Self {
+        self.transitive_tag_keys = Some(keys);
+        self
+    }
+
+    /// Set the source identity specified by the principal that is assuming the role.
+    ///
+    /// This parameter is optional.
+    /// For more information, see
+    /// [source_identity](aws_sdk_sts::input::assume_role_input::Builder::source_identity)
+    pub fn source_identity(mut self, source_identity: impl Into<String>) -> Self {
+        self.source_identity = Some(source_identity.into());
+        self
+    }
+
+    /// Set the serial number (or ARN) of the MFA device required to assume the role.
+    ///
+    /// When set, a token code is loaded from the [MFA token code provider](Self::mfa_token_code_provider)
+    /// every time the role is assumed.
+    pub fn mfa_serial(mut self, mfa_serial: impl Into<String>) -> Self {
+        self.mfa_serial = Some(mfa_serial.into());
+        self
+    }
+
+    /// Set the provider of MFA token codes used when an [MFA serial](Self::mfa_serial) is set.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use aws_config::sts::AssumeRoleProvider;
+    /// use aws_config::sts::mfa::provide_mfa_token_code_fn;
+    ///
+    /// let builder = AssumeRoleProvider::builder("arn:aws:iam::123456789012:role/demo")
+    ///     .mfa_serial("arn:aws:iam::123456789012:mfa/user")
+    ///     .mfa_token_code_provider(provide_mfa_token_code_fn(|_mfa_serial| async {
+    ///         // prompt for the current token code
+    ///         Ok("123456".to_string())
+    ///     }));
+    /// ```
+    pub fn mfa_token_code_provider(mut self, provider: impl ProvideMfaTokenCode + 'static) -> Self {
+        self.mfa_token_code_provider = Some(SharedMfaTokenCodeProvider::new(provider));
+        self
+    }
+
     /// Set the expiration time of the role session.
     ///
     /// When unset, this value defaults to 1 hour.
@@ -160,6 +247,22 @@ impl AssumeRoleProviderBuilder {
         self
     }
 
+    /// Cache the assumed role credentials in a file shared with other processes.
+    ///
+    /// Processes that assume the same role with the same settings reuse the cached credentials
+    /// until they expire instead of calling STS, e.g. successive invocations of a command line
+    /// tool. The access key ID of the source credentials is part of the cache key, so credentials
+    /// assumed by one identity are never used by another. The role session name is only part of
+    /// the cache key when it is set explicitly.
+    ///
+    /// See [`file_cache`](crate::meta::credentials::file_cache) for more information.
+    ///
+    /// Defaults to `false`.
+    pub fn file_cache(mut self, enabled: bool) -> Self {
+        self.file_cache = enabled;
+        self
+    }
+
     /// Set the region to assume the role in.
     ///
     /// This dictates which STS endpoint the AssumeRole action is invoked on.
@@ -190,9 +293,11 @@ impl AssumeRoleProviderBuilder {
 
     /// Build a credentials provider for this role authorized by the given `provider`.
     pub fn build(self, provider: impl Into<SharedCredentialsProvider>) -> AssumeRoleProvider {
+        let cache_settings = self.file_cache.then(|| self.cache_settings());
         let conf = self.conf.unwrap_or_default();
+        let provider = provider.into();
         let config = aws_sdk_sts::Config::builder()
-            .credentials_provider(provider.into())
+            .credentials_provider(provider.clone())
             .region(self.region.clone())
             .build();
 
@@ -216,31 +321,106 @@ impl AssumeRoleProviderBuilder {
             .set_policy(self.policy)
             .set_policy_arns(self.policy_arns)
             .set_duration_seconds(self.session_length.map(|dur| dur.as_secs() as i32))
-            .build()
-            .expect("operation is valid");
+            .set_tags(self.tags)
+            .set_transitive_tag_keys(self.transitive_tag_keys)
+            .set_source_identity(self.source_identity);
 
         let inner = Inner {
             sts: client,
             conf: config,
             op: operation,
+            mfa_serial: self.mfa_serial,
+            mfa_token_code_provider: self.mfa_token_code_provider,
+            file_cache: cache_settings.map(|settings| RoleFileCache {
+                cache: FileCache::new(&conf),
+                settings,
+                source: provider,
+                region: self.region,
+            }),
         };
-        let cache = LazyCachingCredentialsProvider::builder()
-            .configure(&conf)
-            .load(inner)
-            .build();
-        AssumeRoleProvider { cache }
+        AssumeRoleProvider {
+            cache: LazyCachingCredentialsProvider::builder()
+                .configure(&conf)
+                .load(inner)
+                .build(),
+        }
+    }
+
+    /// Settings of the role session identifying the credentials in the file cache
+    fn cache_settings(&self) -> Vec<(&'static str, String)> {
+        let mut settings = vec![("role_arn", self.role_arn.clone())];
+        let mut push = |name, value: Option<&str>| {
+            if let Some(value) = value {
+                settings.push((name, value.to_string()));
+            }
+        };
+        push("external_id", self.external_id.as_deref());
+        push("session_name", self.session_name.as_deref());
+        push("policy", self.policy.as_deref());
+        for policy_arn in self.policy_arns.iter().flatten() {
+            push("policy_arn", policy_arn.arn());
+        }
+        for tag in self.tags.iter().flatten() {
+            push("tag_key", tag.key());
+            push("tag_value", tag.value());
+        }
+        for key in self.transitive_tag_keys.iter().flatten() {
+            push("transitive_tag_key", Some(key));
+        }
+        push("source_identity", self.source_identity.as_deref());
+        push("mfa_serial", self.mfa_serial.as_deref());
+        push("region", self.region.as_ref().map(|region| region.as_ref()));
+        if let Some(length) = self.session_length {
+            settings.push(("duration_seconds", length.as_secs().to_string()));
+        }
+        settings
     }
 }
 
 impl Inner {
     async fn credentials(&self) -> credentials::Result {
+        let file_cache = match &self.file_cache {
+            Some(file_cache) => file_cache,
+            None => return self.assume_role(&self.conf).await,
+        };
+        // The source credentials are loaded first since their identity is part of the cache key.
+        // They are then used to call STS so that the source provider is only called once.
+        let source = file_cache.source.provide_credentials().await?;
+        let cache_key = file_cache::cache_key(
+            file_cache
+                .settings
+                .iter()
+                .map(|(name, value)| (*name, value.as_str()))
+                .chain([("source_access_key_id", source.access_key_id())]),
+        );
+        if let Some(credentials) = file_cache.cache.load(&cache_key).await {
+            tracing::debug!("loaded assumed credentials from the file cache");
+            return Ok(credentials);
+        }
+        let conf = aws_sdk_sts::Config::builder()
+            .credentials_provider(SharedCredentialsProvider::new(source))
+            .region(file_cache.region.clone())
+            .build();
+        let credentials = self.assume_role(&conf).await?;
+        file_cache.cache.store(&cache_key, &credentials).await;
+        Ok(credentials)
+    }
+
+    async fn assume_role(&self, conf: &aws_sdk_sts::Config) -> credentials::Result {
         tracing::info!("assuming role");
 
+        let mut op = self.op.clone();
+        if let Some(mfa_serial) = &self.mfa_serial {
+            let token_code =
+                mfa::load_token_code(mfa_serial, self.mfa_token_code_provider.as_ref()).await?;
+            op = op.serial_number(mfa_serial).token_code(token_code);
+        }
+
         tracing::debug!("retrieving assumed credentials");
-        let op = self
-            .op
-            .clone()
-            .make_operation(&self.conf)
+        let op = op
+            .build()
+            .expect("operation is valid")
+            .make_operation(conf)
             .await
             .expect("valid operation");
 
@@ -298,13 +478,16 @@ impl ProvideCredentials for AssumeRoleProvider {
 #[cfg(test)]
 mod test {
     use crate::provider_config::ProviderConfig;
+    use crate::sts::mfa::provide_mfa_token_code_fn;
     use crate::sts::AssumeRoleProvider;
+    use crate::test_case::no_traffic_connector;
+    use aws_sdk_sts::model::Tag;
     use aws_smithy_async::rt::sleep::TokioSleep;
     use aws_smithy_client::erase::DynConnector;
     use aws_smithy_client::test_connection::capture_request;
     use aws_smithy_http::body::SdkBody;
-    use aws_types::credentials::{ProvideCredentials, SharedCredentialsProvider};
-    use aws_types::os_shim_internal::{ManualTimeSource, TimeSource};
+    use aws_types::credentials::{CredentialsError, ProvideCredentials, SharedCredentialsProvider};
+    use aws_types::os_shim_internal::{Env, Fs, ManualTimeSource, TimeSource};
     use aws_types::region::Region;
     use aws_types::Credentials;
     use std::time::{Duration, UNIX_EPOCH};
@@ -335,6 +518,81 @@ mod test {
         assert!(str_body.contains("1234567"), "{}", str_body);
     }
 
+    #[tokio::test]
+    async fn configures_mfa_tags_and_source_identity() {
+        let (server, request) = capture_request(None);
+        let provider_conf = ProviderConfig::empty()
+            .with_sleep(TokioSleep::new())
+            .with_time_source(TimeSource::manual(&ManualTimeSource::new(
+                UNIX_EPOCH + Duration::from_secs(1234567890 - 120),
+            )))
+            .with_http_connector(DynConnector::new(server));
+        let provider = AssumeRoleProvider::builder("myrole")
+            .configure(&provider_conf)
+            .region(Region::new("us-east-1"))
+            .mfa_serial("GAHT12345678")
+            .mfa_token_code_provider(provide_mfa_token_code_fn(|mfa_serial| {
+                assert_eq!("GAHT12345678", mfa_serial);
+                async { Ok("123456".to_string()) }
+            }))
+            .tags(vec![Tag::builder().key("Project").value("Blue").build()])
+            .transitive_tag_keys(vec!["Project".to_string()])
+            .source_identity("alice")
+            .build(SharedCredentialsProvider::new(Credentials::new(
+                "base",
+                "basesecret",
+                Some("token".to_string()),
+                None,
+                "inner",
+            )));
+        let _ = provider.provide_credentials().await;
+        let req = request.expect_request();
+        let str_body = std::str::from_utf8(req.body().bytes().unwrap()).unwrap();
+        for expected in [
+            "SerialNumber=GAHT12345678",
+            "TokenCode=123456",
+            "Tags.member.1.Key=Project",
+            "Tags.member.1.Value=Blue",
+            "TransitiveTagKeys.member.1=Project",
+            "SourceIdentity=alice",
+        ] {
+            assert!(
+                str_body.contains(expected),
+                "{} not in {}",
+                expected,
+                str_body
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn mfa_serial_without_token_code_provider_is_an_error() {
+        let provider = AssumeRoleProvider::builder("myrole")
+            .configure(
+                &ProviderConfig::empty()
+                    .with_sleep(TokioSleep::new())
+                    .with_http_connector(no_traffic_connector()),
+            )
+            .region(Region::new("us-east-1"))
+            .mfa_serial("GAHT12345678")
+            .build(SharedCredentialsProvider::new(Credentials::new(
+                "base",
+                "basesecret",
+                None,
+                None,
+                "inner",
+            )));
+        let err = provider
+            .provide_credentials()
+            .await
+            .expect_err("no token code provider");
+        assert!(
+            matches!(err, CredentialsError::InvalidConfiguration { .. }),
+            "{:?}",
+            err
+        );
+    }
+
     #[tokio::test]
     async fn provider_caches_credentials() {
         let resp = http::Response::new(SdkBody::from(
@@ -368,4 +626,55 @@ mod test {
             .expect("cached credentials should be returned");
         assert_eq!(creds_first, creds_second);
     }
+
+    #[tokio::test]
+    async fn file_cache_is_keyed_by_the_source_identity() {
+        let resp = || {
+            http::Response::new(SdkBody::from(
+                "<AssumeRoleResponse xmlns=\"https://sts.amazonaws.com/doc/2011-06-15/\">\n  <AssumeRoleResult>\n    <Credentials>\n      <AccessKeyId>ASIARCORRECT</AccessKeyId>\n      <SecretAccessKey>secretkeycorrect</SecretAccessKey>\n      <SessionToken>tokencorrect</SessionToken>\n      <Expiration>2009-02-13T23:31:30Z</Expiration>\n    </Credentials>\n  </AssumeRoleResult>\n</AssumeRoleResponse>\n",
+            ))
+        };
+        let provider_conf = ProviderConfig::empty()
+            .with_sleep(TokioSleep::new())
+            .with_env(Env::from_slice(&[("HOME", "/home/user")]))
+            .with_fs(Fs::from_slice(&[]))
+            .with_time_source(TimeSource::manual(&ManualTimeSource::new(
+                UNIX_EPOCH + Duration::from_secs(1234567890 - 3600),
+            )));
+        let provider = |conn: DynConnector, access_key_id: &str| {
+            AssumeRoleProvider::builder("myrole")
+                .configure(&provider_conf.clone().with_http_connector(conn))
+                .region(Region::new("us-east-1"))
+                .file_cache(true)
+                .build(SharedCredentialsProvider::new(Credentials::new(
+                    access_key_id,
+                    "basesecret",
+                    None,
+                    None,
+                    "inner",
+                )))
+        };
+
+        let (server, request) = capture_request(Some(resp()));
+        provider(DynConnector::new(server), "base")
+            .provide_credentials()
+            .await
+            .expect("assumed credentials");
+        request.expect_request();
+
+        // the same source identity, e.g. in another process, uses the cached credentials
+        let cached = provider(no_traffic_connector(), "base")
+            .provide_credentials()
+            .await
+            .expect("cached credentials");
+        assert_eq!("ASIARCORRECT", cached.access_key_id());
+
+        // another source identity assumes the role itself
+        let (server, request) = capture_request(Some(resp()));
+        provider(DynConnector::new(server), "other")
+            .provide_credentials()
+            .await
+            .expect("assumed credentials");
+        request.expect_request();
+    }
 }
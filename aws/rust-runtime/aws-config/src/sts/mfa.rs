@@ -0,0 +1,190 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Multi-factor authentication (MFA) token codes for roles that require MFA to be assumed.
+
+use aws_types::credentials::CredentialsError;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::sync::Arc;
+
+/// Result of loading an MFA token code
+pub type Result = std::result::Result<String, CredentialsError>;
+
+/// Future types for MFA token code providers
+pub mod future {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use aws_smithy_async::future::now_or_later::NowOrLater;
+
+    type BoxFuture<'a> = Pin<Box<dyn Future<Output = super::Result> + Send + 'a>>;
+
+    /// Future returned by [`ProvideMfaTokenCode`](super::ProvideMfaTokenCode)
+    ///
+    /// - When wrapping an already loaded token code, use [`ready`](ProvideMfaTokenCode::ready).
+    /// - When wrapping an asynchronously loaded token code, use [`new`](ProvideMfaTokenCode::new).
+    #[derive(Debug)]
+    pub struct ProvideMfaTokenCode<'a>(NowOrLater<super::Result, BoxFuture<'a>>);
+
+    impl<'a> ProvideMfaTokenCode<'a> {
+        /// A future that wraps the given future
+        pub fn new(future: impl Future<Output = super::Result> + Send + 'a) -> Self {
+            Self(NowOrLater::new(Box::pin(future)))
+        }
+
+        /// A future that resolves to a given token code
+        pub fn ready(token_code: super::Result) -> Self {
+            Self(NowOrLater::ready(token_code))
+        }
+    }
+
+    impl Future for ProvideMfaTokenCode<'_> {
+        type Output = super::Result;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            Pin::new(&mut self.0).poll(cx)
+        }
+    }
+}
+
+/// Provider of MFA token codes
+///
+/// A token code is requested every time a role configured with an MFA device is assumed, e.g. by
+/// prompting the user or reading it from a hardware token. Token codes are single use and must not
+/// be cached.
+pub trait ProvideMfaTokenCode: Send + Sync + Debug {
+    /// Load the current token code of the MFA device identified by `mfa_serial`
+    ///
+    /// `mfa_serial` is either the serial number of a hardware device (e.g. `GAHT12345678`) or the
+    /// ARN of a virtual device (e.g. `arn:aws:iam::123456789012:mfa/user`).
+    fn token_code<'a>(&'a self, mfa_serial: &'a str) -> future::ProvideMfaTokenCode<'a>;
+}
+
+/// MFA token code provider wrapper that may be shared
+///
+/// Newtype wrapper around [`ProvideMfaTokenCode`] that implements `Clone` using an internal `Arc`.
+#[derive(Clone, Debug)]
+pub struct SharedMfaTokenCodeProvider(Arc<dyn ProvideMfaTokenCode>);
+
+impl SharedMfaTokenCodeProvider {
+    /// Create a new `SharedMfaTokenCodeProvider` from a [`ProvideMfaTokenCode`]
+    pub fn new(provider: impl ProvideMfaTokenCode + 'static) -> Self {
+        Self(Arc::new(provider))
+    }
+}
+
+impl ProvideMfaTokenCode for SharedMfaTokenCodeProvider {
+    fn token_code<'a>(&'a self, mfa_serial: &'a str) -> future::ProvideMfaTokenCode<'a> {
+        self.0.token_code(mfa_serial)
+    }
+}
+
+/// A [`ProvideMfaTokenCode`] implemented by a closure.
+///
+/// See [`provide_mfa_token_code_fn`] for more details.
+#[derive(Copy, Clone)]
+pub struct ProvideMfaTokenCodeFn<T> {
+    f: T,
+}
+
+impl<T> Debug for ProvideMfaTokenCodeFn<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "ProvideMfaTokenCodeFn")
+    }
+}
+
+impl<T, F> ProvideMfaTokenCode for ProvideMfaTokenCodeFn<T>
+where
+    T: Fn(&str) -> F + Send + Sync,
+    F: Future<Output = Result> + Send + 'static,
+{
+    fn token_code<'a>(&'a self, mfa_serial: &'a str) -> future::ProvideMfaTokenCode<'a> {
+        future::ProvideMfaTokenCode::new((self.f)(mfa_serial))
+    }
+}
+
+/// Returns a new MFA token code provider built with the given closure.
+///
+/// The closure is called with the serial number (or ARN) of the MFA device every time a token
+/// code is required.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_config::sts::mfa::provide_mfa_token_code_fn;
+///
+/// async fn prompt(mfa_serial: String) -> String {
+///     todo!()
+/// }
+///
+/// provide_mfa_token_code_fn(|mfa_serial| {
+///     let mfa_serial = mfa_serial.to_string();
+///     async move { Ok(prompt(mfa_serial).await) }
+/// });
+/// ```
+pub fn provide_mfa_token_code_fn<T, F>(f: T) -> ProvideMfaTokenCodeFn<T>
+where
+    T: Fn(&str) -> F + Send + Sync,
+    F: Future<Output = Result> + Send + 'static,
+{
+    ProvideMfaTokenCodeFn { f }
+}
+
+/// Load a token code for `mfa_serial`, failing when no provider was configured
+pub(crate) async fn load_token_code(
+    mfa_serial: &str,
+    provider: Option<&SharedMfaTokenCodeProvider>,
+) -> Result {
+    let provider = provider.ok_or_else(|| {
+        CredentialsError::invalid_configuration(format!(
+            "assuming the role requires an MFA token code for `{}` but no MFA token code provider was configured",
+            mfa_serial
+        ))
+    })?;
+    provider.token_code(mfa_serial).await.map_err(|err| {
+        tracing::warn!(mfa_serial = %mfa_serial, "failed to load MFA token code");
+        err
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sts::mfa::{
+        load_token_code, provide_mfa_token_code_fn, ProvideMfaTokenCode, SharedMfaTokenCodeProvider,
+    };
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn shared_provider_is_send_sync() {
+        assert_send_sync::<SharedMfaTokenCodeProvider>();
+    }
+
+    #[tokio::test]
+    async fn closure_receives_mfa_serial() {
+        let provider = provide_mfa_token_code_fn(|mfa_serial| {
+            let code = format!("code-for-{}", mfa_serial);
+            async move { Ok(code) }
+        });
+        assert_eq!(
+            "code-for-GAHT12345678",
+            provider.token_code("GAHT12345678").await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_provider_is_a_configuration_error() {
+        let err = load_token_code("GAHT12345678", None)
+            .await
+            .expect_err("no provider was configured");
+        assert!(
+            format!("{}", err).contains("no MFA token code provider was configured"),
+            "{}",
+            err
+        );
+    }
+}
@@ -10,14 +10,13 @@ pub mod partition;
 pub use partition::Partition;
 #[doc(hidden)]
 pub use partition::PartitionResolver;
-use std::collections::HashMap;
 
 use aws_smithy_http::endpoint::Error as EndpointError;
 use aws_smithy_http::endpoint::{apply_endpoint, EndpointPrefix, ResolveEndpoint};
 use aws_smithy_http::middleware::MapRequest;
 use aws_smithy_http::operation::Request;
 use aws_smithy_types::endpoint::Endpoint as SmithyEndpoint;
-use aws_smithy_types::Document;
+use aws_smithy_types::{Document, DocumentMap};
 use aws_types::region::{Region, SigningRegion};
 use aws_types::SigningService;
 use http::header::HeaderName;
@@ -28,7 +27,9 @@ use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 use std::sync::Arc;
 
-pub use aws_types::endpoint::{AwsEndpoint, BoxError, CredentialScope, ResolveAwsEndpoint};
+pub use aws_types::endpoint::{
+    AwsEndpoint, BoxError, CredentialScope, EndpointVariant, ResolveAwsEndpoint,
+};
 
 #[doc(hidden)]
 pub struct Params {
@@ -42,14 +43,38 @@ impl Params {
 }
 
 #[doc(hidden)]
-pub struct EndpointShim(Arc<dyn ResolveAwsEndpoint>);
+pub struct EndpointShim {
+    resolver: Arc<dyn ResolveAwsEndpoint>,
+    endpoint_url: Option<String>,
+    variant: EndpointVariant,
+}
+
 impl EndpointShim {
     pub fn from_resolver(resolver: impl ResolveAwsEndpoint + 'static) -> Self {
-        Self(Arc::new(resolver))
+        Self::from_arc(Arc::new(resolver))
     }
 
     pub fn from_arc(arc: Arc<dyn ResolveAwsEndpoint>) -> Self {
-        Self(arc)
+        Self {
+            resolver: arc,
+            endpoint_url: None,
+            variant: EndpointVariant::default(),
+        }
+    }
+
+    /// Use `endpoint_url` instead of the URL returned by the resolver.
+    ///
+    /// The credential scope of the resolved endpoint is still used for signing. This is how
+    /// endpoint URLs configured with `AWS_ENDPOINT_URL` or in a profile are honored.
+    pub fn with_endpoint_url(mut self, endpoint_url: Option<String>) -> Self {
+        self.endpoint_url = endpoint_url;
+        self
+    }
+
+    /// Resolve the FIPS and/or dual-stack `variant` of the endpoint.
+    pub fn with_variant(mut self, variant: EndpointVariant) -> Self {
+        self.variant = variant;
+        self
     }
 }
 
@@ -58,25 +83,42 @@ impl ResolveEndpoint<Params> for EndpointShim {
         &self,
         params: &Params,
     ) -> Result<SmithyEndpoint, aws_smithy_http::endpoint::Error> {
-        let aws_endpoint = self
-            .0
-            .resolve_endpoint(
-                params
-                    .region
-                    .as_ref()
-                    .ok_or_else(|| EndpointError::message("no region in params"))?,
-            )
-            .map_err(|err| EndpointError::message("failure resolving endpoint").with_cause(err))?;
-        let uri = aws_endpoint.endpoint().uri();
+        let region = params
+            .region
+            .as_ref()
+            .ok_or_else(|| EndpointError::message("no region in params"))?;
+        let resolved = self.resolver.resolve_endpoint_variant(region, self.variant);
+        let (uri, credential_scope) = match &self.endpoint_url {
+            Some(endpoint_url) => {
+                let uri: Uri = endpoint_url.parse().map_err(|err| {
+                    EndpointError::message(format!("invalid endpoint URL `{}`", endpoint_url))
+                        .with_cause(err)
+                })?;
+                // The resolver only provides the signing scope here, so a failure is not fatal.
+                let credential_scope = resolved
+                    .map(|endpoint| endpoint.credential_scope().clone())
+                    .unwrap_or_default();
+                (uri, credential_scope)
+            }
+            None => {
+                let aws_endpoint = resolved.map_err(|err| {
+                    EndpointError::message("failure resolving endpoint").with_cause(err)
+                })?;
+                (
+                    aws_endpoint.endpoint().uri().clone(),
+                    aws_endpoint.credential_scope().clone(),
+                )
+            }
+        };
         let mut auth_scheme =
-            HashMap::from([("name".to_string(), Document::String("sigv4".into()))]);
-        if let Some(region) = aws_endpoint.credential_scope().region() {
+            DocumentMap::from([("name".to_string(), Document::String("sigv4".into()))]);
+        if let Some(region) = credential_scope.region() {
             auth_scheme.insert(
                 "signingRegion".to_string(),
                 region.as_ref().to_string().into(),
             );
         }
-        if let Some(service) = aws_endpoint.credential_scope().service() {
+        if let Some(service) = credential_scope.service() {
             auth_scheme.insert(
                 "signingName".to_string(),
                 service.as_ref().to_string().into(),
@@ -221,7 +263,7 @@ mod test {
     use aws_types::SigningService;
 
     use crate::partition::endpoint::{Metadata, Protocol, SignatureVersion};
-    use crate::{AwsEndpointStage, EndpointShim, Params};
+    use crate::{AwsEndpointStage, EndpointShim, EndpointVariant, Params, Partition};
 
     #[test]
     fn default_endpoint_updates_request() {
@@ -326,4 +368,91 @@ mod test {
             Some(&SigningService::from_static("qldb"))
         );
     }
+
+    #[test]
+    fn endpoint_url_overrides_resolved_uri() {
+        let provider = Arc::new(Metadata {
+            uri_template: "kinesis.{region}.amazonaws.com",
+            protocol: Protocol::Https,
+            credential_scope: CredentialScope::builder()
+                .service(SigningService::from_static("kinesis-override"))
+                .build(),
+            signature_versions: SignatureVersion::V4,
+        });
+        let req = http::Request::new(SdkBody::from(""));
+        let region = Region::new("us-east-1");
+        let mut req = operation::Request::new(req);
+        {
+            let mut props = req.properties_mut();
+            props.insert(region.clone());
+            props.insert(SigningService::from_static("kinesis"));
+            props.insert(
+                EndpointShim::from_arc(provider)
+                    .with_endpoint_url(Some("http://localhost:4566".to_string()))
+                    .resolve_endpoint(&Params::new(Some(region.clone()))),
+            );
+        };
+        let req = AwsEndpointStage.apply(req).expect("should succeed");
+        assert_eq!(
+            req.properties().get(),
+            Some(&SigningService::from_static("kinesis-override"))
+        );
+        let (req, _conf) = req.into_parts();
+        assert_eq!(req.uri(), &Uri::from_static("http://localhost:4566/"));
+    }
+
+    #[test]
+    fn invalid_endpoint_url_fails_resolution() {
+        let provider = Arc::new(Metadata {
+            uri_template: "kinesis.{region}.amazonaws.com",
+            protocol: Protocol::Https,
+            credential_scope: Default::default(),
+            signature_versions: SignatureVersion::V4,
+        });
+        let err = EndpointShim::from_arc(provider)
+            .with_endpoint_url(Some("not a url".to_string()))
+            .resolve_endpoint(&Params::new(Some(Region::new("us-east-1"))))
+            .expect_err("invalid URL");
+        assert!(
+            format!("{}", err).contains("invalid endpoint URL `not a url`"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn resolves_endpoint_variant() {
+        let provider = Arc::new(
+            Partition::builder()
+                .id("aws")
+                .region_regex(r#"^us-\w+-\d+$"#)
+                .default_endpoint(Metadata {
+                    uri_template: "kinesis.{region}.amazonaws.com",
+                    protocol: Protocol::Https,
+                    credential_scope: Default::default(),
+                    signature_versions: SignatureVersion::V4,
+                })
+                .default_variant(
+                    EndpointVariant::default().with_fips(true),
+                    Metadata {
+                        uri_template: "kinesis-fips.{region}.amazonaws.com",
+                        protocol: Protocol::Https,
+                        credential_scope: Default::default(),
+                        signature_versions: SignatureVersion::V4,
+                    },
+                )
+                .build()
+                .expect("valid partition"),
+        );
+        let params = Params::new(Some(Region::new("us-east-1")));
+        let shim = EndpointShim::from_arc(provider);
+        let endpoint = shim
+            .with_variant(EndpointVariant::default().with_fips(true))
+            .resolve_endpoint(&params)
+            .expect("valid endpoint");
+        assert_eq!(
+            endpoint.url(),
+            "https://kinesis-fips.us-east-1.amazonaws.com/"
+        );
+    }
 }
@@ -5,7 +5,7 @@
 
 pub mod endpoint;
 
-use aws_types::endpoint::{AwsEndpoint, BoxError, ResolveAwsEndpoint};
+use aws_types::endpoint::{AwsEndpoint, BoxError, EndpointVariant, ResolveAwsEndpoint};
 use aws_types::region::Region;
 use regex::Regex;
 use std::collections::HashMap;
@@ -48,16 +48,30 @@ impl ResolveAwsEndpoint for PartitionResolver {
             .unwrap_or(&self.base);
         matching_partition.resolve_endpoint(region)
     }
+
+    fn resolve_endpoint_variant(
+        &self,
+        region: &Region,
+        variant: EndpointVariant,
+    ) -> Result<AwsEndpoint, BoxError> {
+        let matching_partition = self
+            .partitions()
+            .find(|partition| partition.can_resolve(region))
+            .unwrap_or(&self.base);
+        matching_partition.resolve_endpoint_variant(region, variant)
+    }
 }
 
 #[derive(Debug)]
 pub struct Partition {
-    _id: &'static str,
+    id: &'static str,
     region_regex: Regex,
     partition_endpoint: Option<Region>,
     regionalized: Regionalized,
     default_endpoint: endpoint::Metadata,
     endpoints: HashMap<Region, endpoint::Metadata>,
+    default_variants: HashMap<EndpointVariant, endpoint::Metadata>,
+    variants: HashMap<(Region, EndpointVariant), endpoint::Metadata>,
 }
 
 #[derive(Default)]
@@ -68,6 +82,8 @@ pub struct Builder {
     regionalized: Option<Regionalized>,
     default_endpoint: Option<endpoint::Metadata>,
     endpoints: HashMap<Region, endpoint::Metadata>,
+    default_variants: HashMap<EndpointVariant, endpoint::Metadata>,
+    variants: HashMap<(Region, EndpointVariant), endpoint::Metadata>,
 }
 
 impl Builder {
@@ -106,6 +122,28 @@ impl Builder {
         self
     }
 
+    /// Set the endpoint used for `variant` in regions without a variant-specific endpoint
+    pub fn default_variant(
+        mut self,
+        variant: EndpointVariant,
+        endpoint: endpoint::Metadata,
+    ) -> Self {
+        self.default_variants.insert(variant, endpoint);
+        self
+    }
+
+    /// Set the endpoint used for `variant` in `region`
+    pub fn endpoint_variant(
+        mut self,
+        region: &'static str,
+        variant: EndpointVariant,
+        endpoint: endpoint::Metadata,
+    ) -> Self {
+        self.variants
+            .insert((Region::new(region), variant), endpoint);
+        self
+    }
+
     /// Construct a Partition from the builder
     ///
     /// Returns `None` if:
@@ -115,12 +153,14 @@ impl Builder {
         let default_endpoint = self.default_endpoint?;
         let endpoints = self.endpoints.into_iter().collect();
         Some(Partition {
-            _id: self.id?,
+            id: self.id?,
             region_regex: self.region_regex?,
             partition_endpoint: self.partition_endpoint,
             regionalized: self.regionalized.unwrap_or_default(),
             default_endpoint,
             endpoints,
+            default_variants: self.default_variants,
+            variants: self.variants,
         })
     }
 }
@@ -161,6 +201,34 @@ impl ResolveAwsEndpoint for Partition {
             .unwrap_or(&self.default_endpoint);
         endpoint_for_region.resolve_endpoint(region)
     }
+
+    fn resolve_endpoint_variant(
+        &self,
+        region: &Region,
+        variant: EndpointVariant,
+    ) -> Result<AwsEndpoint, BoxError> {
+        if variant.is_default() {
+            return self.resolve_endpoint(region);
+        }
+        let variant_for = |region: &Region| self.variants.get(&(region.clone(), variant));
+        if let Some(endpoint) = variant_for(region) {
+            return endpoint.resolve_endpoint(region);
+        }
+        let resolved_region = match self.regionalized {
+            Regionalized::NotRegionalized => self.partition_endpoint.as_ref(),
+            Regionalized::Regionalized => Some(region),
+        };
+        resolved_region
+            .and_then(variant_for)
+            .or_else(|| self.default_variants.get(&variant))
+            .ok_or_else(|| {
+                format!(
+                    "no {} endpoint is available in region `{}` (partition `{}`)",
+                    variant, region, self.id
+                )
+            })?
+            .resolve_endpoint(region)
+    }
 }
 
 #[cfg(test)]
@@ -171,6 +239,7 @@ mod test {
     use crate::partition::{endpoint, Partition};
     use crate::partition::{PartitionResolver, Regionalized};
     use crate::{CredentialScope, ResolveAwsEndpoint};
+    use aws_types::endpoint::EndpointVariant;
     use aws_types::region::{Region, SigningRegion};
     use aws_types::SigningService;
     use http::Uri;
@@ -387,4 +456,70 @@ mod test {
                 .as_ref()
         )
     }
+
+    fn variant_partition() -> Partition {
+        let metadata = |uri_template| Metadata {
+            uri_template,
+            protocol: Https,
+            credential_scope: CredentialScope::default(),
+            signature_versions: V4,
+        };
+        Partition::builder()
+            .id("part-id-4")
+            .region_regex(r#"^(us)-\w+-\d+$"#)
+            .default_endpoint(metadata("service.{region}.amazonaws.com"))
+            .default_variant(
+                EndpointVariant::default().with_fips(true),
+                metadata("service-fips.{region}.amazonaws.com"),
+            )
+            .endpoint_variant(
+                "us-west-2",
+                EndpointVariant::default().with_dual_stack(true),
+                metadata("service.us-west-2.api.aws"),
+            )
+            .build()
+            .expect("valid partition")
+    }
+
+    #[test]
+    fn resolves_endpoint_variants() {
+        let partition = variant_partition();
+        let fips = EndpointVariant::default().with_fips(true);
+        let dual_stack = EndpointVariant::default().with_dual_stack(true);
+        let uri = |region, variant| {
+            let endpoint = partition
+                .resolve_endpoint_variant(&Region::new(region), variant)
+                .expect("valid variant");
+            let mut uri = Uri::from_static("/");
+            endpoint.set_endpoint(&mut uri, None);
+            uri
+        };
+        assert_eq!(
+            uri("us-east-1", EndpointVariant::default()),
+            Uri::from_static("https://service.us-east-1.amazonaws.com")
+        );
+        assert_eq!(
+            uri("us-east-1", fips),
+            Uri::from_static("https://service-fips.us-east-1.amazonaws.com")
+        );
+        assert_eq!(
+            uri("us-west-2", dual_stack),
+            Uri::from_static("https://service.us-west-2.api.aws")
+        );
+    }
+
+    #[test]
+    fn missing_endpoint_variant_is_an_error() {
+        let resolver = PartitionResolver::new(variant_partition(), vec![]);
+        let err = resolver
+            .resolve_endpoint_variant(
+                &Region::new("us-east-1"),
+                EndpointVariant::default().with_dual_stack(true),
+            )
+            .expect_err("no dual-stack endpoint in us-east-1");
+        assert_eq!(
+            err.to_string(),
+            "no dual-stack endpoint is available in region `us-east-1` (partition `part-id-4`)"
+        );
+    }
 }
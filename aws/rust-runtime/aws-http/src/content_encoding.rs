@@ -8,12 +8,37 @@ use http::{HeaderMap, HeaderValue};
 use http_body::{Body, SizeHint};
 use pin_project_lite::pin_project;
 
+use std::fmt;
+use std::num::NonZeroUsize;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
 const CRLF: &str = "\r\n";
 const CHUNK_TERMINATOR: &str = "0\r\n";
 const TRAILER_SEPARATOR: &[u8] = b":";
+const CHUNK_SIGNATURE: &str = ";chunk-signature=";
+const TRAILER_SIGNATURE: &str = "x-amz-trailer-signature:";
+/// Length of a hex encoded SigV4 signature
+const SIGNATURE_LENGTH: usize = 64;
+/// Default size of the chunks of a signed `AwsChunkedBody`
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Error returned by a [`SignChunk`] implementation
+pub type SignChunkError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Signs the chunks and the trailers of an [`AwsChunkedBody`].
+///
+/// Each signature is chained from the previous one: implementations keep track of the last
+/// signature they returned, starting from the seed signature of the request.
+pub trait SignChunk: fmt::Debug + Send + Sync {
+    /// Signs the next chunk of the body and returns its hex encoded signature.
+    ///
+    /// The body ends with an empty chunk that is signed too.
+    fn sign_chunk(&mut self, chunk: &[u8]) -> Result<String, SignChunkError>;
+
+    /// Signs the trailers of the body, after its last chunk, and returns their hex encoded signature.
+    fn sign_trailers(&mut self, trailers: &HeaderMap) -> Result<String, SignChunkError>;
+}
 
 /// Content encoding header value constants
 pub mod header_value {
@@ -22,16 +47,31 @@ pub mod header_value {
 }
 
 /// Options used when constructing an [`AwsChunkedBody`][AwsChunkedBody].
-#[derive(Debug, Default)]
+#[derive(Debug)]
 #[non_exhaustive]
 pub struct AwsChunkedBodyOptions {
-    /// The total size of the stream. Because we only support unsigned encoding
-    /// this implies that there will only be a single chunk containing the
-    /// underlying payload.
+    /// The total size of the stream. Unsigned bodies are written as a single chunk
+    /// containing the underlying payload, while signed bodies are split into chunks of
+    /// `chunk_size` bytes.
     stream_length: u64,
     /// The length of each trailer sent within an `AwsChunkedBody`. Necessary in
     /// order to correctly calculate the total size of the body accurately.
     trailer_lengths: Vec<u64>,
+    /// The size of the chunks of a signed body. The last chunk may be smaller.
+    chunk_size: usize,
+    /// When set, every chunk and the trailers of the body are signed.
+    signer: Option<Box<dyn SignChunk>>,
+}
+
+impl Default for AwsChunkedBodyOptions {
+    fn default() -> Self {
+        Self {
+            stream_length: 0,
+            trailer_lengths: Vec::new(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            signer: None,
+        }
+    }
 }
 
 impl AwsChunkedBodyOptions {
@@ -40,9 +80,27 @@ impl AwsChunkedBodyOptions {
         Self {
             stream_length,
             trailer_lengths,
+            ..Default::default()
         }
     }
 
+    /// Sign every chunk of the body, and its trailers, with the given signer
+    ///
+    /// The request must be signed with a `STREAMING-AWS4-HMAC-SHA256-PAYLOAD` payload, or a
+    /// `STREAMING-AWS4-HMAC-SHA256-PAYLOAD-TRAILER` payload when the body has trailers.
+    pub fn with_signer(mut self, signer: impl SignChunk + 'static) -> Self {
+        self.signer = Some(Box::new(signer));
+        self
+    }
+
+    /// Set the size of the chunks of a signed body (defaults to 64 KiB)
+    ///
+    /// Every chunk but the last one has this size. S3 requires chunks of at least 8 KiB.
+    pub fn with_chunk_size(mut self, chunk_size: NonZeroUsize) -> Self {
+        self.chunk_size = chunk_size.get();
+        self
+    }
+
     fn total_trailer_length(&self) -> u64 {
         self.trailer_lengths.iter().sum::<u64>()
             // We need to account for a CRLF after each trailer name/value pair
@@ -65,6 +123,10 @@ enum AwsChunkedBodyState {
     /// all data is written out. Once there is no more data to write, transition into the
     /// `WritingTrailers` state.
     WritingChunk,
+    /// Write out the signed chunks of the inner body, each of them prefixed by its size and its
+    /// signature, and then the signed final chunk. Once there is no more data to write, transition
+    /// into the `WritingTrailers` state.
+    WritingSignedChunks,
     /// Write out all trailers associated with this `AwsChunkedBody` and then transition into the
     /// `Closed` state.
     WritingTrailers,
@@ -73,8 +135,10 @@ enum AwsChunkedBodyState {
 }
 
 pin_project! {
-    /// A request body compatible with `Content-Encoding: aws-chunked`. This implementation is only
-    /// capable of writing a single chunk and does not support signed chunks.
+    /// A request body compatible with `Content-Encoding: aws-chunked`. Unsigned bodies are written as
+    /// a single chunk. When a [`SignChunk`] signer is set in the options, the body is written as
+    /// chunks of a fixed size, each of them carrying its signature, and the trailers are followed
+    /// by their signature.
     ///
     /// Chunked-Body grammar is defined in [ABNF] as:
     ///
@@ -84,9 +148,10 @@ pin_project! {
     ///                   chunked-trailer
     ///                   CRLF
     ///
-    /// chunk           = chunk-size CRLF chunk-data CRLF
+    /// chunk           = chunk-size [ chunk-signature ] CRLF chunk-data CRLF
     /// chunk-size      = 1*HEXDIG
-    /// last-chunk      = 1*("0") CRLF
+    /// chunk-signature = ";chunk-signature=" 64HEXDIG
+    /// last-chunk      = 1*("0") [ chunk-signature ] CRLF
     /// chunked-trailer = *( entity-header CRLF )
     /// entity-header   = field-name ":" OWS field-value OWS
     /// ```
@@ -101,21 +166,32 @@ pin_project! {
         state: AwsChunkedBodyState,
         options: AwsChunkedBodyOptions,
         inner_body_bytes_read_so_far: usize,
+        buffer: BytesMut,
     }
 }
 
 impl<Inner> AwsChunkedBody<Inner> {
     /// Wrap the given body in an outer body compatible with `Content-Encoding: aws-chunked`
     pub fn new(body: Inner, options: AwsChunkedBodyOptions) -> Self {
+        let state = if options.signer.is_some() {
+            AwsChunkedBodyState::WritingSignedChunks
+        } else {
+            AwsChunkedBodyState::WritingChunkSize
+        };
         Self {
             inner: body,
-            state: AwsChunkedBodyState::WritingChunkSize,
+            state,
             options,
             inner_body_bytes_read_so_far: 0,
+            buffer: BytesMut::new(),
         }
     }
 
     fn encoded_length(&self) -> u64 {
+        if self.options.signer.is_some() {
+            return self.signed_encoded_length();
+        }
+
         let mut length = 0;
         if self.options.stream_length != 0 {
             length += get_unsigned_chunk_bytes_length(self.options.stream_length);
@@ -136,6 +212,70 @@ impl<Inner> AwsChunkedBody<Inner> {
     }
 }
 
+impl<Inner> AwsChunkedBody<Inner> {
+    fn signed_encoded_length(&self) -> u64 {
+        let chunk_size = self.options.chunk_size as u64;
+        let stream_length = self.options.stream_length;
+        let mut length = (stream_length / chunk_size) * get_signed_chunk_bytes_length(chunk_size);
+        let last_chunk_length = stream_length % chunk_size;
+        if last_chunk_length != 0 {
+            length += get_signed_chunk_bytes_length(last_chunk_length);
+        }
+
+        // Signed final chunk
+        length += get_signed_chunk_bytes_length(0);
+
+        // Trailers and their signature
+        if !self.options.trailer_lengths.is_empty() {
+            length += self.options.total_trailer_length();
+            length += (TRAILER_SIGNATURE.len() + SIGNATURE_LENGTH + CRLF.len()) as u64;
+        }
+
+        // Encoding terminator
+        length += CRLF.len() as u64;
+
+        length
+    }
+}
+
+fn get_signed_chunk_bytes_length(payload_length: u64) -> u64 {
+    let signed_chunk_size_length =
+        int_log16(payload_length).max(1) + (CHUNK_SIGNATURE.len() + SIGNATURE_LENGTH) as u64;
+    if payload_length == 0 {
+        // The final chunk has no data
+        signed_chunk_size_length + CRLF.len() as u64
+    } else {
+        signed_chunk_size_length + CRLF.len() as u64 + payload_length + CRLF.len() as u64
+    }
+}
+
+/// Signs `chunk` and writes it out, prefixed by its size and signature, into `output`.
+fn write_signed_chunk(
+    signer: &mut dyn SignChunk,
+    chunk: &[u8],
+    output: &mut BytesMut,
+) -> Result<(), SignChunkError> {
+    let signature = signer.sign_chunk(chunk)?;
+    check_signature_length(&signature)?;
+    output.extend_from_slice(
+        format!("{:X}{CHUNK_SIGNATURE}{signature}{CRLF}", chunk.len()).as_bytes(),
+    );
+    if !chunk.is_empty() {
+        output.extend_from_slice(chunk);
+        output.extend_from_slice(CRLF.as_bytes());
+    }
+    Ok(())
+}
+
+fn check_signature_length(signature: &str) -> Result<(), SignChunkError> {
+    if signature.len() != SIGNATURE_LENGTH {
+        return Err(Box::new(AwsChunkedBodyError::InvalidSignatureLength {
+            actual: signature.len(),
+        }));
+    }
+    Ok(())
+}
+
 fn get_unsigned_chunk_bytes_length(payload_length: u64) -> u64 {
     let hex_repr_len = int_log16(payload_length);
     hex_repr_len + CRLF.len() as u64 + payload_length + CRLF.len() as u64
@@ -253,6 +393,61 @@ where
                 Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(e))),
                 Poll::Pending => Poll::Pending,
             },
+            AwsChunkedBodyState::WritingSignedChunks => loop {
+                let chunk_size = this.options.chunk_size;
+                let signer = this
+                    .options
+                    .signer
+                    .as_deref_mut()
+                    .expect("signed bodies have a signer");
+                if this.buffer.len() >= chunk_size {
+                    let chunk = this.buffer.split_to(chunk_size);
+                    let mut output = BytesMut::with_capacity(chunk_size + 128);
+                    tracing::trace!(len = chunk.len(), "writing signed chunk");
+                    return match write_signed_chunk(signer, &chunk, &mut output) {
+                        Ok(_) => Poll::Ready(Some(Ok(output.freeze()))),
+                        Err(err) => Poll::Ready(Some(Err(err))),
+                    };
+                }
+
+                match this.inner.as_mut().poll_data(cx) {
+                    Poll::Ready(Some(Ok(data))) => {
+                        *this.inner_body_bytes_read_so_far += data.len();
+                        this.buffer.extend_from_slice(&data);
+                    }
+                    Poll::Ready(None) => {
+                        let actual_stream_length = *this.inner_body_bytes_read_so_far as u64;
+                        let expected_stream_length = this.options.stream_length;
+                        if actual_stream_length != expected_stream_length {
+                            let err = Box::new(AwsChunkedBodyError::StreamLengthMismatch {
+                                actual: actual_stream_length,
+                                expected: expected_stream_length,
+                            });
+                            return Poll::Ready(Some(Err(err)));
+                        };
+
+                        tracing::trace!(
+                            len = this.buffer.len(),
+                            "no more chunk data, writing last signed chunks"
+                        );
+                        *this.state = AwsChunkedBodyState::WritingTrailers;
+                        let remaining = this.buffer.split();
+                        let mut output = BytesMut::with_capacity(remaining.len() + 256);
+                        let result = if remaining.is_empty() {
+                            Ok(())
+                        } else {
+                            write_signed_chunk(signer, &remaining, &mut output)
+                        }
+                        .and_then(|_| write_signed_chunk(signer, &[], &mut output));
+                        return match result {
+                            Ok(_) => Poll::Ready(Some(Ok(output.freeze()))),
+                            Err(err) => Poll::Ready(Some(Err(err))),
+                        };
+                    }
+                    Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                    Poll::Pending => return Poll::Pending,
+                }
+            },
             AwsChunkedBodyState::WritingTrailers => {
                 return match this.inner.poll_trailers(cx) {
                     Poll::Ready(Ok(trailers)) => {
@@ -269,8 +464,27 @@ where
                             return Poll::Ready(Some(Err(err)));
                         }
 
+                        let trailer_signature = match this.options.signer.as_deref_mut() {
+                            Some(signer) if !this.options.trailer_lengths.is_empty() => {
+                                match signer
+                                    .sign_trailers(trailers.as_ref().unwrap_or(&HeaderMap::new()))
+                                    .and_then(|signature| {
+                                        check_signature_length(&signature).map(|_| signature)
+                                    }) {
+                                    Ok(signature) => Some(signature),
+                                    Err(err) => return Poll::Ready(Some(Err(err))),
+                                }
+                            }
+                            _ => None,
+                        };
+
                         let mut trailers =
                             trailers_as_aws_chunked_bytes(trailers, actual_length + 1);
+                        if let Some(signature) = trailer_signature {
+                            trailers.extend_from_slice(TRAILER_SIGNATURE.as_bytes());
+                            trailers.extend_from_slice(signature.as_bytes());
+                            trailers.extend_from_slice(CRLF.as_bytes());
+                        }
                         // Insert the final CRLF to close the body
                         trailers.extend_from_slice(CRLF.as_bytes());
 
@@ -313,6 +527,10 @@ enum AwsChunkedBodyError {
     /// equal to the actual length of the body returned by the inner `http_body::Body` implementor.
     /// `stream_length` must be correct in order to set an accurate content length header.
     StreamLengthMismatch { actual: u64, expected: u64 },
+    /// Error that occurs when a [`SignChunk`] implementor returns a signature that isn't a hex
+    /// encoded SigV4 signature. Signatures must have a fixed length in order to calculate the
+    /// total size of the body.
+    InvalidSignatureLength { actual: usize },
 }
 
 impl std::fmt::Display for AwsChunkedBodyError {
@@ -324,6 +542,9 @@ impl std::fmt::Display for AwsChunkedBodyError {
             Self::StreamLengthMismatch { actual, expected } => {
                 write!(f, "When creating this AwsChunkedBody, stream length was reported as {expected}. However, when double checking during body encoding, length was found to be {actual} instead.")
             }
+            Self::InvalidSignatureLength { actual } => {
+                write!(f, "Chunk signatures must be {SIGNATURE_LENGTH} characters long, but a signature of {actual} characters was returned.")
+            }
         }
     }
 }
@@ -351,7 +572,7 @@ where
 mod tests {
     use super::{
         total_rendered_length_of_trailers, trailers_as_aws_chunked_bytes, AwsChunkedBody,
-        AwsChunkedBodyOptions, CHUNK_TERMINATOR, CRLF,
+        AwsChunkedBodyOptions, SignChunk, SignChunkError, CHUNK_TERMINATOR, CRLF,
     };
 
     use aws_smithy_http::body::SdkBody;
@@ -362,6 +583,7 @@ mod tests {
     use pin_project_lite::pin_project;
 
     use std::io::Read;
+    use std::num::NonZeroUsize;
     use std::pin::Pin;
     use std::task::{Context, Poll};
     use std::time::Duration;
@@ -429,6 +651,84 @@ mod tests {
         }
     }
 
+    /// Returns the chunks it signs, and the trailers, as signatures
+    #[derive(Debug, Default)]
+    struct TestSigner {
+        signed: usize,
+    }
+
+    impl TestSigner {
+        fn signature(&mut self, signed: String) -> String {
+            self.signed += 1;
+            test_signature(self.signed, &signed)
+        }
+    }
+
+    fn test_signature(n: usize, signed: &str) -> String {
+        format!("{:-<64}", format!("{}:{}", n, signed))
+    }
+
+    impl SignChunk for TestSigner {
+        fn sign_chunk(&mut self, chunk: &[u8]) -> Result<String, SignChunkError> {
+            Ok(self.signature(String::from_utf8(chunk.to_vec()).unwrap()))
+        }
+
+        fn sign_trailers(&mut self, trailers: &HeaderMap) -> Result<String, SignChunkError> {
+            Ok(self.signature(trailers.keys().map(|k| k.as_str()).collect()))
+        }
+    }
+
+    pin_project! {
+        struct BodyWithTrailers {
+            data: Option<Bytes>,
+            trailers: Option<HeaderMap>,
+        }
+    }
+
+    impl Body for BodyWithTrailers {
+        type Data = Bytes;
+        type Error = aws_smithy_http::body::Error;
+
+        fn poll_data(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+            Poll::Ready(self.project().data.take().map(Ok))
+        }
+
+        fn poll_trailers(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<HeaderMap<HeaderValue>>, Self::Error>> {
+            Poll::Ready(Ok(self.project().trailers.take()))
+        }
+
+        fn is_end_stream(&self) -> bool {
+            false
+        }
+
+        fn size_hint(&self) -> SizeHint {
+            SizeHint::new()
+        }
+    }
+
+    async fn read_to_string<B>(mut body: B) -> String
+    where
+        B: Body<Data = Bytes> + Unpin,
+        B::Error: std::fmt::Debug,
+    {
+        let mut output = SegmentedBuf::new();
+        while let Some(buf) = body.data().await {
+            output.push(buf.unwrap());
+        }
+        let mut actual_output = String::new();
+        output
+            .reader()
+            .read_to_string(&mut actual_output)
+            .expect("Doesn't cause IO errors");
+        actual_output
+    }
+
     #[tokio::test]
     async fn test_aws_chunked_encoding() {
         let test_fut = async {
@@ -610,4 +910,103 @@ mod tests {
 
         assert_eq!(expected_length, actual_length);
     }
+
+    #[tokio::test]
+    async fn test_signed_aws_chunked_encoding_sputtering_body() {
+        let input = SputteringBody {
+            parts: vec![
+                Some(Bytes::from_static(b"chunk 1, ")),
+                None,
+                Some(Bytes::from_static(b"chunk 2, ")),
+                Some(Bytes::from_static(b"chunk 3")),
+            ],
+            cursor: 0,
+            delay_in_millis: 10,
+        };
+        let opts = AwsChunkedBodyOptions::new(input.len() as u64, Vec::new())
+            .with_chunk_size(NonZeroUsize::new(10).unwrap())
+            .with_signer(TestSigner::default());
+        let body = AwsChunkedBody::new(input, opts);
+        let size_hint = body.size_hint().exact().unwrap();
+
+        let actual_output = read_to_string(body).await;
+        let expected_output = format!(
+            "A;chunk-signature={}\r\nchunk 1, c\r\n\
+            A;chunk-signature={}\r\nhunk 2, ch\r\n\
+            5;chunk-signature={}\r\nunk 3\r\n\
+            0;chunk-signature={}\r\n\r\n",
+            test_signature(1, "chunk 1, c"),
+            test_signature(2, "hunk 2, ch"),
+            test_signature(3, "unk 3"),
+            test_signature(4, ""),
+        );
+        assert_eq!(expected_output, actual_output);
+        assert_eq!(size_hint, actual_output.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_signed_aws_chunked_encoding_with_trailers() {
+        let mut trailers = HeaderMap::new();
+        trailers.insert("x-amz-checksum-crc32", HeaderValue::from_static("AAAAAA=="));
+        let trailer_len = "x-amz-checksum-crc32:AAAAAA==".len() as u64;
+        let input = BodyWithTrailers {
+            data: Some(Bytes::from_static(b"Hello world")),
+            trailers: Some(trailers),
+        };
+        let opts =
+            AwsChunkedBodyOptions::new(11, vec![trailer_len]).with_signer(TestSigner::default());
+        let body = AwsChunkedBody::new(input, opts);
+        let size_hint = body.size_hint().exact().unwrap();
+
+        let actual_output = read_to_string(body).await;
+        let expected_output = format!(
+            "B;chunk-signature={}\r\nHello world\r\n\
+            0;chunk-signature={}\r\n\
+            x-amz-checksum-crc32:AAAAAA==\r\n\
+            x-amz-trailer-signature:{}\r\n\r\n",
+            test_signature(1, "Hello world"),
+            test_signature(2, ""),
+            test_signature(3, "x-amz-checksum-crc32"),
+        );
+        assert_eq!(expected_output, actual_output);
+        assert_eq!(size_hint, actual_output.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_signed_aws_chunked_encoding_empty_body() {
+        let opts = AwsChunkedBodyOptions::new(0, Vec::new()).with_signer(TestSigner::default());
+        let body = AwsChunkedBody::new(SdkBody::from(""), opts);
+        let size_hint = body.size_hint().exact().unwrap();
+
+        let actual_output = read_to_string(body).await;
+        let expected_output = format!("0;chunk-signature={}\r\n\r\n", test_signature(1, ""));
+        assert_eq!(expected_output, actual_output);
+        assert_eq!(size_hint, actual_output.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_signed_aws_chunked_encoding_invalid_signature() {
+        #[derive(Debug)]
+        struct ShortSigner;
+
+        impl SignChunk for ShortSigner {
+            fn sign_chunk(&mut self, _chunk: &[u8]) -> Result<String, SignChunkError> {
+                Ok("short".into())
+            }
+
+            fn sign_trailers(&mut self, _trailers: &HeaderMap) -> Result<String, SignChunkError> {
+                Ok("short".into())
+            }
+        }
+
+        let opts = AwsChunkedBodyOptions::new(11, Vec::new()).with_signer(ShortSigner);
+        let mut body = AwsChunkedBody::new(SdkBody::from("Hello world"), opts);
+        let err = body.data().await.unwrap().unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Chunk signatures must be 64 characters long"),
+            "{}",
+            err
+        );
+    }
 }
@@ -24,6 +24,9 @@ pub mod no_credentials;
 /// Support types required for adding presigning to an operation in a generated service.
 pub mod presigning;
 
+/// Presigned POST forms for browser based uploads to S3
+pub mod presigned_post;
+
 /// Special logic for handling S3's error responses.
 pub mod s3_errors;
 
@@ -106,6 +106,9 @@
 //! }
 //! ```
 
+#[cfg(feature = "sign-chunked")]
+pub mod chunked;
+
 #[cfg(feature = "sign-eventstream")]
 pub mod event_stream;
 
@@ -211,6 +211,43 @@ mod test {
         assert!(signature.is_some());
     }
 
+    #[cfg(feature = "sigv4a")]
+    #[test]
+    fn signs_with_sigv4a() {
+        use crate::signer::SigningAlgorithm;
+
+        let req = http::Request::builder()
+            .uri("https://test-service.test-region.amazonaws.com/")
+            .body(SdkBody::from(""))
+            .unwrap();
+        let mut config = OperationSigningConfig::default_config();
+        config.algorithm = SigningAlgorithm::SigV4a;
+        config.region_set = Some("*".into());
+        let req = operation::Request::new(req)
+            .augment(|req, properties| {
+                properties.insert(UNIX_EPOCH + Duration::new(1611160427, 0));
+                properties.insert(SigningService::from_static("kinesis"));
+                properties.insert(config);
+                properties.insert(Credentials::new("AKIAfoo", "bar", None, None, "test"));
+                properties.insert(SigningRegion::from(Region::new("us-east-1")));
+                Result::<_, Infallible>::Ok(req)
+            })
+            .expect("succeeds");
+
+        let signer = SigV4SigningStage::new(SigV4Signer::new());
+        let (req, _) = signer.apply(req).unwrap().into_parts();
+        assert_eq!("*", req.headers()["x-amz-region-set"]);
+        let auth_header = req.headers()[AUTHORIZATION].to_str().unwrap();
+        assert!(
+            auth_header.starts_with(
+                "AWS4-ECDSA-P256-SHA256 Credential=AKIAfoo/20210120/kinesis/aws4_request, \
+                SignedHeaders=host;x-amz-date;x-amz-region-set, Signature="
+            ),
+            "{}",
+            auth_header
+        );
+    }
+
     // check that the endpoint middleware followed by signing middleware produce the expected result
     #[test]
     fn endpoint_plus_signer() {
@@ -6,7 +6,7 @@
 use crate::middleware::Signature;
 use aws_sigv4::http_request::{
     sign, PayloadChecksumKind, PercentEncodingMode, SignableRequest, SignatureLocation,
-    SigningParams, SigningSettings,
+    SignatureVersion, SigningParams, SigningSettings,
 };
 use aws_smithy_http::body::SdkBody;
 use aws_types::region::SigningRegion;
@@ -22,8 +22,15 @@ const EXPIRATION_WARNING: &str = "Presigned request will expire before the given
     `expires_in` duration because the credentials used to sign it will expire first.";
 
 #[derive(Eq, PartialEq, Clone, Copy)]
+#[non_exhaustive]
 pub enum SigningAlgorithm {
     SigV4,
+    /// SigV4a, the asymmetric variant of SigV4 that signs requests for a set of regions
+    ///
+    /// The region set defaults to the signing region of the request, and can be overridden
+    /// with [`OperationSigningConfig::region_set`].
+    #[cfg(feature = "sigv4a")]
+    SigV4a,
 }
 
 #[derive(Eq, PartialEq, Clone, Copy)]
@@ -49,6 +56,9 @@ pub struct OperationSigningConfig {
     pub signing_options: SigningOptions,
     pub signing_requirements: SigningRequirements,
     pub expires_in: Option<Duration>,
+    /// The regions that a SigV4a signature is valid in, e.g. `*` for all regions
+    #[cfg(feature = "sigv4a")]
+    pub region_set: Option<String>,
 }
 
 impl OperationSigningConfig {
@@ -65,6 +75,8 @@ impl OperationSigningConfig {
             },
             signing_requirements: SigningRequirements::Required,
             expires_in: None,
+            #[cfg(feature = "sigv4a")]
+            region_set: None,
         }
     }
 }
@@ -145,14 +157,20 @@ impl SigV4Signer {
             HttpSignatureType::HttpRequestQueryParams => SignatureLocation::QueryParams,
         };
         settings.expires_in = operation_config.expires_in;
+        settings.signature_version = match operation_config.algorithm {
+            SigningAlgorithm::SigV4 => SignatureVersion::V4,
+            #[cfg(feature = "sigv4a")]
+            SigningAlgorithm::SigV4a => SignatureVersion::V4a,
+        };
         settings
     }
 
     fn signing_params<'a>(
-        settings: SigningSettings,
+        operation_config: &'a OperationSigningConfig,
         credentials: &'a Credentials,
         request_config: &'a RequestConfig<'a>,
     ) -> SigningParams<'a> {
+        let settings = Self::settings(operation_config);
         if let Some(expires_in) = settings.expires_in {
             if let Some(creds_expires_time) = credentials.expiry() {
                 let presigned_expires_time = request_config.request_ts + expires_in;
@@ -170,10 +188,12 @@ impl SigV4Signer {
             .time(request_config.request_ts)
             .settings(settings);
         builder.set_security_token(credentials.session_token());
+        #[cfg(feature = "sigv4a")]
+        builder.set_region_set(operation_config.region_set.as_deref());
         builder.build().expect("all required fields set")
     }
 
-    /// Sign a request using the SigV4 Protocol
+    /// Sign a request using the SigV4 Protocol, or SigV4a if it is the algorithm of the `operation_config`
     ///
     /// Although this function may be used, end users will not typically
     /// interact with this code. It is generally used via middleware in the request pipeline. See [`SigV4SigningStage`](crate::middleware::SigV4SigningStage).
@@ -184,8 +204,7 @@ impl SigV4Signer {
         credentials: &Credentials,
         request: &mut http::Request<SdkBody>,
     ) -> Result<Signature, SigningError> {
-        let settings = Self::settings(operation_config);
-        let signing_params = Self::signing_params(settings, credentials, request_config);
+        let signing_params = Self::signing_params(operation_config, credentials, request_config);
 
         let (signing_instructions, signature) = {
             // A body that is already in memory can be signed directly. A body that is not in memory
@@ -221,8 +240,7 @@ impl SigV4Signer {
 
 #[cfg(test)]
 mod tests {
-    use super::{RequestConfig, SigV4Signer, EXPIRATION_WARNING};
-    use aws_sigv4::http_request::SigningSettings;
+    use super::{OperationSigningConfig, RequestConfig, SigV4Signer, EXPIRATION_WARNING};
     use aws_types::region::SigningRegion;
     use aws_types::{Credentials, SigningService};
     use std::time::{Duration, SystemTime};
@@ -234,8 +252,8 @@ mod tests {
         let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
         let creds_expire_in = Duration::from_secs(100);
 
-        let mut settings = SigningSettings::default();
-        settings.expires_in = Some(creds_expire_in - Duration::from_secs(10));
+        let mut operation_config = OperationSigningConfig::default_config();
+        operation_config.expires_in = Some(creds_expire_in - Duration::from_secs(10));
 
         let credentials = Credentials::new(
             "test-access-key",
@@ -250,13 +268,11 @@ mod tests {
             service: &SigningService::from_static("test"),
             payload_override: None,
         };
-        SigV4Signer::signing_params(settings, &credentials, &request_config);
+        SigV4Signer::signing_params(&operation_config, &credentials, &request_config);
         assert!(!logs_contain(EXPIRATION_WARNING));
 
-        let mut settings = SigningSettings::default();
-        settings.expires_in = Some(creds_expire_in + Duration::from_secs(10));
-
-        SigV4Signer::signing_params(settings, &credentials, &request_config);
+        operation_config.expires_in = Some(creds_expire_in + Duration::from_secs(10));
+        SigV4Signer::signing_params(&operation_config, &credentials, &request_config);
         assert!(logs_contain(EXPIRATION_WARNING));
     }
 }
@@ -6,8 +6,9 @@
 // Some of the functions in this file are unused when disabling certain features
 #![allow(dead_code)]
 
+use std::convert::TryFrom;
 use std::time::SystemTime;
-use time::{OffsetDateTime, Time};
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time};
 
 /// Truncates the subseconds from the given `SystemTime` to zero.
 pub(crate) fn truncate_subsecs(time: SystemTime) -> SystemTime {
@@ -45,6 +46,37 @@ pub(crate) fn format_date_time(time: SystemTime) -> String {
     )
 }
 
+/// Parses a `YYYYMMDD'T'HHMMSS'Z'` formatted date time into a `SystemTime`.
+///
+/// Returns `None` if the given string isn't a valid date time in that format.
+pub(crate) fn parse_date_time(date_time: &str) -> Option<SystemTime> {
+    fn digits(value: &str) -> Option<u32> {
+        if value.bytes().all(|b| b.is_ascii_digit()) {
+            value.parse().ok()
+        } else {
+            None
+        }
+    }
+
+    let bytes = date_time.as_bytes();
+    if !date_time.is_ascii() || bytes.len() != 16 || bytes[8] != b'T' || bytes[15] != b'Z' {
+        return None;
+    }
+    let date = Date::from_calendar_date(
+        digits(&date_time[0..4])? as i32,
+        Month::try_from(digits(&date_time[4..6])? as u8).ok()?,
+        digits(&date_time[6..8])? as u8,
+    )
+    .ok()?;
+    let time = Time::from_hms(
+        digits(&date_time[9..11])? as u8,
+        digits(&date_time[11..13])? as u8,
+        digits(&date_time[13..15])? as u8,
+    )
+    .ok()?;
+    Some(PrimitiveDateTime::new(date, time).assume_utc().into())
+}
+
 /// Parse functions that are only needed for unit tests.
 #[cfg(test)]
 pub(crate) mod test_parsers {
@@ -129,6 +161,24 @@ mod tests {
         assert_eq!("20150830T123600Z", format_date_time(time));
     }
 
+    #[test]
+    fn parse_signing_date_time() {
+        assert_eq!(
+            parse_date_time("20150830T123600Z").ok(),
+            super::parse_date_time("20150830T123600Z")
+        );
+        for invalid in [
+            "20150830T123600",
+            "20150830 123600Z",
+            "20151330T123600Z",
+            "20150830T126000Z",
+            "2015083OT123600Z",
+            "+2015083T123600Z",
+        ] {
+            assert_eq!(None, super::parse_date_time(invalid), "{}", invalid);
+        }
+    }
+
     #[test]
     fn date_roundtrip() {
         let time = parse_date("20150830").unwrap();
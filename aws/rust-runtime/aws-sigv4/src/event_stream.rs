@@ -158,6 +158,8 @@ mod tests {
             secret_key: "fake secret key",
             security_token: None,
             region: "us-east-1",
+            #[cfg(feature = "sigv4a")]
+            region_set: None,
             service_name: "testservice",
             time: (UNIX_EPOCH + Duration::new(123_456_789_u64, 1234u32)),
             settings: (),
@@ -196,6 +198,8 @@ mod tests {
             secret_key: "fake secret key",
             security_token: None,
             region: "us-east-1",
+            #[cfg(feature = "sigv4a")]
+            region_set: None,
             service_name: "testservice",
             time: (UNIX_EPOCH + Duration::new(123_456_789_u64, 1234u32)),
             settings: (),
@@ -4,10 +4,12 @@
  */
 
 use super::query_writer::QueryWriter;
-use super::{Error, PayloadChecksumKind, SignableBody, SignatureLocation, SigningParams};
+use super::{
+    Error, PayloadChecksumKind, SignableBody, SignatureLocation, SignatureVersion, SigningParams,
+};
 use crate::date_time::{format_date, format_date_time};
 use crate::http_request::sign::SignableRequest;
-use crate::http_request::url_escape::percent_encode_path;
+use crate::http_request::url_escape::{percent_encode_path, percent_encode_query};
 use crate::http_request::PercentEncodingMode;
 use crate::sign::sha256_hex_string;
 use http::header::{HeaderName, HOST};
@@ -25,6 +27,8 @@ pub(crate) mod header {
     pub(crate) const X_AMZ_DATE: &str = "x-amz-date";
     pub(crate) const X_AMZ_SECURITY_TOKEN: &str = "x-amz-security-token";
     pub(crate) const X_AMZ_USER_AGENT: &str = "x-amz-user-agent";
+    #[cfg(feature = "sigv4a")]
+    pub(crate) const X_AMZ_REGION_SET: &str = "x-amz-region-set";
 }
 
 pub(crate) mod param {
@@ -35,18 +39,23 @@ pub(crate) mod param {
     pub(crate) const X_AMZ_SECURITY_TOKEN: &str = "X-Amz-Security-Token";
     pub(crate) const X_AMZ_SIGNED_HEADERS: &str = "X-Amz-SignedHeaders";
     pub(crate) const X_AMZ_SIGNATURE: &str = "X-Amz-Signature";
+    #[cfg(feature = "sigv4a")]
+    pub(crate) const X_AMZ_REGION_SET: &str = "X-Amz-Region-Set";
 }
 
 pub(crate) const HMAC_256: &str = "AWS4-HMAC-SHA256";
 
-const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+pub(super) const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
 const STREAMING_UNSIGNED_PAYLOAD_TRAILER: &str = "STREAMING-UNSIGNED-PAYLOAD-TRAILER";
+const STREAMING_SIGNED_PAYLOAD: &str = "STREAMING-AWS4-HMAC-SHA256-PAYLOAD";
+const STREAMING_SIGNED_PAYLOAD_TRAILER: &str = "STREAMING-AWS4-HMAC-SHA256-PAYLOAD-TRAILER";
 
 #[derive(Debug, PartialEq)]
 pub(super) struct HeaderValues<'a> {
     pub(super) content_sha256: Cow<'a, str>,
     pub(super) date_time: String,
     pub(super) security_token: Option<&'a str>,
+    pub(super) region_set: Option<&'a str>,
     pub(super) signed_headers: SignedHeaders,
 }
 
@@ -58,6 +67,7 @@ pub(super) struct QueryParamValues<'a> {
     pub(super) date_time: String,
     pub(super) expires: String,
     pub(super) security_token: Option<&'a str>,
+    pub(super) region_set: Option<&'a str>,
     pub(super) signed_headers: SignedHeaders,
 }
 
@@ -121,6 +131,9 @@ impl<'a> CanonicalRequest<'a> {
     ///   checksum. This is the same checksum used as the "payload_hash" in the canonical request
     /// - `settings.signature_location` determines where the signature will be placed in a request,
     ///   and also alters the kinds of signing values that go along with it in the request.
+    /// - If `settings.signature_version` is SigV4a, the `region_set` of the params, or its `region`
+    ///   if it has none, is signed as the region set of the request. The region is left out of the
+    ///   credential scope.
     pub(super) fn from<'b>(
         req: &'b SignableRequest<'b>,
         params: &'b SigningParams<'b>,
@@ -144,17 +157,21 @@ impl<'a> CanonicalRequest<'a> {
                 content_sha256: payload_hash,
                 date_time,
                 security_token: params.security_token,
+                region_set: Self::region_set(params),
                 signed_headers,
             }),
             SignatureLocation::QueryParams => SignatureValues::QueryParams(QueryParamValues {
-                algorithm: "AWS4-HMAC-SHA256",
+                algorithm: params.settings.signature_version.algorithm(),
                 content_sha256: payload_hash,
                 credential: format!(
-                    "{}/{}/{}/{}/aws4_request",
+                    "{}/{}",
                     params.access_key,
-                    format_date(params.time),
-                    params.region,
-                    params.service_name,
+                    SigningScope {
+                        time: params.time,
+                        region: params.region,
+                        service: params.service_name,
+                        signature_version: params.settings.signature_version,
+                    }
                 ),
                 date_time,
                 expires: params
@@ -164,6 +181,7 @@ impl<'a> CanonicalRequest<'a> {
                     .as_secs()
                     .to_string(),
                 security_token: params.security_token,
+                region_set: Self::region_set(params),
                 signed_headers,
             }),
         };
@@ -177,6 +195,66 @@ impl<'a> CanonicalRequest<'a> {
         Ok(creq)
     }
 
+    /// Reconstruct the CanonicalRequest that was signed to produce the signature of `req`.
+    ///
+    /// Unlike [`CanonicalRequest::from`], no signing values are added to the request: the signed
+    /// headers are the ones listed in the signature, and the query string is the one of the
+    /// request without the `X-Amz-Signature` parameter.
+    pub(super) fn from_signed<'b>(
+        req: &'b SignableRequest<'b>,
+        signed_headers: &[&str],
+        date_time: &str,
+        percent_encoding_mode: &PercentEncodingMode,
+    ) -> Result<CanonicalRequest<'b>, Error> {
+        let path = req.uri().path();
+        let path = match percent_encoding_mode {
+            PercentEncodingMode::Double => Cow::Owned(percent_encode_path(path)),
+            PercentEncodingMode::Single => Cow::Borrowed(path),
+        };
+
+        let mut canonical_headers = HeaderMap::with_capacity(req.headers().len());
+        for (name, value) in req.headers().iter() {
+            canonical_headers.append(
+                HeaderName::from_str(&name.as_str().to_lowercase())?,
+                normalize_header_value(value)?,
+            );
+        }
+        if !canonical_headers.contains_key(&HOST) {
+            if let Some(authority) = req.uri().authority() {
+                canonical_headers.insert(HOST, HeaderValue::try_from(authority.as_str())?);
+            }
+        }
+        let mut names = Vec::with_capacity(signed_headers.len());
+        for name in signed_headers {
+            let name = HeaderName::from_str(name)?;
+            if !canonical_headers.contains_key(&name) {
+                return Err(format!("signed header `{}` is missing from the request", name).into());
+            }
+            names.push(CanonicalHeaderName(name));
+        }
+
+        let content_sha256 = match canonical_headers.get(header::X_AMZ_CONTENT_SHA_256) {
+            Some(value) => Cow::Owned(value.to_str()?.to_string()),
+            None => Self::payload_hash(req.body()),
+        };
+        let params = form_urlencoded::parse(req.uri().query().unwrap_or_default().as_bytes())
+            .filter(|(name, _)| name != param::X_AMZ_SIGNATURE)
+            .collect();
+        Ok(CanonicalRequest {
+            method: req.method(),
+            path,
+            params: Self::canonical_query(req.uri(), params),
+            headers: canonical_headers,
+            values: SignatureValues::Headers(HeaderValues {
+                content_sha256,
+                date_time: date_time.to_string(),
+                security_token: None,
+                region_set: None,
+                signed_headers: SignedHeaders::new(names),
+            }),
+        })
+    }
+
     fn headers(
         req: &SignableRequest<'_>,
         params: &SigningParams<'_>,
@@ -215,10 +293,16 @@ impl<'a> CanonicalRequest<'a> {
                 let header = HeaderValue::from_str(payload_hash)?;
                 canonical_headers.insert(header::X_AMZ_CONTENT_SHA_256, header);
             }
+
+            #[cfg(feature = "sigv4a")]
+            if let Some(region_set) = Self::region_set(params) {
+                let header = HeaderValue::from_str(region_set)?;
+                canonical_headers.insert(header::X_AMZ_REGION_SET, header);
+            }
         }
 
         let mut signed_headers = Vec::with_capacity(canonical_headers.len());
-        for (name, _) in &canonical_headers {
+        for name in canonical_headers.keys() {
             if let Some(excluded_headers) = params.settings.excluded_headers.as_ref() {
                 if excluded_headers.contains(name) {
                     continue;
@@ -236,6 +320,15 @@ impl<'a> CanonicalRequest<'a> {
         Ok((signed_headers, canonical_headers))
     }
 
+    /// Returns the region set to sign the request for, if the request is signed with SigV4a
+    fn region_set<'b>(params: &SigningParams<'b>) -> Option<&'b str> {
+        match params.settings.signature_version {
+            SignatureVersion::V4 => None,
+            #[cfg(feature = "sigv4a")]
+            SignatureVersion::V4a => Some(params.region_set.unwrap_or(params.region)),
+        }
+    }
+
     fn payload_hash<'b>(body: &'b SignableBody<'b>) -> Cow<'b, str> {
         // Payload hash computation
         //
@@ -246,6 +339,8 @@ impl<'a> CanonicalRequest<'a> {
         // - use `UnsignedPayload`
         // - use `UnsignedPayload` for streaming requests
         // - use `StreamingUnsignedPayloadTrailer` for streaming requests with trailers
+        // - use `StreamingSignedPayload` for streaming requests with signed chunks
+        // - use `StreamingSignedPayloadTrailer` for streaming requests with signed chunks and trailers
         match body {
             SignableBody::Bytes(data) => Cow::Owned(sha256_hex_string(data)),
             SignableBody::Precomputed(digest) => Cow::Borrowed(digest.as_str()),
@@ -253,6 +348,10 @@ impl<'a> CanonicalRequest<'a> {
             SignableBody::StreamingUnsignedPayloadTrailer => {
                 Cow::Borrowed(STREAMING_UNSIGNED_PAYLOAD_TRAILER)
             }
+            SignableBody::StreamingSignedPayload => Cow::Borrowed(STREAMING_SIGNED_PAYLOAD),
+            SignableBody::StreamingSignedPayloadTrailer => {
+                Cow::Borrowed(STREAMING_SIGNED_PAYLOAD_TRAILER)
+            }
         }
     }
 
@@ -276,9 +375,20 @@ impl<'a> CanonicalRequest<'a> {
             if let Some(security_token) = values.security_token {
                 add_param(&mut params, param::X_AMZ_SECURITY_TOKEN, security_token);
             }
+            #[cfg(feature = "sigv4a")]
+            if let Some(region_set) = values.region_set {
+                add_param(&mut params, param::X_AMZ_REGION_SET, region_set);
+            }
         }
-        // Sort by param name, and then by param value
-        params.sort();
+        Self::canonical_query(uri, params)
+    }
+
+    fn canonical_query(uri: &Uri, mut params: Vec<(Cow<'_, str>, Cow<'_, str>)>) -> Option<String> {
+        // Sort by encoded param name, and then by encoded param value: names that are encoded,
+        // like non-ASCII names, don't sort in the same order as their encoding
+        params.sort_by_cached_key(|(key, value)| {
+            (percent_encode_query(key), percent_encode_query(value))
+        });
 
         let mut query = QueryWriter::new(uri);
         query.clear_params();
@@ -330,15 +440,23 @@ impl<'a> fmt::Display for CanonicalRequest<'a> {
         writeln!(f, "{}", self.params.as_deref().unwrap_or(""))?;
         // write out _all_ the headers
         for header in &self.values.signed_headers().headers {
-            // a missing header is a bug, so we should panic.
-            let value = &self.headers[&header.0];
             write!(f, "{}:", header.0.as_str())?;
-            writeln!(
-                f,
-                "{}",
-                std::str::from_utf8(value.as_bytes())
-                    .expect("SDK request header values are valid UTF-8")
-            )?;
+            // the values of a header sent several times are joined, in the order they were sent
+            let mut values = self.headers.get_all(&header.0).iter().peekable();
+            // a missing header is a bug, so we should panic.
+            assert!(values.peek().is_some(), "signed header is missing");
+            while let Some(value) = values.next() {
+                write!(
+                    f,
+                    "{}",
+                    std::str::from_utf8(value.as_bytes())
+                        .expect("SDK request header values are valid UTF-8")
+                )?;
+                if values.peek().is_some() {
+                    write!(f, ",")?;
+                }
+            }
+            writeln!(f)?;
         }
         writeln!(f)?;
         // write out the signed headers
@@ -436,17 +554,28 @@ pub(super) struct SigningScope<'a> {
     pub(super) time: SystemTime,
     pub(super) region: &'a str,
     pub(super) service: &'a str,
+    pub(super) signature_version: SignatureVersion,
 }
 
 impl<'a> fmt::Display for SigningScope<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}/{}/{}/aws4_request",
-            format_date(self.time),
-            self.region,
-            self.service
-        )
+        match self.signature_version {
+            SignatureVersion::V4 => write!(
+                f,
+                "{}/{}/{}/aws4_request",
+                format_date(self.time),
+                self.region,
+                self.service
+            ),
+            // SigV4a signatures are scoped to the region set sent along with the signature
+            #[cfg(feature = "sigv4a")]
+            SignatureVersion::V4a => write!(
+                f,
+                "{}/{}/aws4_request",
+                format_date(self.time),
+                self.service
+            ),
+        }
     }
 }
 
@@ -465,11 +594,13 @@ impl<'a> StringToSign<'a> {
         region: &'a str,
         service: &'a str,
         hashed_creq: &'a str,
+        signature_version: SignatureVersion,
     ) -> Self {
         let scope = SigningScope {
             time,
             region,
             service,
+            signature_version,
         };
         Self {
             scope,
@@ -486,7 +617,7 @@ impl<'a> fmt::Display for StringToSign<'a> {
         write!(
             f,
             "{}\n{}\n{}\n{}",
-            HMAC_256,
+            self.scope.signature_version.algorithm(),
             format_date_time(self.time),
             self.scope,
             self.hashed_creq
@@ -505,7 +636,7 @@ mod tests {
     use crate::http_request::{
         PayloadChecksumKind, SignableBody, SignableRequest, SigningSettings,
     };
-    use crate::http_request::{SignatureLocation, SigningParams};
+    use crate::http_request::{SignatureLocation, SignatureVersion, SigningParams};
     use crate::sign::sha256_hex_string;
     use http::Uri;
     use http::{header::HeaderName, HeaderValue};
@@ -519,6 +650,8 @@ mod tests {
             secret_key: "test-secret-key",
             security_token: None,
             region: "test-region",
+            #[cfg(feature = "sigv4a")]
+            region_set: None,
             service_name: "testservicename",
             time: parse_date_time("20210511T154045Z").unwrap(),
             settings,
@@ -596,6 +729,7 @@ mod tests {
             time: parse_date_time("20150830T123600Z").unwrap(),
             region: "us-east-1",
             service: "iam",
+            signature_version: SignatureVersion::V4,
         };
         assert_eq!(format!("{}\n", scope), expected);
     }
@@ -607,7 +741,8 @@ mod tests {
         let expected_sts = test_sts("get-vanilla-query-order-key-case");
         let encoded = sha256_hex_string(creq.as_bytes());
 
-        let actual = StringToSign::new(time, "us-east-1", "service", &encoded);
+        let actual =
+            StringToSign::new(time, "us-east-1", "service", &encoded, SignatureVersion::V4);
         assert_eq!(expected_sts, actual.to_string());
     }
 
@@ -631,6 +766,18 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_repeated_headers() {
+        for name in ["get-header-key-duplicate", "get-header-value-order"] {
+            let req = test_request(name);
+            let req = SignableRequest::from(&req);
+            let mut signing_params = signing_params(SigningSettings::default());
+            signing_params.time = parse_date_time("20150830T123600Z").unwrap();
+            let creq = CanonicalRequest::from(&req, &signing_params).unwrap();
+            assert_eq!(test_canonical_request(name), creq.to_string());
+        }
+    }
+
     #[test]
     fn test_double_url_encode() {
         let req = test_request("double-url-encode");
@@ -678,6 +825,18 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_query_params_are_sorted_by_their_encoding() {
+        let req = http::Request::builder()
+            .uri("https://example.amazonaws.com/?z=1&%E1%88%B4=bar&a%20b=2&a=3")
+            .body("")
+            .unwrap();
+        let req = SignableRequest::from(&req);
+        let signing_params = signing_params(SigningSettings::default());
+        let creq = CanonicalRequest::from(&req, &signing_params).unwrap();
+        assert_eq!("%E1%88%B4=bar&a=3&a%20b=2&z=1", creq.params.unwrap());
+    }
+
     // It should exclude user-agent and x-amz-user-agent headers from presigning
     #[test]
     fn presigning_header_exclusion() {
@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-//! Utilities to sign HTTP requests.
+//! Utilities to sign HTTP requests and to verify their signatures.
 //!
 //! # Example: Signing an HTTP request
 //!
@@ -42,15 +42,22 @@
 //!
 
 mod canonical_request;
+mod chunked;
 mod query_writer;
 mod settings;
 mod sign;
 mod url_escape;
+mod verify;
 
 #[cfg(test)]
 pub(crate) mod test;
 
+pub use chunked::ChunkSigner;
 pub use settings::{
-    PayloadChecksumKind, PercentEncodingMode, SignatureLocation, SigningParams, SigningSettings,
+    PayloadChecksumKind, PercentEncodingMode, SignatureLocation, SignatureVersion, SigningParams,
+    SigningSettings,
 };
 pub use sign::{sign, Error, SignableBody, SignableRequest};
+pub use verify::{
+    verify, SignatureMismatch, VerificationError, VerificationSettings, VerifiedSignature,
+};
@@ -29,6 +29,9 @@ pub struct SigningSettings {
 
     /// Headers that should be excluded from the signing process
     pub excluded_headers: Option<Vec<HeaderName>>,
+
+    /// The version of the signature algorithm
+    pub signature_version: SignatureVersion,
 }
 
 /// HTTP payload checksum type
@@ -72,6 +75,7 @@ impl Default for SigningSettings {
             signature_location: SignatureLocation::Headers,
             expires_in: None,
             excluded_headers: Some(EXCLUDED_HEADERS.to_vec()),
+            signature_version: SignatureVersion::V4,
         }
     }
 }
@@ -85,3 +89,29 @@ pub enum SignatureLocation {
     /// Place the signature in the request query parameters
     QueryParams,
 }
+
+/// The version of the signature algorithm used to sign HTTP requests
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SignatureVersion {
+    /// SigV4: an HMAC-SHA256 signature that is valid in the `region` of the [`SigningParams`]
+    V4,
+
+    /// SigV4a: an ECDSA P-256 signature that is valid in a set of regions
+    ///
+    /// The signature is valid in the region set of the [`SigningParams`]: a comma separated list
+    /// of regions, in which `*` matches any region, e.g. `us-east-1,us-west-*`. The region set
+    /// defaults to the `region`.
+    #[cfg(feature = "sigv4a")]
+    V4a,
+}
+
+impl SignatureVersion {
+    pub(crate) fn algorithm(self) -> &'static str {
+        match self {
+            SignatureVersion::V4 => crate::http_request::canonical_request::HMAC_256,
+            #[cfg(feature = "sigv4a")]
+            SignatureVersion::V4a => crate::sigv4a::ECDSA_256,
+        }
+    }
+}
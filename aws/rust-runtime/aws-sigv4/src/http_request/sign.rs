@@ -3,10 +3,10 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use super::{PayloadChecksumKind, SignatureLocation};
+use super::{PayloadChecksumKind, SignatureLocation, SignatureVersion};
 use crate::http_request::canonical_request::header;
 use crate::http_request::canonical_request::param;
-use crate::http_request::canonical_request::{CanonicalRequest, StringToSign, HMAC_256};
+use crate::http_request::canonical_request::{CanonicalRequest, StringToSign};
 use crate::http_request::query_writer::QueryWriter;
 use crate::http_request::SigningParams;
 use crate::sign::{calculate_signature, generate_signing_key, sha256_hex_string};
@@ -104,6 +104,14 @@ pub enum SignableBody<'a> {
 
     /// Set when a streaming body has checksum trailers.
     StreamingUnsignedPayloadTrailer,
+
+    /// Set when a streaming body is sent with `aws-chunked` content encoding, and each of its
+    /// chunks is signed with a [`ChunkSigner`](super::ChunkSigner).
+    StreamingSignedPayload,
+
+    /// Set when a streaming body is sent with `aws-chunked` content encoding, and each of its
+    /// chunks and its trailers are signed with a [`ChunkSigner`](super::ChunkSigner).
+    StreamingSignedPayloadTrailer,
 }
 
 #[derive(Debug)]
@@ -191,14 +199,9 @@ fn calculate_signing_params<'a>(
         params.region,
         params.service_name,
         encoded_creq,
+        params.settings.signature_version,
     );
-    let signing_key = generate_signing_key(
-        params.secret_key,
-        params.time,
-        params.region,
-        params.service_name,
-    );
-    let signature = calculate_signature(signing_key, sts.to_string().as_bytes());
+    let signature = calculate_string_to_sign_signature(params, &sts);
 
     let values = creq.values.into_query_params().expect("signing with query");
     let mut signing_params = vec![
@@ -218,9 +221,37 @@ fn calculate_signing_params<'a>(
             Cow::Owned(security_token.to_string()),
         ));
     }
+    #[cfg(feature = "sigv4a")]
+    if let Some(region_set) = values.region_set {
+        signing_params.push((param::X_AMZ_REGION_SET, Cow::Owned(region_set.to_string())));
+    }
     Ok((signing_params, signature))
 }
 
+/// Signs the string to sign with the signature version of the signing settings
+fn calculate_string_to_sign_signature(
+    params: &SigningParams<'_>,
+    sts: &StringToSign<'_>,
+) -> String {
+    match params.settings.signature_version {
+        SignatureVersion::V4 => {
+            let signing_key = generate_signing_key(
+                params.secret_key,
+                params.time,
+                params.region,
+                params.service_name,
+            );
+            calculate_signature(signing_key, sts.to_string().as_bytes())
+        }
+        #[cfg(feature = "sigv4a")]
+        SignatureVersion::V4a => {
+            let signing_key =
+                crate::sigv4a::generate_signing_key(params.access_key, params.secret_key);
+            crate::sigv4a::calculate_signature(&signing_key, sts.to_string().as_bytes())
+        }
+    }
+}
+
 /// Calculates the signature headers that need to get added to the given `request`.
 ///
 /// `request` MUST NOT contain any of the following headers:
@@ -242,16 +273,11 @@ fn calculate_signing_headers<'a>(
         params.region,
         params.service_name,
         encoded_creq,
+        params.settings.signature_version,
     );
 
     // Step 3: https://docs.aws.amazon.com/en_pv/general/latest/gr/sigv4-calculate-signature.html
-    let signing_key = generate_signing_key(
-        params.secret_key,
-        params.time,
-        params.region,
-        params.service_name,
-    );
-    let signature = calculate_signature(signing_key, sts.to_string().as_bytes());
+    let signature = calculate_string_to_sign_signature(params, &sts);
 
     // Step 4: https://docs.aws.amazon.com/en_pv/general/latest/gr/sigv4-add-signature-to-request.html
     let values = creq.values.as_headers().expect("signing with headers");
@@ -271,6 +297,10 @@ fn calculate_signing_headers<'a>(
     if let Some(security_token) = values.security_token {
         add_header(&mut headers, header::X_AMZ_SECURITY_TOKEN, security_token);
     }
+    #[cfg(feature = "sigv4a")]
+    if let Some(region_set) = values.region_set {
+        add_header(&mut headers, header::X_AMZ_REGION_SET, region_set);
+    }
     Ok(SigningOutput::new(headers, signature))
 }
 
@@ -288,7 +318,7 @@ fn build_authorization_header(
 ) -> HeaderValue {
     let mut value = HeaderValue::try_from(format!(
         "{} Credential={}/{}, SignedHeaders={}, Signature={}",
-        HMAC_256,
+        sts.scope.signature_version.algorithm(),
         access_key,
         sts.scope,
         creq.values.signed_headers().as_str(),
@@ -331,6 +361,8 @@ mod tests {
             secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
             security_token: None,
             region: "us-east-1",
+            #[cfg(feature = "sigv4a")]
+            region_set: None,
             service_name: "service",
             time: parse_date_time("20150830T123600Z").unwrap(),
             settings,
@@ -360,6 +392,8 @@ mod tests {
             secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
             security_token: None,
             region: "us-east-1",
+            #[cfg(feature = "sigv4a")]
+            region_set: None,
             service_name: "service",
             time: parse_date_time("20150830T123600Z").unwrap(),
             settings,
@@ -390,6 +424,8 @@ mod tests {
             secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
             security_token: None,
             region: "us-east-1",
+            #[cfg(feature = "sigv4a")]
+            region_set: None,
             service_name: "service",
             time: parse_date_time("20150830T123600Z").unwrap(),
             settings,
@@ -410,6 +446,181 @@ mod tests {
         assert_req_eq!(expected, signed);
     }
 
+    #[cfg(feature = "sigv4a")]
+    #[test]
+    fn test_sigv4a_test_suite() {
+        use crate::http_request::canonical_request::{CanonicalRequest, StringToSign};
+        use crate::http_request::test::v4a;
+        use crate::http_request::SignatureVersion;
+        use crate::sign::sha256_hex_string;
+        use p256::ecdsa::signature::Verifier;
+        use p256::ecdsa::Signature;
+
+        let cases = v4a::test_cases();
+        assert!(!cases.is_empty());
+        for case in cases {
+            let context = v4a::test_context(&case);
+            for location in [SignatureLocation::Headers, SignatureLocation::QueryParams] {
+                let settings = SigningSettings {
+                    signature_location: location,
+                    expires_in: Some(context.expires_in),
+                    signature_version: SignatureVersion::V4a,
+                    ..Default::default()
+                };
+                let params = SigningParams {
+                    access_key: &context.access_key_id,
+                    secret_key: &context.secret_access_key,
+                    security_token: None,
+                    region: &context.region,
+                    region_set: None,
+                    service_name: &context.service,
+                    time: parse_date_time(&context.timestamp).unwrap(),
+                    settings,
+                };
+
+                let request = v4a::test_request(&case);
+                let signable = SignableRequest::from(&request);
+                let creq = CanonicalRequest::from(&signable, &params).unwrap();
+                assert_eq!(
+                    v4a::test_canonical_request(&case, location),
+                    creq.to_string(),
+                    "canonical request of `{}` signed with {:?}",
+                    case,
+                    location
+                );
+                let sts = StringToSign::new(
+                    params.time,
+                    params.region,
+                    params.service_name,
+                    &sha256_hex_string(creq.to_string().as_bytes()),
+                    SignatureVersion::V4a,
+                )
+                .to_string();
+                assert_eq!(
+                    v4a::test_sts(&case, location),
+                    sts,
+                    "string to sign of `{}` signed with {:?}",
+                    case,
+                    location
+                );
+
+                let out = sign(signable, &params).unwrap();
+                let signature =
+                    Signature::from_der(&hex::decode(out.signature()).unwrap()).unwrap();
+                assert!(
+                    v4a::test_public_key(&case)
+                        .verify(sts.as_bytes(), &signature)
+                        .is_ok(),
+                    "signature of `{}` signed with {:?}",
+                    case,
+                    location
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "sigv4a")]
+    #[test]
+    fn test_sign_vanilla_sigv4a_with_headers() {
+        use crate::http_request::SignatureVersion;
+        use crate::sigv4a::tests::is_test_suite_signature;
+
+        let settings = SigningSettings {
+            signature_version: SignatureVersion::V4a,
+            ..Default::default()
+        };
+        let params = SigningParams {
+            access_key: "AKIDEXAMPLE",
+            secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            security_token: None,
+            region: "us-east-1",
+            region_set: None,
+            service_name: "service",
+            time: parse_date_time("20150830T123600Z").unwrap(),
+            settings,
+        };
+
+        let mut request = test_request("get-vanilla");
+        let out = sign(SignableRequest::from(&request), &params).unwrap();
+        let creq = "GET\n/\n\n\
+            host:example.amazonaws.com\n\
+            x-amz-date:20150830T123600Z\n\
+            x-amz-region-set:us-east-1\n\n\
+            host;x-amz-date;x-amz-region-set\n\
+            e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        let sts = format!(
+            "AWS4-ECDSA-P256-SHA256\n20150830T123600Z\n20150830/service/aws4_request\n{}",
+            crate::sign::sha256_hex_string(creq)
+        );
+        assert!(is_test_suite_signature(sts.as_bytes(), out.signature()));
+
+        let signature = out.signature().to_string();
+        out.output.apply_to_request(&mut request);
+        assert_eq!(
+            format!(
+                "AWS4-ECDSA-P256-SHA256 Credential=AKIDEXAMPLE/20150830/service/aws4_request, \
+                SignedHeaders=host;x-amz-date;x-amz-region-set, Signature={}",
+                signature
+            ),
+            request.headers()["authorization"]
+        );
+        assert_eq!("us-east-1", request.headers()["x-amz-region-set"]);
+    }
+
+    #[cfg(feature = "sigv4a")]
+    #[test]
+    fn test_sign_vanilla_sigv4a_with_query_params() {
+        use crate::http_request::SignatureVersion;
+        use crate::sigv4a::tests::is_test_suite_signature;
+
+        let settings = SigningSettings {
+            signature_location: SignatureLocation::QueryParams,
+            expires_in: Some(Duration::from_secs(35)),
+            signature_version: SignatureVersion::V4a,
+            ..Default::default()
+        };
+        let params = SigningParams {
+            access_key: "AKIDEXAMPLE",
+            secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            security_token: None,
+            region: "us-east-1",
+            region_set: Some("us-east-1,us-west-*"),
+            service_name: "service",
+            time: parse_date_time("20150830T123600Z").unwrap(),
+            settings,
+        };
+
+        let mut request = test_request("get-vanilla");
+        request.headers_mut().remove("x-amz-date");
+        let out = sign(SignableRequest::from(&request), &params).unwrap();
+        let creq = "GET\n/\n\
+            X-Amz-Algorithm=AWS4-ECDSA-P256-SHA256\
+            &X-Amz-Credential=AKIDEXAMPLE%2F20150830%2Fservice%2Faws4_request\
+            &X-Amz-Date=20150830T123600Z&X-Amz-Expires=35\
+            &X-Amz-Region-Set=us-east-1%2Cus-west-%2A&X-Amz-SignedHeaders=host\n\
+            host:example.amazonaws.com\n\n\
+            host\n\
+            e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        let sts = format!(
+            "AWS4-ECDSA-P256-SHA256\n20150830T123600Z\n20150830/service/aws4_request\n{}",
+            crate::sign::sha256_hex_string(creq)
+        );
+        assert!(is_test_suite_signature(sts.as_bytes(), out.signature()));
+
+        let signature = out.signature().to_string();
+        out.output.apply_to_request(&mut request);
+        assert_eq!(
+            format!(
+                "/?X-Amz-Algorithm=AWS4-ECDSA-P256-SHA256\
+                &X-Amz-Credential=AKIDEXAMPLE%2F20150830%2Fservice%2Faws4_request\
+                &X-Amz-Date=20150830T123600Z&X-Amz-Expires=35&X-Amz-SignedHeaders=host\
+                &X-Amz-Signature={}&X-Amz-Region-Set=us-east-1%2Cus-west-%2A",
+                signature
+            ),
+            request.uri().path_and_query().unwrap().as_str()
+        );
+    }
+
     #[test]
     fn test_sign_headers_utf8() {
         let settings = SigningSettings::default();
@@ -418,6 +629,8 @@ mod tests {
             secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
             security_token: None,
             region: "us-east-1",
+            #[cfg(feature = "sigv4a")]
+            region_set: None,
             service_name: "service",
             time: parse_date_time("20150830T123600Z").unwrap(),
             settings,
@@ -468,6 +681,8 @@ mod tests {
             secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
             security_token: None,
             region: "us-east-1",
+            #[cfg(feature = "sigv4a")]
+            region_set: None,
             service_name: "service",
             time: parse_date_time("20150830T123600Z").unwrap(),
             settings,
@@ -524,6 +739,8 @@ mod tests {
             secret_key: "asdf",
             security_token: None,
             region: "us-east-1",
+            #[cfg(feature = "sigv4a")]
+            region_set: None,
             service_name: "foo",
             time: std::time::SystemTime::now(),
             settings,
@@ -553,6 +770,8 @@ mod tests {
                 secret_key: "asdf",
                 security_token: None,
                 region: "us-east-1",
+                #[cfg(feature = "sigv4a")]
+                region_set: None,
                 service_name: "foo",
                 time: std::time::SystemTime::now(),
                 settings,
@@ -54,6 +54,97 @@ fn test_parsed_request(name: &str, ext: &str) -> Request<Bytes> {
     }
 }
 
+/// Test cases of the SigV4a test suite, in the layout of the aws-c-auth test suite
+#[cfg(feature = "sigv4a")]
+pub(crate) mod v4a {
+    use super::{parse_request, read};
+    use crate::http_request::SignatureLocation;
+    use bytes::Bytes;
+    use http::Request;
+    use p256::ecdsa::VerifyingKey;
+    use std::time::Duration;
+
+    const TEST_SUITE: &str = "aws-sig-v4a-test-suite";
+
+    fn path(name: &str, file: &str) -> String {
+        format!("{}/{}/{}", TEST_SUITE, name, file)
+    }
+
+    /// Returns the names of all the cases of the test suite
+    pub(crate) fn test_cases() -> Vec<String> {
+        let mut cases: Vec<String> = std::fs::read_dir(TEST_SUITE)
+            .expect("the test suite is readable")
+            .map(|entry| entry.unwrap())
+            .filter(|entry| entry.file_type().unwrap().is_dir())
+            .map(|entry| entry.file_name().into_string().unwrap())
+            .collect();
+        cases.sort();
+        cases
+    }
+
+    pub(crate) fn test_request(name: &str) -> Request<Bytes> {
+        let path = path(name, "request.txt");
+        match parse_request(read(&path).as_bytes()) {
+            Ok(parsed) => parsed,
+            Err(err) => panic!("Failed to parse {}: {}", path, err),
+        }
+    }
+
+    /// Signing parameters of a test case, read from its `context.json`
+    pub(crate) struct TestContext {
+        pub(crate) access_key_id: String,
+        pub(crate) secret_access_key: String,
+        pub(crate) region: String,
+        pub(crate) service: String,
+        /// Time of the signature, in the `20150830T123600Z` format
+        pub(crate) timestamp: String,
+        pub(crate) expires_in: Duration,
+    }
+
+    pub(crate) fn test_context(name: &str) -> TestContext {
+        let context: serde_json::Value =
+            serde_json::from_str(&read(&path(name, "context.json"))).unwrap();
+        let string = |value: &serde_json::Value| value.as_str().unwrap().to_string();
+        TestContext {
+            access_key_id: string(&context["credentials"]["access_key_id"]),
+            secret_access_key: string(&context["credentials"]["secret_access_key"]),
+            region: string(&context["region"]),
+            service: string(&context["service"]),
+            // e.g. `2015-08-30T12:36:00Z`
+            timestamp: string(&context["timestamp"]).replace(['-', ':'], ""),
+            expires_in: Duration::from_secs(context["expiration_in_seconds"].as_u64().unwrap()),
+        }
+    }
+
+    fn prefix(location: SignatureLocation) -> &'static str {
+        match location {
+            SignatureLocation::Headers => "header",
+            SignatureLocation::QueryParams => "query",
+        }
+    }
+
+    pub(crate) fn test_canonical_request(name: &str, location: SignatureLocation) -> String {
+        let file = format!("{}-canonical-request.txt", prefix(location));
+        read(&path(name, &file)).trim().to_string()
+    }
+
+    pub(crate) fn test_sts(name: &str, location: SignatureLocation) -> String {
+        let file = format!("{}-string-to-sign.txt", prefix(location));
+        read(&path(name, &file)).trim().to_string()
+    }
+
+    pub(crate) fn test_public_key(name: &str) -> VerifyingKey {
+        let public_key: serde_json::Value =
+            serde_json::from_str(&read(&path(name, "public-key.json"))).unwrap();
+        let sec1 = format!(
+            "04{}{}",
+            public_key["X"].as_str().unwrap(),
+            public_key["Y"].as_str().unwrap()
+        );
+        VerifyingKey::from_sec1_bytes(&hex::decode(sec1).unwrap()).unwrap()
+    }
+}
+
 pub(crate) fn make_headers_comparable<B>(request: &mut Request<B>) {
     for (_name, value) in request.headers_mut() {
         value.set_sensitive(false);
@@ -87,7 +178,9 @@ fn parse_request(
 
     let mut uri_builder = Uri::builder().scheme("https");
     if let Some(path) = req.path {
-        uri_builder = uri_builder.path_and_query(path);
+        // Non-ASCII characters of the request target are sent percent-encoded
+        let path = percent_encoding::utf8_percent_encode(path, percent_encoding::CONTROLS);
+        uri_builder = uri_builder.path_and_query(path.to_string());
     }
     for header in req.headers {
         let name = header.name.to_lowercase();
@@ -0,0 +1,825 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use super::{
+    PercentEncodingMode, SignableBody, SignableRequest, SignatureLocation, SignatureVersion,
+};
+use crate::date_time::{format_date, format_date_time, parse_date_time};
+use crate::http_request::canonical_request::{
+    header, param, CanonicalRequest, StringToSign, HMAC_256, UNSIGNED_PAYLOAD,
+};
+use crate::sign::{calculate_signature, generate_signing_key, sha256_hex_string};
+use http::header::AUTHORIZATION;
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+/// Settings used when verifying the signature of an HTTP request
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub struct VerificationSettings {
+    /// How the request URL was encoded when it was signed. This must match the
+    /// [`percent_encoding_mode`](super::SigningSettings::percent_encoding_mode) used by the signer.
+    pub percent_encoding_mode: PercentEncodingMode,
+
+    /// Maximum difference between the signing time of a request and the verification time
+    ///
+    /// Presigned requests may be verified until they expire, but they are rejected if they are
+    /// signed further than this in the future.
+    pub max_clock_skew: Duration,
+
+    /// Maximum amount of time that a presigned request may be valid for
+    pub max_expires_in: Duration,
+}
+
+impl Default for VerificationSettings {
+    fn default() -> Self {
+        Self {
+            percent_encoding_mode: PercentEncodingMode::Double,
+            max_clock_skew: Duration::from_secs(15 * 60),
+            max_expires_in: Duration::from_secs(7 * 24 * 60 * 60),
+        }
+    }
+}
+
+/// The identity and scope of a request whose signature was successfully verified
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct VerifiedSignature {
+    access_key: String,
+    security_token: Option<String>,
+    region: String,
+    service_name: String,
+    time: SystemTime,
+    signature_location: SignatureLocation,
+    signed_headers: Vec<String>,
+}
+
+impl VerifiedSignature {
+    /// Returns the access key ID the request was signed with
+    pub fn access_key(&self) -> &str {
+        &self.access_key
+    }
+
+    /// Returns the security token sent along with the request, if it is covered by the signature
+    ///
+    /// A security token sent in an `x-amz-security-token` header that isn't part of the signed
+    /// headers is ignored. The token isn't validated: callers that issue temporary credentials
+    /// must check that it belongs to the access key.
+    pub fn security_token(&self) -> Option<&str> {
+        self.security_token.as_deref()
+    }
+
+    /// Returns the region of the credential scope
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    /// Returns the service name of the credential scope
+    pub fn service_name(&self) -> &str {
+        &self.service_name
+    }
+
+    /// Returns the time the request was signed at
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// Returns whether the signature was sent in the headers or in the query string of the request
+    pub fn signature_location(&self) -> SignatureLocation {
+        self.signature_location
+    }
+
+    /// Returns the names of the headers covered by the signature
+    pub fn signed_headers(&self) -> &[String] {
+        &self.signed_headers
+    }
+}
+
+/// Details of a signature that doesn't match the one calculated from the request
+///
+/// When a signer and a verifier disagree, comparing the canonical request and the string to sign
+/// logged by the signer with the ones of this mismatch usually points out the difference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SignatureMismatch {
+    signature: String,
+    expected_signature: String,
+    canonical_request: String,
+    string_to_sign: String,
+}
+
+impl SignatureMismatch {
+    /// Returns the signature sent with the request
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    /// Returns the signature calculated from the request
+    pub fn expected_signature(&self) -> &str {
+        &self.expected_signature
+    }
+
+    /// Returns the canonical request reconstructed from the request
+    pub fn canonical_request(&self) -> &str {
+        &self.canonical_request
+    }
+
+    /// Returns the string to sign that the expected signature was calculated from
+    pub fn string_to_sign(&self) -> &str {
+        &self.string_to_sign
+    }
+}
+
+/// Reason why the signature of a request couldn't be verified
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum VerificationError {
+    /// The request has neither an `Authorization` header nor an `X-Amz-Signature` query param
+    MissingSignature,
+
+    /// The signature was made with an algorithm other than `AWS4-HMAC-SHA256`
+    UnsupportedAlgorithm(String),
+
+    /// The signing values of the request are missing or malformed
+    InvalidSignatureValues(String),
+
+    /// The secret lookup didn't return a secret key for the access key of the request
+    UnknownAccessKey(String),
+
+    /// The request was signed too far from the verification time
+    RequestTimeTooSkewed {
+        /// The time the request was signed at
+        request_time: SystemTime,
+        /// The time the request was verified at
+        time: SystemTime,
+    },
+
+    /// The presigned request is no longer valid
+    Expired {
+        /// The time the presigned request expired at
+        expired_at: SystemTime,
+        /// The time the request was verified at
+        time: SystemTime,
+    },
+
+    /// The `x-amz-content-sha256` header doesn't match the body of the request
+    PayloadHashMismatch {
+        /// The payload hash sent with the request
+        payload_hash: String,
+        /// The hash of the request body
+        expected_payload_hash: String,
+    },
+
+    /// The canonical request couldn't be reconstructed, e.g. because a signed header is missing
+    InvalidRequest(super::Error),
+
+    /// The signature of the request doesn't match the one calculated from the request
+    SignatureMismatch(Box<SignatureMismatch>),
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use VerificationError::*;
+        match self {
+            MissingSignature => write!(f, "the request is not signed"),
+            UnsupportedAlgorithm(algorithm) => {
+                write!(f, "unsupported signing algorithm `{}`", algorithm)
+            }
+            InvalidSignatureValues(reason) => write!(f, "invalid signing values: {}", reason),
+            UnknownAccessKey(access_key) => write!(f, "unknown access key `{}`", access_key),
+            RequestTimeTooSkewed { request_time, time } => write!(
+                f,
+                "the request time {} is too far from the current time {}",
+                format_date_time(*request_time),
+                format_date_time(*time)
+            ),
+            Expired { expired_at, time } => write!(
+                f,
+                "the presigned request expired at {} (current time: {})",
+                format_date_time(*expired_at),
+                format_date_time(*time)
+            ),
+            PayloadHashMismatch {
+                payload_hash,
+                expected_payload_hash,
+            } => write!(
+                f,
+                "the payload hash `{}` doesn't match the hash of the body `{}`",
+                payload_hash, expected_payload_hash
+            ),
+            InvalidRequest(_) => write!(f, "the canonical request could not be constructed"),
+            SignatureMismatch(mismatch) => write!(
+                f,
+                "the signature `{}` doesn't match the expected signature `{}`. The string to sign was:\n{}",
+                mismatch.signature, mismatch.expected_signature, mismatch.string_to_sign
+            ),
+        }
+    }
+}
+
+impl StdError for VerificationError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            VerificationError::InvalidRequest(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// The signing values sent with a request, either in the `Authorization` header or in the query
+#[derive(Debug)]
+struct SignatureValues<'a> {
+    location: SignatureLocation,
+    credential: &'a str,
+    signed_headers: &'a str,
+    signature: &'a str,
+    date_time: String,
+    expires: Option<String>,
+    security_token: Option<String>,
+}
+
+impl<'a> SignatureValues<'a> {
+    fn from_headers(
+        request: &'a SignableRequest<'a>,
+        authorization: &'a str,
+    ) -> Result<Self, VerificationError> {
+        // Authorization: algorithm Credential=access key ID/credential scope, SignedHeaders=SignedHeaders, Signature=signature
+        let (algorithm, components) = authorization
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| invalid("malformed authorization header"))?;
+        if algorithm != HMAC_256 {
+            return Err(VerificationError::UnsupportedAlgorithm(algorithm.into()));
+        }
+        let (mut credential, mut signed_headers, mut signature) = (None, None, None);
+        for component in components.split(',') {
+            match component.trim().split_once('=') {
+                Some(("Credential", value)) => credential = Some(value),
+                Some(("SignedHeaders", value)) => signed_headers = Some(value),
+                Some(("Signature", value)) => signature = Some(value),
+                _ => return Err(invalid("malformed authorization header")),
+            }
+        }
+        let header_value = |name: &str| {
+            request
+                .headers()
+                .get(name)
+                .map(|value| value.to_str().map(str::to_string))
+                .transpose()
+                .map_err(|_| invalid(format!("invalid `{}` header", name)))
+        };
+        Ok(Self {
+            location: SignatureLocation::Headers,
+            credential: credential.ok_or_else(|| invalid("missing credential"))?,
+            signed_headers: signed_headers.ok_or_else(|| invalid("missing signed headers"))?,
+            signature: signature.ok_or_else(|| invalid("missing signature"))?,
+            date_time: header_value(header::X_AMZ_DATE)?
+                .ok_or_else(|| invalid("missing `x-amz-date` header"))?,
+            expires: None,
+            // An unsigned token could have been added by anyone and is ignored
+            security_token: match signed_headers {
+                Some(signed_headers)
+                    if signed_headers
+                        .split(';')
+                        .any(|name| name == header::X_AMZ_SECURITY_TOKEN) =>
+                {
+                    header_value(header::X_AMZ_SECURITY_TOKEN)?
+                }
+                _ => None,
+            },
+        })
+    }
+
+    fn from_query(
+        query: &'a [(String, String)],
+        signature: &'a str,
+    ) -> Result<Self, VerificationError> {
+        let query_param = |name: &str| {
+            query
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        };
+        let required =
+            |name: &str| query_param(name).ok_or_else(|| invalid(format!("missing `{}`", name)));
+        let algorithm = required(param::X_AMZ_ALGORITHM)?;
+        if algorithm != HMAC_256 {
+            return Err(VerificationError::UnsupportedAlgorithm(algorithm.into()));
+        }
+        Ok(Self {
+            location: SignatureLocation::QueryParams,
+            credential: required(param::X_AMZ_CREDENTIAL)?,
+            signed_headers: required(param::X_AMZ_SIGNED_HEADERS)?,
+            signature,
+            date_time: required(param::X_AMZ_DATE)?.into(),
+            expires: Some(required(param::X_AMZ_EXPIRES)?.into()),
+            security_token: query_param(param::X_AMZ_SECURITY_TOKEN).map(Into::into),
+        })
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> VerificationError {
+    VerificationError::InvalidSignatureValues(reason.into())
+}
+
+/// Verifies the SigV4 signature of `request` at the given `time`.
+///
+/// The signature is read from the `Authorization` header of the request or, for presigned
+/// requests, from its `X-Amz-*` query params. `secret_key` is called with the access key ID of
+/// the signature and returns the matching secret access key, or `None` if the access key is
+/// unknown.
+///
+/// The canonical request is reconstructed from the request exactly as it is received, so
+/// `request` must have the same method, URI, headers and body as the signed request. The body
+/// isn't available for requests signed with an unsigned or precomputed payload: pass
+/// [`SignableBody::UnsignedPayload`] or the precomputed [`SignableBody::Precomputed`] hash like
+/// the signer did.
+///
+/// # Example
+///
+/// ```rust
+/// use aws_sigv4::http_request::{verify, SignableRequest, VerificationSettings};
+/// use std::time::SystemTime;
+///
+/// # fn example(request: http::Request<Vec<u8>>) {
+/// let settings = VerificationSettings::default();
+/// match verify(SignableRequest::from(&request), &settings, SystemTime::now(), |access_key| {
+///     (access_key == "AKIDEXAMPLE").then(|| "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".into())
+/// }) {
+///     Ok(verified) => println!("request signed by {}", verified.access_key()),
+///     Err(err) => println!("rejecting request: {}", err),
+/// }
+/// # }
+/// ```
+pub fn verify(
+    request: SignableRequest<'_>,
+    settings: &VerificationSettings,
+    time: SystemTime,
+    secret_key: impl FnOnce(&str) -> Option<String>,
+) -> Result<VerifiedSignature, VerificationError> {
+    let query: Vec<(String, String)> =
+        form_urlencoded::parse(request.uri().query().unwrap_or_default().as_bytes())
+            .into_owned()
+            .collect();
+    let query_signature = query
+        .iter()
+        .find(|(key, _)| key == param::X_AMZ_SIGNATURE)
+        .map(|(_, value)| value.as_str());
+    let values = match (request.headers().get(AUTHORIZATION), query_signature) {
+        (Some(authorization), _) => {
+            let authorization = authorization
+                .to_str()
+                .map_err(|_| invalid("malformed authorization header"))?;
+            SignatureValues::from_headers(&request, authorization)?
+        }
+        (None, Some(signature)) => SignatureValues::from_query(&query, signature)?,
+        (None, None) => return Err(VerificationError::MissingSignature),
+    };
+    tracing::trace!(values = ?values, "verifying request signature");
+
+    let request_time =
+        parse_date_time(&values.date_time).ok_or_else(|| invalid("malformed request date"))?;
+    let (access_key, scope) = values
+        .credential
+        .split_once('/')
+        .ok_or_else(|| invalid("malformed credential"))?;
+    let (date, region, service_name) = match scope.split('/').collect::<Vec<_>>()[..] {
+        [date, region, service_name, "aws4_request"] => (date, region, service_name),
+        _ => return Err(invalid("malformed credential scope")),
+    };
+    if date != format_date(request_time) {
+        return Err(invalid(
+            "the credential scope date doesn't match the request date",
+        ));
+    }
+    check_time(&values, settings, request_time, time)?;
+
+    let signed_headers: Vec<&str> = values.signed_headers.split(';').collect();
+    if !signed_headers.contains(&"host") {
+        return Err(invalid("the `host` header must be signed"));
+    }
+    let creq = CanonicalRequest::from_signed(
+        &request,
+        &signed_headers,
+        &values.date_time,
+        &settings.percent_encoding_mode,
+    )
+    .map_err(VerificationError::InvalidRequest)?;
+    // Unsigned and streaming payloads aren't covered by the payload hash
+    let payload_hash = request
+        .headers()
+        .get(header::X_AMZ_CONTENT_SHA_256)
+        .filter(|hash| {
+            let hash = hash.as_bytes();
+            hash != UNSIGNED_PAYLOAD.as_bytes() && !hash.starts_with(b"STREAMING-")
+        });
+    if let Some(payload_hash) = payload_hash {
+        if let SignableBody::Bytes(body) = request.body() {
+            let expected_payload_hash = sha256_hex_string(body);
+            if payload_hash.as_bytes() != expected_payload_hash.as_bytes() {
+                return Err(VerificationError::PayloadHashMismatch {
+                    payload_hash: String::from_utf8_lossy(payload_hash.as_bytes()).into(),
+                    expected_payload_hash,
+                });
+            }
+        }
+    }
+
+    let secret_key = secret_key(access_key)
+        .ok_or_else(|| VerificationError::UnknownAccessKey(access_key.into()))?;
+    let canonical_request = creq.to_string();
+    let encoded_creq = sha256_hex_string(canonical_request.as_bytes());
+    let sts = StringToSign::new(
+        request_time,
+        region,
+        service_name,
+        &encoded_creq,
+        SignatureVersion::V4,
+    )
+    .to_string();
+    let signing_key = generate_signing_key(&secret_key, request_time, region, service_name);
+    let expected_signature = calculate_signature(signing_key, sts.as_bytes());
+    if !constant_time_eq(values.signature.as_bytes(), expected_signature.as_bytes()) {
+        return Err(VerificationError::SignatureMismatch(Box::new(
+            SignatureMismatch {
+                signature: values.signature.into(),
+                expected_signature,
+                canonical_request,
+                string_to_sign: sts,
+            },
+        )));
+    }
+
+    Ok(VerifiedSignature {
+        access_key: access_key.into(),
+        security_token: values.security_token,
+        region: region.into(),
+        service_name: service_name.into(),
+        time: request_time,
+        signature_location: values.location,
+        signed_headers: signed_headers.into_iter().map(Into::into).collect(),
+    })
+}
+
+fn check_time(
+    values: &SignatureValues<'_>,
+    settings: &VerificationSettings,
+    request_time: SystemTime,
+    time: SystemTime,
+) -> Result<(), VerificationError> {
+    let skewed = || VerificationError::RequestTimeTooSkewed { request_time, time };
+    // Requests signed in the future are always subject to the clock skew
+    if matches!(request_time.duration_since(time), Ok(ahead) if ahead > settings.max_clock_skew) {
+        return Err(skewed());
+    }
+    match &values.expires {
+        None => {
+            if matches!(time.duration_since(request_time), Ok(behind) if behind > settings.max_clock_skew)
+            {
+                return Err(skewed());
+            }
+        }
+        Some(expires) => {
+            let expires_in = expires
+                .parse()
+                .map(Duration::from_secs)
+                .map_err(|_| invalid("malformed expiration"))?;
+            if expires_in > settings.max_expires_in {
+                return Err(invalid(format!(
+                    "the request expires after more than {} seconds",
+                    settings.max_expires_in.as_secs()
+                )));
+            }
+            let expired_at = request_time + expires_in;
+            if time > expired_at {
+                return Err(VerificationError::Expired { expired_at, time });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Compares two byte strings in an amount of time that only depends on their length
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify, VerificationError, VerificationSettings};
+    use crate::date_time::test_parsers::parse_date_time;
+    use crate::http_request::test::{test_signed_request, test_sts};
+    use crate::http_request::{
+        sign, SignableBody, SignableRequest, SignatureLocation, SigningParams, SigningSettings,
+    };
+    use http::{HeaderValue, Request};
+    use std::time::Duration;
+
+    const SECRET_KEY: &str = "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY";
+
+    fn secret_key(access_key: &str) -> Option<String> {
+        (access_key == "AKIDEXAMPLE").then(|| SECRET_KEY.to_string())
+    }
+
+    fn signed_request(location: SignatureLocation) -> Request<&'static str> {
+        let mut request = Request::builder()
+            .uri("https://example.amazonaws.com/some/path?b=2&a=1")
+            .header("content-type", "application/json")
+            .body("{\"hello\":\"world\"}")
+            .unwrap();
+        let params = SigningParams {
+            access_key: "AKIDEXAMPLE",
+            secret_key: SECRET_KEY,
+            security_token: Some("token"),
+            region: "us-east-1",
+            #[cfg(feature = "sigv4a")]
+            region_set: None,
+            service_name: "service",
+            time: parse_date_time("20150830T123600Z").unwrap(),
+            settings: SigningSettings {
+                signature_location: location,
+                expires_in: Some(Duration::from_secs(60)),
+                ..Default::default()
+            },
+        };
+        let (instructions, _) = sign(SignableRequest::from(&request), &params)
+            .unwrap()
+            .into_parts();
+        instructions.apply_to_request(&mut request);
+        request
+    }
+
+    #[test]
+    fn verify_signed_requests() {
+        let time = parse_date_time("20150830T123630Z").unwrap();
+        for location in [SignatureLocation::Headers, SignatureLocation::QueryParams] {
+            let request = signed_request(location);
+            let verified = verify(
+                SignableRequest::from(&request),
+                &VerificationSettings::default(),
+                time,
+                secret_key,
+            )
+            .expect("valid signature");
+            assert_eq!("AKIDEXAMPLE", verified.access_key());
+            assert_eq!(Some("token"), verified.security_token());
+            assert_eq!("us-east-1", verified.region());
+            assert_eq!("service", verified.service_name());
+            assert_eq!(location, verified.signature_location());
+            assert_eq!(
+                parse_date_time("20150830T123600Z").unwrap(),
+                verified.time()
+            );
+        }
+    }
+
+    #[test]
+    fn verify_test_suite_request() {
+        let request = test_signed_request("get-vanilla-query-order-key-case");
+        let time = parse_date_time("20150830T123600Z").unwrap();
+        let settings = VerificationSettings::default();
+        let verified = verify(SignableRequest::from(&request), &settings, time, secret_key)
+            .expect("valid signature");
+        assert_eq!(&["host", "x-amz-date"], verified.signed_headers());
+    }
+
+    #[test]
+    fn tampered_request_reports_the_expected_string_to_sign() {
+        let mut request = test_signed_request("get-vanilla-query-order-key-case");
+        let authorization = request.headers()["authorization"].to_str().unwrap();
+        let (unsigned, _) = authorization.split_once("Signature=").unwrap();
+        let tampered = format!("{}Signature={}", unsigned, "0".repeat(64));
+        request
+            .headers_mut()
+            .insert("authorization", HeaderValue::from_str(&tampered).unwrap());
+
+        let time = parse_date_time("20150830T123600Z").unwrap();
+        let settings = VerificationSettings::default();
+        match verify(SignableRequest::from(&request), &settings, time, secret_key) {
+            Err(VerificationError::SignatureMismatch(mismatch)) => {
+                assert_eq!("0".repeat(64), mismatch.signature());
+                assert_eq!(
+                    test_sts("get-vanilla-query-order-key-case"),
+                    mismatch.string_to_sign()
+                );
+                assert!(mismatch.canonical_request().starts_with("GET\n/\n"));
+            }
+            other => panic!("expected a signature mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn modified_request_is_rejected() {
+        let time = parse_date_time("20150830T123630Z").unwrap();
+        for location in [SignatureLocation::Headers, SignatureLocation::QueryParams] {
+            let request = signed_request(location);
+            let request = SignableRequest::new(
+                request.method(),
+                request.uri(),
+                request.headers(),
+                SignableBody::Bytes(b"{\"hello\":\"there\"}"),
+            );
+            let result = verify(request, &VerificationSettings::default(), time, secret_key);
+            assert!(
+                matches!(result, Err(VerificationError::SignatureMismatch(_))),
+                "{:?}",
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_access_key() {
+        let request = signed_request(SignatureLocation::Headers);
+        let time = parse_date_time("20150830T123600Z").unwrap();
+        let result = verify(
+            SignableRequest::from(&request),
+            &VerificationSettings::default(),
+            time,
+            |_| None,
+        );
+        assert!(
+            matches!(result, Err(VerificationError::UnknownAccessKey(key)) if key == "AKIDEXAMPLE")
+        );
+    }
+
+    #[test]
+    fn unsigned_request() {
+        let request = Request::builder()
+            .uri("https://example.amazonaws.com/?a=1")
+            .body("")
+            .unwrap();
+        let time = parse_date_time("20150830T123600Z").unwrap();
+        let result = verify(
+            SignableRequest::from(&request),
+            &VerificationSettings::default(),
+            time,
+            secret_key,
+        );
+        assert!(matches!(result, Err(VerificationError::MissingSignature)));
+    }
+
+    #[test]
+    fn skewed_request_is_rejected() {
+        let request = signed_request(SignatureLocation::Headers);
+        let settings = VerificationSettings::default();
+        for time in ["20150830T115000Z", "20150830T132200Z"] {
+            let time = parse_date_time(time).unwrap();
+            let result = verify(SignableRequest::from(&request), &settings, time, secret_key);
+            assert!(
+                matches!(result, Err(VerificationError::RequestTimeTooSkewed { .. })),
+                "{:?}",
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn expired_presigned_request_is_rejected() {
+        let request = signed_request(SignatureLocation::QueryParams);
+        let settings = VerificationSettings::default();
+        let time = parse_date_time("20150830T123701Z").unwrap();
+        let result = verify(SignableRequest::from(&request), &settings, time, secret_key);
+        assert!(matches!(result, Err(VerificationError::Expired { .. })));
+
+        let settings = VerificationSettings {
+            max_expires_in: Duration::from_secs(30),
+            ..Default::default()
+        };
+        let time = parse_date_time("20150830T123600Z").unwrap();
+        let result = verify(SignableRequest::from(&request), &settings, time, secret_key);
+        assert!(matches!(
+            result,
+            Err(VerificationError::InvalidSignatureValues(_))
+        ));
+    }
+
+    fn sign_request(request: &mut Request<&'static str>, settings: SigningSettings) {
+        let params = SigningParams {
+            access_key: "AKIDEXAMPLE",
+            secret_key: SECRET_KEY,
+            security_token: None,
+            region: "us-east-1",
+            #[cfg(feature = "sigv4a")]
+            region_set: None,
+            service_name: "service",
+            time: parse_date_time("20150830T123600Z").unwrap(),
+            settings,
+        };
+        let (instructions, _) = sign(SignableRequest::from(&*request), &params)
+            .unwrap()
+            .into_parts();
+        instructions.apply_to_request(request);
+    }
+
+    #[test]
+    fn unsigned_payload_is_not_hashed() {
+        let mut request = Request::builder()
+            .uri("https://example.amazonaws.com/")
+            .header("x-amz-content-sha256", "UNSIGNED-PAYLOAD")
+            .body("hello")
+            .unwrap();
+        let unsigned_request = SignableRequest::new(
+            request.method(),
+            request.uri(),
+            request.headers(),
+            SignableBody::UnsignedPayload,
+        );
+        let params = SigningParams {
+            access_key: "AKIDEXAMPLE",
+            secret_key: SECRET_KEY,
+            security_token: None,
+            region: "us-east-1",
+            #[cfg(feature = "sigv4a")]
+            region_set: None,
+            service_name: "service",
+            time: parse_date_time("20150830T123600Z").unwrap(),
+            settings: SigningSettings::default(),
+        };
+        let (instructions, _) = sign(unsigned_request, &params).unwrap().into_parts();
+        instructions.apply_to_request(&mut request);
+
+        let time = parse_date_time("20150830T123600Z").unwrap();
+        verify(
+            SignableRequest::from(&request),
+            &VerificationSettings::default(),
+            time,
+            secret_key,
+        )
+        .expect("valid signature");
+    }
+
+    #[test]
+    fn host_must_be_signed() {
+        let mut request = test_signed_request("get-vanilla-query-order-key-case");
+        let authorization = request.headers()["authorization"].to_str().unwrap();
+        let authorization = authorization.replace("SignedHeaders=host;", "SignedHeaders=");
+        request.headers_mut().insert(
+            "authorization",
+            HeaderValue::from_str(&authorization).unwrap(),
+        );
+
+        let time = parse_date_time("20150830T123600Z").unwrap();
+        let settings = VerificationSettings::default();
+        let result = verify(SignableRequest::from(&request), &settings, time, secret_key);
+        assert!(
+            matches!(result, Err(VerificationError::InvalidSignatureValues(_))),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn unsigned_security_token_is_ignored() {
+        let mut request = Request::builder()
+            .uri("https://example.amazonaws.com/")
+            .body("")
+            .unwrap();
+        sign_request(&mut request, SigningSettings::default());
+        request
+            .headers_mut()
+            .insert("x-amz-security-token", HeaderValue::from_static("token"));
+
+        let time = parse_date_time("20150830T123600Z").unwrap();
+        let verified = verify(
+            SignableRequest::from(&request),
+            &VerificationSettings::default(),
+            time,
+            secret_key,
+        )
+        .expect("valid signature");
+        assert_eq!(None, verified.security_token());
+    }
+
+    #[test]
+    fn payload_hash_must_match_the_body() {
+        let mut request = Request::builder()
+            .uri("https://example.amazonaws.com/")
+            .body("hello")
+            .unwrap();
+        sign_request(
+            &mut request,
+            SigningSettings {
+                payload_checksum_kind: crate::http_request::PayloadChecksumKind::XAmzSha256,
+                ..Default::default()
+            },
+        );
+        *request.body_mut() = "goodbye";
+
+        let time = parse_date_time("20150830T123600Z").unwrap();
+        let result = verify(
+            SignableRequest::from(&request),
+            &VerificationSettings::default(),
+            time,
+            secret_key,
+        );
+        assert!(
+            matches!(result, Err(VerificationError::PayloadHashMismatch { .. })),
+            "{:?}",
+            result
+        );
+    }
+}
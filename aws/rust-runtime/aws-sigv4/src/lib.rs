@@ -26,6 +26,9 @@ pub mod event_stream;
 #[cfg(feature = "sign-http")]
 pub mod http_request;
 
+#[cfg(feature = "sigv4a")]
+pub mod sigv4a;
+
 /// Parameters to use when signing.
 #[non_exhaustive]
 #[derive(Debug)]
@@ -39,6 +42,10 @@ pub struct SigningParams<'a, S> {
 
     /// Region to sign for.
     pub(crate) region: &'a str,
+    /// (Optional) Regions that a SigV4a signature is valid in, e.g. `us-east-1,us-west-*`.
+    /// Defaults to `region`.
+    #[cfg(feature = "sigv4a")]
+    pub(crate) region_set: Option<&'a str>,
     /// AWS Service Name to sign for.
     pub(crate) service_name: &'a str,
     /// Timestamp to use in the signature (should be `SystemTime::now()` unless testing).
@@ -88,6 +95,8 @@ pub mod signing_params {
         secret_key: Option<&'a str>,
         security_token: Option<&'a str>,
         region: Option<&'a str>,
+        #[cfg(feature = "sigv4a")]
+        region_set: Option<&'a str>,
         service_name: Option<&'a str>,
         time: Option<SystemTime>,
         settings: Option<S>,
@@ -134,6 +143,20 @@ pub mod signing_params {
             self.region = region;
         }
 
+        /// Sets the comma-separated regions that a SigV4a signature is valid in (optional)
+        ///
+        /// Region names may use `*` as a wildcard, e.g. `us-west-*`. Defaults to the region.
+        #[cfg(feature = "sigv4a")]
+        pub fn region_set(mut self, region_set: &'a str) -> Self {
+            self.region_set = Some(region_set);
+            self
+        }
+        /// Sets the comma-separated regions that a SigV4a signature is valid in (optional)
+        #[cfg(feature = "sigv4a")]
+        pub fn set_region_set(&mut self, region_set: Option<&'a str>) {
+            self.region_set = region_set;
+        }
+
         /// Sets the service name (required)
         pub fn service_name(mut self, service_name: &'a str) -> Self {
             self.service_name = Some(service_name);
@@ -178,6 +201,8 @@ pub mod signing_params {
                 region: self
                     .region
                     .ok_or_else(|| BuildError::new("region is required"))?,
+                #[cfg(feature = "sigv4a")]
+                region_set: self.region_set,
                 service_name: self
                     .service_name
                     .ok_or_else(|| BuildError::new("service name is required"))?,
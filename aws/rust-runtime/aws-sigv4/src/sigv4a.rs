@@ -0,0 +1,138 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Functions to create SigV4a signing keys and calculate signatures.
+//!
+//! SigV4a signatures are ECDSA P-256 signatures that are valid in a set of regions rather than
+//! in a single region. They are required by multi-region endpoints, e.g. S3 Multi-Region Access
+//! Points.
+
+use hmac::{digest::FixedOutput, Hmac, Mac};
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::Signature;
+use p256::elliptic_curve::bigint::{ArrayEncoding, U256};
+use sha2::Sha256;
+use std::fmt;
+use zeroize::Zeroizing;
+
+/// The SigV4a signing algorithm
+pub(crate) const ECDSA_256: &str = "AWS4-ECDSA-P256-SHA256";
+
+/// The order of the P-256 curve minus two
+const N_MINUS_TWO: U256 =
+    U256::from_be_hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc63254f");
+
+/// A SigV4a private signing key, derived from AWS credentials
+pub struct SigningKey(p256::ecdsa::SigningKey);
+
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SigningKey(** redacted **)")
+    }
+}
+
+impl SigningKey {
+    /// Returns the SEC1 encoding of the public key that verifies signatures made with this key
+    pub fn public_key_sec1(&self, compress: bool) -> Vec<u8> {
+        self.0
+            .verifying_key()
+            .to_encoded_point(compress)
+            .as_bytes()
+            .to_vec()
+    }
+}
+
+/// Generates a SigV4a signing key for the given credentials
+///
+/// The key is derived with the NIST SP 800-108 KDF in counter mode, using HMAC-SHA256 keyed by
+/// `"AWS4A" + secret_key` and a context that includes the access key ID. Unlike SigV4 signing
+/// keys, SigV4a signing keys don't depend on the date, region or service of the request, so they
+/// may be reused for as long as the credentials are.
+pub fn generate_signing_key(access_key: &str, secret_key: &str) -> SigningKey {
+    let input_key = Zeroizing::new(format!("AWS4A{}", secret_key));
+    // The counter of the derivation context is incremented until the derived value is a valid
+    // private key, which happens on the first try with overwhelming probability
+    for counter in 1..=u8::MAX {
+        let mut mac = Hmac::<Sha256>::new_from_slice(input_key.as_bytes())
+            .expect("HMAC can take key of any size");
+        // Fixed input string: i || label || 0x00 || context || L
+        mac.update(&1u32.to_be_bytes());
+        mac.update(ECDSA_256.as_bytes());
+        mac.update(&[0]);
+        mac.update(access_key.as_bytes());
+        mac.update(&[counter]);
+        mac.update(&256u32.to_be_bytes());
+        let candidate = Zeroizing::new(U256::from_be_slice(&mac.finalize_fixed()));
+        if *candidate <= N_MINUS_TWO {
+            let private_key: Zeroizing<[u8; 32]> =
+                Zeroizing::new(candidate.wrapping_add(&U256::ONE).to_be_byte_array().into());
+            return SigningKey(
+                p256::ecdsa::SigningKey::from_bytes(private_key.as_ref())
+                    .expect("the private key is in the range of the curve order"),
+            );
+        }
+    }
+    unreachable!("a valid signing key is derived long before the counter overflows")
+}
+
+/// Calculates a SigV4a signature, a DER encoded ECDSA signature of `string_to_sign`, hex encoded
+///
+/// Unlike SigV4 signatures, ECDSA signatures can't be recomputed by a verifier: they are checked
+/// against the public key of the signing key instead.
+pub fn calculate_signature(signing_key: &SigningKey, string_to_sign: &[u8]) -> String {
+    let signature: Signature = signing_key.0.sign(string_to_sign);
+    hex::encode(signature.to_der().as_bytes())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{calculate_signature, generate_signing_key};
+    use p256::ecdsa::signature::Verifier;
+    use p256::ecdsa::{Signature, VerifyingKey};
+
+    // The public key of the signing key derived from the credentials of the SigV4a test suite
+    const TEST_SUITE_PUBLIC_KEY_X: &str =
+        "b6618f6a65740a99e650b33b6b4b5bd0d43b176d721a3edfea7e7d2d56d936b1";
+    const TEST_SUITE_PUBLIC_KEY_Y: &str =
+        "865ed22a7eadc9c5cb9d2cbaca1b3699139fedc5043dc6661864218330c8e518";
+
+    /// Returns true if `signature` is a valid signature of `string_to_sign` by the signing key
+    /// of the SigV4a test suite
+    pub(crate) fn is_test_suite_signature(string_to_sign: &[u8], signature: &str) -> bool {
+        let public_key = hex::decode(format!(
+            "04{}{}",
+            TEST_SUITE_PUBLIC_KEY_X, TEST_SUITE_PUBLIC_KEY_Y
+        ))
+        .unwrap();
+        let public_key = VerifyingKey::from_sec1_bytes(&public_key).unwrap();
+        let signature = Signature::from_der(&hex::decode(signature).unwrap()).unwrap();
+        public_key.verify(string_to_sign, &signature).is_ok()
+    }
+
+    #[test]
+    fn derive_test_suite_signing_key() {
+        let key = generate_signing_key("AKIDEXAMPLE", "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY");
+        let expected = format!("04{}{}", TEST_SUITE_PUBLIC_KEY_X, TEST_SUITE_PUBLIC_KEY_Y);
+        assert_eq!(expected, hex::encode(key.public_key_sec1(false)));
+    }
+
+    #[test]
+    fn signatures_verify_with_the_public_key() {
+        let key = generate_signing_key("AKIDEXAMPLE", "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY");
+        let signature = calculate_signature(&key, b"string to sign");
+        assert!(is_test_suite_signature(b"string to sign", &signature));
+        assert!(!is_test_suite_signature(b"another string", &signature));
+
+        let other_key = generate_signing_key("AKIDEXAMPLE", "another secret");
+        let signature = calculate_signature(&other_key, b"string to sign");
+        assert!(!is_test_suite_signature(b"string to sign", &signature));
+    }
+
+    #[test]
+    fn debug_does_not_leak_the_key() {
+        let key = generate_signing_key("AKIDEXAMPLE", "secret");
+        assert_eq!("SigningKey(** redacted **)", format!("{:?}", key));
+    }
+}
@@ -8,8 +8,10 @@
 use crate::region::{Region, SigningRegion};
 use crate::SigningService;
 use aws_smithy_http::endpoint::{Endpoint, EndpointPrefix};
+use std::collections::HashMap;
 use std::error::Error;
-use std::fmt::Debug;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
 
 /// Endpoint to connect to an AWS Service
 ///
@@ -82,6 +84,76 @@ pub type BoxError = Box<dyn Error + Send + Sync + 'static>;
 pub trait ResolveAwsEndpoint: Send + Sync + Debug {
     /// Resolves the AWS endpoint for a given region.
     fn resolve_endpoint(&self, region: &Region) -> Result<AwsEndpoint, BoxError>;
+
+    /// Resolves a FIPS and/or dual-stack variant of the AWS endpoint for a given region.
+    ///
+    /// Resolvers that know about endpoint variants must return an error when the requested
+    /// variant doesn't exist in `region`. By default, the variant is ignored: a custom endpoint
+    /// takes precedence over the FIPS and dual-stack settings.
+    fn resolve_endpoint_variant(
+        &self,
+        region: &Region,
+        variant: EndpointVariant,
+    ) -> Result<AwsEndpoint, BoxError> {
+        let _ = variant;
+        self.resolve_endpoint(region)
+    }
+}
+
+/// A variant of an AWS endpoint: FIPS compliant, dual-stack (IPv4 and IPv6), or both.
+///
+/// # Examples
+/// ```rust
+/// use aws_types::endpoint::EndpointVariant;
+/// let variant = EndpointVariant::default().with_fips(true);
+/// assert!(variant.fips());
+/// assert!(!variant.dual_stack());
+/// assert_eq!(variant.to_string(), "FIPS");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EndpointVariant {
+    fips: bool,
+    dual_stack: bool,
+}
+
+impl EndpointVariant {
+    /// Sets whether this variant is a FIPS endpoint.
+    pub fn with_fips(mut self, fips: bool) -> Self {
+        self.fips = fips;
+        self
+    }
+
+    /// Sets whether this variant is a dual-stack endpoint.
+    pub fn with_dual_stack(mut self, dual_stack: bool) -> Self {
+        self.dual_stack = dual_stack;
+        self
+    }
+
+    /// Returns true if this variant is a FIPS endpoint.
+    pub fn fips(&self) -> bool {
+        self.fips
+    }
+
+    /// Returns true if this variant is a dual-stack endpoint.
+    pub fn dual_stack(&self) -> bool {
+        self.dual_stack
+    }
+
+    /// Returns true if this is the standard endpoint, neither FIPS nor dual-stack.
+    pub fn is_default(&self) -> bool {
+        !self.fips && !self.dual_stack
+    }
+}
+
+impl Display for EndpointVariant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.fips, self.dual_stack) {
+            (false, false) => write!(f, "standard"),
+            (true, false) => write!(f, "FIPS"),
+            (false, true) => write!(f, "dual-stack"),
+            (true, true) => write!(f, "FIPS and dual-stack"),
+        }
+    }
 }
 
 /// The scope for AWS credentials.
@@ -165,9 +237,134 @@ impl ResolveAwsEndpoint for Endpoint {
     }
 }
 
+/// Endpoint URLs configured outside of code, e.g. with `AWS_ENDPOINT_URL`.
+///
+/// Holds an optional URL used by every service and per-service URLs keyed by service ID. Service
+/// IDs are normalized so that `DynamoDB`, `dynamodb` and `DYNAMODB` refer to the same service and
+/// `Secrets Manager` matches `secrets_manager` and `secrets-manager`.
+///
+/// # Examples
+/// ```rust
+/// use aws_types::endpoint::EndpointUrlConfig;
+/// let config = EndpointUrlConfig::builder()
+///     .global("http://localhost:4566")
+///     .service("DynamoDB", "http://localhost:8000")
+///     .build();
+/// assert_eq!(config.endpoint_url("dynamodb"), Some("http://localhost:8000"));
+/// assert_eq!(config.endpoint_url("S3"), Some("http://localhost:4566"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EndpointUrlConfig {
+    global: Option<String>,
+    services: HashMap<String, String>,
+}
+
+impl EndpointUrlConfig {
+    /// Creates a builder for [`EndpointUrlConfig`].
+    pub fn builder() -> endpoint_url_config::Builder {
+        endpoint_url_config::Builder::default()
+    }
+
+    /// Returns the endpoint URL for the service with the given service ID.
+    ///
+    /// A service-specific URL takes precedence over the global one.
+    pub fn endpoint_url(&self, service_id: &str) -> Option<&str> {
+        self.services
+            .get(&normalize_service_id(service_id))
+            .or(self.global.as_ref())
+            .map(|url| url.as_str())
+    }
+
+    /// Returns the endpoint URL used by every service without a service-specific URL.
+    pub fn global(&self) -> Option<&str> {
+        self.global.as_deref()
+    }
+
+    /// Uses the values from `other` as fallbacks for this configuration.
+    ///
+    /// Every URL of this configuration takes precedence over the URLs of `other`, including the
+    /// global URL: when it is set, the URLs of `other` are never used.
+    pub fn merge(&self, other: &EndpointUrlConfig) -> EndpointUrlConfig {
+        if self.global.is_some() {
+            return self.clone();
+        }
+        let mut services = other.services.clone();
+        services.extend(self.services.clone());
+        EndpointUrlConfig {
+            global: other.global.clone(),
+            services,
+        }
+    }
+
+    /// Returns true if no endpoint URL is configured.
+    pub fn is_empty(&self) -> bool {
+        self.global.is_none() && self.services.is_empty()
+    }
+}
+
+/// Normalizes a service ID for endpoint URL lookup: `Secrets Manager` becomes `secrets_manager`.
+pub fn normalize_service_id(service_id: &str) -> String {
+    service_id
+        .trim()
+        .chars()
+        .map(|c| match c {
+            ' ' | '-' => '_',
+            c => c.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+/// Types associated with [`EndpointUrlConfig`].
+pub mod endpoint_url_config {
+    use super::{normalize_service_id, EndpointUrlConfig};
+    use std::collections::HashMap;
+
+    /// A builder for [`EndpointUrlConfig`].
+    #[derive(Debug, Default)]
+    pub struct Builder {
+        global: Option<String>,
+        services: HashMap<String, String>,
+    }
+
+    impl Builder {
+        /// Sets the endpoint URL used by every service.
+        pub fn global(mut self, url: impl Into<String>) -> Self {
+            self.set_global(Some(url.into()));
+            self
+        }
+
+        /// Sets the endpoint URL used by every service.
+        pub fn set_global(&mut self, url: Option<String>) -> &mut Self {
+            self.global = url;
+            self
+        }
+
+        /// Sets the endpoint URL for the service with the given service ID.
+        pub fn service(mut self, service_id: &str, url: impl Into<String>) -> Self {
+            self.set_service(service_id, url);
+            self
+        }
+
+        /// Sets the endpoint URL for the service with the given service ID.
+        pub fn set_service(&mut self, service_id: &str, url: impl Into<String>) -> &mut Self {
+            self.services
+                .insert(normalize_service_id(service_id), url.into());
+            self
+        }
+
+        /// Constructs an [`EndpointUrlConfig`] from the builder.
+        pub fn build(self) -> EndpointUrlConfig {
+            EndpointUrlConfig {
+                global: self.global,
+                services: self.services,
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::endpoint::CredentialScope;
+    use crate::endpoint::{CredentialScope, EndpointUrlConfig};
     use crate::region::SigningRegion;
     use crate::SigningService;
 
@@ -183,4 +380,49 @@ mod test {
             Some(&SigningRegion::from_static("us-east-1"))
         );
     }
+
+    #[test]
+    fn endpoint_url_lookup_normalizes_service_ids() {
+        let config = EndpointUrlConfig::builder()
+            .service("Secrets Manager", "http://secrets")
+            .build();
+        assert_eq!(
+            config.endpoint_url("secrets-manager"),
+            Some("http://secrets")
+        );
+        assert_eq!(
+            config.endpoint_url("SECRETS_MANAGER"),
+            Some("http://secrets")
+        );
+        assert_eq!(config.endpoint_url("s3"), None);
+
+        let config = EndpointUrlConfig::builder()
+            .global("http://global")
+            .service("s3", "http://s3")
+            .build();
+        assert_eq!(config.endpoint_url("S3"), Some("http://s3"));
+        assert_eq!(config.endpoint_url("sts"), Some("http://global"));
+    }
+
+    #[test]
+    fn merge_endpoint_url_configs() {
+        let fallback = EndpointUrlConfig::builder()
+            .global("http://fallback")
+            .service("s3", "http://fallback-s3")
+            .service("sts", "http://fallback-sts")
+            .build();
+        let merged = EndpointUrlConfig::builder()
+            .service("s3", "http://s3")
+            .build()
+            .merge(&fallback);
+        assert_eq!(merged.endpoint_url("s3"), Some("http://s3"));
+        assert_eq!(merged.endpoint_url("sts"), Some("http://fallback-sts"));
+        assert_eq!(merged.endpoint_url("iam"), Some("http://fallback"));
+
+        let merged = EndpointUrlConfig::builder()
+            .global("http://global")
+            .build()
+            .merge(&fallback);
+        assert_eq!(merged.endpoint_url("sts"), Some("http://global"));
+    }
 }
@@ -5,7 +5,7 @@
 
 //! Abstractions for testing code that interacts with the operating system:
 //! - Reading environment variables
-//! - Reading from the file system
+//! - Reading from and writing to the file system
 
 use std::collections::HashMap;
 use std::env::VarError;
@@ -53,7 +53,7 @@ impl Fs {
     }
 
     pub fn from_raw_map(fs: HashMap<OsString, Vec<u8>>) -> Self {
-        Fs(fs::Inner::Fake(Arc::new(Fake::MapFs(fs))))
+        Fs(fs::Inner::Fake(Arc::new(Fake::MapFs(Mutex::new(fs)))))
     }
 
     pub fn from_map(data: HashMap<String, impl Into<Vec<u8>>>) -> Self {
@@ -131,6 +131,8 @@ impl Fs {
             Inner::Real => std::fs::read(path),
             Inner::Fake(fake) => match fake.as_ref() {
                 Fake::MapFs(fs) => fs
+                    .lock()
+                    .unwrap()
                     .get(path.as_os_str())
                     .cloned()
                     .ok_or_else(|| std::io::ErrorKind::NotFound.into()),
@@ -146,13 +148,91 @@ impl Fs {
             },
         }
     }
+
+    /// Atomically replace the contents of a file, creating it and its parent directories if needed
+    ///
+    /// The contents are first written to a temporary file next to `path` which is then renamed,
+    /// so that concurrent readers never observe a partially written file. On Unix, the file is only
+    /// readable and writable by its owner.
+    ///
+    /// _Note: Like [`read_to_end`](Fs::read_to_end), IO is performed directly within the function._
+    pub async fn write(
+        &self,
+        path: impl AsRef<Path>,
+        contents: impl AsRef<[u8]>,
+    ) -> std::io::Result<()> {
+        use fs::Inner;
+        let path = path.as_ref();
+        let contents = contents.as_ref();
+        match &self.0 {
+            Inner::Real => fs::write_atomic(path, contents),
+            Inner::Fake(fake) => match fake.as_ref() {
+                Fake::MapFs(fs) => {
+                    fs.lock()
+                        .unwrap()
+                        .insert(path.as_os_str().to_owned(), contents.to_vec());
+                    Ok(())
+                }
+                Fake::NamespacedFs {
+                    real_path,
+                    namespaced_to,
+                } => {
+                    let actual_path = path
+                        .strip_prefix(namespaced_to)
+                        .map_err(|_| std::io::Error::from(std::io::ErrorKind::NotFound))?;
+                    fs::write_atomic(&real_path.join(actual_path), contents)
+                }
+            },
+        }
+    }
+
+    /// Read the entire contents of a file unless it is readable or writable by users other than
+    /// its owner, in which case `None` is returned
+    ///
+    /// The permissions are checked on the opened file before reading it, so the file can't be
+    /// replaced between the check and the read. Files of in-memory file systems and files on
+    /// platforms without Unix permissions are always considered private.
+    ///
+    /// _Note: Like [`read_to_end`](Fs::read_to_end), IO is performed directly within the function._
+    pub async fn read_private_to_end(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> std::io::Result<Option<Vec<u8>>> {
+        use fs::Inner;
+        let path = path.as_ref();
+        match &self.0 {
+            Inner::Real => fs::read_private(path),
+            Inner::Fake(fake) => match fake.as_ref() {
+                Fake::MapFs(fs) => fs
+                    .lock()
+                    .unwrap()
+                    .get(path.as_os_str())
+                    .cloned()
+                    .map(Some)
+                    .ok_or_else(|| std::io::ErrorKind::NotFound.into()),
+                Fake::NamespacedFs {
+                    real_path,
+                    namespaced_to,
+                } => {
+                    let actual_path = path
+                        .strip_prefix(namespaced_to)
+                        .map_err(|_| std::io::Error::from(std::io::ErrorKind::NotFound))?;
+                    fs::read_private(&real_path.join(actual_path))
+                }
+            },
+        }
+    }
 }
 
 mod fs {
     use std::collections::HashMap;
     use std::ffi::OsString;
-    use std::path::PathBuf;
-    use std::sync::Arc;
+    use std::io::{Read, Write};
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    static TMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
 
     #[derive(Clone, Debug)]
     pub(super) enum Inner {
@@ -162,12 +242,68 @@ mod fs {
 
     #[derive(Debug)]
     pub(super) enum Fake {
-        MapFs(HashMap<OsString, Vec<u8>>),
+        MapFs(Mutex<HashMap<OsString, Vec<u8>>>),
         NamespacedFs {
             real_path: PathBuf,
             namespaced_to: PathBuf,
         },
     }
+
+    pub(super) fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::InvalidInput))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // The process ID and a per-process counter make the temporary file unique to this call,
+        // so that concurrent writers never write to or rename each other's temporary file.
+        let mut tmp_name = OsString::from(".");
+        tmp_name.push(file_name);
+        tmp_name.push(format!(
+            ".{}.{}.tmp",
+            std::process::id(),
+            TMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let tmp_path = path.with_file_name(tmp_name);
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&tmp_path)?;
+        let result = file
+            .write_all(contents)
+            .and_then(|_| file.sync_all())
+            .and_then(|_| std::fs::rename(&tmp_path, path));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&tmp_path);
+        }
+        result
+    }
+
+    pub(super) fn read_private(path: &Path) -> std::io::Result<Option<Vec<u8>>> {
+        let mut file = std::fs::File::open(path)?;
+        if !is_private(&file.metadata()?) {
+            return Ok(None);
+        }
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)?;
+        Ok(Some(contents))
+    }
+
+    #[cfg(unix)]
+    fn is_private(metadata: &std::fs::Metadata) -> bool {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o077 == 0
+    }
+
+    #[cfg(not(unix))]
+    fn is_private(_metadata: &std::fs::Metadata) -> bool {
+        true
+    }
 }
 
 /// Environment variable abstraction
@@ -197,6 +333,26 @@ impl Env {
         }
     }
 
+    /// Returns the names of all environment variables starting with `prefix`.
+    ///
+    /// Variables whose name is not valid unicode are skipped.
+    pub fn keys_with_prefix(&self, prefix: &str) -> Vec<String> {
+        use env::Inner;
+        let mut keys: Vec<String> = match &self.0 {
+            Inner::Real => std::env::vars_os()
+                .filter_map(|(k, _)| k.into_string().ok())
+                .filter(|k| k.starts_with(prefix))
+                .collect(),
+            Inner::Fake(map) => map
+                .keys()
+                .filter(|k| k.starts_with(prefix))
+                .cloned()
+                .collect(),
+        };
+        keys.sort();
+        keys
+    }
+
     /// Create a fake process environment from a slice of tuples.
     ///
     /// # Examples
@@ -353,6 +509,13 @@ mod test {
         )
     }
 
+    #[test]
+    fn env_keys_with_prefix() {
+        let env = Env::from_slice(&[("AWS_B", "1"), ("AWS_A", "2"), ("OTHER", "3")]);
+        assert_eq!(env.keys_with_prefix("AWS_"), vec!["AWS_A", "AWS_B"]);
+        assert!(env.keys_with_prefix("NONE_").is_empty());
+    }
+
     #[test]
     fn fs_works() {
         let fs = Fs::from_test_dir(".", "/users/test-data");
@@ -369,6 +532,109 @@ mod test {
             .expect_err("file doesnt exists");
     }
 
+    #[test]
+    fn fs_write_works() {
+        let fs = Fs::from_slice(&[("config", "old")]);
+        fs.write("config", "new")
+            .now_or_never()
+            .expect("future should not poll")
+            .expect("write succeeds");
+        fs.write("other", "created")
+            .now_or_never()
+            .expect("future should not poll")
+            .expect("write succeeds");
+        for (path, expected) in [("config", "new"), ("other", "created")] {
+            let contents = fs
+                .read_to_end(path)
+                .now_or_never()
+                .expect("future should not poll")
+                .expect("file exists");
+            assert_eq!(contents, expected.as_bytes());
+        }
+    }
+
+    #[test]
+    fn fs_write_is_private() {
+        let dir = std::env::temp_dir().join(format!("aws-types-fs-{}", std::process::id()));
+        let fs = Fs::from_test_dir(&dir, "/home");
+        fs.write("/home/cache/file", "secret")
+            .now_or_never()
+            .expect("future should not poll")
+            .expect("write succeeds");
+        let read_private = |fs: &Fs| {
+            fs.read_private_to_end("/home/cache/file")
+                .now_or_never()
+                .expect("future should not poll")
+                .expect("file exists")
+        };
+        assert_eq!(Some(b"secret".to_vec()), read_private(&fs));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(
+                dir.join("cache/file"),
+                std::fs::Permissions::from_mode(0o644),
+            )
+            .unwrap();
+            assert_eq!(None, read_private(&fs));
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let fs = Fs::from_slice(&[("file", "contents")]);
+        assert_eq!(
+            Some(b"contents".to_vec()),
+            fs.read_private_to_end("file")
+                .now_or_never()
+                .unwrap()
+                .unwrap()
+        );
+        assert!(fs
+            .read_private_to_end("other")
+            .now_or_never()
+            .unwrap()
+            .is_err());
+    }
+
+    #[test]
+    fn fs_concurrent_writes_do_not_clobber_each_other() {
+        let dir =
+            std::env::temp_dir().join(format!("aws-types-fs-concurrent-{}", std::process::id()));
+        let fs = Fs::from_test_dir(&dir, "/home");
+        let contents: Vec<String> = (0..8).map(|i| i.to_string().repeat(64 * 1024)).collect();
+        let writers: Vec<_> = contents
+            .iter()
+            .cloned()
+            .map(|contents| {
+                let fs = fs.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..10 {
+                        fs.write("/home/cache/file", &contents)
+                            .now_or_never()
+                            .expect("future should not poll")
+                            .expect("write succeeds");
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        let written = fs
+            .read_to_end("/home/cache/file")
+            .now_or_never()
+            .expect("future should not poll")
+            .expect("file exists");
+        assert!(contents
+            .iter()
+            .any(|contents| contents.as_bytes() == written));
+        let files: Vec<_> = std::fs::read_dir(dir.join("cache"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(files, vec!["file"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn ts_works() {
         let real = TimeSource::real();
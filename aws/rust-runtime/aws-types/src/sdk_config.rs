@@ -18,7 +18,7 @@ use aws_smithy_types::timeout::TimeoutConfig;
 
 use crate::app_name::AppName;
 use crate::credentials::SharedCredentialsProvider;
-use crate::endpoint::ResolveAwsEndpoint;
+use crate::endpoint::{EndpointUrlConfig, ResolveAwsEndpoint};
 use crate::region::Region;
 
 /// AWS Shared Configuration
@@ -28,6 +28,9 @@ pub struct SdkConfig {
     credentials_provider: Option<SharedCredentialsProvider>,
     region: Option<Region>,
     endpoint_resolver: Option<Arc<dyn ResolveAwsEndpoint>>,
+    endpoint_url_config: Option<EndpointUrlConfig>,
+    use_fips: Option<bool>,
+    use_dual_stack: Option<bool>,
     retry_config: Option<RetryConfig>,
     sleep_impl: Option<Arc<dyn AsyncSleep>>,
     timeout_config: Option<TimeoutConfig>,
@@ -45,6 +48,9 @@ pub struct Builder {
     credentials_provider: Option<SharedCredentialsProvider>,
     region: Option<Region>,
     endpoint_resolver: Option<Arc<dyn ResolveAwsEndpoint>>,
+    endpoint_url_config: Option<EndpointUrlConfig>,
+    use_fips: Option<bool>,
+    use_dual_stack: Option<bool>,
     retry_config: Option<RetryConfig>,
     sleep_impl: Option<Arc<dyn AsyncSleep>>,
     timeout_config: Option<TimeoutConfig>,
@@ -129,6 +135,87 @@ impl Builder {
         self
     }
 
+    /// Set the endpoint URLs configured for all services or for individual services
+    ///
+    /// An endpoint resolver set with [Self::endpoint_resolver] takes precedence over these URLs.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use aws_types::SdkConfig;
+    /// use aws_types::endpoint::EndpointUrlConfig;
+    /// let endpoint_urls = EndpointUrlConfig::builder()
+    ///     .service("DynamoDB", "http://localhost:8000")
+    ///     .build();
+    /// let config = SdkConfig::builder().endpoint_url_config(endpoint_urls).build();
+    /// ```
+    pub fn endpoint_url_config(mut self, endpoint_url_config: EndpointUrlConfig) -> Self {
+        self.set_endpoint_url_config(Some(endpoint_url_config));
+        self
+    }
+
+    /// Set the endpoint URLs configured for all services or for individual services
+    ///
+    /// # Examples
+    /// ```rust
+    /// use aws_types::SdkConfig;
+    /// use aws_types::endpoint::EndpointUrlConfig;
+    /// fn endpoint_urls_override() -> Option<EndpointUrlConfig> {
+    ///     // ...
+    ///     # None
+    /// }
+    /// let mut config = SdkConfig::builder();
+    /// config.set_endpoint_url_config(endpoint_urls_override());
+    /// config.build();
+    /// ```
+    pub fn set_endpoint_url_config(
+        &mut self,
+        endpoint_url_config: Option<EndpointUrlConfig>,
+    ) -> &mut Self {
+        self.endpoint_url_config = endpoint_url_config;
+        self
+    }
+
+    /// When true, send requests to FIPS compliant endpoints
+    ///
+    /// Resolving the endpoint fails if the service has no FIPS endpoint in the configured region.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use aws_types::SdkConfig;
+    /// let config = SdkConfig::builder().use_fips(true).build();
+    /// ```
+    pub fn use_fips(mut self, use_fips: bool) -> Self {
+        self.set_use_fips(Some(use_fips));
+        self
+    }
+
+    /// When true, send requests to FIPS compliant endpoints
+    pub fn set_use_fips(&mut self, use_fips: Option<bool>) -> &mut Self {
+        self.use_fips = use_fips;
+        self
+    }
+
+    /// When true, send requests to dual-stack endpoints, reachable over both IPv4 and IPv6
+    ///
+    /// Resolving the endpoint fails if the service has no dual-stack endpoint in the configured
+    /// region.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use aws_types::SdkConfig;
+    /// let config = SdkConfig::builder().use_dual_stack(true).build();
+    /// ```
+    pub fn use_dual_stack(mut self, use_dual_stack: bool) -> Self {
+        self.set_use_dual_stack(Some(use_dual_stack));
+        self
+    }
+
+    /// When true, send requests to dual-stack endpoints, reachable over both IPv4 and IPv6
+    pub fn set_use_dual_stack(&mut self, use_dual_stack: Option<bool>) -> &mut Self {
+        self.use_dual_stack = use_dual_stack;
+        self
+    }
+
     /// Set the retry_config for the builder
     ///
     /// _Note:_ Retries require a sleep implementation in order to work. When enabling retry, make
@@ -378,6 +465,9 @@ impl Builder {
             credentials_provider: self.credentials_provider,
             region: self.region,
             endpoint_resolver: self.endpoint_resolver,
+            endpoint_url_config: self.endpoint_url_config,
+            use_fips: self.use_fips,
+            use_dual_stack: self.use_dual_stack,
             retry_config: self.retry_config,
             sleep_impl: self.sleep_impl,
             timeout_config: self.timeout_config,
@@ -397,6 +487,21 @@ impl SdkConfig {
         self.endpoint_resolver.clone()
     }
 
+    /// Configured endpoint URLs
+    pub fn endpoint_url_config(&self) -> Option<&EndpointUrlConfig> {
+        self.endpoint_url_config.as_ref()
+    }
+
+    /// Configured FIPS setting
+    pub fn use_fips(&self) -> Option<bool> {
+        self.use_fips
+    }
+
+    /// Configured dual-stack setting
+    pub fn use_dual_stack(&self) -> Option<bool> {
+        self.use_dual_stack
+    }
+
     /// Configured retry config
     pub fn retry_config(&self) -> Option<&RetryConfig> {
         self.retry_config.as_ref()
@@ -106,6 +106,7 @@ class EndpointConfigCustomization(
         "PlaceholderParams" to placeholderEndpointParams,
         "ResolveAwsEndpoint" to resolveAwsEndpoint,
         "EndpointShim" to endpointShim,
+        "EndpointVariant" to runtimeConfig.awsEndpoint().asType().member("EndpointVariant"),
         "aws_types" to awsTypes(runtimeConfig).asType(),
     )
 
@@ -126,8 +127,12 @@ class EndpointConfigCustomization(
 //                """,
 //                *codegenScope,
 //            )
-            is ServiceConfig.BuilderStruct ->
+            is ServiceConfig.BuilderStruct -> {
                 rustTemplate("endpoint_resolver: Option<std::sync::Arc<dyn #{SmithyResolver}<#{PlaceholderParams}>>>,", *codegenScope)
+                rust("endpoint_url: Option<String>,")
+                rust("use_fips: Option<bool>,")
+                rust("use_dual_stack: Option<bool>,")
+            }
             ServiceConfig.BuilderImpl ->
                 rustTemplate(
                     """
@@ -157,6 +162,57 @@ class EndpointConfigCustomization(
                         self.endpoint_resolver = endpoint_resolver.map(|res|std::sync::Arc::new(#{EndpointShim}::from_arc(res) ) as _);
                         self
                     }
+
+                    /// Sets the URL of the endpoint to send requests to, e.g. to use a local service emulator.
+                    ///
+                    /// The generated endpoint resolver for `$moduleUseName` still determines the signing region and
+                    /// service. An endpoint resolver set with [`endpoint_resolver`](Self::endpoint_resolver) takes
+                    /// precedence over this URL.
+                    ///
+                    /// ## Examples
+                    /// ```no_run
+                    /// let config = $moduleUseName::Config::builder()
+                    ///     .endpoint_url("http://localhost:8080")
+                    ///     .build();
+                    /// ```
+                    pub fn endpoint_url(mut self, endpoint_url: impl Into<String>) -> Self {
+                        self.endpoint_url = Some(endpoint_url.into());
+                        self
+                    }
+
+                    /// Sets the URL of the endpoint to send requests to, e.g. to use a local service emulator.
+                    pub fn set_endpoint_url(&mut self, endpoint_url: Option<String>) -> &mut Self {
+                        self.endpoint_url = endpoint_url;
+                        self
+                    }
+
+                    /// When true, send requests to FIPS compliant endpoints.
+                    ///
+                    /// Requests fail if `$moduleUseName` has no FIPS endpoint in the configured region.
+                    pub fn use_fips(mut self, use_fips: bool) -> Self {
+                        self.use_fips = Some(use_fips);
+                        self
+                    }
+
+                    /// When true, send requests to FIPS compliant endpoints.
+                    pub fn set_use_fips(&mut self, use_fips: Option<bool>) -> &mut Self {
+                        self.use_fips = use_fips;
+                        self
+                    }
+
+                    /// When true, send requests to dual-stack endpoints, reachable over both IPv4 and IPv6.
+                    ///
+                    /// Requests fail if `$moduleUseName` has no dual-stack endpoint in the configured region.
+                    pub fn use_dual_stack(mut self, use_dual_stack: bool) -> Self {
+                        self.use_dual_stack = Some(use_dual_stack);
+                        self
+                    }
+
+                    /// When true, send requests to dual-stack endpoints, reachable over both IPv4 and IPv6.
+                    pub fn set_use_dual_stack(&mut self, use_dual_stack: Option<bool>) -> &mut Self {
+                        self.use_dual_stack = use_dual_stack;
+                        self
+                    }
                     """,
                     *codegenScope,
                 )
@@ -165,9 +221,16 @@ class EndpointConfigCustomization(
                 val resolverGenerator = EndpointResolverGenerator(codegenContext, endpointData)
                 rustTemplate(
                     """
-                    endpoint_resolver: self.endpoint_resolver.unwrap_or_else(||
-                        std::sync::Arc::new(#{EndpointShim}::from_resolver(#{Resolver}()))
-                    ),
+                    endpoint_resolver: self.endpoint_resolver.unwrap_or_else(|| {
+                        let variant = #{EndpointVariant}::default()
+                            .with_fips(self.use_fips.unwrap_or_default())
+                            .with_dual_stack(self.use_dual_stack.unwrap_or_default());
+                        std::sync::Arc::new(
+                            #{EndpointShim}::from_resolver(#{Resolver}())
+                                .with_endpoint_url(self.endpoint_url)
+                                .with_variant(variant)
+                        )
+                    }),
                     """,
                     *codegenScope, "Resolver" to resolverGenerator.resolver(),
                 )
@@ -232,6 +295,7 @@ class EndpointResolverGenerator(codegenContext: CodegenContext, private val endp
             "endpoint" to awsEndpoint.member("partition::endpoint"),
             "CredentialScope" to awsEndpoint.member("CredentialScope"),
             "Regionalized" to awsEndpoint.member("partition::Regionalized"),
+            "EndpointVariant" to awsEndpoint.member("EndpointVariant"),
             "Protocol" to awsEndpoint.member("partition::endpoint::Protocol"),
             "SignatureVersion" to awsEndpoint.member("partition::endpoint::SignatureVersion"),
             "PartitionResolver" to awsEndpoint.member("PartitionResolver"),
@@ -315,10 +379,49 @@ class EndpointResolverGenerator(codegenContext: CodegenContext, private val endp
                 }
             }
         }
+        partition.defaultVariants.forEach { (variant, endpoint) ->
+            withBlockTemplate(".default_variant(${variant.template}, ", ")", *codegenScope) {
+                with(endpoint) {
+                    render()
+                }
+            }
+        }
+        partition.endpointVariants.forEach { (region, variant, endpoint) ->
+            withBlockTemplate(".endpoint_variant(${region.dq()}, ${variant.template}, ", ")", *codegenScope) {
+                with(endpoint) {
+                    render()
+                }
+            }
+        }
         rust(""".build().expect("invalid partition")""")
     }
 
-    inner class EndpointMeta(private val endpoint: ObjectNode, service: String, dnsSuffix: String) {
+    /**
+     * The tags of an endpoint variant from endpoints.json, e.g. `["dualstack", "fips"]`
+     */
+    data class VariantTags(val fips: Boolean, val dualStack: Boolean) {
+        /** Rust template constructing the matching `EndpointVariant` */
+        val template = "#{EndpointVariant}::default().with_fips($fips).with_dual_stack($dualStack)"
+
+        companion object {
+            private val supportedTags = setOf("fips", "dualstack")
+
+            /** Returns `null` when the variant has tags that the SDK doesn't support */
+            fun fromNode(variant: ObjectNode): VariantTags? {
+                val tags = variant.expectArrayMember("tags").map { it.expectStringNode().value }.toSet()
+                if (tags.isEmpty() || !supportedTags.containsAll(tags)) {
+                    return null
+                }
+                return VariantTags(fips = "fips" in tags, dualStack = "dualstack" in tags)
+            }
+        }
+    }
+
+    inner class EndpointMeta(
+        private val endpoint: ObjectNode,
+        private val service: String,
+        private val dnsSuffix: String,
+    ) {
         private val uriTemplate =
             (endpoint.getStringMember("hostname").orNull() ?: throw CodegenException("endpoint must be defined"))
                 .value
@@ -347,6 +450,23 @@ class EndpointResolverGenerator(codegenContext: CodegenContext, private val endp
             return "V4"
         }
 
+        /**
+         * The FIPS and dual-stack variants listed in [variantSource], keyed by their tags
+         *
+         * Variants share the protocol, signature versions and credential scope of this endpoint. Variants without a
+         * hostname are skipped: they use the hostname of the partition's default variant at runtime.
+         */
+        fun variants(variantSource: ObjectNode): Map<VariantTags, EndpointMeta> =
+            variantSource.getArrayMember("variants").map { it.elements }.orElse(listOf())
+                .map { it.expectObjectNode() }
+                .mapNotNull { variant ->
+                    val tags = VariantTags.fromNode(variant) ?: return@mapNotNull null
+                    val hostname = variant.getStringMember("hostname").orNull() ?: return@mapNotNull null
+                    val variantDnsSuffix = variant.getStringMember("dnsSuffix").map { it.value }.orElse(dnsSuffix)
+                    tags to EndpointMeta(endpoint.withMember("hostname", hostname), service, variantDnsSuffix)
+                }
+                .toMap()
+
         fun RustWriter.render() {
             rustBlockTemplate("#{endpoint}::Metadata", *codegenScope) {
                 rust("uri_template: ${uriTemplate.dq()},")
@@ -382,6 +502,12 @@ class EndpointResolverGenerator(codegenContext: CodegenContext, private val endp
 
         val endpoints: List<Pair<String, EndpointMeta>>
 
+        // FIPS and dual-stack variants used in regions without a region-specific variant
+        val defaultVariants: Map<VariantTags, EndpointMeta>
+
+        // region-specific FIPS and dual-stack variants
+        val endpointVariants: List<Triple<String, VariantTags, EndpointMeta>>
+
         init {
 
             val partitionDefaults = config.expectObjectMember("defaults")
@@ -398,6 +524,12 @@ class EndpointResolverGenerator(codegenContext: CodegenContext, private val endp
             }
 
             defaults = EndpointMeta(mergedDefaults, endpointPrefix, dnsSuffix)
+            // service defaults only override the partition default variants with the same tags
+            defaultVariants = defaults.variants(partitionDefaults) + defaults.variants(serviceDefaults)
+            endpointVariants = service.getObjectMember("endpoints").orElse(Node.objectNode()).members.flatMap { (k, v) ->
+                val endpoint = EndpointMeta(mergedDefaults.merge(v.expectObjectNode()), endpointPrefix, dnsSuffix)
+                endpoint.variants(v.expectObjectNode()).map { (tags, variant) -> Triple(k.value, tags, variant) }
+            }
         }
 
         val regionalized: Boolean = service.getBooleanMemberOrDefault("isRegionalized", true)
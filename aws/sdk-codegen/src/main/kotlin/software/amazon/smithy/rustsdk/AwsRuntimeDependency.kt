@@ -43,9 +43,21 @@ fun RuntimeConfig.awsRoot(): RuntimeCrateLocation {
 
 object AwsRuntimeType {
     val S3Errors by lazy { RuntimeType.forInlineDependency(InlineAwsDependency.forRustFile("s3_errors")) }
-    val Presigning by lazy {
-        RuntimeType.forInlineDependency(InlineAwsDependency.forRustFile("presigning", visibility = Visibility.PUBLIC))
+    private val presigningDependency by lazy {
+        InlineAwsDependency.forRustFile("presigning", visibility = Visibility.PUBLIC)
     }
+    val Presigning by lazy { RuntimeType.forInlineDependency(presigningDependency) }
+
+    fun RuntimeConfig.presignedPost() = RuntimeType.forInlineDependency(
+        InlineAwsDependency.forRustFile(
+            "presigned_post", visibility = Visibility.PUBLIC,
+            presigningDependency,
+            CargoDependency.smithyJson(this),
+            CargoDependency.SmithyTypes(this),
+            awsTypes(),
+            awsRuntimeDependency("aws-sigv4"),
+        ),
+    )
 
     fun RuntimeConfig.defaultMiddleware() = RuntimeType.forInlineDependency(
         InlineAwsDependency.forRustFile(
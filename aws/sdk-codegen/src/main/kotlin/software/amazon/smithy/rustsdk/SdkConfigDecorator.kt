@@ -5,6 +5,7 @@
 
 package software.amazon.smithy.rustsdk
 
+import software.amazon.smithy.aws.traits.ServiceTrait
 import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
 import software.amazon.smithy.rust.codegen.client.smithy.customize.RustCodegenDecorator
 import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ConfigCustomization
@@ -18,6 +19,8 @@ import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
 import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.getTrait
 
 /**
  * Adds functionality for constructing `<service>::Config` objects from `aws_types::SdkConfig`s
@@ -40,6 +43,8 @@ class SdkConfigDecorator : RustCodegenDecorator<ClientProtocolGenerator, ClientC
         val codegenScope = arrayOf(
             "SdkConfig" to awsTypes(runtimeConfig = codegenContext.runtimeConfig).asType().member("sdk_config::SdkConfig"),
         )
+        val serviceShape = codegenContext.serviceShape
+        val sdkId = serviceShape.getTrait<ServiceTrait>()?.sdkId ?: serviceShape.id.name
         rustCrate.withModule(RustModule.Config) {
             // !!NOTE!! As more items are added to aws_types::SdkConfig, use them here to configure the config builder
             rustTemplate(
@@ -49,6 +54,11 @@ class SdkConfigDecorator : RustCodegenDecorator<ClientProtocolGenerator, ClientC
                         let mut builder = Builder::default();
                         builder = builder.region(input.region().cloned());
                         builder.set_endpoint_resolver(input.endpoint_resolver().clone());
+                        builder.set_endpoint_url(
+                            input.endpoint_url_config().and_then(|config| config.endpoint_url(${sdkId.dq()})).map(str::to_owned)
+                        );
+                        builder.set_use_fips(input.use_fips());
+                        builder.set_use_dual_stack(input.use_dual_stack());
                         builder.set_retry_config(input.retry_config().cloned());
                         builder.set_timeout_config(input.timeout_config().cloned());
                         builder.set_sleep_impl(input.sleep_impl());
@@ -26,6 +26,7 @@ import software.amazon.smithy.rust.codegen.core.rustlang.rustBlockTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.generators.LibRsCustomization
 import software.amazon.smithy.rust.codegen.core.smithy.generators.LibRsSection
@@ -34,6 +35,7 @@ import software.amazon.smithy.rust.codegen.core.smithy.protocols.RestXml
 import software.amazon.smithy.rust.codegen.core.smithy.traits.AllowInvalidXmlRoot
 import software.amazon.smithy.rust.codegen.core.util.letIf
 import software.amazon.smithy.rustsdk.AwsRuntimeType
+import software.amazon.smithy.rustsdk.AwsRuntimeType.presignedPost
 import java.util.logging.Logger
 
 /**
@@ -78,7 +80,7 @@ class S3Decorator : RustCodegenDecorator<ClientProtocolGenerator, ClientCodegenC
         codegenContext: ClientCodegenContext,
         baseCustomizations: List<LibRsCustomization>,
     ): List<LibRsCustomization> = baseCustomizations.letIf(applies(codegenContext.serviceShape.id)) {
-        it + S3PubUse()
+        it + S3PubUse() + S3PresignedPost(codegenContext.runtimeConfig)
     }
 
     override fun supportsCodegenContext(clazz: Class<out CodegenContext>): Boolean =
@@ -138,3 +140,15 @@ class S3PubUse : LibRsCustomization() {
         else -> emptySection
     }
 }
+
+/**
+ * Adds the `presigned_post` module, to create presigned POST forms for browser based uploads
+ */
+class S3PresignedPost(private val runtimeConfig: RuntimeConfig) : LibRsCustomization() {
+    override fun section(section: LibRsSection): Writable = when (section) {
+        is LibRsSection.Body -> writable {
+            addDependency(runtimeConfig.presignedPost().dependency!!)
+        }
+        else -> emptySection
+    }
+}
@@ -64,7 +64,11 @@ internal class EndpointConfigCustomizationTest {
             "defaults" : {
               "hostname" : "{service}.{region}.{dnsSuffix}",
               "protocols" : [ "https" ],
-              "signatureVersions" : [ "v4" ]
+              "signatureVersions" : [ "v4" ],
+              "variants" : [ {
+                "hostname" : "{service}-fips.{region}.{dnsSuffix}",
+                "tags" : [ "fips" ]
+              } ]
             },
             "dnsSuffix" : "amazonaws.com",
             "partition" : "aws",
@@ -101,7 +105,11 @@ internal class EndpointConfigCustomizationTest {
                     "credentialScope" : {
                       "region" : "us-east-1"
                     },
-                    "hostname" : "iam.amazonaws.com"
+                    "hostname" : "iam.amazonaws.com",
+                    "variants" : [ {
+                      "hostname" : "iam-fips.amazonaws.com",
+                      "tags" : [ "fips" ]
+                    } ]
                   },
                   "iam-fips" : {
                     "credentialScope" : {
@@ -175,6 +183,29 @@ internal class EndpointConfigCustomizationTest {
         }
     }
 
+    @Test
+    fun `support endpoint variants`() {
+        validateEndpointCustomizationForService("test#TestService") { crate ->
+            crate.lib {
+                unitTest("endpoint_variants") {
+                    rustTemplate(
+                        """
+                        let params = ::#{PlaceholderParams}::new(Some(#{aws_types}::region::Region::new("us-west-2")));
+                        let conf = crate::config::Config::builder().use_fips(true).build();
+                        let endpoint = conf.endpoint_resolver.resolve_endpoint(&params).expect("FIPS endpoint exists");
+                        assert_eq!(endpoint.url(), "https://service-with-prefix-fips.us-west-2.amazonaws.com/");
+
+                        let conf = crate::config::Config::builder().use_dual_stack(true).build();
+                        let err = conf.endpoint_resolver.resolve_endpoint(&params).expect_err("no dual-stack endpoint");
+                        assert!(format!("{:?}", err).contains("no dual-stack endpoint is available in region `us-west-2`"), "{:?}", err);
+                        """,
+                        *codegenScope,
+                    )
+                }
+            }
+        }
+    }
+
     @Test
     fun `support region-agnostic services`() {
         validateEndpointCustomizationForService("test#NoRegions") { crate ->
@@ -190,6 +221,11 @@ internal class EndpointConfigCustomizationTest {
                         let endpoint = conf.endpoint_resolver
                             .resolve_endpoint(&::#{PlaceholderParams}::new(Some(#{aws_types}::region::Region::new("iam-fips")))).expect("default resolver produces a valid endpoint");
                         assert_eq!(endpoint.url(), "https://iam-fips.amazonaws.com/");
+
+                        let conf = crate::config::Config::builder().use_fips(true).build();
+                        let endpoint = conf.endpoint_resolver
+                            .resolve_endpoint(&::#{PlaceholderParams}::new(Some(#{aws_types}::region::Region::new("us-east-1")))).expect("default resolver produces a valid endpoint");
+                        assert_eq!(endpoint.url(), "https://iam-fips.amazonaws.com/");
                         """,
                         *codegenScope,
                     )
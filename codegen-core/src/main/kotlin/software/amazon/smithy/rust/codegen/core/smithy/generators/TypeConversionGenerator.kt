@@ -8,6 +8,7 @@ package software.amazon.smithy.rust.codegen.core.smithy.generators
 import software.amazon.smithy.codegen.core.Symbol
 import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.shapes.BlobShape
+import software.amazon.smithy.model.shapes.DocumentShape
 import software.amazon.smithy.model.shapes.Shape
 import software.amazon.smithy.model.shapes.TimestampShape
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
@@ -22,7 +23,7 @@ import software.amazon.smithy.rust.codegen.core.smithy.rustType
  * Utility class used to force casting a non primitive type into one overriden by a new symbol provider,
  * by explicitly calling `from()` or into().
  *
- * For example we use this in the server Python implementation, where we override types like [Blob] and [DateTime]
+ * For example we use this in the server Python implementation, where we override types like [Blob], [DateTime] and [Document]
  * with wrappers compatible with Python, without touching the original implementation coming from `aws-smithy-types`.
  */
 class TypeConversionGenerator(private val model: Model, private val symbolProvider: RustSymbolProvider, private val runtimeConfig: RuntimeConfig) {
@@ -30,6 +31,7 @@ class TypeConversionGenerator(private val model: Model, private val symbolProvid
         return when (shape) {
             is BlobShape -> RuntimeType.Blob(runtimeConfig).toSymbol()
             is TimestampShape -> RuntimeType.DateTime(runtimeConfig).toSymbol()
+            is DocumentShape -> RuntimeType.Document(runtimeConfig).toSymbol()
             else -> symbolProvider.toSymbol(shape)
         }
     }
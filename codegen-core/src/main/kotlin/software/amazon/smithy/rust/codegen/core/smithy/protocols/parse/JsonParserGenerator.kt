@@ -229,7 +229,11 @@ class JsonParserGenerator(
             is MapShape -> deserializeMap(target)
             is StructureShape -> deserializeStruct(target)
             is UnionShape -> deserializeUnion(target)
-            is DocumentShape -> rustTemplate("Some(#{expect_document}(tokens)?)", *codegenScope)
+            is DocumentShape -> rustTemplate(
+                "Some(#{expect_document}(tokens)?)#{ConvertFrom:W}",
+                "ConvertFrom" to typeConversionGenerator.convertViaFrom(target),
+                *codegenScope,
+            )
             else -> PANIC("unexpected shape: $target")
         }
         val symbol = symbolProvider.toSymbol(memberShape)
@@ -399,7 +399,10 @@ class JsonSerializerGenerator(
             is UnionShape -> jsonObjectWriter(context) { objectName ->
                 serializeUnion(Context(objectName, value, target))
             }
-            is DocumentShape -> rust("$writer.document(${value.asRef()});")
+            is DocumentShape -> rustTemplate(
+                "$writer.document(${value.asRef()}#{ConvertInto:W});",
+                "ConvertInto" to typeConversionGenerator.convertViaInto(target),
+            )
             else -> TODO(target.toString())
         }
     }
@@ -27,6 +27,9 @@ object PythonServerRuntimeType {
     fun DateTime(runtimeConfig: RuntimeConfig) =
         RuntimeType("DateTime", PythonServerCargoDependency.SmithyHttpServerPython(runtimeConfig), "${runtimeConfig.crateSrcPrefix}_http_server_python::types")
 
+    fun Document(runtimeConfig: RuntimeConfig) =
+        RuntimeType("Document", PythonServerCargoDependency.SmithyHttpServerPython(runtimeConfig), "${runtimeConfig.crateSrcPrefix}_http_server_python::types")
+
     fun PyError(runtimeConfig: RuntimeConfig) =
         RuntimeType("Error", PythonServerCargoDependency.SmithyHttpServerPython(runtimeConfig), "${runtimeConfig.crateSrcPrefix}_http_server_python")
 }
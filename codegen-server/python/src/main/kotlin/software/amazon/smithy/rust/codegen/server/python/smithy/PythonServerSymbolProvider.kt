@@ -8,6 +8,7 @@ package software.amazon.smithy.rust.codegen.server.python.smithy
 import software.amazon.smithy.codegen.core.Symbol
 import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.shapes.BlobShape
+import software.amazon.smithy.model.shapes.DocumentShape
 import software.amazon.smithy.model.shapes.MemberShape
 import software.amazon.smithy.model.shapes.ServiceShape
 import software.amazon.smithy.model.shapes.Shape
@@ -77,6 +78,10 @@ class PythonServerSymbolVisitor(
     override fun blobShape(shape: BlobShape?): Symbol {
         return PythonServerRuntimeType.Blob(runtimeConfig).toSymbol()
     }
+
+    override fun documentShape(shape: DocumentShape?): Symbol {
+        return PythonServerRuntimeType.Document(runtimeConfig).toSymbol()
+    }
 }
 
 /**
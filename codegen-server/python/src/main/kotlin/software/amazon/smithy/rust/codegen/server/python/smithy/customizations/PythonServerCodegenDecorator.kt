@@ -61,6 +61,7 @@ class PubUsePythonTypes(private val codegenContext: ServerCodegenContext) : LibR
                 rustBlock("pub mod python_types") {
                     rust("pub use #T;", PythonServerRuntimeType.Blob(codegenContext.runtimeConfig).toSymbol())
                     rust("pub use #T;", PythonServerRuntimeType.DateTime(codegenContext.runtimeConfig).toSymbol())
+                    rust("pub use #T;", PythonServerRuntimeType.Document(codegenContext.runtimeConfig).toSymbol())
                 }
             }
             else -> emptySection
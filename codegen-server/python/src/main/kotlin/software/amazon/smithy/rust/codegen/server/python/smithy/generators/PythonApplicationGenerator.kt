@@ -55,6 +55,8 @@ import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.Ser
  * * `App()`: constructor to create an instance of `App`.
  * * `run()`: run the application on a number of workers.
  * * `context()`: register the context object that is passed to the Python handlers.
+ * * `test_client()`: build the service and return a client sending requests to it in-process.
+ * * `sync_handler_pool_stats()`: return the load of the pool running the synchronous handlers.
  * * One register method per operation that can be used as decorator. For example if
  *   the model has one operation called `RegisterServer`, it will codegenerate a method
  *   of `App` called `register_service()` that can be used to decorate the Python implementation
@@ -110,6 +112,7 @@ class PythonApplicationGenerator(
                 middlewares: Vec<#{SmithyPython}::PyMiddlewareHandler>,
                 context: Option<#{pyo3}::PyObject>,
                 workers: #{parking_lot}::Mutex<Vec<#{pyo3}::PyObject>>,
+                sync_handler_pool: Option<#{SmithyPython}::SyncHandlerPool>,
             }
             """,
             *codegenScope,
@@ -126,6 +129,7 @@ class PythonApplicationGenerator(
                         middlewares: self.middlewares.clone(),
                         context: self.context.clone(),
                         workers: #{parking_lot}::Mutex::new(vec![]),
+                        sync_handler_pool: None,
                     }
                 }
             }
@@ -144,6 +148,7 @@ class PythonApplicationGenerator(
                         middlewares: vec![],
                         context: None,
                         workers: #{parking_lot}::Mutex::new(vec![]),
+                        sync_handler_pool: None,
                     }
                 }
             }
@@ -177,7 +182,11 @@ class PythonApplicationGenerator(
 
             rustBlockTemplate(
                 """
-                fn build_service(&mut self, event_loop: &#{pyo3}::PyAny) -> #{pyo3}::PyResult<
+                fn build_service(
+                    &mut self,
+                    event_loop: &#{pyo3}::PyAny,
+                    sync_handler_pool: #{SmithyPython}::SyncHandlerPool,
+                ) -> #{pyo3}::PyResult<
                     #{tower}::util::BoxCloneService<
                         #{http}::Request<#{SmithyServer}::body::Body>, 
                         #{http}::Response<#{SmithyServer}::body::BoxBody>, 
@@ -189,6 +198,7 @@ class PythonApplicationGenerator(
             ) {
                 rustTemplate(
                     """
+                    self.sync_handler_pool = Some(sync_handler_pool.clone());
                     let builder = crate::service::$serviceName::builder();
                     """,
                     *codegenScope,
@@ -200,34 +210,31 @@ class PythonApplicationGenerator(
                         """
                         let ${name}_locals = #{pyo3_asyncio}::TaskLocals::new(event_loop);
                         let handler = self.handlers.get("$name").expect("Python handler for operation `$name` not found").clone();
-                        let builder = builder.$name(move |input, state| {
-                            #{pyo3_asyncio}::tokio::scope(${name}_locals.clone(), crate::operation_handler::$name(input, state, handler.clone()))
-                        });
+                        let ${name}_operation = {
+                            use #{SmithyServer}::operation::OperationShapeExt;
+                            let operation_ext = #{SmithyServer}::extension::OperationExtension::new("${operation.id.namespace}.${operation.id.name}").expect("malformed absolute shape ID");
+                            let mut layer = #{SmithyPython}::PyOperationLayer::<#{Protocol}>::new(operation_ext, self.middlewares.clone(), ${name}_locals.clone());
+                            if !handler.is_coroutine {
+                                layer = layer.with_sync_handler_pool(sync_handler_pool.clone());
+                            }
+                            let pool = sync_handler_pool.clone();
+                            crate::operation_shape::${operationName.toPascalCase()}::from_handler(move |input, state, context| {
+                                #{pyo3_asyncio}::tokio::scope(${name}_locals.clone(), crate::operation_handler::$name(input, state, context, handler.clone(), pool.clone()))
+                            })
+                            .layer(layer)
+                        };
+                        let builder = builder.${name}_operation(${name}_operation);
                         """,
+                        "Protocol" to protocol.markerStruct(),
                         *codegenScope,
                     )
                 }
                 rustTemplate(
                     """
-                    let mut service = #{tower}::util::BoxCloneService::new(builder.build());
-
-                    {
-                        use #{tower}::Layer;
-                        #{tracing}::trace!("adding middlewares to rust python router");
-                        let mut middlewares = self.middlewares.clone();
-                        // Reverse the middlewares, so they run with same order as they defined
-                        middlewares.reverse();
-                        for handler in middlewares {
-                            #{tracing}::trace!(name = &handler.name, "adding python middleware");
-                            let locals = #{pyo3_asyncio}::TaskLocals::new(event_loop);
-                            let layer = #{SmithyPython}::PyMiddlewareLayer::<#{Protocol}>::new(handler, locals);
-                            service = #{tower}::util::BoxCloneService::new(layer.layer(service));
-                        }
-                    }
+                    let service = #{tower}::util::BoxCloneService::new(builder.build());
 
                     Ok(service)
                     """,
-                    "Protocol" to protocol.markerStruct(),
                     *codegenScope,
                 )
             }
@@ -235,6 +242,7 @@ class PythonApplicationGenerator(
     }
 
     private fun renderPyMethods(writer: RustWriter) {
+        val operationNames = operations.joinToString(", ") { "\"${it.id.name}\", \"${it.id.namespace}.${it.id.name}\"" }
         writer.rustBlockTemplate(
             """
             ##[#{pyo3}::pymethods]
@@ -255,19 +263,41 @@ class PythonApplicationGenerator(
                    self.context = Some(context);
                 }
                 /// Register a Python function to be executed inside a Tower middleware layer.
-                ##[pyo3(text_signature = "(${'$'}self, func)")]
-                pub fn middleware(&mut self, py: #{pyo3}::Python, func: #{pyo3}::PyObject) -> #{pyo3}::PyResult<()> {
-                    let handler = #{SmithyPython}::PyMiddlewareHandler::new(py, func)?;
+                ///
+                /// If `operations` is set, the middleware only runs for the operations listed by name.
+                /// Middlewares run after routing, so requests that don't match any operation,
+                /// such as requests answered with a 404, don't go through them.
+                ##[pyo3(text_signature = "(${'$'}self, func, operations=None)")]
+                ##[args(operations = "None")]
+                pub fn middleware(
+                    &mut self,
+                    py: #{pyo3}::Python,
+                    func: #{pyo3}::PyObject,
+                    operations: Option<Vec<String>>,
+                ) -> #{pyo3}::PyResult<()> {
+                    const OPERATIONS: &[&str] = &[$operationNames];
+                    if let Some(unknown) = operations.iter().flatten().find(|name| !OPERATIONS.contains(&name.as_str())) {
+                        return Err(#{pyo3}::exceptions::PyValueError::new_err(format!("unknown operation `{}`", unknown)));
+                    }
+                    let mut handler = #{SmithyPython}::PyMiddlewareHandler::new(py, func)?;
+                    handler.operations = operations;
                     #{tracing}::trace!(
                         name = &handler.name,
                         is_coroutine = handler.is_coroutine,
+                        operations = ?handler.operations,
                         "registering middleware function",
                     );
                     self.middlewares.push(handler);
                     Ok(())
                 }
+                /// Return the current load of the pool running the synchronous handlers of this worker,
+                /// or `None` if the service hasn't been built in this process.
+                ##[pyo3(text_signature = "(${'$'}self)")]
+                pub fn sync_handler_pool_stats(&self) -> Option<#{SmithyPython}::SyncHandlerPoolStats> {
+                    self.sync_handler_pool.as_ref().map(#{SmithyPython}::SyncHandlerPool::stats)
+                }
                 /// Main entrypoint: start the server on multiple workers.
-                ##[pyo3(text_signature = "(${'$'}self, address, port, backlog, workers)")]
+                ##[pyo3(text_signature = "(${'$'}self, address, port, backlog, workers, tls, worker_config)")]
                 pub fn run(
                     &mut self,
                     py: #{pyo3}::Python,
@@ -275,9 +305,11 @@ class PythonApplicationGenerator(
                     port: Option<i32>,
                     backlog: Option<i32>,
                     workers: Option<usize>,
+                    tls: Option<#{SmithyPython}::PyTlsConfig>,
+                    worker_config: Option<#{SmithyPython}::PyWorkerConfig>,
                 ) -> #{pyo3}::PyResult<()> {
                     use #{SmithyPython}::PyApp;
-                    self.run_server(py, address, port, backlog, workers)
+                    self.run_server(py, address, port, backlog, workers, tls, worker_config)
                 }
                 /// Lambda entrypoint: start the server on Lambda.
                 ##[pyo3(text_signature = "(${'$'}self)")]
@@ -288,18 +320,37 @@ class PythonApplicationGenerator(
                     use #{SmithyPython}::PyApp;
                     self.run_lambda_handler(py)
                 }
+                /// Build the service and return a client sending requests to it in-process,
+                /// without binding any socket or starting workers.
+                ///
+                /// When called from a coroutine, the client runs handlers on the running event loop.
+                ##[pyo3(text_signature = "(${'$'}self)")]
+                pub fn test_client(
+                    &mut self,
+                    py: #{pyo3}::Python,
+                ) -> #{pyo3}::PyResult<#{SmithyPython}::PyTestClient> {
+                    use #{SmithyPython}::PyApp;
+                    let event_loop = match py.import("asyncio")?.call_method0("get_running_loop") {
+                        Ok(event_loop) => event_loop,
+                        Err(_) => self.configure_python_event_loop(py)?,
+                    };
+                    let service = self.build_and_configure_service(py, event_loop, &#{SmithyPython}::PyWorkerConfig::default())?;
+                    Ok(#{SmithyPython}::PyTestClient::new(service, event_loop))
+                }
                 /// Build the service and start a single worker.
-                ##[pyo3(text_signature = "(${'$'}self, socket, worker_number)")]
+                ##[pyo3(text_signature = "(${'$'}self, socket, worker_number, tls, worker_config)")]
                 pub fn start_worker(
                     &mut self,
                     py: pyo3::Python,
                     socket: &pyo3::PyCell<#{SmithyPython}::PySocket>,
                     worker_number: isize,
+                    tls: Option<#{SmithyPython}::PyTlsConfig>,
+                    worker_config: Option<#{SmithyPython}::PyWorkerConfig>,
                 ) -> pyo3::PyResult<()> {
                     use #{SmithyPython}::PyApp;
                     let event_loop = self.configure_python_event_loop(py)?;
-                    let service = self.build_and_configure_service(py, event_loop)?;
-                    self.start_hyper_worker(py, socket, event_loop, service, worker_number)
+                    let service = self.build_and_configure_service(py, event_loop, &worker_config.clone().unwrap_or_default())?;
+                    self.start_hyper_worker(py, socket, event_loop, service, worker_number, tls, worker_config)
                 }
                 """,
                 *codegenScope,
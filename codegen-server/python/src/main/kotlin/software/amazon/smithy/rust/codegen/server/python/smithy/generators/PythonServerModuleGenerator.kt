@@ -47,6 +47,8 @@ class PythonServerModuleGenerator(
                 renderPyCodegeneratedTypes()
                 renderPyWrapperTypes()
                 renderPySocketType()
+                renderPyTlsTypes()
+                renderPyTestingTypes()
                 renderPyLogging()
                 renderPyMiddlewareTypes()
                 renderPyApplicationType()
@@ -127,6 +129,41 @@ class PythonServerModuleGenerator(
         )
     }
 
+    // Render Python TLS configuration type.
+    private fun RustWriter.renderPyTlsTypes() {
+        rustTemplate(
+            """
+            let tls = #{pyo3}::types::PyModule::new(py, "tls")?;
+            tls.add_class::<#{SmithyPython}::PyTlsConfig>()?;
+            #{pyo3}::py_run!(
+                py,
+                tls,
+                "import sys; sys.modules['$libName.tls'] = tls"
+            );
+            m.add_submodule(tls)?;
+            """,
+            *codegenScope,
+        )
+    }
+
+    // Render Python in-process test client types.
+    private fun RustWriter.renderPyTestingTypes() {
+        rustTemplate(
+            """
+            let testing = #{pyo3}::types::PyModule::new(py, "testing")?;
+            testing.add_class::<#{SmithyPython}::PyTestClient>()?;
+            testing.add_class::<#{SmithyPython}::PyTestResponse>()?;
+            #{pyo3}::py_run!(
+                py,
+                testing,
+                "import sys; sys.modules['$libName.testing'] = testing"
+            );
+            m.add_submodule(testing)?;
+            """,
+            *codegenScope,
+        )
+    }
+
     // Render Python shared socket type.
     private fun RustWriter.renderPyLogging() {
         rustTemplate(
@@ -168,6 +205,8 @@ class PythonServerModuleGenerator(
         rustTemplate(
             """
             m.add_class::<crate::python_server_application::App>()?;
+            m.add_class::<#{SmithyPython}::PyWorkerConfig>()?;
+            m.add_class::<#{SmithyPython}::SyncHandlerPoolStats>()?;
             Ok(())
             """,
             *codegenScope,
@@ -27,7 +27,7 @@ import software.amazon.smithy.rust.codegen.server.smithy.generators.protocol.Ser
  *
  * To call a Python function from Rust, anything dealing with Python runs inside an async
  * block that allows to catch stacktraces. The handler function is extracted from `PyHandler`
- * and called with the necessary arguments inside a blocking Tokio task.
+ * and called with the necessary arguments on a thread of the worker's `SyncHandlerPool`.
  * At the end the block is awaited and errors are collected and reported.
  *
  * To call a Python coroutine, the same happens, but scheduled in a `tokio::Future`.
@@ -68,7 +68,9 @@ class PythonServerOperationHandlerGenerator(
                 pub(crate) async fn $fnName(
                     input: $input,
                     state: #{SmithyServer}::Extension<#{pyo3}::PyObject>,
+                    context: #{SmithyServer}::Extension<#{SmithyPython}::PyRequestContext>,
                     handler: #{SmithyPython}::PyHandler,
+                    sync_handler_pool: #{SmithyPython}::SyncHandlerPool,
                 ) -> std::result::Result<$output, $error> {
                     // Async block used to run the handler and catch any Python error.
                     let result = if handler.is_coroutine {
@@ -92,15 +94,18 @@ class PythonServerOperationHandlerGenerator(
             rustTemplate(
                 """
                 #{tracing}::trace!(name = "$name", "executing python handler function");
-                #{pyo3}::Python::with_gil(|py| {
+                sync_handler_pool.run(move || #{pyo3}::Python::with_gil(|py| {
                     let pyhandler: &#{pyo3}::types::PyFunction = handler.extract(py)?;
-                    let output = if handler.args == 1 {
-                        pyhandler.call1((input,))?
-                    } else {
-                        pyhandler.call1((input, state.0))?
+                    let output = match handler.args {
+                        1 => pyhandler.call1((input,))?,
+                        2 => pyhandler.call1((input, state.0))?,
+                        3 => pyhandler.call1((input, state.0, context.0))?,
+                        args => return Err(#{pyo3}::exceptions::PyTypeError::new_err(format!(
+                            "handler `$name` must take 1, 2 or 3 arguments, not {}", args
+                        ))),
                     };
                     output.extract::<$output>()
-                })
+                })).await
                 """,
                 *codegenScope,
             )
@@ -113,10 +118,13 @@ class PythonServerOperationHandlerGenerator(
                 #{tracing}::trace!(name = "$name", "executing python handler coroutine");
                 let result = #{pyo3}::Python::with_gil(|py| {
                     let pyhandler: &#{pyo3}::types::PyFunction = handler.extract(py)?;
-                    let coroutine = if handler.args == 1 {
-                        pyhandler.call1((input,))?
-                    } else {
-                        pyhandler.call1((input, state.0))?
+                    let coroutine = match handler.args {
+                        1 => pyhandler.call1((input,))?,
+                        2 => pyhandler.call1((input, state.0))?,
+                        3 => pyhandler.call1((input, state.0, context.0))?,
+                        args => return Err(#{pyo3}::exceptions::PyTypeError::new_err(format!(
+                            "handler `$name` must take 1, 2 or 3 arguments, not {}", args
+                        ))),
                     };
                     #{pyo3_asyncio}::tokio::into_future(coroutine)
                 })?;
@@ -153,7 +153,7 @@ class ServerInstantiatorTest {
                 rust(
                     """
                     use std::collections::HashMap;
-                    use aws_smithy_types::{DateTime, Document};
+                    use aws_smithy_types::{DateTime, Document, DocumentMap};
 
                     let expected = MyStructRequired {
                         str: "".to_owned(),
@@ -171,7 +171,7 @@ class ServerInstantiatorTest {
                         },
                         list: Vec::new(),
                         map: HashMap::new(),
-                        doc: Document::Object(HashMap::new()),
+                        doc: Document::Object(DocumentMap::new()),
                     };
                     assert_eq!(result, expected);
                     """,
@@ -0,0 +1,587 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::major_type::*;
+use crate::{
+    BREAK, DOUBLE_FLOAT, EPOCH_DATE_TIME_TAG, FALSE, HALF_FLOAT, INDEFINITE_LENGTH, NULL,
+    SINGLE_FLOAT, TRUE, UNDEFINED,
+};
+use aws_smithy_types::{DateTime, Number};
+use std::borrow::Cow;
+
+mod error;
+pub mod token;
+
+pub use error::{Error, ErrorReason};
+pub use token::{Offset, Token};
+
+use ErrorReason::*;
+
+/// CBOR token parser as a Rust iterator
+///
+/// This parser will parse and yield exactly one [`Token`] per iterator `next()` call.
+/// Validation is done on the fly, so it is possible for it to parse an invalid CBOR data item
+/// until it gets to the first [`Error`].
+///
+/// Definite and indefinite length arrays and maps both yield start and end tokens. Definite
+/// length strings are borrowed from the `input`, while indefinite length strings are
+/// concatenated into an owned value. Epoch-based date/times (tag 1) are yielded as
+/// [`Token::ValueDateTime`], and other tags are rejected.
+///
+/// The parser *will* accept multiple consecutive data items. It is the responsibility of the
+/// caller to handle this for their use-case.
+pub fn cbor_token_iter(input: &[u8]) -> CborTokenIterator<'_> {
+    CborTokenIterator {
+        input,
+        index: 0,
+        state_stack: Vec::new(),
+    }
+}
+
+/// Internal parser state for an array or map being parsed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct State {
+    /// True if the container is a map.
+    object: bool,
+    /// Number of data items left in the container, or `None` if it has an indefinite length.
+    /// The keys and values of a map count as separate data items.
+    remaining: Option<u64>,
+    /// True if the next data item of a map is a key.
+    expecting_key: bool,
+}
+
+/// An iterator over a `&[u8]` that yields `Result<Token, Error>` with [Token] being CBOR tokens.
+/// Construct with [cbor_token_iter].
+pub struct CborTokenIterator<'a> {
+    input: &'a [u8],
+    index: usize,
+    state_stack: Vec<State>,
+}
+
+impl<'a> CborTokenIterator<'a> {
+    /// Creates an error at the given `offset` in the stream.
+    fn error_at(&self, offset: usize, reason: ErrorReason) -> Error {
+        Error::new(reason, Some(offset))
+    }
+
+    /// Creates an error at the current offset in the stream.
+    fn error(&self, reason: ErrorReason) -> Error {
+        self.error_at(self.index, reason)
+    }
+
+    /// Returns current offset
+    fn offset(&self) -> Offset {
+        Offset(self.index)
+    }
+
+    /// Returns the next `len` bytes and advances past them.
+    fn next_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let end = self
+            .index
+            .checked_add(len)
+            .filter(|end| *end <= self.input.len())
+            .ok_or_else(|| self.error_at(self.input.len(), UnexpectedEos))?;
+        let bytes = &self.input[self.index..end];
+        self.index = end;
+        Ok(bytes)
+    }
+
+    fn next_byte(&mut self) -> Result<u8, Error> {
+        Ok(self.next_bytes(1)?[0])
+    }
+
+    /// Reads the argument of a data item, given the additional information of its initial byte.
+    /// Returns `None` for indefinite lengths.
+    fn read_argument(&mut self, offset: usize, info: u8) -> Result<Option<u64>, Error> {
+        Ok(Some(match info {
+            0..=23 => info as u64,
+            24 => self.next_byte()? as u64,
+            25 => u16::from_be_bytes(self.next_bytes(2)?.try_into().unwrap()) as u64,
+            26 => u32::from_be_bytes(self.next_bytes(4)?.try_into().unwrap()) as u64,
+            27 => u64::from_be_bytes(self.next_bytes(8)?.try_into().unwrap()),
+            INDEFINITE_LENGTH => return Ok(None),
+            _ => return Err(self.error_at(offset, InvalidAdditionalInfo(info))),
+        }))
+    }
+
+    /// Reads a definite length argument.
+    fn read_definite_argument(&mut self, offset: usize, info: u8) -> Result<u64, Error> {
+        self.read_argument(offset, info)?
+            .ok_or_else(|| self.error_at(offset, InvalidAdditionalInfo(info)))
+    }
+
+    fn read_len(&mut self, offset: usize, len: u64) -> Result<&'a [u8], Error> {
+        let len = usize::try_from(len).map_err(|_| self.error_at(offset, UnexpectedEos))?;
+        self.next_bytes(len)
+    }
+
+    /// Reads a byte or text string of the given major type, concatenating the chunks of
+    /// indefinite length strings.
+    fn read_string(
+        &mut self,
+        offset: usize,
+        major_type: u8,
+        info: u8,
+    ) -> Result<Cow<'a, [u8]>, Error> {
+        match self.read_argument(offset, info)? {
+            Some(len) => Ok(Cow::Borrowed(self.read_len(offset, len)?)),
+            None => {
+                let mut value = Vec::new();
+                loop {
+                    let chunk_offset = self.index;
+                    let initial_byte = self.next_byte()?;
+                    if initial_byte == BREAK {
+                        break;
+                    }
+                    if initial_byte >> 5 != major_type || initial_byte & 0x1f == INDEFINITE_LENGTH {
+                        return Err(self.error_at(chunk_offset, InvalidIndefiniteLengthChunk));
+                    }
+                    let len = self.read_definite_argument(chunk_offset, initial_byte & 0x1f)?;
+                    value.extend_from_slice(self.read_len(chunk_offset, len)?);
+                }
+                Ok(Cow::Owned(value))
+            }
+        }
+    }
+
+    fn read_text(&mut self, offset: usize, info: u8) -> Result<Cow<'a, str>, Error> {
+        match self.read_string(offset, TEXT_STRING, info)? {
+            Cow::Borrowed(bytes) => std::str::from_utf8(bytes)
+                .map(Cow::Borrowed)
+                .map_err(|err| self.error_at(offset, err.into())),
+            Cow::Owned(bytes) => String::from_utf8(bytes)
+                .map(Cow::Owned)
+                .map_err(|err| self.error_at(offset, err.utf8_error().into())),
+        }
+    }
+
+    /// Reads an integer or floating-point number.
+    fn read_number(&mut self) -> Result<Number, Error> {
+        let offset = self.index;
+        let initial_byte = self.next_byte()?;
+        let info = initial_byte & 0x1f;
+        match initial_byte >> 5 {
+            UNSIGNED_INTEGER => Ok(Number::PosInt(self.read_definite_argument(offset, info)?)),
+            NEGATIVE_INTEGER => {
+                let argument = self.read_definite_argument(offset, info)?;
+                // The value is `-1 - argument`
+                i64::try_from(argument)
+                    .map(|argument| Number::NegInt(-1 - argument))
+                    .map_err(|_| self.error_at(offset, IntegerOverflow))
+            }
+            SIMPLE_AND_FLOAT => match initial_byte {
+                HALF_FLOAT => {
+                    let bits = u16::from_be_bytes(self.next_bytes(2)?.try_into().unwrap());
+                    Ok(Number::Float(f16_to_f64(bits)))
+                }
+                SINGLE_FLOAT => Ok(Number::Float(f32::from_be_bytes(
+                    self.next_bytes(4)?.try_into().unwrap(),
+                ) as f64)),
+                DOUBLE_FLOAT => Ok(Number::Float(f64::from_be_bytes(
+                    self.next_bytes(8)?.try_into().unwrap(),
+                ))),
+                _ => Err(self.error_at(offset, Custom(Cow::Borrowed("expected a number")))),
+            },
+            _ => Err(self.error_at(offset, Custom(Cow::Borrowed("expected a number")))),
+        }
+    }
+
+    /// Reads the next data item, the key or value of the current container if any.
+    fn read_item(&mut self, key: bool) -> Result<Token<'a>, Error> {
+        let offset = self.offset();
+        let initial_byte = self.input[self.index];
+        let info = initial_byte & 0x1f;
+        let major_type = initial_byte >> 5;
+        if key {
+            if major_type != TEXT_STRING {
+                return Err(self.error(ExpectedObjectKey));
+            }
+            self.index += 1;
+            let key = self.read_text(offset.0, info)?;
+            return Ok(Token::ObjectKey { offset, key });
+        }
+
+        if major_type == UNSIGNED_INTEGER
+            || major_type == NEGATIVE_INTEGER
+            || matches!(initial_byte, HALF_FLOAT | SINGLE_FLOAT | DOUBLE_FLOAT)
+        {
+            let value = self.read_number()?;
+            return Ok(Token::ValueNumber { offset, value });
+        }
+
+        self.index += 1;
+        match major_type {
+            BYTE_STRING => {
+                let value = self.read_string(offset.0, BYTE_STRING, info)?;
+                Ok(Token::ValueBlob { offset, value })
+            }
+            TEXT_STRING => {
+                let value = self.read_text(offset.0, info)?;
+                Ok(Token::ValueString { offset, value })
+            }
+            ARRAY | MAP => {
+                let object = major_type == MAP;
+                let len = self.read_argument(offset.0, info)?;
+                let remaining = match (len, object) {
+                    (Some(len), true) => Some(
+                        len.checked_mul(2)
+                            .ok_or_else(|| self.error_at(offset.0, IntegerOverflow))?,
+                    ),
+                    (len, _) => len,
+                };
+                self.state_stack.push(State {
+                    object,
+                    remaining,
+                    expecting_key: object,
+                });
+                let len = len.map(|len| len as usize);
+                Ok(match object {
+                    true => Token::StartObject { offset, len },
+                    false => Token::StartArray { offset, len },
+                })
+            }
+            TAG => match self.read_definite_argument(offset.0, info)? {
+                EPOCH_DATE_TIME_TAG => {
+                    let value = match self.read_number()? {
+                        Number::PosInt(secs) => DateTime::from_secs(
+                            i64::try_from(secs)
+                                .map_err(|_| self.error_at(offset.0, IntegerOverflow))?,
+                        ),
+                        Number::NegInt(secs) => DateTime::from_secs(secs),
+                        Number::Float(secs) if secs.is_finite() => DateTime::from_secs_f64(secs),
+                        Number::Float(_) => {
+                            return Err(self.error_at(
+                                offset.0,
+                                Custom(Cow::Borrowed("NaN and Infinity are not valid epochs")),
+                            ))
+                        }
+                    };
+                    Ok(Token::ValueDateTime { offset, value })
+                }
+                tag => Err(self.error_at(offset.0, UnsupportedTag(tag))),
+            },
+            _ => match initial_byte {
+                FALSE => Ok(Token::ValueBool {
+                    offset,
+                    value: false,
+                }),
+                TRUE => Ok(Token::ValueBool {
+                    offset,
+                    value: true,
+                }),
+                NULL | UNDEFINED => Ok(Token::ValueNull { offset }),
+                BREAK => Err(self.error_at(offset.0, UnexpectedBreak)),
+                _ => Err(self.error_at(offset.0, UnsupportedSimpleValue(info))),
+            },
+        }
+    }
+
+    fn read_next(&mut self) -> Result<Option<Token<'a>>, Error> {
+        let offset = self.offset();
+        let next_byte = self.input.get(self.index).copied();
+        let state = match self.state_stack.last_mut() {
+            None if self.index >= self.input.len() => return Ok(None),
+            None => return self.read_item(false).map(Some),
+            Some(state) => state,
+        };
+
+        let end = match state.remaining {
+            Some(0) => true,
+            Some(_) => false,
+            None if next_byte == Some(BREAK) => {
+                if state.object && !state.expecting_key {
+                    return Err(Error::new(UnexpectedBreak, Some(offset.0)));
+                }
+                self.index += 1;
+                true
+            }
+            None => false,
+        };
+        if end {
+            let object = state.object;
+            self.state_stack.pop();
+            return Ok(Some(match object {
+                true => Token::EndObject { offset },
+                false => Token::EndArray { offset },
+            }));
+        }
+
+        if next_byte.is_none() {
+            return Err(Error::new(UnexpectedEos, Some(offset.0)));
+        }
+        let key = state.object && state.expecting_key;
+        if let Some(remaining) = state.remaining.as_mut() {
+            *remaining -= 1;
+        }
+        if state.object {
+            state.expecting_key = !state.expecting_key;
+        }
+        self.read_item(key).map(Some)
+    }
+}
+
+impl<'a> Iterator for CborTokenIterator<'a> {
+    type Item = Result<Token<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read_next() {
+            Ok(token) => token.map(Ok),
+            Err(err) => {
+                // Stop parsing after the first error
+                self.index = self.input.len();
+                self.state_stack.clear();
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Converts the bits of a half-precision float into a double-precision float.
+fn f16_to_f64(bits: u16) -> f64 {
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = (bits & 0x3ff) as f64;
+    let value = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        0x1f if mantissa == 0.0 => f64::INFINITY,
+        0x1f => f64::NAN,
+        _ => (mantissa + 1024.0) * 2f64.powi(exponent as i32 - 25),
+    };
+    if bits & 0x8000 != 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deserialize::error::{Error, ErrorReason::*};
+    use crate::deserialize::token::test::*;
+    use crate::deserialize::{cbor_token_iter, Token};
+    use aws_smithy_types::{DateTime, Number};
+    use proptest::prelude::*;
+    use std::borrow::Cow;
+
+    fn hex(input: &str) -> Vec<u8> {
+        (0..input.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&input[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn tokens(input: &str) -> Vec<Result<Token<'static>, Error>> {
+        let input: &'static [u8] = Box::leak(hex(input).into_boxed_slice());
+        cbor_token_iter(input).collect()
+    }
+
+    fn number(input: &str) -> Number {
+        match tokens(input).pop().unwrap().unwrap() {
+            Token::ValueNumber { value, .. } => value,
+            token => panic!("expected a number, got {:?}", token),
+        }
+    }
+
+    // Examples from https://www.rfc-editor.org/rfc/rfc8949.html#name-examples-of-encoded-cbor-da
+    #[test]
+    fn numbers() {
+        assert_eq!(Number::PosInt(0), number("00"));
+        assert_eq!(Number::PosInt(23), number("17"));
+        assert_eq!(Number::PosInt(24), number("1818"));
+        assert_eq!(Number::PosInt(1000), number("1903e8"));
+        assert_eq!(Number::PosInt(1000000), number("1a000f4240"));
+        assert_eq!(Number::PosInt(1000000000000), number("1b000000e8d4a51000"));
+        assert_eq!(Number::PosInt(u64::MAX), number("1bffffffffffffffff"));
+        assert_eq!(Number::NegInt(-1), number("20"));
+        assert_eq!(Number::NegInt(-1000), number("3903e7"));
+        assert_eq!(Number::NegInt(i64::MIN), number("3b7fffffffffffffff"));
+        assert_eq!(Number::Float(0.0), number("f90000"));
+        assert_eq!(Number::Float(-0.0), number("f98000"));
+        assert_eq!(Number::Float(1.0), number("f93c00"));
+        assert_eq!(Number::Float(1.5), number("f93e00"));
+        assert_eq!(Number::Float(65504.0), number("f97bff"));
+        assert_eq!(Number::Float(5.960464477539063e-8), number("f90001"));
+        assert_eq!(Number::Float(0.00006103515625), number("f90400"));
+        assert_eq!(Number::Float(-4.0), number("f9c400"));
+        assert_eq!(Number::Float(f64::INFINITY), number("f97c00"));
+        assert_eq!(Number::Float(f64::NEG_INFINITY), number("f9fc00"));
+        assert!(number("f97e00").to_f64_lossy().is_nan());
+        assert_eq!(Number::Float(100000.0), number("fa47c35000"));
+        assert_eq!(Number::Float(3.4028234663852886e+38), number("fa7f7fffff"));
+        assert_eq!(Number::Float(1.1), number("fb3ff199999999999a"));
+        assert_eq!(Number::Float(-4.1), number("fbc010666666666666"));
+
+        assert_eq!(
+            vec![Err(Error::new(IntegerOverflow, Some(0)))],
+            tokens("3bffffffffffffffff")
+        );
+    }
+
+    #[test]
+    fn simple_values() {
+        assert_eq!(vec![value_bool(0, false)], tokens("f4"));
+        assert_eq!(vec![value_bool(0, true)], tokens("f5"));
+        assert_eq!(vec![value_null(0)], tokens("f6"));
+        assert_eq!(vec![value_null(0)], tokens("f7"));
+        assert_eq!(
+            vec![Err(Error::new(UnsupportedSimpleValue(16), Some(0)))],
+            tokens("f0")
+        );
+        assert_eq!(
+            vec![Err(Error::new(UnexpectedBreak, Some(0)))],
+            tokens("ff")
+        );
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(vec![value_string(0, "")], tokens("60"));
+        assert_eq!(vec![value_string(0, "IETF")], tokens("6449455446"));
+        assert_eq!(vec![value_string(0, "\u{6c34}")], tokens("63e6b0b4"));
+        assert_eq!(vec![value_blob(0, &[1, 2, 3, 4])], tokens("4401020304"));
+        // Indefinite length strings
+        assert_eq!(
+            vec![value_blob(0, &[1, 2, 3, 4, 5])],
+            tokens("5f42010243030405ff")
+        );
+        assert_eq!(
+            vec![value_string(0, "streaming")],
+            tokens("7f657374726561646d696e67ff")
+        );
+        assert!(matches!(
+            cbor_token_iter(&hex("6449455446")).next(),
+            Some(Ok(Token::ValueString {
+                value: Cow::Borrowed(_),
+                ..
+            }))
+        ));
+
+        assert_eq!(
+            vec![Err(Error::new(InvalidIndefiniteLengthChunk, Some(1)))],
+            tokens("7f4101ff")
+        );
+        assert_eq!(
+            vec![Err(Error::new(InvalidUtf8, Some(0)))],
+            tokens("62c328")
+        );
+        assert_eq!(
+            vec![Err(Error::new(UnexpectedEos, Some(3)))],
+            tokens("644945")
+        );
+    }
+
+    #[test]
+    fn date_times() {
+        assert_eq!(
+            vec![Ok(Token::ValueDateTime {
+                offset: crate::deserialize::Offset(0),
+                value: DateTime::from_secs(1363896240)
+            })],
+            tokens("c11a514b67b0")
+        );
+        assert_eq!(
+            vec![Ok(Token::ValueDateTime {
+                offset: crate::deserialize::Offset(0),
+                value: DateTime::from_secs_f64(1363896240.5)
+            })],
+            tokens("c1fb41d452d9ec200000")
+        );
+        assert_eq!(
+            vec![Err(Error::new(UnsupportedTag(0), Some(0)))],
+            tokens("c074323031332d30332d32315432303a30343a30305a")
+        );
+    }
+
+    #[test]
+    fn arrays_and_maps() {
+        assert_eq!(vec![start_array(0, Some(0)), end_array(1)], tokens("80"));
+        assert_eq!(
+            vec![
+                start_array(0, Some(3)),
+                value_number(1, Number::PosInt(1)),
+                start_array(2, Some(2)),
+                value_number(3, Number::PosInt(2)),
+                value_number(4, Number::PosInt(3)),
+                end_array(5),
+                start_array(5, None),
+                value_number(6, Number::PosInt(4)),
+                end_array(7),
+                end_array(8),
+            ],
+            tokens("8301820203 9f04ff".replace(' ', "").as_str())
+        );
+        assert_eq!(
+            vec![
+                start_object(0, Some(2)),
+                object_key(1, "a"),
+                value_number(3, Number::PosInt(1)),
+                object_key(4, "b"),
+                start_array(6, Some(2)),
+                value_number(7, Number::PosInt(2)),
+                value_number(8, Number::PosInt(3)),
+                end_array(9),
+                end_object(9),
+            ],
+            tokens("a26161016162820203")
+        );
+        assert_eq!(
+            vec![
+                start_object(0, None),
+                object_key(1, "Fun"),
+                value_bool(5, true),
+                object_key(6, "Amt"),
+                value_number(10, Number::NegInt(-2)),
+                end_object(11),
+            ],
+            tokens("bf6346756ef563416d7421ff")
+        );
+    }
+
+    #[test]
+    fn invalid_containers() {
+        // Map keys must be text strings
+        assert_eq!(
+            vec![
+                start_object(0, Some(1)),
+                Err(Error::new(ExpectedObjectKey, Some(1)))
+            ],
+            tokens("a10102")
+        );
+        // A map can't end between a key and its value
+        assert_eq!(
+            vec![
+                start_object(0, None),
+                object_key(1, "a"),
+                Err(Error::new(UnexpectedBreak, Some(3)))
+            ],
+            tokens("bf6161ff")
+        );
+        assert_eq!(
+            vec![
+                start_array(0, Some(2)),
+                value_number(1, Number::PosInt(1)),
+                Err(Error::new(UnexpectedEos, Some(2)))
+            ],
+            tokens("8201")
+        );
+        assert_eq!(
+            vec![
+                start_array(0, None),
+                Err(Error::new(UnexpectedEos, Some(1)))
+            ],
+            tokens("9f")
+        );
+        assert_eq!(
+            vec![Err(Error::new(InvalidAdditionalInfo(28), Some(0)))],
+            tokens("1c")
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn doesnt_panic(input in proptest::collection::vec(any::<u8>(), 0..64)) {
+            for _ in cbor_token_iter(&input) {}
+        }
+    }
+}
@@ -0,0 +1,82 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::borrow::Cow;
+use std::fmt;
+use std::str::Utf8Error;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ErrorReason {
+    Custom(Cow<'static, str>),
+    ExpectedObjectKey,
+    IntegerOverflow,
+    InvalidAdditionalInfo(u8),
+    InvalidIndefiniteLengthChunk,
+    InvalidUtf8,
+    UnexpectedBreak,
+    UnexpectedEos,
+    UnsupportedSimpleValue(u8),
+    UnsupportedTag(u64),
+}
+use ErrorReason::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Error {
+    reason: ErrorReason,
+    offset: Option<usize>,
+}
+
+impl Error {
+    pub fn new(reason: ErrorReason, offset: Option<usize>) -> Self {
+        Error { reason, offset }
+    }
+
+    /// Returns a custom error without an offset.
+    pub fn custom(message: impl Into<Cow<'static, str>>) -> Error {
+        Error::new(ErrorReason::Custom(message.into()), None)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(offset) = self.offset {
+            write!(f, "Error at offset {}: ", offset)?;
+        }
+        match &self.reason {
+            Custom(msg) => write!(f, "failed to parse CBOR: {}", msg),
+            ExpectedObjectKey => write!(f, "expected a text string map key"),
+            IntegerOverflow => write!(f, "integer doesn't fit in 64 bits"),
+            InvalidAdditionalInfo(info) => {
+                write!(f, "invalid additional information in initial byte: {}", info)
+            }
+            InvalidIndefiniteLengthChunk => write!(
+                f,
+                "indefinite length strings can only contain definite length strings of the same type"
+            ),
+            InvalidUtf8 => write!(f, "invalid UTF-8 codepoint in CBOR text string"),
+            UnexpectedBreak => write!(f, "unexpected break stop code"),
+            UnexpectedEos => write!(f, "unexpected end of stream"),
+            UnsupportedSimpleValue(value) => write!(f, "unsupported simple value: {}", value),
+            UnsupportedTag(tag) => write!(f, "unsupported tag: {}", tag),
+        }
+    }
+}
+
+impl From<Utf8Error> for ErrorReason {
+    fn from(_: Utf8Error) -> Self {
+        InvalidUtf8
+    }
+}
+
+impl From<aws_smithy_types::TryFromNumberError> for Error {
+    fn from(_: aws_smithy_types::TryFromNumberError) -> Self {
+        Error {
+            reason: ErrorReason::IntegerOverflow,
+            offset: None,
+        }
+    }
+}
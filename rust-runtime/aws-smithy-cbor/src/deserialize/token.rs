@@ -0,0 +1,583 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::deserialize::error::{Error, ErrorReason};
+use aws_smithy_types::{Blob, DateTime, Document, DocumentMap, Number};
+use std::borrow::Cow;
+use std::iter::Peekable;
+
+/// Represents the location of a token
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Offset(pub usize);
+
+impl Offset {
+    /// Creates a custom error from the offset
+    pub fn error(&self, msg: Cow<'static, str>) -> Error {
+        Error::new(ErrorReason::Custom(msg), Some(self.0))
+    }
+}
+
+/// Enum representing the different CBOR tokens that can be returned by
+/// [`crate::deserialize::cbor_token_iter`].
+///
+/// Arrays and maps carry their length if it is definite. String values are borrowed from the
+/// input unless they were encoded as indefinite length strings.
+#[derive(Debug, PartialEq)]
+pub enum Token<'a> {
+    StartArray {
+        offset: Offset,
+        len: Option<usize>,
+    },
+    EndArray {
+        offset: Offset,
+    },
+    ObjectKey {
+        offset: Offset,
+        key: Cow<'a, str>,
+    },
+    StartObject {
+        offset: Offset,
+        len: Option<usize>,
+    },
+    EndObject {
+        offset: Offset,
+    },
+    ValueBlob {
+        offset: Offset,
+        value: Cow<'a, [u8]>,
+    },
+    ValueBool {
+        offset: Offset,
+        value: bool,
+    },
+    ValueDateTime {
+        offset: Offset,
+        value: DateTime,
+    },
+    ValueNull {
+        offset: Offset,
+    },
+    ValueNumber {
+        offset: Offset,
+        value: Number,
+    },
+    ValueString {
+        offset: Offset,
+        value: Cow<'a, str>,
+    },
+}
+
+impl<'a> Token<'a> {
+    pub fn offset(&self) -> Offset {
+        use Token::*;
+        *match self {
+            StartArray { offset, .. } => offset,
+            EndArray { offset } => offset,
+            ObjectKey { offset, .. } => offset,
+            StartObject { offset, .. } => offset,
+            EndObject { offset } => offset,
+            ValueBlob { offset, .. } => offset,
+            ValueBool { offset, .. } => offset,
+            ValueDateTime { offset, .. } => offset,
+            ValueNull { offset } => offset,
+            ValueNumber { offset, .. } => offset,
+            ValueString { offset, .. } => offset,
+        }
+    }
+
+    /// Builds an error from the token's offset
+    pub fn error(&self, msg: Cow<'static, str>) -> Error {
+        self.offset().error(msg)
+    }
+}
+
+macro_rules! expect_fn {
+    ($name:ident, $token:ident, $doc:tt) => {
+        #[doc=$doc]
+        pub fn $name(token_result: Option<Result<Token<'_>, Error>>) -> Result<(), Error> {
+            match token_result.transpose()? {
+                Some(Token::$token { .. }) => Ok(()),
+                Some(token) => {
+                    Err(token.error(Cow::Borrowed(concat!("expected ", stringify!($token)))))
+                }
+                None => Err(Error::new(
+                    ErrorReason::Custom(Cow::Borrowed(concat!("expected ", stringify!($token)))),
+                    None,
+                )),
+            }
+        }
+    };
+}
+
+expect_fn!(
+    expect_start_object,
+    StartObject,
+    "Expects a [Token::StartObject] token and returns an error if it's not present."
+);
+expect_fn!(
+    expect_start_array,
+    StartArray,
+    "Expects a [Token::StartArray] token and returns an error if it's not present."
+);
+
+macro_rules! expect_value_or_null_fn {
+    ($name:ident, $token:ident, $typ:ty, $doc:tt) => {
+        #[doc=$doc]
+        pub fn $name<'a>(token: Option<Result<Token<'a>, Error>>) -> Result<Option<$typ>, Error> {
+            match token.transpose()? {
+                Some(Token::ValueNull { .. }) => Ok(None),
+                Some(Token::$token { value, .. }) => Ok(Some(value)),
+                _ => Err(Error::custom(concat!(
+                    "expected ",
+                    stringify!($token),
+                    " or ValueNull"
+                ))),
+            }
+        }
+    };
+}
+
+expect_value_or_null_fn!(expect_bool_or_null, ValueBool, bool, "Expects a [Token::ValueBool] or [Token::ValueNull], and returns the bool value if it's not null.");
+expect_value_or_null_fn!(expect_string_or_null, ValueString, Cow<'a, str>, "Expects a [Token::ValueString] or [Token::ValueNull], and returns the string value if it's not null.");
+expect_value_or_null_fn!(expect_number_or_null, ValueNumber, Number, "Expects a [Token::ValueNumber] or [Token::ValueNull], and returns the [Number] value if it's not null.");
+
+/// Expects a [Token::ValueBlob] or [Token::ValueNull], and returns the [Blob] value if it's not null.
+pub fn expect_blob_or_null(token: Option<Result<Token<'_>, Error>>) -> Result<Option<Blob>, Error> {
+    match token.transpose()? {
+        Some(Token::ValueNull { .. }) => Ok(None),
+        Some(Token::ValueBlob { value, .. }) => Ok(Some(Blob::new(value.into_owned()))),
+        _ => Err(Error::custom("expected ValueBlob or ValueNull")),
+    }
+}
+
+/// Expects a [Token::ValueDateTime] or [Token::ValueNull], and returns the [DateTime] value
+/// if it's not null.
+///
+/// Timestamps are always encoded as epoch-based date/times (tag 1) in CBOR, so unlike JSON,
+/// there is no timestamp format to pass in.
+pub fn expect_timestamp_or_null(
+    token: Option<Result<Token<'_>, Error>>,
+) -> Result<Option<DateTime>, Error> {
+    match token.transpose()? {
+        Some(Token::ValueNull { .. }) => Ok(None),
+        Some(Token::ValueDateTime { value, .. }) => Ok(Some(value)),
+        _ => Err(Error::custom("expected ValueDateTime or ValueNull")),
+    }
+}
+
+/// Expects and parses a complete document value.
+///
+/// Blobs and date-times can't be represented in a [Document], so they are rejected.
+pub fn expect_document<'a, I>(tokens: &mut Peekable<I>) -> Result<Document, Error>
+where
+    I: Iterator<Item = Result<Token<'a>, Error>>,
+{
+    expect_document_inner(tokens, 0)
+}
+
+const MAX_DOCUMENT_RECURSION: usize = 256;
+
+fn expect_document_inner<'a, I>(tokens: &mut Peekable<I>, depth: usize) -> Result<Document, Error>
+where
+    I: Iterator<Item = Result<Token<'a>, Error>>,
+{
+    if depth >= MAX_DOCUMENT_RECURSION {
+        return Err(Error::custom(
+            "exceeded max recursion depth while parsing document",
+        ));
+    }
+    match tokens.next().transpose()? {
+        Some(Token::ValueNull { .. }) => Ok(Document::Null),
+        Some(Token::ValueBool { value, .. }) => Ok(Document::Bool(value)),
+        Some(Token::ValueNumber { value, .. }) => Ok(Document::Number(value)),
+        Some(Token::ValueString { value, .. }) => Ok(Document::String(value.into_owned())),
+        Some(Token::StartObject { .. }) => {
+            let mut object = DocumentMap::new();
+            loop {
+                match tokens.next().transpose()? {
+                    Some(Token::EndObject { .. }) => break,
+                    Some(Token::ObjectKey { key, .. }) => {
+                        let key = key.into_owned();
+                        let value = expect_document_inner(tokens, depth + 1)?;
+                        object.insert(key, value);
+                    }
+                    _ => return Err(Error::custom("expected object key or end object")),
+                }
+            }
+            Ok(Document::Object(object))
+        }
+        Some(Token::StartArray { .. }) => {
+            let mut array = Vec::new();
+            loop {
+                match tokens.peek() {
+                    Some(Ok(Token::EndArray { .. })) => {
+                        tokens.next().transpose().unwrap();
+                        break;
+                    }
+                    _ => array.push(expect_document_inner(tokens, depth + 1)?),
+                }
+            }
+            Ok(Document::Array(array))
+        }
+        Some(token @ Token::ValueBlob { .. }) | Some(token @ Token::ValueDateTime { .. }) => {
+            Err(token.error(Cow::Borrowed(
+                "blobs and timestamps can't be represented in a document",
+            )))
+        }
+        Some(Token::EndObject { .. }) | Some(Token::ObjectKey { .. }) => {
+            unreachable!("end object and object key are handled in start object")
+        }
+        Some(Token::EndArray { .. }) => unreachable!("end array is handled in start array"),
+        None => Err(Error::custom("expected value")),
+    }
+}
+
+/// Skips an entire value in the token stream. Errors if it isn't a value.
+pub fn skip_value<'a>(
+    tokens: &mut impl Iterator<Item = Result<Token<'a>, Error>>,
+) -> Result<(), Error> {
+    skip_inner(0, tokens)
+}
+
+/// Assumes a start object/array token has already been consumed and skips tokens until
+/// until its corresponding end object/array token is found.
+pub fn skip_to_end<'a>(
+    tokens: &mut impl Iterator<Item = Result<Token<'a>, Error>>,
+) -> Result<(), Error> {
+    skip_inner(1, tokens)
+}
+
+fn skip_inner<'a>(
+    depth: isize,
+    tokens: &mut impl Iterator<Item = Result<Token<'a>, Error>>,
+) -> Result<(), Error> {
+    loop {
+        match tokens.next().transpose()? {
+            Some(Token::StartObject { .. }) | Some(Token::StartArray { .. }) => {
+                skip_inner(depth + 1, tokens)?;
+                if depth == 0 {
+                    break;
+                }
+            }
+            Some(Token::EndObject { .. }) | Some(Token::EndArray { .. }) => {
+                debug_assert!(depth > 0);
+                break;
+            }
+            Some(Token::ValueBlob { .. })
+            | Some(Token::ValueBool { .. })
+            | Some(Token::ValueDateTime { .. })
+            | Some(Token::ValueNull { .. })
+            | Some(Token::ValueNumber { .. })
+            | Some(Token::ValueString { .. }) => {
+                if depth == 0 {
+                    break;
+                }
+            }
+            Some(Token::ObjectKey { .. }) => {}
+            _ => return Err(Error::custom("expected value")),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::deserialize::cbor_token_iter;
+    use crate::serialize::CborValueWriter;
+    use proptest::prelude::*;
+
+    pub fn start_array<'a>(offset: usize, len: Option<usize>) -> Result<Token<'a>, Error> {
+        Ok(Token::StartArray {
+            offset: Offset(offset),
+            len,
+        })
+    }
+
+    pub fn end_array<'a>(offset: usize) -> Result<Token<'a>, Error> {
+        Ok(Token::EndArray {
+            offset: Offset(offset),
+        })
+    }
+
+    pub fn start_object<'a>(offset: usize, len: Option<usize>) -> Result<Token<'a>, Error> {
+        Ok(Token::StartObject {
+            offset: Offset(offset),
+            len,
+        })
+    }
+
+    pub fn end_object<'a>(offset: usize) -> Result<Token<'a>, Error> {
+        Ok(Token::EndObject {
+            offset: Offset(offset),
+        })
+    }
+
+    pub fn object_key(offset: usize, key: &str) -> Result<Token<'_>, Error> {
+        Ok(Token::ObjectKey {
+            offset: Offset(offset),
+            key: Cow::Borrowed(key),
+        })
+    }
+
+    pub fn value_blob(offset: usize, blob: &[u8]) -> Result<Token<'_>, Error> {
+        Ok(Token::ValueBlob {
+            offset: Offset(offset),
+            value: Cow::Borrowed(blob),
+        })
+    }
+
+    pub fn value_bool<'a>(offset: usize, boolean: bool) -> Result<Token<'a>, Error> {
+        Ok(Token::ValueBool {
+            offset: Offset(offset),
+            value: boolean,
+        })
+    }
+
+    pub fn value_number<'a>(offset: usize, number: Number) -> Result<Token<'a>, Error> {
+        Ok(Token::ValueNumber {
+            offset: Offset(offset),
+            value: number,
+        })
+    }
+
+    pub fn value_null<'a>(offset: usize) -> Result<Token<'a>, Error> {
+        Ok(Token::ValueNull {
+            offset: Offset(offset),
+        })
+    }
+
+    pub fn value_string(offset: usize, string: &str) -> Result<Token<'_>, Error> {
+        Ok(Token::ValueString {
+            offset: Offset(offset),
+            value: Cow::Borrowed(string),
+        })
+    }
+
+    fn encode(f: impl FnOnce(CborValueWriter<'_>)) -> Vec<u8> {
+        let mut output = Vec::new();
+        f(CborValueWriter::new(&mut output));
+        output
+    }
+
+    #[test]
+    fn skip_simple_value() {
+        // null true
+        let input = [0xf6, 0xf5];
+        let mut tokens = cbor_token_iter(&input);
+        skip_value(&mut tokens).unwrap();
+        assert!(matches!(
+            tokens.next(),
+            Some(Ok(Token::ValueBool { value: true, .. }))
+        ))
+    }
+
+    #[test]
+    fn skip_nested() {
+        // {"struct": {"foo": 5, "arr": [_ 1, h'01', {}]}, "arr": [[]]} true
+        let mut input = encode(|value| {
+            let mut object = value.start_object();
+            let mut inner = object.key("struct").start_object();
+            inner.key("foo").number(Number::PosInt(5));
+            let mut array = inner.key("arr").start_array();
+            array.value().number(Number::PosInt(1));
+            array.value().blob(&Blob::new(vec![1]));
+            array.value().start_object().finish();
+            array.finish();
+            inner.finish();
+            let mut array = object.key("arr").start_array();
+            array.value().start_array().finish();
+            array.finish();
+            object.finish();
+        });
+        input.push(0xf5);
+        let mut tokens = cbor_token_iter(&input);
+        skip_value(&mut tokens).unwrap();
+        assert!(matches!(
+            tokens.next(),
+            Some(Ok(Token::ValueBool { value: true, .. }))
+        ))
+    }
+
+    #[test]
+    fn test_skip_to_end() {
+        // {"one": {"two": []}, "three": 2} with definite lengths
+        let input = [
+            0xa2, 0x63, b'o', b'n', b'e', 0xa1, 0x63, b't', b'w', b'o', 0x80, 0x65, b't', b'h',
+            b'r', b'e', b'e', 0x02,
+        ];
+        let tokens = cbor_token_iter(&input);
+        let mut tokens = tokens.skip(2);
+        assert!(matches!(tokens.next(), Some(Ok(Token::StartObject { .. }))));
+        skip_to_end(&mut tokens).unwrap();
+        match tokens.next() {
+            Some(Ok(Token::ObjectKey { key, .. })) => {
+                assert_eq!("three", key);
+            }
+            _ => panic!("expected object key three"),
+        }
+    }
+
+    #[test]
+    fn test_expect_start_object() {
+        assert_eq!(
+            Err(Error::new(
+                ErrorReason::Custom("expected StartObject".into()),
+                Some(2)
+            )),
+            expect_start_object(Some(value_bool(2, true)))
+        );
+        assert_eq!(Ok(()), expect_start_object(Some(start_object(0, None))));
+    }
+
+    #[test]
+    fn test_expect_start_array() {
+        assert_eq!(
+            Err(Error::new(
+                ErrorReason::Custom("expected StartArray".into()),
+                Some(2)
+            )),
+            expect_start_array(Some(value_bool(2, true)))
+        );
+        assert_eq!(Ok(()), expect_start_array(Some(start_array(0, Some(1)))));
+    }
+
+    #[test]
+    fn test_expect_values_or_null() {
+        assert_eq!(Ok(None), expect_string_or_null(Some(value_null(0))));
+        assert_eq!(
+            Ok(Some(Cow::Borrowed("test"))),
+            expect_string_or_null(Some(value_string(0, "test")))
+        );
+        assert_eq!(
+            Err(Error::custom("expected ValueString or ValueNull")),
+            expect_string_or_null(Some(value_bool(0, true)))
+        );
+        assert_eq!(
+            Ok(Some(Number::NegInt(-5))),
+            expect_number_or_null(Some(value_number(0, Number::NegInt(-5))))
+        );
+        assert_eq!(
+            Ok(Some(Blob::new(b"hello!".to_vec()))),
+            expect_blob_or_null(Some(value_blob(0, b"hello!")))
+        );
+        assert_eq!(
+            Err(Error::custom("expected ValueBlob or ValueNull")),
+            expect_blob_or_null(Some(value_string(0, "aGVsbG8h")))
+        );
+        assert_eq!(
+            Ok(Some(DateTime::from_secs(1445412480))),
+            expect_timestamp_or_null(Some(Ok(Token::ValueDateTime {
+                offset: Offset(0),
+                value: DateTime::from_secs(1445412480)
+            })))
+        );
+        assert_eq!(
+            Err(Error::custom("expected ValueDateTime or ValueNull")),
+            expect_timestamp_or_null(Some(value_number(0, Number::PosInt(1445412480))))
+        );
+    }
+
+    #[test]
+    fn test_expect_document() {
+        let test = |value: &[u8]| expect_document(&mut cbor_token_iter(value).peekable());
+        assert_eq!(Ok(Document::Null), test(&[0xf6]));
+        assert_eq!(Ok(Document::Bool(true)), test(&[0xf5]));
+        assert_eq!(Ok(Document::Array(Vec::new())), test(&[0x80]));
+        assert_eq!(
+            Ok(Document::Object(DocumentMap::new())),
+            test(&[0xbf, 0xff])
+        );
+        assert_eq!(
+            Err(Error::new(
+                ErrorReason::Custom(
+                    "blobs and timestamps can't be represented in a document".into()
+                ),
+                Some(1)
+            )),
+            test(&[0x81, 0x41, 0x01])
+        );
+
+        let document = Document::Object(
+            vec![
+                ("num".to_string(), Document::Number(Number::NegInt(-1))),
+                ("float".to_string(), Document::Number(Number::Float(3.2))),
+                ("string".to_string(), Document::String("s".into())),
+                (
+                    "array".to_string(),
+                    Document::Array(vec![
+                        Document::Bool(false),
+                        Document::Null,
+                        Document::Object(
+                            vec![("nested".to_string(), Document::Array(Vec::new()))]
+                                .into_iter()
+                                .collect(),
+                        ),
+                    ]),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        assert_eq!(
+            Ok(document.clone()),
+            test(&encode(|value| value.document(&document)))
+        );
+    }
+
+    #[test]
+    fn test_document_recursion_limit() {
+        let mut value = vec![0x81; 300];
+        value.push(0xf6);
+        assert_eq!(
+            Err(Error::custom(
+                "exceeded max recursion depth while parsing document"
+            )),
+            expect_document(&mut cbor_token_iter(&value).peekable())
+        );
+    }
+
+    fn document() -> impl Strategy<Value = Document> {
+        let leaf = prop_oneof![
+            Just(Document::Null),
+            any::<bool>().prop_map(Document::Bool),
+            any::<u64>().prop_map(|value| Document::Number(Number::PosInt(value))),
+            (i64::MIN..0).prop_map(|value| Document::Number(Number::NegInt(value))),
+            any::<f64>()
+                .prop_filter("NaN isn't equal to itself", |value| !value.is_nan())
+                .prop_map(|value| Document::Number(Number::Float(value))),
+            ".*".prop_map(Document::String),
+        ];
+        leaf.prop_recursive(4, 64, 8, |inner| {
+            prop_oneof![
+                proptest::collection::vec(inner.clone(), 0..8).prop_map(Document::Array),
+                proptest::collection::vec((".*", inner), 0..8)
+                    .prop_map(|members| Document::Object(members.into_iter().collect())),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn document_roundtrip(document in document()) {
+            let input = encode(|value| value.document(&document));
+            let mut tokens = cbor_token_iter(&input).peekable();
+            prop_assert_eq!(Ok(document), expect_document(&mut tokens));
+            prop_assert!(tokens.next().is_none());
+        }
+
+        #[test]
+        fn timestamp_roundtrip(secs in -10_000_000_000i64..10_000_000_000, nanos in 0u32..999_000_000) {
+            let date_time = DateTime::from_secs_and_nanos(secs, nanos);
+            let input = encode(|value| value.date_time(&date_time));
+            let parsed = expect_timestamp_or_null(cbor_token_iter(&input).next()).unwrap().unwrap();
+            // Fractional seconds go through an f64, so they are only as precise as the f64
+            prop_assert_eq!(date_time.secs(), parsed.secs());
+            if nanos == 0 {
+                prop_assert_eq!(date_time, parsed);
+            }
+        }
+    }
+}
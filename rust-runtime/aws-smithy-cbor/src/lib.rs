@@ -0,0 +1,39 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! CBOR Abstractions for Smithy
+//!
+//! [CBOR](https://www.rfc-editor.org/rfc/rfc8949.html) is the wire format of the
+//! [Smithy RPC v2 CBOR protocol](https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html).
+
+pub mod deserialize;
+pub mod serialize;
+
+/// CBOR major types, the high-order 3 bits of the initial byte of a data item
+pub(crate) mod major_type {
+    pub(crate) const UNSIGNED_INTEGER: u8 = 0;
+    pub(crate) const NEGATIVE_INTEGER: u8 = 1;
+    pub(crate) const BYTE_STRING: u8 = 2;
+    pub(crate) const TEXT_STRING: u8 = 3;
+    pub(crate) const ARRAY: u8 = 4;
+    pub(crate) const MAP: u8 = 5;
+    pub(crate) const TAG: u8 = 6;
+    pub(crate) const SIMPLE_AND_FLOAT: u8 = 7;
+}
+
+/// The additional information of an initial byte that denotes an indefinite length
+pub(crate) const INDEFINITE_LENGTH: u8 = 31;
+/// The "break" stop code that ends indefinite length items
+pub(crate) const BREAK: u8 = 0xff;
+/// The tag of epoch-based date/times
+pub(crate) const EPOCH_DATE_TIME_TAG: u64 = 1;
+
+pub(crate) const FALSE: u8 = 0xf4;
+pub(crate) const TRUE: u8 = 0xf5;
+pub(crate) const NULL: u8 = 0xf6;
+pub(crate) const UNDEFINED: u8 = 0xf7;
+pub(crate) const HALF_FLOAT: u8 = 0xf9;
+pub(crate) const SINGLE_FLOAT: u8 = 0xfa;
+pub(crate) const DOUBLE_FLOAT: u8 = 0xfb;
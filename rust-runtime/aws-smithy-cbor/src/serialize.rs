@@ -0,0 +1,303 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::major_type::*;
+use crate::{BREAK, DOUBLE_FLOAT, EPOCH_DATE_TIME_TAG, FALSE, INDEFINITE_LENGTH, NULL, TRUE};
+use aws_smithy_types::{Blob, DateTime, Document, Number};
+
+/// Writes the head of a data item: its major type and its argument, in the shortest form.
+fn write_head(output: &mut Vec<u8>, major_type: u8, argument: u64) {
+    let major_type = major_type << 5;
+    if argument < 24 {
+        output.push(major_type | argument as u8);
+    } else if argument <= u8::MAX as u64 {
+        output.push(major_type | 24);
+        output.push(argument as u8);
+    } else if argument <= u16::MAX as u64 {
+        output.push(major_type | 25);
+        output.extend_from_slice(&(argument as u16).to_be_bytes());
+    } else if argument <= u32::MAX as u64 {
+        output.push(major_type | 26);
+        output.extend_from_slice(&(argument as u32).to_be_bytes());
+    } else {
+        output.push(major_type | 27);
+        output.extend_from_slice(&argument.to_be_bytes());
+    }
+}
+
+pub struct CborValueWriter<'a> {
+    output: &'a mut Vec<u8>,
+}
+
+impl<'a> CborValueWriter<'a> {
+    pub fn new(output: &'a mut Vec<u8>) -> Self {
+        CborValueWriter { output }
+    }
+
+    /// Writes a null value.
+    pub fn null(self) {
+        self.output.push(NULL);
+    }
+
+    /// Writes the boolean `value`.
+    pub fn boolean(self, value: bool) {
+        self.output.push(match value {
+            true => TRUE,
+            _ => FALSE,
+        });
+    }
+
+    /// Writes a document `value`.
+    pub fn document(self, value: &Document) {
+        match value {
+            Document::Array(values) => {
+                let mut array = self.start_array();
+                for value in values {
+                    array.value().document(value);
+                }
+                array.finish();
+            }
+            Document::Bool(value) => self.boolean(*value),
+            Document::Null => self.null(),
+            Document::Number(value) => self.number(*value),
+            Document::Object(values) => {
+                let mut object = self.start_object();
+                for (key, value) in values {
+                    object.key(key).document(value);
+                }
+                object.finish();
+            }
+            Document::String(value) => self.string(value),
+        }
+    }
+
+    /// Writes a text string `value`.
+    pub fn string(self, value: &str) {
+        write_head(self.output, TEXT_STRING, value.len() as u64);
+        self.output.extend_from_slice(value.as_bytes());
+    }
+
+    /// Writes a byte string `value`.
+    pub fn blob(self, value: &Blob) {
+        write_head(self.output, BYTE_STRING, value.as_ref().len() as u64);
+        self.output.extend_from_slice(value.as_ref());
+    }
+
+    /// Writes a number `value`.
+    ///
+    /// Integers are written in the shortest form that holds them, and floats are written as
+    /// double-precision floats.
+    pub fn number(self, value: Number) {
+        match value {
+            Number::PosInt(value) => write_head(self.output, UNSIGNED_INTEGER, value),
+            Number::NegInt(value) if value >= 0 => {
+                write_head(self.output, UNSIGNED_INTEGER, value as u64)
+            }
+            // A negative integer `n` is encoded as `-1 - n`
+            Number::NegInt(value) => write_head(self.output, NEGATIVE_INTEGER, !(value as u64)),
+            Number::Float(value) => {
+                self.output.push(DOUBLE_FLOAT);
+                self.output.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+    }
+
+    /// Writes a date-time `value` as an epoch-based date/time (tag 1).
+    ///
+    /// Date-times without a fractional second are written as integers, other date-times as
+    /// double-precision floats.
+    pub fn date_time(self, date_time: &DateTime) {
+        write_head(self.output, TAG, EPOCH_DATE_TIME_TAG);
+        let value = CborValueWriter::new(self.output);
+        if date_time.has_subsec_nanos() {
+            value.number(Number::Float(date_time.as_secs_f64()));
+        } else if date_time.secs() >= 0 {
+            value.number(Number::PosInt(date_time.secs() as u64));
+        } else {
+            value.number(Number::NegInt(date_time.secs()));
+        }
+    }
+
+    /// Starts an array of indefinite length.
+    pub fn start_array(self) -> CborArrayWriter<'a> {
+        CborArrayWriter::new(self.output)
+    }
+
+    /// Starts a map of indefinite length.
+    pub fn start_object(self) -> CborObjectWriter<'a> {
+        CborObjectWriter::new(self.output)
+    }
+}
+
+/// Writes a CBOR map of indefinite length, with text string keys.
+pub struct CborObjectWriter<'a> {
+    cbor: &'a mut Vec<u8>,
+}
+
+impl<'a> CborObjectWriter<'a> {
+    pub fn new(output: &'a mut Vec<u8>) -> Self {
+        output.push(MAP << 5 | INDEFINITE_LENGTH);
+        Self { cbor: output }
+    }
+
+    /// Starts a value with the given `key`.
+    pub fn key(&mut self, key: &str) -> CborValueWriter<'_> {
+        CborValueWriter::new(self.cbor).string(key);
+        CborValueWriter::new(self.cbor)
+    }
+
+    /// Finishes the map.
+    pub fn finish(self) {
+        self.cbor.push(BREAK);
+    }
+}
+
+/// Writes a CBOR array of indefinite length.
+pub struct CborArrayWriter<'a> {
+    cbor: &'a mut Vec<u8>,
+}
+
+impl<'a> CborArrayWriter<'a> {
+    pub fn new(output: &'a mut Vec<u8>) -> Self {
+        output.push(ARRAY << 5 | INDEFINITE_LENGTH);
+        Self { cbor: output }
+    }
+
+    /// Starts a new value in the array.
+    pub fn value(&mut self) -> CborValueWriter<'_> {
+        CborValueWriter::new(self.cbor)
+    }
+
+    /// Finishes the array.
+    pub fn finish(self) {
+        self.cbor.push(BREAK);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CborArrayWriter, CborObjectWriter, CborValueWriter};
+    use aws_smithy_types::{Blob, DateTime, Document, Number};
+
+    fn write(f: impl FnOnce(CborValueWriter<'_>)) -> String {
+        let mut output = Vec::new();
+        f(CborValueWriter::new(&mut output));
+        output.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    // Examples from https://www.rfc-editor.org/rfc/rfc8949.html#name-examples-of-encoded-cbor-da
+    #[test]
+    fn numbers() {
+        for (number, expected) in [
+            (Number::PosInt(0), "00"),
+            (Number::PosInt(1), "01"),
+            (Number::PosInt(10), "0a"),
+            (Number::PosInt(23), "17"),
+            (Number::PosInt(24), "1818"),
+            (Number::PosInt(25), "1819"),
+            (Number::PosInt(100), "1864"),
+            (Number::PosInt(1000), "1903e8"),
+            (Number::PosInt(1000000), "1a000f4240"),
+            (Number::PosInt(1000000000000), "1b000000e8d4a51000"),
+            (Number::PosInt(u64::MAX), "1bffffffffffffffff"),
+            (Number::NegInt(i64::MIN), "3b7fffffffffffffff"),
+            (Number::NegInt(-1), "20"),
+            (Number::NegInt(-10), "29"),
+            (Number::NegInt(-100), "3863"),
+            (Number::NegInt(-1000), "3903e7"),
+            (Number::NegInt(5), "05"),
+            (Number::Float(1.1), "fb3ff199999999999a"),
+            (Number::Float(-4.1), "fbc010666666666666"),
+            (Number::Float(1.0e300), "fb7e37e43c8800759c"),
+            (Number::Float(f64::INFINITY), "fb7ff0000000000000"),
+            (Number::Float(f64::NEG_INFINITY), "fbfff0000000000000"),
+        ] {
+            assert_eq!(expected, write(|w| w.number(number)), "{:?}", number);
+        }
+        assert_eq!(
+            "fb7ff8000000000000",
+            write(|w| w.number(Number::Float(f64::NAN)))
+        );
+    }
+
+    #[test]
+    fn simple_values() {
+        assert_eq!("f4", write(|w| w.boolean(false)));
+        assert_eq!("f5", write(|w| w.boolean(true)));
+        assert_eq!("f6", write(|w| w.null()));
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!("60", write(|w| w.string("")));
+        assert_eq!("6161", write(|w| w.string("a")));
+        assert_eq!("6449455446", write(|w| w.string("IETF")));
+        assert_eq!("62225c", write(|w| w.string("\"\\")));
+        assert_eq!("62c3bc", write(|w| w.string("\u{00fc}")));
+        assert_eq!("63e6b0b4", write(|w| w.string("\u{6c34}")));
+        assert_eq!("40", write(|w| w.blob(&Blob::new(vec![]))));
+        assert_eq!(
+            "4401020304",
+            write(|w| w.blob(&Blob::new(vec![1, 2, 3, 4])))
+        );
+        let long = "a".repeat(300);
+        assert_eq!(
+            format!("79012c{}", "61".repeat(300)),
+            write(|w| w.string(&long))
+        );
+    }
+
+    #[test]
+    fn date_times() {
+        assert_eq!(
+            "c11a514b67b0",
+            write(|w| w.date_time(&DateTime::from_secs(1363896240)))
+        );
+        assert_eq!(
+            "c1fb41d452d9ec200000",
+            write(|w| w.date_time(&DateTime::from_secs_f64(1363896240.5)))
+        );
+        assert_eq!("c120", write(|w| w.date_time(&DateTime::from_secs(-1))));
+    }
+
+    #[test]
+    fn empty() {
+        let mut output = Vec::new();
+        CborObjectWriter::new(&mut output).finish();
+        assert_eq!(vec![0xbf, 0xff], output);
+
+        let mut output = Vec::new();
+        CborArrayWriter::new(&mut output).finish();
+        assert_eq!(vec![0x9f, 0xff], output);
+    }
+
+    #[test]
+    fn nested() {
+        // {_ "a": 1, "b": [_ 2, 3]}
+        let mut output = Vec::new();
+        let mut object = CborObjectWriter::new(&mut output);
+        object.key("a").number(Number::PosInt(1));
+        let mut array = object.key("b").start_array();
+        array.value().number(Number::PosInt(2));
+        array.value().number(Number::PosInt(3));
+        array.finish();
+        object.finish();
+        assert_eq!(
+            vec![0xbf, 0x61, 0x61, 0x01, 0x61, 0x62, 0x9f, 0x02, 0x03, 0xff, 0xff],
+            output
+        );
+    }
+
+    #[test]
+    fn document() {
+        let document = Document::Array(vec![
+            Document::Null,
+            Document::Bool(true),
+            Document::String("a".into()),
+            Document::Object([("b".to_string(), Document::Number(Number::NegInt(-1)))].into()),
+        ]);
+        assert_eq!("9ff6f56161bf616220ffff", write(|w| w.document(&document)));
+    }
+}
@@ -90,6 +90,10 @@ use crate::hyper_ext::Adapter as HyperAdapter;
 #[cfg(feature = "rustls")]
 impl<M, R> Builder<(), M, R> {
     /// Connect to the service over HTTPS using Rustls using dynamic dispatch.
+    ///
+    /// # Panics
+    /// Panics if the [`TlsSettings`](crate::tls::TlsSettings) of `connector_settings` are invalid.
+    /// Use [`hyper_ext::Builder::build_https`](crate::hyper_ext::Builder::build_https) to handle that error.
     pub fn rustls_connector(
         self,
         connector_settings: ConnectorSettings,
@@ -97,7 +101,8 @@ impl<M, R> Builder<(), M, R> {
         self.connector(DynConnector::new(
             HyperAdapter::builder()
                 .connector_settings(connector_settings)
-                .build(crate::conns::https()),
+                .build_https()
+                .expect("invalid TLS settings"),
         ))
     }
 }
@@ -106,6 +111,11 @@ impl<M, R> Builder<(), M, R> {
 impl<M, R> Builder<(), M, R> {
     /// Connect to the service over HTTPS using the native TLS library on your
     /// platform using dynamic dispatch.
+    ///
+    /// # Panics
+    /// Panics if the [`TlsSettings`](crate::tls::TlsSettings) of `connector_settings` are invalid.
+    /// Use [`hyper_ext::Builder::build_native_tls`](crate::hyper_ext::Builder::build_native_tls) to
+    /// handle that error.
     pub fn native_tls_connector(
         self,
         connector_settings: ConnectorSettings,
@@ -113,7 +123,8 @@ impl<M, R> Builder<(), M, R> {
         self.connector(DynConnector::new(
             HyperAdapter::builder()
                 .connector_settings(connector_settings)
-                .build(crate::conns::native_tls()),
+                .build_native_tls()
+                .expect("invalid TLS settings"),
         ))
     }
 }
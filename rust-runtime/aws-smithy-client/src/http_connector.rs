@@ -7,6 +7,7 @@
 //! that enable passing HTTP connectors around.
 
 use crate::erase::DynConnector;
+use crate::tls::TlsSettings;
 use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_types::timeout::TimeoutConfig;
 use std::time::Duration;
@@ -62,6 +63,7 @@ impl HttpConnector {
 pub struct ConnectorSettingsBuilder {
     connect_timeout: Option<Duration>,
     read_timeout: Option<Duration>,
+    tls_settings: Option<TlsSettings>,
 }
 
 impl ConnectorSettingsBuilder {
@@ -104,11 +106,32 @@ impl ConnectorSettingsBuilder {
         self
     }
 
+    /// Sets the TLS settings that should be used.
+    ///
+    /// TLS settings configure additional trusted root certificates and a client certificate. They are
+    /// honored by connectors built with [`hyper_ext::Builder::build_https`](crate::hyper_ext::Builder)
+    /// and [`hyper_ext::Builder::build_native_tls`](crate::hyper_ext::Builder).
+    pub fn tls_settings(mut self, tls_settings: TlsSettings) -> Self {
+        self.tls_settings = Some(tls_settings);
+        self
+    }
+
+    /// Sets the TLS settings that should be used.
+    ///
+    /// TLS settings configure additional trusted root certificates and a client certificate. They are
+    /// honored by connectors built with [`hyper_ext::Builder::build_https`](crate::hyper_ext::Builder)
+    /// and [`hyper_ext::Builder::build_native_tls`](crate::hyper_ext::Builder).
+    pub fn set_tls_settings(&mut self, tls_settings: Option<TlsSettings>) -> &mut Self {
+        self.tls_settings = tls_settings;
+        self
+    }
+
     /// Builds the [`ConnectorSettings`].
     pub fn build(self) -> ConnectorSettings {
         ConnectorSettings {
             connect_timeout: self.connect_timeout,
             read_timeout: self.read_timeout,
+            tls_settings: self.tls_settings.unwrap_or_default(),
         }
     }
 }
@@ -119,6 +142,7 @@ impl ConnectorSettingsBuilder {
 pub struct ConnectorSettings {
     connect_timeout: Option<Duration>,
     read_timeout: Option<Duration>,
+    tls_settings: TlsSettings,
 }
 
 impl ConnectorSettings {
@@ -142,6 +166,17 @@ impl ConnectorSettings {
         self.read_timeout
     }
 
+    /// Returns the TLS settings that should be used.
+    pub fn tls_settings(&self) -> &TlsSettings {
+        &self.tls_settings
+    }
+
+    /// Replaces the TLS settings, keeping all other settings.
+    pub fn with_tls_settings(mut self, tls_settings: TlsSettings) -> Self {
+        self.tls_settings = tls_settings;
+        self
+    }
+
     // This function may be removed/refactored in the future if other non-timeout
     // properties are added to the `ConnectorSettings` struct.
     #[doc(hidden)]
@@ -149,6 +184,7 @@ impl ConnectorSettings {
         Self {
             connect_timeout: timeout_config.connect_timeout(),
             read_timeout: timeout_config.read_timeout(),
+            tls_settings: TlsSettings::default(),
         }
     }
 }
@@ -267,6 +267,53 @@ impl Builder {
         Adapter(read_timeout)
     }
 
+    /// Create a HyperAdapter that connects over HTTPS using rustls
+    ///
+    /// Unlike [`Builder::build`], this honors the [`TlsSettings`](crate::tls::TlsSettings) of the
+    /// configured [`ConnectorSettings`], e.g. additional trusted root certificates or a client certificate.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use aws_smithy_client::http_connector::ConnectorSettings;
+    /// use aws_smithy_client::hyper_ext;
+    /// use aws_smithy_client::tls::TlsSettings;
+    ///
+    /// # fn example() -> Result<(), aws_smithy_client::tls::TlsSettingsError> {
+    /// let ca_bundle = std::fs::read("/etc/corporate/ca-bundle.pem").expect("readable");
+    /// let connector = hyper_ext::Adapter::builder()
+    ///     .connector_settings(
+    ///         ConnectorSettings::builder()
+    ///             .tls_settings(TlsSettings::builder().ca_bundle(ca_bundle).build())
+    ///             .build(),
+    ///     )
+    ///     .build_https()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rustls")]
+    pub fn build_https(self) -> Result<Adapter<crate::conns::Https>, crate::tls::TlsSettingsError> {
+        let connector = match &self.connector_settings {
+            Some(settings) => crate::conns::https_with_tls_settings(settings.tls_settings())?,
+            None => crate::conns::https(),
+        };
+        Ok(self.build(connector))
+    }
+
+    /// Create a HyperAdapter that connects over HTTPS using the platform's native TLS library
+    ///
+    /// Unlike [`Builder::build`], this honors the [`TlsSettings`](crate::tls::TlsSettings) of the
+    /// configured [`ConnectorSettings`], e.g. additional trusted root certificates or a client certificate.
+    #[cfg(feature = "native-tls")]
+    pub fn build_native_tls(
+        self,
+    ) -> Result<Adapter<crate::conns::NativeTls>, crate::tls::TlsSettingsError> {
+        let connector = match &self.connector_settings {
+            Some(settings) => crate::conns::native_tls_with_tls_settings(settings.tls_settings())?,
+            None => crate::conns::native_tls(),
+        };
+        Ok(self.build(connector))
+    }
+
     /// Set the async sleep implementation used for timeouts
     ///
     /// Calling this is only necessary for testing or to use something other than
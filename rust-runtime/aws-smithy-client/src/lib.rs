@@ -36,6 +36,7 @@ pub mod dvr;
 pub mod test_connection;
 
 pub mod http_connector;
+pub mod tls;
 
 #[cfg(feature = "client-hyper")]
 pub mod hyper_ext;
@@ -76,11 +77,48 @@ pub mod conns {
         HTTPS_NATIVE_ROOTS.clone()
     }
 
+    /// Create a rustls HTTPS connector that honors `tls_settings`
+    ///
+    /// When `tls_settings` is empty, this is equivalent to [`https`].
+    #[cfg(feature = "rustls")]
+    pub fn https_with_tls_settings(
+        tls_settings: &crate::tls::TlsSettings,
+    ) -> Result<Https, crate::tls::TlsSettingsError> {
+        if tls_settings.is_empty() {
+            return Ok(https());
+        }
+        Ok(hyper_rustls::HttpsConnectorBuilder::new()
+            .with_tls_config(crate::tls::rustls_config::client_config(tls_settings)?)
+            .https_or_http()
+            .enable_http1()
+            .enable_http2()
+            .build())
+    }
+
     #[cfg(feature = "native-tls")]
     pub fn native_tls() -> NativeTls {
         hyper_tls::HttpsConnector::new()
     }
 
+    /// Create a native-tls HTTPS connector that honors `tls_settings`
+    ///
+    /// When `tls_settings` is empty, this is equivalent to [`native_tls`].
+    #[cfg(feature = "native-tls")]
+    pub fn native_tls_with_tls_settings(
+        tls_settings: &crate::tls::TlsSettings,
+    ) -> Result<NativeTls, crate::tls::TlsSettingsError> {
+        if tls_settings.is_empty() {
+            return Ok(native_tls());
+        }
+        let tls_connector = crate::tls::native_tls_config::tls_connector(tls_settings)?;
+        let mut http = hyper::client::HttpConnector::new();
+        http.enforce_http(false);
+        Ok(hyper_tls::HttpsConnector::from((
+            http,
+            tls_connector.into(),
+        )))
+    }
+
     #[cfg(feature = "native-tls")]
     pub type NativeTls = hyper_tls::HttpsConnector<hyper::client::HttpConnector>;
 
@@ -4,15 +4,20 @@
 # NOTE: This is manually created to surpass some mypy errors and it is incomplete,
 #       in future we will autogenerate correct stubs.
 
-from typing import Any, TypeVar, Callable
+from typing import Any, Dict, List, Optional, TypeVar, Callable, Union
 
 F = TypeVar("F", bound=Callable[..., Any])
 
+# Smithy documents are converted to and from native Python values.
+Document = Union[Dict[str, "Document"], List["Document"], str, int, float, bool, None]
+
 class App:
     context: Any
     run: Any
+    test_client: Any
+    sync_handler_pool_stats: Any
 
-    def middleware(self, func: F) -> F: ...
+    def middleware(self, func: F, operations: Optional[List[str]] = None) -> F: ...
     def do_nothing(self, func: F) -> F: ...
     def get_pokemon_species(self, func: F) -> F: ...
     def get_server_statistics(self, func: F) -> F: ...
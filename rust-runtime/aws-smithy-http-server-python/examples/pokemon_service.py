@@ -5,9 +5,10 @@
 
 import logging
 import random
+import time
 from threading import Lock
 from dataclasses import dataclass
-from typing import List, Optional, Callable, Awaitable
+from typing import Any, Dict, List, Optional, Callable, Awaitable
 
 from libpokemon_service_server_sdk import App
 from libpokemon_service_server_sdk.error import ResourceNotFoundException  # type: ignore
@@ -141,6 +142,11 @@ app.context(Context())
 # the `Response` returned by the handler.
 # It can also raise an `MiddlewareException` with custom error message and HTTP status code,
 # any other raised exceptions will cause an internal server error response to be returned.
+# Middlewares run after the request has been routed to an operation, whose name is available
+# in `request.operation_name`, and they can be restricted to some operations by passing
+# `operations` to `app.middleware`.
+# Values stored in `request.context` are passed to the operation handlers that take it
+# as third argument.
 
 # Next is either the next middleware in the stack or the handler.
 Next = Callable[[Request], Awaitable[Response]]
@@ -180,6 +186,17 @@ async def check_x_amzn_answer_header(request: Request, next: Next) -> Response:
     return await next(request)
 
 
+# This middleware only runs for the `GetPokemonSpecies` operation and
+# records when the request was received in the request context.
+async def record_request_time(request: Request, next: Next) -> Response:
+    request.context["received_at"] = time.monotonic()
+    logging.debug("Received `%s` request", request.operation_name)
+    return await next(request)
+
+
+app.middleware(record_request_time, operations=["GetPokemonSpecies"])
+
+
 ###########################################################
 # App handlers definition
 ###########################################################
@@ -193,9 +210,13 @@ def do_nothing(_: DoNothingInput) -> DoNothingOutput:
 # Get the translation of a Pokémon specie or an error.
 @app.get_pokemon_species
 def get_pokemon_species(
-    input: GetPokemonSpeciesInput, context: Context
+    input: GetPokemonSpeciesInput, context: Context, request_context: Dict[str, Any]
 ) -> GetPokemonSpeciesOutput:
     context.increment_calls_count()
+    logging.debug(
+        "Request received %.3fs ago",
+        time.monotonic() - request_context["received_at"],
+    )
     flavor_text_entries = context.get_pokemon_description(input.name)
     if flavor_text_entries:
         logging.debug("Total requests executed: %s", context.get_calls_count())
@@ -8,7 +8,8 @@
 use aws_smithy_http_server::{
     body::{to_boxed, BoxBody},
     proto::{
-        aws_json_10::AwsJson1_0, aws_json_11::AwsJson1_1, rest_json_1::RestJson1, rest_xml::RestXml,
+        aws_json_10::AwsJson1_0, aws_json_11::AwsJson1_1, rest_json_1::RestJson1,
+        rest_xml::RestXml, rpc_v2_cbor::RpcV2Cbor,
     },
     response::IntoResponse,
 };
@@ -115,6 +116,17 @@ impl IntoResponse<AwsJson1_1> for PyMiddlewareException {
     }
 }
 
+impl IntoResponse<RpcV2Cbor> for PyMiddlewareException {
+    fn into_response(self) -> http::Response<BoxBody> {
+        http::Response::builder()
+            .status(self.status_code)
+            .header("Content-Type", "application/cbor")
+            .header("smithy-protocol", "rpc-v2-cbor")
+            .body(to_boxed(self.cbor_body()))
+            .expect("invalid HTTP response for `MiddlewareException`; please file a bug report under https://github.com/awslabs/smithy-rs/issues")
+    }
+}
+
 impl PyMiddlewareException {
     /// Serialize the body into a JSON object.
     fn json_body(&self) -> String {
@@ -125,6 +137,15 @@ impl PyMiddlewareException {
         out
     }
 
+    /// Serialize the body into a CBOR map.
+    fn cbor_body(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut object = aws_smithy_cbor::serialize::CborObjectWriter::new(&mut out);
+        object.key("message").string(self.message.as_str());
+        object.finish();
+        out
+    }
+
     /// Serialize the body into a XML object.
     fn xml_body(&self) -> String {
         let mut out = String::new();
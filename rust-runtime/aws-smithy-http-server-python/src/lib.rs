@@ -14,8 +14,11 @@
 mod error;
 pub mod logging;
 pub mod middleware;
+pub mod pool;
 mod server;
 mod socket;
+pub mod testing;
+pub mod tls;
 pub mod types;
 mod util;
 
@@ -24,12 +27,21 @@ pub use error::{PyError, PyMiddlewareException};
 #[doc(inline)]
 pub use logging::{py_tracing_event, PyTracingHandler};
 #[doc(inline)]
-pub use middleware::{PyMiddlewareHandler, PyMiddlewareLayer, PyRequest, PyResponse};
+pub use middleware::{
+    PyMiddlewareHandler, PyMiddlewareLayer, PyOperationLayer, PyRequest, PyRequestContext,
+    PyResponse,
+};
 #[doc(inline)]
-pub use server::{PyApp, PyHandler};
+pub use pool::{SyncHandlerPool, SyncHandlerPoolStats};
+#[doc(inline)]
+pub use server::{PyApp, PyHandler, PyWorkerConfig};
 #[doc(inline)]
 pub use socket::PySocket;
 #[doc(inline)]
+pub use testing::{PyTestClient, PyTestResponse};
+#[doc(inline)]
+pub use tls::PyTlsConfig;
+#[doc(inline)]
 pub use util::error::{rich_py_err, RichPyErr};
 
 #[cfg(test)]
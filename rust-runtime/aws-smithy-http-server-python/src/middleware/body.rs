@@ -0,0 +1,106 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Python-compatible middleware bodies.
+//!
+//! Request and response bodies are exposed to Python as [ByteStream]s that can be iterated
+//! asynchronously without buffering the whole body in memory. Bodies can be replaced with
+//! `bytes`, a [ByteStream] or any Python async iterable yielding `bytes`, which is polled
+//! lazily while the body is sent.
+
+use aws_smithy_http::body::SdkBody;
+use aws_smithy_http_server::body::Body;
+use bytes::Bytes;
+use futures::{stream, Stream};
+use hyper::body::HttpBody;
+use pyo3::{
+    exceptions::{PyStopAsyncIteration, PyTypeError},
+    prelude::*,
+    types::PyBytes,
+};
+use pyo3_asyncio::TaskLocals;
+use tower::BoxError;
+
+use crate::types::ByteStream;
+
+/// A body set from Python.
+#[derive(FromPyObject)]
+pub enum PyBody {
+    /// Python `bytes`.
+    Bytes(Py<PyBytes>),
+    /// A [ByteStream], usually obtained from another request or response.
+    Stream(ByteStream),
+    /// Any other object, which must be an async iterable yielding `bytes`.
+    AsyncIterable(PyObject),
+}
+
+impl PyBody {
+    /// Convert this body into a [Body].
+    ///
+    /// Async iterables are polled on the event loop running the current middleware.
+    pub fn into_body(self, py: Python) -> PyResult<Body> {
+        match self {
+            PyBody::Bytes(bytes) => Ok(Body::from(bytes.as_ref(py).as_bytes().to_vec())),
+            PyBody::Stream(stream) => Ok(Body::wrap_stream(stream)),
+            PyBody::AsyncIterable(iterable) => {
+                let iterable = iterable.as_ref(py);
+                if !iterable.hasattr("__aiter__")? {
+                    return Err(PyTypeError::new_err(format!(
+                        "body must be `bytes`, `ByteStream` or an async iterable of `bytes`, not `{}`",
+                        iterable.get_type().name()?
+                    )));
+                }
+                let iterator = iterable.call_method0("__aiter__")?.into_py(py);
+                let locals = pyo3_asyncio::tokio::get_current_locals(py)?;
+                Ok(Body::wrap_stream(async_iterator_stream(iterator, locals)))
+            }
+        }
+    }
+}
+
+/// Wrap a body into a [ByteStream] without buffering it.
+pub(crate) fn into_byte_stream<B>(body: B) -> ByteStream
+where
+    B: HttpBody<Data = Bytes> + Send + Unpin + 'static,
+    B::Error: Into<BoxError>,
+{
+    let chunks = stream::unfold(body, |mut body| async move {
+        body.data().await.map(|chunk| (chunk, body))
+    });
+    ByteStream::new(SdkBody::from(Body::wrap_stream(chunks)))
+}
+
+// Turns a Python async iterator into a [Stream] of chunks, awaiting `__anext__` on the
+// event loop referenced by `locals` every time a new chunk is requested.
+fn async_iterator_stream(
+    iterator: PyObject,
+    locals: TaskLocals,
+) -> impl Stream<Item = PyResult<Bytes>> + Send + 'static {
+    stream::unfold(Some(iterator), move |iterator| {
+        let locals = locals.clone();
+        async move {
+            let iterator = iterator?;
+            let next = Python::with_gil(|py| {
+                let awaitable = iterator.as_ref(py).call_method0("__anext__")?;
+                pyo3_asyncio::into_future_with_locals(&locals, awaitable)
+            });
+            let chunk = match next {
+                Ok(next) => next.await,
+                Err(err) => Err(err),
+            };
+            Python::with_gil(|py| match chunk {
+                Ok(chunk) => {
+                    let chunk = chunk
+                        .extract::<&[u8]>(py)
+                        .map(Bytes::copy_from_slice)
+                        .map_err(|_| PyTypeError::new_err("body chunks must be `bytes`"));
+                    Some((chunk, Some(iterator)))
+                }
+                Err(err) if err.is_instance_of::<PyStopAsyncIteration>(py) => None,
+                Err(err) => Some((Err(err), None)),
+            })
+        }
+    })
+}
@@ -15,6 +15,8 @@ pub enum PyMiddlewareError {
     RequestGone,
     #[error("response is called after it is returned")]
     ResponseGone,
+    #[error("body is accessed while it is being read")]
+    BodyInUse,
 }
 
 impl From<PyMiddlewareError> for PyErr {
@@ -5,7 +5,10 @@
 
 //! Execute pure-Python middleware handler.
 
-use aws_smithy_http_server::body::{Body, BoxBody};
+use aws_smithy_http_server::{
+    body::{Body, BoxBody},
+    extension::OperationExtension,
+};
 use http::{Request, Response};
 use pyo3::{exceptions::PyRuntimeError, prelude::*, types::PyFunction};
 use pyo3_asyncio::TaskLocals;
@@ -67,11 +70,16 @@ impl PyNext {
 ///
 /// The Python business logic implementation needs to carry some information
 /// to be executed properly like if it is a coroutine.
+///
+/// If `operations` is set, the middleware only runs for the listed operations,
+/// matched against either the operation name (`GetPokemonSpecies`) or the absolute
+/// operation shape ID with `#` replaced by `.` (`com.aws.example.GetPokemonSpecies`).
 #[derive(Debug, Clone)]
 pub struct PyMiddlewareHandler {
     pub name: String,
     pub func: PyObject,
     pub is_coroutine: bool,
+    pub operations: Option<Vec<String>>,
 }
 
 impl PyMiddlewareHandler {
@@ -81,9 +89,20 @@ impl PyMiddlewareHandler {
             name: func_metadata.name,
             func,
             is_coroutine: func_metadata.is_coroutine,
+            operations: None,
         })
     }
 
+    /// Returns `true` if this middleware must run for the given operation.
+    pub fn applies_to(&self, operation: &OperationExtension) -> bool {
+        match &self.operations {
+            Some(operations) => operations
+                .iter()
+                .any(|name| name == operation.name() || name == operation.absolute()),
+            None => true,
+        }
+    }
+
     // Calls pure-Python middleware handler with given `Request` and the next Tower service
     // and returns the `Response` that returned from the pure-Python handler.
     pub async fn call(
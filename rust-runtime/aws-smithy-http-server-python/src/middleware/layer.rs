@@ -14,16 +14,21 @@ use std::{
 
 use aws_smithy_http_server::{
     body::{Body, BoxBody},
+    extension::OperationExtension,
     response::IntoResponse,
+    runtime_error::RuntimeError,
 };
-use futures::{future::BoxFuture, TryFutureExt};
+use futures::{future::BoxFuture, FutureExt, TryFutureExt};
 use http::{Request, Response};
 use pyo3::Python;
 use pyo3_asyncio::TaskLocals;
-use tower::{util::BoxService, Layer, Service, ServiceExt};
+use tower::{
+    util::{BoxCloneService, BoxService},
+    Layer, Service, ServiceExt,
+};
 
-use super::PyMiddlewareHandler;
-use crate::{util::error::rich_py_err, PyMiddlewareException};
+use super::{PyMiddlewareHandler, PyRequestContext};
+use crate::{pool::SyncHandlerPool, util::error::rich_py_err, PyMiddlewareException};
 
 /// Tower [Layer] implementation of Python middleware handling.
 ///
@@ -128,3 +133,126 @@ where
         )
     }
 }
+
+/// Tower [Layer] running the Python middlewares registered for a single operation.
+///
+/// Only the handlers that [apply](PyMiddlewareHandler::applies_to) to `operation` are kept
+/// and they run in the order they were registered. Before the first middleware runs, the
+/// [OperationExtension] and an empty [PyRequestContext] are inserted in the request extensions,
+/// making them available to Python through [PyRequest](super::PyRequest) and to the operation handler.
+///
+/// The layer is applied to each operation after routing: requests that don't match any operation,
+/// such as requests answered with a 404, don't run any Python middleware.
+///
+/// If the operation handler is synchronous, a place in its [SyncHandlerPool] is reserved for the
+/// whole request, which is rejected with [RuntimeError::Throttling] when the pool queue is full.
+#[derive(Debug, Clone)]
+pub struct PyOperationLayer<P> {
+    operation: OperationExtension,
+    handlers: Vec<PyMiddlewareHandler>,
+    locals: TaskLocals,
+    sync_handler_pool: Option<SyncHandlerPool>,
+    _protocol: PhantomData<P>,
+}
+
+impl<P> PyOperationLayer<P> {
+    pub fn new(
+        operation: OperationExtension,
+        handlers: Vec<PyMiddlewareHandler>,
+        locals: TaskLocals,
+    ) -> Self {
+        let handlers = handlers
+            .into_iter()
+            .filter(|handler| handler.applies_to(&operation))
+            .collect();
+        Self {
+            operation,
+            handlers,
+            locals,
+            sync_handler_pool: None,
+            _protocol: PhantomData,
+        }
+    }
+
+    /// Reserve a place in `pool` before processing each request.
+    pub fn with_sync_handler_pool(mut self, pool: SyncHandlerPool) -> Self {
+        self.sync_handler_pool = Some(pool);
+        self
+    }
+}
+
+impl<S, P> Layer<S> for PyOperationLayer<P>
+where
+    S: Service<Request<Body>, Response = Response<BoxBody>, Error = Infallible>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+    PyMiddlewareException: IntoResponse<P>,
+    RuntimeError: IntoResponse<P>,
+{
+    type Service = PyOperationService;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        // Layers are applied in reverse, so the middlewares run with the same order as they are defined.
+        let inner =
+            self.handlers
+                .iter()
+                .rev()
+                .fold(BoxCloneService::new(inner), |service, handler| {
+                    tracing::trace!(
+                        name = &handler.name,
+                        operation = self.operation.absolute(),
+                        "adding python middleware"
+                    );
+                    let layer = PyMiddlewareLayer::<P>::new(handler.clone(), self.locals.clone());
+                    BoxCloneService::new(layer.layer(service))
+                });
+        PyOperationService {
+            inner,
+            operation: self.operation.clone(),
+            sync_handler_pool: self.sync_handler_pool.clone(),
+            into_response: <RuntimeError as IntoResponse<P>>::into_response,
+        }
+    }
+}
+
+/// Tower [Service] wrapping the [PyOperationLayer].
+#[derive(Clone)]
+pub struct PyOperationService {
+    inner: BoxCloneService<Request<Body>, Response<BoxBody>, Infallible>,
+    operation: OperationExtension,
+    sync_handler_pool: Option<SyncHandlerPool>,
+    into_response: fn(RuntimeError) -> http::Response<BoxBody>,
+}
+
+impl Service<Request<Body>> for PyOperationService {
+    type Response = Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        let slot = match self
+            .sync_handler_pool
+            .as_ref()
+            .map(SyncHandlerPool::try_enqueue)
+        {
+            Some(Ok(slot)) => Some(slot),
+            Some(Err(err)) => {
+                tracing::warn!(error = %err, operation = self.operation.absolute(), "rejecting request");
+                let response = (self.into_response)(RuntimeError::Throttling);
+                return Box::pin(async move { Ok(response) });
+            }
+            None => None,
+        };
+        req.extensions_mut().insert(self.operation.clone());
+        let context = Python::with_gil(PyRequestContext::new);
+        req.extensions_mut().insert(context);
+        // The slot is held until the response is ready.
+        Box::pin(self.inner.call(req).inspect(move |_| drop(slot)))
+    }
+}
@@ -72,6 +72,7 @@
 //! You can see this pattern in [PyRequest], [PyResponse] and the others.
 //!
 
+mod body;
 mod error;
 mod handler;
 mod header_map;
@@ -79,9 +80,10 @@ mod layer;
 mod request;
 mod response;
 
+pub use self::body::PyBody;
 pub use self::error::PyMiddlewareError;
 pub use self::handler::PyMiddlewareHandler;
 pub use self::header_map::PyHeaderMap;
-pub use self::layer::PyMiddlewareLayer;
-pub use self::request::PyRequest;
+pub use self::layer::{PyMiddlewareLayer, PyOperationLayer, PyOperationService};
+pub use self::request::{PyRequest, PyRequestContext};
 pub use self::response::PyResponse;
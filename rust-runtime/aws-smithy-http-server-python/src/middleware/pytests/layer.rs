@@ -7,10 +7,11 @@ use std::convert::Infallible;
 
 use aws_smithy_http_server::{
     body::{to_boxed, Body, BoxBody},
+    extension::OperationExtension,
     proto::rest_json_1::RestJson1,
 };
 use aws_smithy_http_server_python::{
-    middleware::{PyMiddlewareHandler, PyMiddlewareLayer},
+    middleware::{PyMiddlewareHandler, PyMiddlewareLayer, PyOperationLayer, PyRequestContext},
     PyMiddlewareException, PyResponse,
 };
 use http::{Request, Response, StatusCode};
@@ -242,6 +243,97 @@ async def middleware(request, next):
     Ok(())
 }
 
+#[pyo3_asyncio::tokio::test]
+async fn operation_layer_exposes_operation_and_context() -> PyResult<()> {
+    let handler = py_handler(
+        r#"
+async def middleware(request, next):
+    assert request.operation_namespace == "com.amazonaws.example"
+    assert request.operation_name == "GetFoo"
+    request.context["from_middleware"] = "yes"
+    return await next(request)
+"#,
+    );
+    let layer = operation_layer(vec![handler]);
+    let (mut service, mut handle) = spawn_service(layer);
+
+    let th = tokio::spawn(async move {
+        let (req, send_response) = handle.next_request().await.unwrap();
+        let operation = req.extensions().get::<OperationExtension>().unwrap();
+        assert_eq!(operation.absolute(), "com.amazonaws.example.GetFoo");
+        let context = req.extensions().get::<PyRequestContext>().unwrap().clone();
+        Python::with_gil(|py| {
+            let value: String = context
+                .0
+                .as_ref(py)
+                .get_item("from_middleware")
+                .expect("context value not set")
+                .extract()
+                .unwrap();
+            assert_eq!(value, "yes");
+        });
+        send_response.send_response(
+            Response::builder()
+                .body(to_boxed("hello client"))
+                .expect("could not create response"),
+        );
+    });
+
+    let request = simple_request("hello server");
+    let response = service.call(request);
+    assert_body(response.await?, "hello client").await;
+
+    th.await.unwrap();
+    Ok(())
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn operation_layer_only_runs_middlewares_scoped_to_the_operation() -> PyResult<()> {
+    let mut other_operation = py_handler(
+        r#"
+def middleware(request, next):
+    raise MiddlewareException("wrong operation", 403)
+"#,
+    );
+    other_operation.operations = Some(vec!["GetBar".to_string()]);
+    let mut by_name = py_handler(
+        r#"
+async def middleware(request, next):
+    request.headers["X-By-Name"] = "yes"
+    return await next(request)
+"#,
+    );
+    by_name.operations = Some(vec!["GetFoo".to_string()]);
+    let mut by_absolute = py_handler(
+        r#"
+async def middleware(request, next):
+    request.headers["X-By-Absolute"] = "yes"
+    return await next(request)
+"#,
+    );
+    by_absolute.operations = Some(vec!["com.amazonaws.example.GetFoo".to_string()]);
+    let layer = operation_layer(vec![other_operation, by_name, by_absolute]);
+    let (mut service, mut handle) = spawn_service(layer);
+
+    let th = tokio::spawn(async move {
+        let (req, send_response) = handle.next_request().await.unwrap();
+        assert_eq!(&"yes", req.headers().get("X-By-Name").unwrap());
+        assert_eq!(&"yes", req.headers().get("X-By-Absolute").unwrap());
+        send_response.send_response(
+            Response::builder()
+                .body(to_boxed("hello client"))
+                .expect("could not create response"),
+        );
+    });
+
+    let request = simple_request("hello server");
+    let response = service.call(request);
+    assert_body(response.await?, "hello client").await;
+
+    th.await.unwrap();
+    Ok(())
+}
+
 async fn assert_body(response: Response<BoxBody>, eq: &str) {
     let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
     assert_eq!(body, eq);
@@ -278,6 +370,11 @@ fn layer(code: &str) -> PyMiddlewareLayer<RestJson1> {
     PyMiddlewareLayer::<RestJson1>::new(py_handler(code), task_locals())
 }
 
+fn operation_layer(handlers: Vec<PyMiddlewareHandler>) -> PyOperationLayer<RestJson1> {
+    let operation = OperationExtension::new("com.amazonaws.example.GetFoo").unwrap();
+    PyOperationLayer::<RestJson1>::new(operation, handlers, task_locals())
+}
+
 fn task_locals() -> TaskLocals {
     Python::with_gil(|py| {
         Ok::<_, PyErr>(TaskLocals::new(pyo3_asyncio::tokio::get_current_loop(py)?))
@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use aws_smithy_http_server::extension::OperationExtension;
 use aws_smithy_http_server_python::PyRequest;
 use http::{Request, Version};
 use hyper::Body;
@@ -71,3 +72,68 @@ async def handler(req):
 
     Ok(())
 }
+
+#[pyo3_asyncio::tokio::test]
+async fn accessing_operation_and_context() -> PyResult<()> {
+    let request = Request::builder()
+        .extension(OperationExtension::new("com.amazonaws.example.GetFoo").unwrap())
+        .body(Body::from("hello world"))
+        .expect("could not build request");
+    let py_request = PyRequest::new(request);
+
+    Python::with_gil(|py| {
+        let req = PyCell::new(py, py_request)?;
+        py_run!(
+            py,
+            req,
+            r#"
+assert req.operation_namespace == "com.amazonaws.example"
+assert req.operation_name == "GetFoo"
+
+assert req.context == {}
+req.context["user"] = "ash"
+assert req.context["user"] == "ash"
+"#
+        );
+        Ok(())
+    })
+}
+
+#[pyo3_asyncio::tokio::test]
+async fn streaming_and_transforming_request_body() -> PyResult<()> {
+    let request = Request::builder()
+        .body(Body::wrap_stream(futures::stream::iter(vec![
+            Ok::<_, std::io::Error>("hello "),
+            Ok("world"),
+        ])))
+        .expect("could not build request");
+    let py_request = Python::with_gil(|py| Py::new(py, PyRequest::new(request)))?;
+
+    Python::with_gil(|py| {
+        let module = PyModule::from_code(
+            py,
+            r#"
+async def handler(req):
+    async def upper(stream):
+        async for chunk in stream:
+            yield bytes(chunk).upper()
+
+    req.body = upper(req.stream)
+"#,
+            "",
+            "",
+        )?;
+        let handler = module.getattr("handler")?;
+
+        let output = handler.call1((py_request.clone_ref(py),))?;
+        Ok::<_, PyErr>(pyo3_asyncio::tokio::into_future(output))
+    })??
+    .await?;
+
+    let request = Python::with_gil(|py| py_request.borrow_mut(py).take_inner())
+        .expect("request is gone");
+    let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+    assert_eq!(body, "HELLO WORLD");
+
+    Ok(())
+}
@@ -104,3 +104,42 @@ async def handler(res):
 
     Ok(())
 }
+
+#[pyo3_asyncio::tokio::test]
+async fn streaming_response_body() -> PyResult<()> {
+    let response = Response::builder()
+        .body(to_boxed("hello world"))
+        .expect("could not build response");
+    let py_response = Python::with_gil(|py| Py::new(py, PyResponse::new(response)))?;
+
+    Python::with_gil(|py| {
+        let module = PyModule::from_code(
+            py,
+            r#"
+async def handler(res):
+    chunks = [bytes(chunk) async for chunk in res.stream]
+    assert b"".join(chunks) == b"hello world"
+
+    async def chunks():
+        yield b"hello "
+        yield b"client"
+
+    res.body = chunks()
+"#,
+            "",
+            "",
+        )?;
+        let handler = module.getattr("handler")?;
+
+        let output = handler.call1((py_response.clone_ref(py),))?;
+        Ok::<_, PyErr>(pyo3_asyncio::tokio::into_future(output))
+    })??
+    .await?;
+
+    let response = Python::with_gil(|py| py_response.borrow_mut(py).take_inner())
+        .expect("response is gone");
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    assert_eq!(body, "hello client");
+
+    Ok(())
+}
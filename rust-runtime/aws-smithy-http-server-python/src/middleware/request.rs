@@ -8,12 +8,36 @@
 use std::mem;
 use std::sync::Arc;
 
-use aws_smithy_http_server::body::Body;
+use aws_smithy_http_server::{body::Body, extension::OperationExtension};
 use http::{request::Parts, Request};
-use pyo3::{exceptions::PyRuntimeError, prelude::*};
+use pyo3::{exceptions::PyRuntimeError, prelude::*, types::PyDict};
 use tokio::sync::Mutex;
 
-use super::{PyHeaderMap, PyMiddlewareError};
+use super::{
+    body::{into_byte_stream, PyBody},
+    PyHeaderMap, PyMiddlewareError,
+};
+use crate::types::ByteStream;
+
+/// Mutable per-request context shared between Python middlewares and the operation handler.
+///
+/// It is stored inside the request extensions and exposed to Python as a `dict`, both through
+/// [PyRequest]'s `context` attribute and as the third argument of operation handlers.
+#[derive(Debug, Clone)]
+pub struct PyRequestContext(pub Py<PyDict>);
+
+impl PyRequestContext {
+    /// Create a new empty context.
+    pub fn new(py: Python) -> Self {
+        Self(PyDict::new(py).into())
+    }
+}
+
+impl IntoPy<PyObject> for PyRequestContext {
+    fn into_py(self, py: Python) -> PyObject {
+        self.0.into_py(py)
+    }
+}
 
 /// Python-compatible [Request] object.
 #[pyclass(name = "Request")]
@@ -51,6 +75,13 @@ impl PyRequest {
         };
         Some(Request::from_parts(parts, body))
     }
+
+    fn operation(&self) -> PyResult<Option<&OperationExtension>> {
+        self.parts
+            .as_ref()
+            .map(|parts| parts.extensions.get::<OperationExtension>())
+            .ok_or_else(|| PyMiddlewareError::RequestGone.into())
+    }
 }
 
 #[pymethods]
@@ -82,6 +113,38 @@ impl PyRequest {
             .ok_or_else(|| PyMiddlewareError::RequestGone.into())
     }
 
+    /// Return the namespace of the operation this request has been routed to.
+    #[getter]
+    fn operation_namespace(&self) -> PyResult<Option<&'static str>> {
+        self.operation()
+            .map(|operation| operation.map(OperationExtension::namespace))
+    }
+
+    /// Return the name of the operation this request has been routed to.
+    #[getter]
+    fn operation_name(&self) -> PyResult<Option<&'static str>> {
+        self.operation()
+            .map(|operation| operation.map(OperationExtension::name))
+    }
+
+    /// Return the context of this request.
+    ///
+    /// The same `dict` is returned on every access and it is passed to the operation handler,
+    /// so middlewares can use it to share data with the handler.
+    #[getter]
+    fn context(&mut self, py: Python) -> PyResult<Py<PyDict>> {
+        let parts = self.parts.as_mut().ok_or(PyMiddlewareError::RequestGone)?;
+        let context = match parts.extensions.get::<PyRequestContext>() {
+            Some(context) => context.clone(),
+            None => {
+                let context = PyRequestContext::new(py);
+                parts.extensions.insert(context.clone());
+                context
+            }
+        };
+        Ok(context.0)
+    }
+
     /// Return the HTTP headers of this request.
     #[getter]
     fn headers(&self) -> PyHeaderMap {
@@ -109,9 +172,28 @@ impl PyRequest {
         })
     }
 
+    /// Return the HTTP body of this request as a [ByteStream] that can be iterated asynchronously.
+    ///
+    /// The body is not buffered: chunks are read lazily and the chunks consumed from Python
+    /// are not forwarded anymore, unless the body is set again.
+    #[getter]
+    fn stream(&self) -> PyResult<ByteStream> {
+        let mut body = self
+            .body
+            .try_lock()
+            .map_err(|_| PyMiddlewareError::BodyInUse)?;
+        let stream = into_byte_stream(body.take().ok_or(PyMiddlewareError::RequestGone)?);
+        body.replace(Body::wrap_stream(stream.clone()));
+        Ok(stream)
+    }
+
     /// Set the HTTP body of this request.
+    ///
+    /// The body can be `bytes`, a `ByteStream` or an async iterable yielding `bytes`,
+    /// which is polled lazily when the request is processed.
     #[setter]
-    fn set_body(&mut self, buf: &[u8]) {
-        self.body = Arc::new(Mutex::new(Some(Body::from(buf.to_owned()))));
+    fn set_body(&mut self, py: Python, body: PyBody) -> PyResult<()> {
+        self.body = Arc::new(Mutex::new(Some(body.into_body(py)?)));
+        Ok(())
     }
 }
@@ -9,12 +9,16 @@ use std::collections::HashMap;
 use std::mem;
 use std::sync::Arc;
 
-use aws_smithy_http_server::body::{to_boxed, BoxBody};
+use aws_smithy_http_server::body::{boxed, Body, BoxBody};
 use http::{response::Parts, Response};
 use pyo3::{exceptions::PyRuntimeError, prelude::*};
 use tokio::sync::Mutex;
 
-use super::{PyHeaderMap, PyMiddlewareError};
+use super::{
+    body::{into_byte_stream, PyBody},
+    PyHeaderMap, PyMiddlewareError,
+};
+use crate::types::ByteStream;
 
 /// Python-compatible [Response] object.
 #[pyclass(name = "Response")]
@@ -58,9 +62,10 @@ impl PyResponse {
     /// Python-compatible [Response] object from the Python side.
     #[new]
     fn newpy(
+        py: Python,
         status: u16,
         headers: Option<HashMap<String, String>>,
-        body: Option<Vec<u8>>,
+        body: Option<PyBody>,
     ) -> PyResult<Self> {
         let mut builder = Response::builder().status(status);
 
@@ -70,8 +75,12 @@ impl PyResponse {
             }
         }
 
+        let body = match body {
+            Some(body) => boxed(body.into_body(py)?),
+            None => BoxBody::default(),
+        };
         let response = builder
-            .body(body.map(to_boxed).unwrap_or_default())
+            .body(body)
             .map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
 
         Ok(Self::new(response))
@@ -114,7 +123,7 @@ impl PyResponse {
                     .await
                     .map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
                 let buf = body.clone();
-                body_guard.replace(to_boxed(body));
+                body_guard.replace(boxed(Body::from(body)));
                 buf
             };
             // TODO(Perf): can we use `PyBytes` here?
@@ -122,9 +131,28 @@ impl PyResponse {
         })
     }
 
+    /// Return the HTTP body of this response as a [ByteStream] that can be iterated asynchronously.
+    ///
+    /// The body is not buffered: chunks are read lazily and the chunks consumed from Python
+    /// are not forwarded anymore, unless the body is set again.
+    #[getter]
+    fn stream(&self) -> PyResult<ByteStream> {
+        let mut body = self
+            .body
+            .try_lock()
+            .map_err(|_| PyMiddlewareError::BodyInUse)?;
+        let stream = into_byte_stream(body.take().ok_or(PyMiddlewareError::ResponseGone)?);
+        body.replace(boxed(Body::wrap_stream(stream.clone())));
+        Ok(stream)
+    }
+
     /// Set the HTTP body of this response.
+    ///
+    /// The body can be `bytes`, a `ByteStream` or an async iterable yielding `bytes`,
+    /// which is polled lazily when the response is sent.
     #[setter]
-    fn set_body(&mut self, buf: &[u8]) {
-        self.body = Arc::new(Mutex::new(Some(to_boxed(buf.to_owned()))));
+    fn set_body(&mut self, py: Python, body: PyBody) -> PyResult<()> {
+        self.body = Arc::new(Mutex::new(Some(boxed(body.into_body(py)?))));
+        Ok(())
     }
 }
@@ -0,0 +1,227 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Bounded thread pool used to run synchronous Python handlers.
+
+use std::{
+    panic,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use pyo3::prelude::*;
+use thiserror::Error;
+use tokio::sync::Semaphore;
+
+/// Thread pool running synchronous Python handlers outside of the Tokio worker threads.
+///
+/// At most `max_threads` handlers run concurrently, on Tokio's blocking threads, and at most
+/// `queue_size` requests wait for a free thread. Further requests are rejected, see
+/// [SyncHandlerPool::try_enqueue], so slow synchronous handlers cannot stall the asynchronous
+/// handlers served by the same worker.
+///
+/// The load of the pool can be observed with [SyncHandlerPool::stats], which Python code reads
+/// with `app.sync_handler_pool_stats()`.
+#[derive(Debug, Clone)]
+pub struct SyncHandlerPool {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    threads: Arc<Semaphore>,
+    max_threads: usize,
+    queue_size: usize,
+    // Requests either waiting for a thread or running on one.
+    pending: AtomicUsize,
+    rejected: AtomicU64,
+}
+
+impl SyncHandlerPool {
+    /// Create a new [SyncHandlerPool].
+    pub fn new(max_threads: usize, queue_size: usize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                threads: Arc::new(Semaphore::new(max_threads)),
+                max_threads,
+                queue_size,
+                pending: AtomicUsize::new(0),
+                rejected: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Reserve a place in the pool for a request, failing if the queue is full.
+    ///
+    /// The place is released when the returned [QueueSlot] is dropped.
+    pub fn try_enqueue(&self) -> Result<QueueSlot, SyncHandlerPoolError> {
+        let limit = self.inner.max_threads + self.inner.queue_size;
+        let pending = self.inner.pending.fetch_add(1, Ordering::SeqCst);
+        if pending >= limit {
+            self.inner.pending.fetch_sub(1, Ordering::SeqCst);
+            self.inner.rejected.fetch_add(1, Ordering::Relaxed);
+            return Err(SyncHandlerPoolError::QueueFull {
+                queue_size: self.inner.queue_size,
+            });
+        }
+        let slot = QueueSlot { pool: self.clone() };
+        tracing::trace!(
+            queue_depth = self.queue_depth(),
+            active_threads = self.active_threads(),
+            "request enqueued for a synchronous handler"
+        );
+        Ok(slot)
+    }
+
+    /// Run `f` on a thread of the pool, waiting for one to be free.
+    pub async fn run<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let permit = self
+            .inner
+            .threads
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("sync handler pool semaphore is never closed");
+        let result = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            f()
+        })
+        .await;
+        match result {
+            Ok(output) => output,
+            Err(err) => panic::resume_unwind(err.into_panic()),
+        }
+    }
+
+    /// Number of requests waiting for a free thread.
+    pub fn queue_depth(&self) -> usize {
+        self.inner
+            .pending
+            .load(Ordering::SeqCst)
+            .saturating_sub(self.active_threads())
+    }
+
+    /// Number of threads currently running a handler.
+    pub fn active_threads(&self) -> usize {
+        self.inner.max_threads - self.inner.threads.available_permits()
+    }
+
+    /// Number of requests rejected because the queue was full since the pool was created.
+    pub fn rejected_requests(&self) -> u64 {
+        self.inner.rejected.load(Ordering::Relaxed)
+    }
+
+    /// Snapshot of the current load of the pool.
+    pub fn stats(&self) -> SyncHandlerPoolStats {
+        SyncHandlerPoolStats {
+            max_threads: self.inner.max_threads,
+            queue_size: self.inner.queue_size,
+            active_threads: self.active_threads(),
+            queue_depth: self.queue_depth(),
+            rejected_requests: self.rejected_requests(),
+        }
+    }
+}
+
+/// Snapshot of the load of a [SyncHandlerPool], readable from Python.
+#[pyclass(name = "SyncHandlerPoolStats")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncHandlerPoolStats {
+    /// Number of threads running synchronous handlers.
+    #[pyo3(get)]
+    pub max_threads: usize,
+    /// Number of requests that can wait for a thread.
+    #[pyo3(get)]
+    pub queue_size: usize,
+    /// Number of threads currently running a handler.
+    #[pyo3(get)]
+    pub active_threads: usize,
+    /// Number of requests waiting for a free thread.
+    #[pyo3(get)]
+    pub queue_depth: usize,
+    /// Number of requests rejected because the queue was full.
+    #[pyo3(get)]
+    pub rejected_requests: u64,
+}
+
+/// A place reserved in a [SyncHandlerPool], released on drop.
+#[derive(Debug)]
+pub struct QueueSlot {
+    pool: SyncHandlerPool,
+}
+
+impl Drop for QueueSlot {
+    fn drop(&mut self) {
+        self.pool.inner.pending.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Possible [SyncHandlerPool] errors.
+#[derive(Error, Debug)]
+pub enum SyncHandlerPoolError {
+    #[error("sync handler queue is full ({queue_size} requests waiting)")]
+    QueueFull { queue_size: usize },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn rejects_requests_when_queue_is_full() {
+        let pool = SyncHandlerPool::new(1, 1);
+        let running = pool.try_enqueue().unwrap();
+        let queued = pool.try_enqueue().unwrap();
+        assert!(matches!(
+            pool.try_enqueue(),
+            Err(SyncHandlerPoolError::QueueFull { queue_size: 1 })
+        ));
+        assert_eq!(
+            SyncHandlerPoolStats {
+                max_threads: 1,
+                queue_size: 1,
+                active_threads: 0,
+                queue_depth: 2,
+                rejected_requests: 1,
+            },
+            pool.stats()
+        );
+
+        drop(queued);
+        let _queued = pool.try_enqueue().unwrap();
+        drop(running);
+    }
+
+    #[tokio::test]
+    async fn runs_at_most_max_threads_handlers() {
+        let pool = SyncHandlerPool::new(1, 10);
+        let _first = pool.try_enqueue().unwrap();
+        let _second = pool.try_enqueue().unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel::<()>();
+        let blocked = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.run(move || rx.recv().unwrap()).await }
+        });
+        while pool.active_threads() == 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(pool.active_threads(), 1);
+        assert_eq!(pool.queue_depth(), 1);
+
+        tx.send(()).unwrap();
+        blocked.await.unwrap();
+        assert_eq!(pool.run(|| 42).await, 42);
+        assert_eq!(pool.active_threads(), 0);
+    }
+}
@@ -3,7 +3,18 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use std::{collections::HashMap, convert::Infallible, ops::Deref, process, thread};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    ops::Deref,
+    process,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
 use aws_smithy_http_server::{
     body::{Body, BoxBody},
@@ -14,10 +25,12 @@ use http::{Request, Response};
 use parking_lot::Mutex;
 use pyo3::{prelude::*, types::IntoPyDict};
 use signal_hook::{consts::*, iterator::Signals};
-use tokio::runtime;
-use tower::{util::BoxCloneService, ServiceBuilder};
+use tokio::{runtime, sync::Notify};
+use tower::{util::BoxCloneService, ServiceBuilder, ServiceExt};
 
 use crate::{
+    pool::SyncHandlerPool,
+    tls::{listener::TlsListener, PyTlsConfig},
     util::{error::rich_py_err, func_metadata},
     PySocket,
 };
@@ -31,7 +44,8 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct PyHandler {
     pub func: PyObject,
-    // Number of args is needed to decide whether handler accepts context as an argument
+    // Number of args is needed to decide whether handler accepts context and the
+    // per-request context as arguments
     pub args: usize,
     pub is_coroutine: bool,
 }
@@ -47,6 +61,161 @@ impl Deref for PyHandler {
 // A `BoxCloneService` with default `Request`, `Response` and `Error`.
 type Service = BoxCloneService<Request<Body>, Response<BoxBody>, Infallible>;
 
+// How often the supervisor checks the state of the workers.
+const SUPERVISOR_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Configuration of the supervision of the Python workers.
+///
+/// Workers that die are restarted by the main process. Workers exiting with a non-zero
+/// exit code are restarted with an exponential backoff, starting at `min_restart_backoff_ms`
+/// and capped at `max_restart_backoff_ms`; the backoff is reset once a worker stays up for
+/// longer than `max_restart_backoff_ms`.
+///
+/// Workers can also be recycled: a worker gracefully shuts down after serving `max_requests`
+/// requests, and it is terminated by the main process when its resident memory exceeds
+/// `max_memory_bytes` (only supported on Linux). Recycled workers are restarted immediately.
+///
+/// Synchronous handlers run on a [SyncHandlerPool] of `sync_handler_threads` threads (the number
+/// of CPUs by default), with at most `sync_handler_queue_size` requests waiting for a thread.
+/// Requests exceeding the queue size are rejected with a `ThrottlingException`.
+#[pyclass(name = "WorkerConfig")]
+#[pyo3(
+    text_signature = "(max_requests=None, max_memory_bytes=None, min_restart_backoff_ms=100, max_restart_backoff_ms=30000, sync_handler_threads=None, sync_handler_queue_size=1024)"
+)]
+#[derive(Debug, Clone)]
+pub struct PyWorkerConfig {
+    /// Number of requests after which a worker is recycled.
+    #[pyo3(get)]
+    pub max_requests: Option<u64>,
+    /// Resident memory, in bytes, after which a worker is recycled.
+    #[pyo3(get)]
+    pub max_memory_bytes: Option<u64>,
+    /// Backoff before the first restart of a crashed worker.
+    #[pyo3(get)]
+    pub min_restart_backoff_ms: u64,
+    /// Maximum backoff before restarting a crashed worker.
+    #[pyo3(get)]
+    pub max_restart_backoff_ms: u64,
+    /// Number of threads running synchronous handlers.
+    #[pyo3(get)]
+    pub sync_handler_threads: Option<usize>,
+    /// Number of requests waiting for a thread running synchronous handlers.
+    #[pyo3(get)]
+    pub sync_handler_queue_size: usize,
+}
+
+impl Default for PyWorkerConfig {
+    fn default() -> Self {
+        Self {
+            max_requests: None,
+            max_memory_bytes: None,
+            min_restart_backoff_ms: 100,
+            max_restart_backoff_ms: 30_000,
+            sync_handler_threads: None,
+            sync_handler_queue_size: 1024,
+        }
+    }
+}
+
+#[pymethods]
+impl PyWorkerConfig {
+    /// Create a new [PyWorkerConfig].
+    #[new]
+    #[args(
+        max_requests = "None",
+        max_memory_bytes = "None",
+        min_restart_backoff_ms = "100",
+        max_restart_backoff_ms = "30000",
+        sync_handler_threads = "None",
+        sync_handler_queue_size = "1024"
+    )]
+    fn newpy(
+        max_requests: Option<u64>,
+        max_memory_bytes: Option<u64>,
+        min_restart_backoff_ms: u64,
+        max_restart_backoff_ms: u64,
+        sync_handler_threads: Option<usize>,
+        sync_handler_queue_size: usize,
+    ) -> Self {
+        Self {
+            max_requests,
+            max_memory_bytes,
+            min_restart_backoff_ms,
+            max_restart_backoff_ms,
+            sync_handler_threads,
+            sync_handler_queue_size,
+        }
+    }
+}
+
+impl PyWorkerConfig {
+    /// Backoff to wait before restarting a worker that crashed `failures` times in a row.
+    fn restart_backoff(&self, failures: u32) -> Duration {
+        let backoff = self
+            .min_restart_backoff_ms
+            .saturating_mul(2u64.saturating_pow(failures));
+        Duration::from_millis(backoff.min(self.max_restart_backoff_ms))
+    }
+
+    /// Create the [SyncHandlerPool] running the synchronous handlers of a worker.
+    pub fn sync_handler_pool(&self) -> SyncHandlerPool {
+        let threads = self
+            .sync_handler_threads
+            .unwrap_or_else(num_cpus::get)
+            .max(1);
+        SyncHandlerPool::new(threads, self.sync_handler_queue_size)
+    }
+}
+
+// Supervision state of a single worker.
+#[derive(Debug)]
+struct WorkerState {
+    started_at: Instant,
+    // Number of consecutive crashes.
+    failures: u32,
+    // When set, the worker is dead and will be restarted at the given instant.
+    restart_at: Option<Instant>,
+    // The worker has been asked to terminate by the supervisor.
+    recycling: bool,
+}
+
+impl WorkerState {
+    fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            failures: 0,
+            restart_at: None,
+            recycling: false,
+        }
+    }
+}
+
+// Returns the resident memory in bytes of the process `pid`, reading it from `/proc/<pid>/status`.
+#[cfg(target_os = "linux")]
+fn resident_memory(pid: i64) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    parse_resident_memory(&status)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn resident_memory(_pid: i64) -> Option<u64> {
+    None
+}
+
+// Parses the `VmRSS` line of a `/proc/<pid>/status` file.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_resident_memory(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kilobytes = line
+        .trim_start_matches("VmRSS:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
 /// Trait defining a Python application.
 ///
 /// A Python application requires handling of multiple processes, signals and allows to register Python
@@ -72,8 +241,13 @@ pub trait PyApp: Clone + pyo3::IntoPy<PyObject> {
     /// Mapping between operation names and their `PyHandler` representation.
     fn handlers(&mut self) -> &mut HashMap<String, PyHandler>;
 
-    /// Build the app's `Service` using given `event_loop`.
-    fn build_service(&mut self, event_loop: &pyo3::PyAny) -> pyo3::PyResult<Service>;
+    /// Build the app's `Service` using given `event_loop`, running synchronous handlers
+    /// on `sync_handler_pool`.
+    fn build_service(
+        &mut self,
+        event_loop: &pyo3::PyAny,
+        sync_handler_pool: SyncHandlerPool,
+    ) -> pyo3::PyResult<Service>;
 
     /// Handle the graceful termination of Python workers by looping through all the
     /// active workers and calling `terminate()` on them. If termination fails, this
@@ -143,24 +317,126 @@ pub trait PyApp: Clone + pyo3::IntoPy<PyObject> {
             Signals::new(&[SIGINT, SIGHUP, SIGQUIT, SIGTERM, SIGUSR1, SIGUSR2, SIGWINCH])
                 .expect("Unable to register signals");
         for sig in signals.forever() {
-            match sig {
-                SIGINT => {
-                    tracing::info!(
-                        sig = %sig, "termination signal received, all workers will be immediately terminated"
-                    );
+            self.handle_rust_signal(sig);
+        }
+    }
+
+    /// Handle a signal received by the main Rust thread, see [PyApp::block_on_rust_signals].
+    fn handle_rust_signal(&self, sig: i32) {
+        match sig {
+            SIGINT => {
+                tracing::info!(
+                    sig = %sig, "termination signal received, all workers will be immediately terminated"
+                );
+
+                self.immediate_termination(self.workers());
+            }
+            SIGTERM | SIGQUIT => {
+                tracing::info!(
+                    sig = %sig, "termination signal received, all workers will be gracefully terminated"
+                );
+                self.graceful_termination(self.workers());
+            }
+            _ => {
+                tracing::debug!(sig = %sig, "signal is ignored by this application");
+            }
+        }
+    }
 
-                    self.immediate_termination(self.workers());
+    /// Supervise the active workers until a termination signal is received.
+    ///
+    /// Signals are handled like in [PyApp::block_on_rust_signals]. In between signals, the
+    /// workers are periodically checked: dead workers are restarted on the same shared socket
+    /// following the backoff policy described in [PyWorkerConfig], and workers exceeding
+    /// `max_memory_bytes` are terminated to be recycled. Every exit is logged with its exit code;
+    /// as in Python `multiprocessing`, a negative exit code `-N` means that the worker was
+    /// killed by signal `N`.
+    fn supervise_workers(
+        &self,
+        py: Python,
+        socket: &PySocket,
+        tls: Option<PyTlsConfig>,
+        worker_config: PyWorkerConfig,
+    ) -> PyResult<()> {
+        let mut signals =
+            Signals::new(&[SIGINT, SIGHUP, SIGQUIT, SIGTERM, SIGUSR1, SIGUSR2, SIGWINCH])
+                .expect("Unable to register signals");
+        let mut states: Vec<WorkerState> = self
+            .workers()
+            .lock()
+            .iter()
+            .map(|_| WorkerState::new())
+            .collect();
+        let max_restart_backoff = Duration::from_millis(worker_config.max_restart_backoff_ms);
+        loop {
+            for sig in signals.pending() {
+                self.handle_rust_signal(sig);
+            }
+
+            let mut workers = self.workers().lock();
+            for (idx, (worker, state)) in workers.iter_mut().zip(states.iter_mut()).enumerate() {
+                let idx = idx + 1;
+                let now = Instant::now();
+                if let Some(restart_at) = state.restart_at {
+                    if now >= restart_at {
+                        *worker =
+                            self.spawn_worker(py, socket, idx, tls.clone(), worker_config.clone())?;
+                        state.started_at = now;
+                        state.restart_at = None;
+                        state.recycling = false;
+                    }
+                    continue;
                 }
-                SIGTERM | SIGQUIT => {
-                    tracing::info!(
-                        sig = %sig, "termination signal received, all workers will be gracefully terminated"
-                    );
-                    self.graceful_termination(self.workers());
+
+                let pid: i64 = worker.getattr(py, "pid")?.extract(py).unwrap_or(-1);
+                let is_alive: bool = worker.call_method0(py, "is_alive")?.extract(py)?;
+                if is_alive {
+                    if let Some(max_memory_bytes) = worker_config.max_memory_bytes {
+                        match resident_memory(pid) {
+                            Some(memory) if memory > max_memory_bytes && !state.recycling => {
+                                tracing::info!(
+                                    idx,
+                                    pid,
+                                    memory,
+                                    max_memory_bytes,
+                                    "worker exceeded its memory threshold, recycling it"
+                                );
+                                worker.call_method0(py, "terminate")?;
+                                state.recycling = true;
+                            }
+                            _ => {}
+                        }
+                    }
+                    continue;
                 }
-                _ => {
-                    tracing::debug!(sig = %sig, "signal is ignored by this application");
+
+                // Reap the dead worker.
+                worker.call_method0(py, "join")?;
+                let exit_code: Option<i32> = worker.getattr(py, "exitcode")?.extract(py)?;
+                if state.recycling || exit_code == Some(0) {
+                    tracing::info!(idx, pid, exit_code, "worker exited, restarting it");
+                    state.failures = 0;
+                    state.restart_at = Some(now);
+                } else {
+                    if state.started_at.elapsed() >= max_restart_backoff {
+                        state.failures = 0;
+                    }
+                    let backoff = worker_config.restart_backoff(state.failures);
+                    state.failures = state.failures.saturating_add(1);
+                    state.restart_at = Some(now + backoff);
+                    tracing::error!(
+                        idx,
+                        pid,
+                        exit_code,
+                        failures = state.failures,
+                        backoff_ms = backoff.as_millis() as u64,
+                        "worker died unexpectedly, restarting it after backoff"
+                    );
                 }
             }
+            drop(workers);
+
+            py.allow_threads(|| thread::sleep(SUPERVISOR_INTERVAL));
         }
     }
 
@@ -213,9 +489,17 @@ event_loop.add_signal_handler(signal.SIGINT,
     /// Now that all the setup is done, we can start the two runtimes and run the [hyper] server.
     /// We spawn a thread with a new [tokio::runtime], setup the middlewares and finally block the
     /// thread on Hyper serve() method.
+    /// If a [PyTlsConfig] is provided, the server accepts connections through a [TlsListener]
+    /// and serves HTTPS, periodically reloading the certificates from disk.
     /// The main process continues and at the end it is blocked on Python `loop.run_forever()`.
     ///
+    /// If [PyWorkerConfig] sets `max_requests`, the Hyper server is gracefully shutdown once that
+    /// many requests have been received and the Python event loop is stopped, letting the worker
+    /// exit so it can be replaced by the supervisor. If the Hyper server fails, the worker exits
+    /// with an error.
+    ///
     /// [uvloop]: https://github.com/MagicStack/uvloop
+    #[allow(clippy::too_many_arguments)]
     fn start_hyper_worker(
         &mut self,
         py: Python,
@@ -223,14 +507,45 @@ event_loop.add_signal_handler(signal.SIGINT,
         event_loop: &PyAny,
         service: Service,
         worker_number: isize,
+        tls: Option<PyTlsConfig>,
+        worker_config: Option<PyWorkerConfig>,
     ) -> PyResult<()> {
         // Clone the socket.
         let borrow = socket.try_borrow_mut()?;
         let held_socket: &PySocket = &*borrow;
         let raw_socket = held_socket.get_socket()?;
+        // Validate the TLS configuration before starting the worker, surfacing errors to Python.
+        if let Some(tls) = &tls {
+            tls.build()?;
+        }
         // Register signals on the Python event loop.
         self.register_python_signals(py, event_loop.to_object(py))?;
 
+        // Count the requests and notify the server to shutdown once `max_requests` is reached.
+        let shutdown = Arc::new(Notify::new());
+        let service = match worker_config.and_then(|config| config.max_requests) {
+            Some(max_requests) => {
+                let received = Arc::new(AtomicU64::new(0));
+                let shutdown = shutdown.clone();
+                BoxCloneService::new(service.map_request(move |request| {
+                    if received.fetch_add(1, Ordering::Relaxed) + 1 == max_requests {
+                        tracing::info!(
+                            max_requests,
+                            "worker reached its maximum number of requests, recycling it"
+                        );
+                        shutdown.notify_one();
+                    }
+                    request
+                }))
+            }
+            None => service,
+        };
+        let graceful_shutdown = async move { shutdown.notified().await };
+
+        let server_error = Arc::new(Mutex::new(None));
+        let thread_server_error = server_error.clone();
+        let thread_event_loop = event_loop.to_object(py);
+
         // Spawn a new background [std::thread] to run the application.
         tracing::trace!("start the tokio runtime in a background task");
         thread::spawn(move || {
@@ -241,35 +556,76 @@ event_loop.add_signal_handler(signal.SIGINT,
                 .build()
                 .expect("Unable to start a new tokio runtime for this process");
             rt.block_on(async move {
-                let server = hyper::Server::from_tcp(
-                    raw_socket
-                        .try_into()
-                        .expect("Unable to convert socket2::Socket into std::net::TcpListener"),
-                )
-                .expect("Unable to create hyper server from shared socket")
-                .serve(IntoMakeService::new(service));
-
-                tracing::trace!("started hyper server from shared socket");
-                // Run forever-ish...
-                if let Err(err) = server.await {
+                let listener: std::net::TcpListener = raw_socket
+                    .try_into()
+                    .expect("Unable to convert socket2::Socket into std::net::TcpListener");
+                let result = match tls {
+                    Some(tls) => {
+                        listener
+                            .set_nonblocking(true)
+                            .expect("Unable to set shared socket as non-blocking");
+                        let listener = tokio::net::TcpListener::from_std(listener)
+                            .expect("Unable to create tokio listener from shared socket");
+                        let acceptor = tls
+                            .watch_acceptor()
+                            .expect("Unable to build TLS configuration");
+                        let server = hyper::Server::builder(TlsListener::new(listener, acceptor))
+                            .serve(IntoMakeService::new(service))
+                            .with_graceful_shutdown(graceful_shutdown);
+
+                        tracing::trace!("started hyper server with tls from shared socket");
+                        // Run forever-ish...
+                        server.await
+                    }
+                    None => {
+                        let server = hyper::Server::from_tcp(listener)
+                            .expect("Unable to create hyper server from shared socket")
+                            .serve(IntoMakeService::new(service))
+                            .with_graceful_shutdown(graceful_shutdown);
+
+                        tracing::trace!("started hyper server from shared socket");
+                        // Run forever-ish...
+                        server.await
+                    }
+                };
+                if let Err(err) = result {
                     tracing::error!(error = ?err, "server error");
+                    *thread_server_error.lock() = Some(err.to_string());
                 }
             });
+            // The server is not running anymore, stop the Python event loop to let the worker exit.
+            Python::with_gil(|py| {
+                let stop = thread_event_loop.getattr(py, "stop")?;
+                thread_event_loop.call_method1(py, "call_soon_threadsafe", (stop,))
+            })
+            .map_err(|e| tracing::error!(error = ?rich_py_err(e), "unable to stop the event loop"))
+            .ok();
         });
         // Block on the event loop forever.
         tracing::trace!("run and block on the python event loop until a signal is received");
         event_loop.call_method0("run_forever")?;
-        Ok(())
+        match server_error.lock().take() {
+            Some(err) => Err(pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "server error: {err}"
+            ))),
+            None => Ok(()),
+        }
     }
 
     /// Register a Python function to be executed inside the Smithy Rust handler.
     ///
     /// There are some information needed to execute the Python code from a Rust handler,
     /// such has if the registered function needs to be awaited (if it is a coroutine) and
-    /// the number of arguments available, which tells us if the handler wants the state to be
-    /// passed or not.
+    /// the number of arguments available, which tells us if the handler wants the state and
+    /// the per-request context to be passed or not.
     fn register_operation(&mut self, py: Python, name: &str, func: PyObject) -> PyResult<()> {
         let func_metadata = func_metadata(py, &func)?;
+        if !(1..=3).contains(&func_metadata.num_args) {
+            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
+                "handler `{name}` must take 1, 2 or 3 arguments, not {}",
+                func_metadata.num_args
+            )));
+        }
         let handler = PyHandler {
             func,
             is_coroutine: func_metadata.is_coroutine,
@@ -316,6 +672,9 @@ event_loop.add_signal_handler(signal.SIGINT,
     /// to clone and start itself as a new process.
     /// The shared sockets is created and Using the [multiprocessing::Process] module, multiple
     /// workers with the method `self.start_worker()` as target are started.
+    /// If `tls` is provided, every worker serves HTTPS using the given [PyTlsConfig].
+    /// Once all the workers are started, the main process supervises them, see
+    /// [PyApp::supervise_workers] and [PyWorkerConfig].
     ///
     /// NOTE: this method ends up calling `self.start_worker` from the Python context, forcing
     /// the struct implementing this trait to also implement a `start_worker` method.
@@ -328,7 +687,7 @@ event_loop.add_signal_handler(signal.SIGINT,
     ///     use std::convert::Infallible;
     ///     use std::collections::HashMap;
     ///     use pyo3::prelude::*;
-    ///     use aws_smithy_http_server_python::{PyApp, PyHandler};
+    ///     use aws_smithy_http_server_python::{PyApp, PyHandler, SyncHandlerPool};
     ///     use aws_smithy_http_server::body::{Body, BoxBody};
     ///     use parking_lot::Mutex;
     ///     use http::{Request, Response};
@@ -342,26 +701,30 @@ event_loop.add_signal_handler(signal.SIGINT,
     ///         fn workers(&self) -> &Mutex<Vec<PyObject>> { todo!() }
     ///         fn context(&self) -> &Option<PyObject> { todo!() }
     ///         fn handlers(&mut self) -> &mut HashMap<String, PyHandler> { todo!() }
-    ///         fn build_service(&mut self, event_loop: &PyAny) -> PyResult<BoxCloneService<Request<Body>, Response<BoxBody>, Infallible>> { todo!() }
+    ///         fn build_service(&mut self, event_loop: &PyAny, sync_handler_pool: SyncHandlerPool) -> PyResult<BoxCloneService<Request<Body>, Response<BoxBody>, Infallible>> { todo!() }
     ///     }
     ///
     ///     #[pymethods]
     ///     impl App {
-    ///     #[pyo3(text_signature = "($self, socket, worker_number)")]
+    ///     #[pyo3(text_signature = "($self, socket, worker_number, tls, worker_config)")]
     ///         pub fn start_worker(
     ///             &mut self,
     ///             py: pyo3::Python,
     ///             socket: &pyo3::PyCell<aws_smithy_http_server_python::PySocket>,
     ///             worker_number: isize,
+    ///             tls: Option<aws_smithy_http_server_python::PyTlsConfig>,
+    ///             worker_config: Option<aws_smithy_http_server_python::PyWorkerConfig>,
     ///         ) -> pyo3::PyResult<()> {
     ///             let event_loop = self.configure_python_event_loop(py)?;
-    ///             let service = self.build_service(event_loop)?;
-    ///             self.start_hyper_worker(py, socket, event_loop, service, worker_number)
+    ///             let sync_handler_pool = worker_config.clone().unwrap_or_default().sync_handler_pool();
+    ///             let service = self.build_service(event_loop, sync_handler_pool)?;
+    ///             self.start_hyper_worker(py, socket, event_loop, service, worker_number, tls, worker_config)
     ///         }
     ///     }
     /// ```
     ///
     /// [multiprocessing::Process]: https://docs.python.org/3/library/multiprocessing.html
+    #[allow(clippy::too_many_arguments)]
     fn run_server(
         &mut self,
         py: Python,
@@ -369,6 +732,8 @@ event_loop.add_signal_handler(signal.SIGINT,
         port: Option<i32>,
         backlog: Option<i32>,
         workers: Option<usize>,
+        tls: Option<PyTlsConfig>,
+        worker_config: Option<PyWorkerConfig>,
     ) -> PyResult<()> {
         // Setup multiprocessing environment, allowing connections and socket
         // sharing between processes.
@@ -394,28 +759,50 @@ event_loop.add_signal_handler(signal.SIGINT,
         let address = address.unwrap_or_else(|| String::from("127.0.0.1"));
         let port = port.unwrap_or(13734);
         let socket = PySocket::new(address, port, backlog)?;
+        let worker_config = worker_config.unwrap_or_default();
         // Lock the workers mutex.
         let mut active_workers = self.workers().lock();
-        // Register the main signal handler.
         // TODO(move from num_cpus to thread::available_parallelism after MSRV is 1.60)
         // Start all the workers as new Python processes and store the in the `workers` attribute.
         for idx in 1..workers.unwrap_or_else(num_cpus::get) + 1 {
-            let sock = socket.try_clone()?;
-            let process = mp.getattr("Process")?;
-            let handle = process.call1((
-                py.None(),
-                self.clone().into_py(py).getattr(py, "start_worker")?,
-                format!("smithy-rs-worker[{idx}]"),
-                (sock.into_py(py), idx),
-            ))?;
-            handle.call_method0("start")?;
-            active_workers.push(handle.to_object(py));
+            let handle = self.spawn_worker(py, &socket, idx, tls.clone(), worker_config.clone())?;
+            active_workers.push(handle);
         }
         // Unlock the workers mutex.
         drop(active_workers);
         tracing::trace!("rust python server started successfully");
-        self.block_on_rust_signals();
-        Ok(())
+        // Register the main signal handler and supervise the workers.
+        self.supervise_workers(py, &socket, tls, worker_config)
+    }
+
+    /// Start a new worker process on a clone of the shared `socket`, using the `start_worker`
+    /// method of the application as target. Returns the `multiprocessing.Process` handle.
+    fn spawn_worker(
+        &self,
+        py: Python,
+        socket: &PySocket,
+        idx: usize,
+        tls: Option<PyTlsConfig>,
+        worker_config: PyWorkerConfig,
+    ) -> PyResult<PyObject> {
+        let mp = py.import("multiprocessing")?;
+        let sock = socket.try_clone()?;
+        let process = mp.getattr("Process")?;
+        let handle = process.call1((
+            py.None(),
+            self.clone().into_py(py).getattr(py, "start_worker")?,
+            format!("smithy-rs-worker[{idx}]"),
+            (
+                sock.into_py(py),
+                idx,
+                tls.into_py(py),
+                worker_config.into_py(py),
+            ),
+        ))?;
+        handle.call_method0("start")?;
+        let pid: isize = handle.getattr("pid")?.extract().unwrap_or(-1);
+        tracing::debug!(idx, pid, "started worker");
+        Ok(handle.to_object(py))
     }
 
     /// Lambda main entrypoint: start the handler on Lambda.
@@ -424,7 +811,8 @@ event_loop.add_signal_handler(signal.SIGINT,
     /// it starts the Lambda handler on the current process.
     fn run_lambda_handler(&mut self, py: Python) -> PyResult<()> {
         let event_loop = self.configure_python_event_loop(py)?;
-        let service = self.build_and_configure_service(py, event_loop)?;
+        let service =
+            self.build_and_configure_service(py, event_loop, &PyWorkerConfig::default())?;
         let rt = runtime::Builder::new_multi_thread()
             .enable_all()
             .build()
@@ -445,8 +833,9 @@ event_loop.add_signal_handler(signal.SIGINT,
         &mut self,
         py: Python,
         event_loop: &pyo3::PyAny,
+        worker_config: &PyWorkerConfig,
     ) -> pyo3::PyResult<Service> {
-        let service = self.build_service(event_loop)?;
+        let service = self.build_service(event_loop, worker_config.sync_handler_pool())?;
         // Create the `PyState` object from the Python context object.
         let context = self.context().clone().unwrap_or_else(|| py.None());
         let service = ServiceBuilder::new()
@@ -456,3 +845,28 @@ event_loop.add_signal_handler(signal.SIGINT,
         Ok(service)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_backoff_grows_exponentially_up_to_the_maximum() {
+        let config = PyWorkerConfig::default();
+        assert_eq!(Duration::from_millis(100), config.restart_backoff(0));
+        assert_eq!(Duration::from_millis(200), config.restart_backoff(1));
+        assert_eq!(Duration::from_millis(400), config.restart_backoff(2));
+        assert_eq!(Duration::from_millis(30_000), config.restart_backoff(10));
+        assert_eq!(
+            Duration::from_millis(30_000),
+            config.restart_backoff(u32::MAX)
+        );
+    }
+
+    #[test]
+    fn resident_memory_is_parsed_from_proc_status() {
+        let status = "Name:\tpython3\nVmPeak:\t  30000 kB\nVmRSS:\t   12345 kB\nThreads:\t4\n";
+        assert_eq!(Some(12345 * 1024), parse_resident_memory(status));
+        assert_eq!(None, parse_resident_memory("Name:\tpython3\n"));
+    }
+}
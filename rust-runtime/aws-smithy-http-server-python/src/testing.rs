@@ -0,0 +1,247 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! In-process test client, allowing to test Python applications without starting workers.
+
+use std::{collections::HashMap, convert::Infallible};
+
+use aws_smithy_http_server::body::{Body, BoxBody};
+use bytes::Bytes;
+use http::{response::Parts, Request, Response};
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    prelude::*,
+    types::PyBytes,
+};
+use pyo3_asyncio::TaskLocals;
+use tower::{util::BoxCloneService, ServiceExt};
+
+/// Test client sending requests straight to the service built by [PyApp::build_service](crate::PyApp::build_service).
+///
+/// No socket is bound and no worker is started: requests are processed by the current process.
+/// When it's created outside of a running event loop, the client uses the Python event loop
+/// configured by [PyApp::configure_python_event_loop](crate::PyApp::configure_python_event_loop)
+/// and `request` runs it until the response is ready. When it's created from a coroutine, the
+/// client uses the running event loop, and responses are awaited with `request_async`.
+///
+/// Example:
+///
+/// ```python
+/// client = app.test_client()
+/// response = client.request("GET", "/stats")
+/// assert response.status == 200
+/// assert response.json()["calls_count"] == 0
+///
+/// async def test_stats():
+///     client = app.test_client()
+///     response = await client.request_async("GET", "/stats")
+///     assert response.status == 200
+/// ```
+#[pyclass(name = "TestClient")]
+pub struct PyTestClient {
+    service: BoxCloneService<Request<Body>, Response<BoxBody>, Infallible>,
+    event_loop: PyObject,
+}
+
+impl PyTestClient {
+    /// Create a new [PyTestClient] for the given service, running Python handlers on `event_loop`.
+    pub fn new(
+        service: BoxCloneService<Request<Body>, Response<BoxBody>, Infallible>,
+        event_loop: &PyAny,
+    ) -> Self {
+        Self {
+            service,
+            event_loop: event_loop.into(),
+        }
+    }
+
+    /// Send a request to the service from Rust.
+    ///
+    /// The Python event loop must be running, for example with `pyo3_asyncio` test utilities.
+    pub async fn send(&self, request: Request<Body>) -> Response<BoxBody> {
+        match self.service.clone().oneshot(request).await {
+            Ok(response) => response,
+            Err(err) => match err {},
+        }
+    }
+}
+
+#[pymethods]
+impl PyTestClient {
+    /// Send a request and wait for the whole response.
+    ///
+    /// This runs the event loop until the response is ready, so it can't be called from a running
+    /// event loop: use `request_async` there.
+    #[pyo3(text_signature = "($self, method, uri, headers=None, body=None)")]
+    #[args(headers = "None", body = "None")]
+    fn request(
+        &self,
+        py: Python,
+        method: &str,
+        uri: &str,
+        headers: Option<HashMap<String, String>>,
+        body: Option<&[u8]>,
+    ) -> PyResult<Py<PyTestResponse>> {
+        let response = self.request_async(py, method, uri, headers, body)?;
+        self.event_loop
+            .as_ref(py)
+            .call_method1("run_until_complete", (response,))?
+            .extract()
+    }
+
+    /// Send a request and return an awaitable resolving to the whole response.
+    ///
+    /// The awaitable must be awaited from the event loop the client was created with, which is
+    /// the running event loop if `app.test_client()` was called from a coroutine.
+    #[pyo3(text_signature = "($self, method, uri, headers=None, body=None)")]
+    #[args(headers = "None", body = "None")]
+    fn request_async<'p>(
+        &self,
+        py: Python<'p>,
+        method: &str,
+        uri: &str,
+        headers: Option<HashMap<String, String>>,
+        body: Option<&[u8]>,
+    ) -> PyResult<&'p PyAny> {
+        let mut builder = Request::builder().method(method).uri(uri);
+        for (name, value) in headers.unwrap_or_default() {
+            builder = builder.header(name, value);
+        }
+        let request = builder
+            .body(Body::from(body.unwrap_or_default().to_vec()))
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+
+        let service = self.service.clone();
+        pyo3_asyncio::tokio::future_into_py_with_locals(
+            py,
+            TaskLocals::new(self.event_loop.as_ref(py)),
+            async move {
+                let response = match service.oneshot(request).await {
+                    Ok(response) => response,
+                    Err(err) => match err {},
+                };
+                let (parts, body) = response.into_parts();
+                let body = hyper::body::to_bytes(body)
+                    .await
+                    .map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
+                Ok(PyTestResponse::new(parts, body))
+            },
+        )
+    }
+}
+
+/// Response returned by [PyTestClient], with the body already read.
+#[pyclass(name = "TestResponse")]
+#[derive(Debug)]
+pub struct PyTestResponse {
+    /// HTTP status of the response.
+    #[pyo3(get)]
+    status: u16,
+    /// HTTP headers of the response, multiple values of the same header are joined with `, `.
+    #[pyo3(get)]
+    headers: HashMap<String, String>,
+    body: Bytes,
+}
+
+impl PyTestResponse {
+    fn new(parts: Parts, body: Bytes) -> Self {
+        let mut headers: HashMap<String, String> = HashMap::new();
+        for (name, value) in parts.headers.iter() {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            headers
+                .entry(name.to_string())
+                .and_modify(|values| {
+                    values.push_str(", ");
+                    values.push_str(&value);
+                })
+                .or_insert_with(|| value.into_owned());
+        }
+        Self {
+            status: parts.status.as_u16(),
+            headers,
+            body,
+        }
+    }
+}
+
+#[pymethods]
+impl PyTestResponse {
+    /// Return the HTTP body of this response.
+    #[getter]
+    fn body<'p>(&self, py: Python<'p>) -> &'p PyBytes {
+        PyBytes::new(py, &self.body)
+    }
+
+    /// Decode the HTTP body of this response as JSON.
+    #[pyo3(text_signature = "($self)")]
+    fn json(&self, py: Python) -> PyResult<PyObject> {
+        let json = py.import("json")?;
+        Ok(json.call_method1("loads", (self.body(py),))?.into_py(py))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_smithy_http_server::body::to_boxed;
+    use pyo3::py_run;
+    use tower::service_fn;
+
+    use super::*;
+
+    fn echo_service() -> BoxCloneService<Request<Body>, Response<BoxBody>, Infallible> {
+        BoxCloneService::new(service_fn(|request: Request<Body>| async move {
+            let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+            let response = Response::builder()
+                .status(201)
+                .header("x-echo", "first")
+                .header("x-echo", "second")
+                .body(to_boxed(body))
+                .unwrap();
+            Ok::<_, Infallible>(response)
+        }))
+    }
+
+    #[test]
+    fn test_client_sends_requests_to_the_service() {
+        let locals = crate::tests::initialize();
+        let service = echo_service();
+        Python::with_gil(|py| {
+            let client =
+                PyCell::new(py, PyTestClient::new(service, locals.event_loop(py))).unwrap();
+            py_run!(
+                py,
+                client,
+                r#"
+response = client.request("POST", "/echo", {"content-type": "application/json"}, b'{"answer": 42}')
+assert response.status == 201
+assert response.headers["x-echo"] == "first, second"
+assert response.body == b'{"answer": 42}'
+assert response.json() == {"answer": 42}
+"#
+            );
+        })
+    }
+
+    #[test]
+    fn test_client_requests_can_be_awaited() {
+        let locals = crate::tests::initialize();
+        Python::with_gil(|py| {
+            let event_loop = locals.event_loop(py);
+            let client = PyCell::new(py, PyTestClient::new(echo_service(), event_loop)).unwrap();
+            py_run!(
+                py,
+                client event_loop,
+                r#"
+async def main():
+    response = await client.request_async("POST", "/echo", body=b"hello")
+    assert response.status == 201
+    assert response.body == b"hello"
+
+event_loop.run_until_complete(main())
+"#
+            );
+        })
+    }
+}
@@ -0,0 +1,299 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! TLS configuration that can be created from Python and shared between workers.
+
+use std::{
+    fs::File,
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+use thiserror::Error;
+use tokio::sync::watch;
+use tokio_rustls::{
+    rustls::{
+        server::AllowAnyAuthenticatedClient, Certificate, Error as RustlsError, PrivateKey,
+        RootCertStore, ServerConfig,
+    },
+    TlsAcceptor,
+};
+
+use crate::error::PyException;
+
+pub mod listener;
+
+/// TLS configuration used by each worker to serve HTTPS.
+///
+/// Certificates and keys are read from disk when the worker starts and then re-read every
+/// `reload_secs` seconds, so rotated certificates are picked up by all workers without
+/// restarting the server. If `client_ca_path` is set, clients are required to present a
+/// certificate signed by one of the CAs contained in that file (mutual TLS).
+#[pyclass(name = "TlsConfig")]
+#[pyo3(text_signature = "(key_path, cert_path, client_ca_path=None, reload_secs=86400)")]
+#[derive(Debug, Clone)]
+pub struct PyTlsConfig {
+    /// Path of the PEM encoded PKCS#8, RSA or EC private key.
+    #[pyo3(get)]
+    key_path: PathBuf,
+    /// Path of the PEM encoded x509 certificate chain.
+    #[pyo3(get)]
+    cert_path: PathBuf,
+    /// Path of the PEM encoded CA certificates used to authenticate clients.
+    #[pyo3(get)]
+    client_ca_path: Option<PathBuf>,
+    /// Number of seconds between two reloads of the certificates.
+    #[pyo3(get)]
+    reload_secs: u64,
+}
+
+#[pymethods]
+impl PyTlsConfig {
+    /// Create a new [PyTlsConfig]. Certificates are reloaded once a day by default.
+    ///
+    /// `reload_secs` must be greater than zero.
+    #[new]
+    #[args(client_ca_path = "None", reload_secs = "86400")]
+    fn newpy(
+        key_path: PathBuf,
+        cert_path: PathBuf,
+        client_ca_path: Option<PathBuf>,
+        reload_secs: u64,
+    ) -> PyResult<Self> {
+        if reload_secs == 0 {
+            return Err(PyValueError::new_err("reload_secs must be greater than 0"));
+        }
+        Ok(Self {
+            key_path,
+            cert_path,
+            client_ca_path,
+            reload_secs,
+        })
+    }
+}
+
+impl PyTlsConfig {
+    /// Build a [ServerConfig] reading certificates and keys from disk.
+    pub fn build(&self) -> Result<ServerConfig, PyTlsConfigError> {
+        let cert_chain = self.cert_chain()?;
+        let key = self.private_key()?;
+        let builder = ServerConfig::builder().with_safe_defaults();
+        let mut config = match self.client_roots()? {
+            Some(roots) => builder
+                .with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots))
+                .with_single_cert(cert_chain, key)?,
+            None => builder
+                .with_no_client_auth()
+                .with_single_cert(cert_chain, key)?,
+        };
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(config)
+    }
+
+    /// Duration between two reloads of the certificates.
+    pub fn reload_duration(&self) -> Duration {
+        Duration::from_secs(self.reload_secs)
+    }
+
+    /// Build the initial [TlsAcceptor] and spawn a task on the current [tokio] runtime
+    /// that rebuilds it every [PyTlsConfig::reload_duration].
+    ///
+    /// If a reload fails, the error is logged and the previous acceptor is kept. The task
+    /// terminates when the returned receiver is dropped.
+    pub fn watch_acceptor(&self) -> Result<watch::Receiver<TlsAcceptor>, PyTlsConfigError> {
+        let acceptor = TlsAcceptor::from(Arc::new(self.build()?));
+        let (tx, rx) = watch::channel(acceptor);
+        let config = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(config.reload_duration());
+            // The first tick completes immediately.
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match config.build() {
+                    Ok(server_config) => {
+                        tracing::info!(cert_path = %config.cert_path.display(), "reloaded tls certificates");
+                        if tx.send(TlsAcceptor::from(Arc::new(server_config))).is_err() {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        tracing::error!(error = %err, "unable to reload tls certificates, keeping the previous ones");
+                    }
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    /// Read the certificate chain from `cert_path`.
+    fn cert_chain(&self) -> Result<Vec<Certificate>, PyTlsConfigError> {
+        let certs = read_pem_certs(&self.cert_path).map_err(PyTlsConfigError::CertParse)?;
+        if certs.is_empty() {
+            return Err(PyTlsConfigError::EmptyCert);
+        }
+        Ok(certs)
+    }
+
+    /// Read the first PKCS#8, RSA or EC private key from `key_path`.
+    fn private_key(&self) -> Result<PrivateKey, PyTlsConfigError> {
+        let file = File::open(&self.key_path).map_err(PyTlsConfigError::KeyParse)?;
+        let mut reader = BufReader::new(file);
+        loop {
+            match rustls_pemfile::read_one(&mut reader).map_err(PyTlsConfigError::KeyParse)? {
+                Some(rustls_pemfile::Item::PKCS8Key(key))
+                | Some(rustls_pemfile::Item::RSAKey(key))
+                | Some(rustls_pemfile::Item::ECKey(key)) => return Ok(PrivateKey(key)),
+                Some(_) => continue,
+                None => return Err(PyTlsConfigError::EmptyKey),
+            }
+        }
+    }
+
+    /// Read the CA certificates used to authenticate clients from `client_ca_path`, if any.
+    fn client_roots(&self) -> Result<Option<RootCertStore>, PyTlsConfigError> {
+        let path = match &self.client_ca_path {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        let certs: Vec<Vec<u8>> = read_pem_certs(path)
+            .map_err(PyTlsConfigError::ClientCaParse)?
+            .into_iter()
+            .map(|cert| cert.0)
+            .collect();
+        let mut roots = RootCertStore::empty();
+        let (added, ignored) = roots.add_parsable_certificates(&certs);
+        if ignored > 0 {
+            tracing::warn!(ignored, path = %path.display(), "ignored invalid client CA certificates");
+        }
+        if added == 0 {
+            return Err(PyTlsConfigError::EmptyClientCa);
+        }
+        Ok(Some(roots))
+    }
+}
+
+fn read_pem_certs(path: &Path) -> Result<Vec<Certificate>, io::Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    Ok(rustls_pemfile::certs(&mut reader)?
+        .into_iter()
+        .map(Certificate)
+        .collect())
+}
+
+/// Possible TLS configuration errors.
+#[derive(Error, Debug)]
+pub enum PyTlsConfigError {
+    #[error("could not parse certificate: {0}")]
+    CertParse(io::Error),
+    #[error("no certificate found")]
+    EmptyCert,
+    #[error("could not parse key: {0}")]
+    KeyParse(io::Error),
+    #[error("no PKCS#8, RSA or EC private key found")]
+    EmptyKey,
+    #[error("could not parse client CA certificates: {0}")]
+    ClientCaParse(io::Error),
+    #[error("no client CA certificate found")]
+    EmptyClientCa,
+    #[error("rustls error: {0}")]
+    Rustls(#[from] RustlsError),
+}
+
+impl From<PyTlsConfigError> for PyErr {
+    fn from(other: PyTlsConfigError) -> PyErr {
+        PyException::new_err(other.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn testdata(file: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/tls/testdata")
+            .join(file)
+    }
+
+    #[test]
+    fn tls_config_can_be_built() {
+        let config = PyTlsConfig::newpy(
+            testdata("localhost.key"),
+            testdata("localhost.crt"),
+            None,
+            86400,
+        )
+        .unwrap();
+        let server_config = config.build().unwrap();
+        assert_eq!(
+            server_config.alpn_protocols,
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+        );
+    }
+
+    #[test]
+    fn tls_config_with_client_ca_can_be_built() {
+        let config = PyTlsConfig::newpy(
+            testdata("localhost.key"),
+            testdata("localhost.crt"),
+            Some(testdata("localhost.crt")),
+            86400,
+        )
+        .unwrap();
+        assert!(config.build().is_ok());
+    }
+
+    #[test]
+    fn tls_config_fails_on_missing_files() {
+        let config = PyTlsConfig::newpy(
+            testdata("missing.key"),
+            testdata("localhost.crt"),
+            None,
+            86400,
+        )
+        .unwrap();
+        assert!(matches!(config.build(), Err(PyTlsConfigError::KeyParse(_))));
+
+        let config = PyTlsConfig::newpy(
+            testdata("localhost.key"),
+            testdata("missing.crt"),
+            None,
+            86400,
+        )
+        .unwrap();
+        assert!(matches!(
+            config.build(),
+            Err(PyTlsConfigError::CertParse(_))
+        ));
+    }
+
+    #[test]
+    fn tls_config_rejects_zero_reload_secs() {
+        let config = PyTlsConfig::newpy(
+            testdata("localhost.key"),
+            testdata("localhost.crt"),
+            None,
+            0,
+        );
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn tls_config_fails_without_key() {
+        // The certificate file does not contain any private key.
+        let config = PyTlsConfig::newpy(
+            testdata("localhost.crt"),
+            testdata("localhost.crt"),
+            None,
+            86400,
+        )
+        .unwrap();
+        assert!(matches!(config.build(), Err(PyTlsConfigError::EmptyKey)));
+    }
+}
@@ -0,0 +1,172 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! TLS listener that can be used with [hyper::Server::builder].
+
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{stream::FuturesUnordered, StreamExt};
+use hyper::server::accept::Accept;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::watch,
+    time::{Sleep, Timeout},
+};
+use tokio_rustls::{server::TlsStream, TlsAcceptor};
+
+/// Default maximum duration of a TLS handshake.
+pub const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Duration to wait before accepting connections again after an accept error, as
+/// [hyper::server::conn::AddrIncoming] does.
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_secs(1);
+
+/// A [TcpListener] wrapper performing the TLS handshake of every accepted connection.
+///
+/// The [TlsAcceptor] is read from a [watch::Receiver] for every new connection, allowing
+/// certificates to be swapped while the server is running. Connections failing the handshake,
+/// or not completing it within the handshake timeout, are dropped instead of being returned to
+/// [hyper], which would otherwise shutdown the server. Errors accepting TCP connections are
+/// logged and accepting is retried after a short backoff.
+pub struct TlsListener {
+    listener: TcpListener,
+    acceptor: watch::Receiver<TlsAcceptor>,
+    handshake_timeout: Duration,
+    handshakes: FuturesUnordered<Timeout<tokio_rustls::Accept<TcpStream>>>,
+    accept_backoff: Option<Pin<Box<Sleep>>>,
+}
+
+impl TlsListener {
+    /// Create a new [TlsListener] from a bound [TcpListener].
+    pub fn new(listener: TcpListener, acceptor: watch::Receiver<TlsAcceptor>) -> Self {
+        Self {
+            listener,
+            acceptor,
+            handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
+            handshakes: FuturesUnordered::new(),
+            accept_backoff: None,
+        }
+    }
+
+    /// Set the maximum duration of a TLS handshake. Defaults to [DEFAULT_HANDSHAKE_TIMEOUT].
+    pub fn with_handshake_timeout(mut self, handshake_timeout: Duration) -> Self {
+        self.handshake_timeout = handshake_timeout;
+        self
+    }
+
+    /// Start the handshake of all the pending TCP connections.
+    fn poll_tcp_accept(&mut self, cx: &mut Context<'_>) {
+        loop {
+            if let Some(backoff) = self.accept_backoff.as_mut() {
+                if backoff.as_mut().poll(cx).is_pending() {
+                    return;
+                }
+                self.accept_backoff = None;
+            }
+            match self.listener.poll_accept(cx) {
+                Poll::Ready(Ok((stream, remote_addr))) => {
+                    tracing::trace!(remote_addr = %remote_addr, "starting tls handshake");
+                    let acceptor = self.acceptor.borrow().clone();
+                    self.handshakes.push(tokio::time::timeout(
+                        self.handshake_timeout,
+                        acceptor.accept(stream),
+                    ));
+                }
+                Poll::Ready(Err(err)) if is_connection_error(&err) => {
+                    tracing::debug!(error = %err, "accepted connection already errored");
+                }
+                Poll::Ready(Err(err)) => {
+                    tracing::error!(error = %err, backoff = ?ACCEPT_ERROR_BACKOFF, "accept error");
+                    let backoff = tokio::time::sleep(ACCEPT_ERROR_BACKOFF);
+                    self.accept_backoff = Some(Box::pin(backoff));
+                }
+                Poll::Pending => return,
+            }
+        }
+    }
+}
+
+/// Returns true for errors affecting a single connection rather than the listener.
+fn is_connection_error(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionReset
+    )
+}
+
+impl Accept for TlsListener {
+    type Conn = TlsStream<TcpStream>;
+    type Error = io::Error;
+
+    fn poll_accept(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Conn, Self::Error>>> {
+        let this = self.get_mut();
+        this.poll_tcp_accept(cx);
+
+        // Return the first connection that completed the handshake.
+        loop {
+            match this.handshakes.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(Ok(stream)))) => return Poll::Ready(Some(Ok(stream))),
+                Poll::Ready(Some(Ok(Err(err)))) => {
+                    tracing::debug!(error = %err, "tls handshake failed, dropping connection");
+                }
+                Poll::Ready(Some(Err(_))) => {
+                    tracing::debug!(
+                        timeout = ?this.handshake_timeout,
+                        "tls handshake timed out, dropping connection"
+                    );
+                }
+                Poll::Ready(None) | Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::tls::PyTlsConfig;
+
+    fn acceptor() -> watch::Receiver<TlsAcceptor> {
+        let testdata = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tls/testdata");
+        PyTlsConfig::newpy(
+            testdata.join("localhost.key"),
+            testdata.join("localhost.crt"),
+            None,
+            86400,
+        )
+        .unwrap()
+        .watch_acceptor()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn stalled_handshakes_time_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut listener = TlsListener::new(listener, acceptor())
+            .with_handshake_timeout(Duration::from_millis(50));
+
+        // The client never sends a ClientHello.
+        let _client = TcpStream::connect(addr).await.unwrap();
+        let accept = futures::future::poll_fn(|cx| Pin::new(&mut listener).poll_accept(cx));
+        assert!(tokio::time::timeout(Duration::from_millis(500), accept)
+            .await
+            .is_err());
+        assert!(listener.handshakes.is_empty());
+    }
+}
@@ -12,12 +12,14 @@ use std::{
     task::{Context, Poll},
 };
 
+use aws_smithy_types::DocumentMap;
 use bytes::Bytes;
 use pyo3::{
-    exceptions::{PyRuntimeError, PyStopIteration},
+    exceptions::{PyRuntimeError, PyStopAsyncIteration, PyStopIteration, PyTypeError},
     iter::IterNextOutput,
     prelude::*,
     pyclass::IterANextOutput,
+    types::{PyBool, PyDict, PyFloat, PyList, PyLong, PyString, PyTuple},
 };
 use tokio::sync::Mutex;
 use tokio_stream::StreamExt;
@@ -248,6 +250,172 @@ impl<'date> From<&'date DateTime> for &'date aws_smithy_types::DateTime {
     }
 }
 
+/// Python Wrapper for [aws_smithy_types::Number].
+///
+/// Numbers are not exposed as a Python class, they are converted to and from native Python
+/// `int` and `float` objects instead. Non-negative integers become [aws_smithy_types::Number::PosInt],
+/// negative integers become [aws_smithy_types::Number::NegInt] and floats become
+/// [aws_smithy_types::Number::Float], so a number keeps its variant when it round trips through Python.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Number(aws_smithy_types::Number);
+
+impl IntoPy<PyObject> for Number {
+    fn into_py(self, py: Python) -> PyObject {
+        match self.0 {
+            aws_smithy_types::Number::PosInt(value) => value.into_py(py),
+            aws_smithy_types::Number::NegInt(value) => value.into_py(py),
+            aws_smithy_types::Number::Float(value) => value.into_py(py),
+        }
+    }
+}
+
+impl<'source> FromPyObject<'source> for Number {
+    fn extract(obj: &'source PyAny) -> PyResult<Self> {
+        // Python `bool` is a subclass of `int`, we don't want to silently convert it to a number.
+        if obj.is_instance_of::<PyBool>()? {
+            return Err(PyTypeError::new_err("cannot convert `bool` into a Number"));
+        }
+        if obj.is_instance_of::<PyLong>()? {
+            let number = match obj.extract::<u64>() {
+                Ok(value) => aws_smithy_types::Number::PosInt(value),
+                // Values not fitting an `u64` are either negative or too big, in which
+                // case the `i64` conversion raises an `OverflowError`.
+                Err(_) => aws_smithy_types::Number::NegInt(obj.extract::<i64>()?),
+            };
+            return Ok(Self(number));
+        }
+        if obj.is_instance_of::<PyFloat>()? {
+            return Ok(Self(aws_smithy_types::Number::Float(obj.extract::<f64>()?)));
+        }
+        Err(PyTypeError::new_err(format!(
+            "cannot convert `{}` into a Number",
+            obj.get_type().name()?
+        )))
+    }
+}
+
+impl From<aws_smithy_types::Number> for Number {
+    fn from(other: aws_smithy_types::Number) -> Number {
+        Number(other)
+    }
+}
+
+impl From<Number> for aws_smithy_types::Number {
+    fn from(other: Number) -> aws_smithy_types::Number {
+        other.0
+    }
+}
+
+impl<'number> From<&'number Number> for &'number aws_smithy_types::Number {
+    fn from(other: &'number Number) -> &'number aws_smithy_types::Number {
+        &other.0
+    }
+}
+
+/// Python Wrapper for [aws_smithy_types::Document].
+///
+/// Documents are not exposed as a Python class, they are converted to and from native Python
+/// objects instead:
+///
+/// | Document | Python                  |
+/// |----------|-------------------------|
+/// | Object   | `dict` with `str` keys  |
+/// | Array    | `list` (or `tuple`)     |
+/// | Number   | `int` or `float`        |
+/// | String   | `str`                   |
+/// | Bool     | `bool`                  |
+/// | Null     | `None`                  |
+///
+/// Object members keep their order in both directions. See [Number] for details about how
+/// numbers are converted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document(aws_smithy_types::Document);
+
+impl IntoPy<PyObject> for Document {
+    fn into_py(self, py: Python) -> PyObject {
+        match self.0 {
+            aws_smithy_types::Document::Object(values) => {
+                // Insert members one by one so that the dict keeps the order of the map.
+                let dict = PyDict::new(py);
+                for (key, value) in values {
+                    dict.set_item(key, Document(value).into_py(py))
+                        .expect("string keys can always be inserted into a dict");
+                }
+                dict.into_py(py)
+            }
+            aws_smithy_types::Document::Array(values) => values
+                .into_iter()
+                .map(Document)
+                .collect::<Vec<_>>()
+                .into_py(py),
+            aws_smithy_types::Document::Number(value) => Number(value).into_py(py),
+            aws_smithy_types::Document::String(value) => value.into_py(py),
+            aws_smithy_types::Document::Bool(value) => value.into_py(py),
+            aws_smithy_types::Document::Null => py.None(),
+        }
+    }
+}
+
+impl<'source> FromPyObject<'source> for Document {
+    fn extract(obj: &'source PyAny) -> PyResult<Self> {
+        let document = if obj.is_none() {
+            aws_smithy_types::Document::Null
+        } else if obj.is_instance_of::<PyBool>()? {
+            aws_smithy_types::Document::Bool(obj.extract()?)
+        } else if obj.is_instance_of::<PyLong>()? || obj.is_instance_of::<PyFloat>()? {
+            aws_smithy_types::Document::Number(obj.extract::<Number>()?.into())
+        } else if obj.is_instance_of::<PyString>()? {
+            aws_smithy_types::Document::String(obj.extract()?)
+        } else if let Ok(dict) = obj.downcast::<PyDict>() {
+            let mut values = DocumentMap::with_capacity(dict.len());
+            for (key, value) in dict {
+                let key = key.extract::<String>().map_err(|_| {
+                    PyTypeError::new_err("Document object keys must be of type `str`")
+                })?;
+                values.insert(key, value.extract::<Document>()?.into());
+            }
+            aws_smithy_types::Document::Object(values)
+        } else if let Ok(list) = obj.downcast::<PyList>() {
+            aws_smithy_types::Document::Array(
+                list.iter()
+                    .map(|value| value.extract::<Document>().map(Into::into))
+                    .collect::<PyResult<_>>()?,
+            )
+        } else if let Ok(tuple) = obj.downcast::<PyTuple>() {
+            aws_smithy_types::Document::Array(
+                tuple
+                    .iter()
+                    .map(|value| value.extract::<Document>().map(Into::into))
+                    .collect::<PyResult<_>>()?,
+            )
+        } else {
+            return Err(PyTypeError::new_err(format!(
+                "cannot convert `{}` into a Document",
+                obj.get_type().name()?
+            )));
+        };
+        Ok(Self(document))
+    }
+}
+
+impl From<aws_smithy_types::Document> for Document {
+    fn from(other: aws_smithy_types::Document) -> Document {
+        Document(other)
+    }
+}
+
+impl From<Document> for aws_smithy_types::Document {
+    fn from(other: Document) -> aws_smithy_types::Document {
+        other.0
+    }
+}
+
+impl<'document> From<&'document Document> for &'document aws_smithy_types::Document {
+    fn from(other: &'document Document) -> &'document aws_smithy_types::Document {
+        &other.0
+    }
+}
+
 /// Python Wrapper for [aws_smithy_http::byte_stream::ByteStream].
 ///
 /// ByteStream provides misuse-resistant primitives to make it easier to handle common patterns with streaming data.
@@ -408,8 +576,18 @@ impl ByteStream {
     /// More info: `<https://docs.python.org/3/reference/datamodel.html#object.__anext__.>`
     pub fn __anext__(slf: PyRefMut<Self>) -> PyResult<IterANextOutput<Py<PyAny>, PyObject>> {
         let body = slf.0.clone();
-        let data_chunk = pyo3_asyncio::tokio::local_future_into_py(slf.py(), async move {
-            let data = yield_data_chunk(body).await?;
+        let data_chunk = pyo3_asyncio::tokio::future_into_py(slf.py(), async move {
+            // `StopIteration` can't be raised into a Python future, the end of an
+            // asynchronous iteration is signaled using `StopAsyncIteration`.
+            let data = yield_data_chunk(body).await.map_err(|err| {
+                Python::with_gil(|py| {
+                    if err.is_instance_of::<PyStopIteration>(py) {
+                        PyStopAsyncIteration::new_err("stream exhausted")
+                    } else {
+                        err
+                    }
+                })
+            })?;
             Ok(Python::with_gil(|py| data.into_py(py)))
         });
         match data_chunk {
@@ -496,6 +674,137 @@ mod tests {
         })
     }
 
+    #[test]
+    fn document_can_be_used_in_python_when_initialized_in_rust() {
+        crate::tests::initialize();
+        Python::with_gil(|py| {
+            let document = Document::from(aws_smithy_types::Document::Object(DocumentMap::from([
+                (
+                    "array".to_string(),
+                    aws_smithy_types::Document::Array(vec![
+                        aws_smithy_types::Document::Number(aws_smithy_types::Number::PosInt(1)),
+                        aws_smithy_types::Document::Number(aws_smithy_types::Number::NegInt(-2)),
+                        aws_smithy_types::Document::Number(aws_smithy_types::Number::Float(3.5)),
+                    ]),
+                ),
+                (
+                    "string".to_string(),
+                    aws_smithy_types::Document::String("some data".to_string()),
+                ),
+                ("bool".to_string(), aws_smithy_types::Document::Bool(true)),
+                ("null".to_string(), aws_smithy_types::Document::Null),
+            ])));
+            let document = document.into_py(py);
+            py_run!(
+                py,
+                document,
+                r#"
+                assert document == {
+                    "array": [1, -2, 3.5],
+                    "string": "some data",
+                    "bool": True,
+                    "null": None,
+                }
+                assert type(document["array"][0]) is int
+                assert type(document["array"][2]) is float
+            "#
+            );
+        })
+    }
+
+    #[test]
+    fn document_objects_keep_their_key_order_in_python() {
+        crate::tests::initialize();
+        Python::with_gil(|py| {
+            let document = py
+                .eval(r#"{"z": 1, "a": {"y": 2, "b": 3}, "m": 4}"#, None, None)
+                .unwrap()
+                .extract::<Document>()
+                .unwrap();
+            let document = document.into_py(py);
+            py_run!(
+                py,
+                document,
+                r#"
+                assert list(document) == ["z", "a", "m"]
+                assert list(document["a"]) == ["y", "b"]
+            "#
+            );
+        })
+    }
+
+    #[test]
+    fn document_round_trips_through_python() {
+        crate::tests::initialize();
+        Python::with_gil(|py| {
+            let document = py
+                .eval(
+                    r#"{"a": [1, -1, 1.0, 18446744073709551615, -9223372036854775808], "b": {"c": None}, "d": (True, "e")}"#,
+                    None,
+                    None,
+                )
+                .unwrap()
+                .extract::<Document>()
+                .unwrap();
+            let expected = aws_smithy_types::Document::Object(DocumentMap::from([
+                (
+                    "a".to_string(),
+                    aws_smithy_types::Document::Array(vec![
+                        aws_smithy_types::Document::Number(aws_smithy_types::Number::PosInt(1)),
+                        aws_smithy_types::Document::Number(aws_smithy_types::Number::NegInt(-1)),
+                        aws_smithy_types::Document::Number(aws_smithy_types::Number::Float(1.0)),
+                        aws_smithy_types::Document::Number(aws_smithy_types::Number::PosInt(
+                            u64::MAX,
+                        )),
+                        aws_smithy_types::Document::Number(aws_smithy_types::Number::NegInt(
+                            i64::MIN,
+                        )),
+                    ]),
+                ),
+                (
+                    "b".to_string(),
+                    aws_smithy_types::Document::Object(DocumentMap::from([(
+                        "c".to_string(),
+                        aws_smithy_types::Document::Null,
+                    )])),
+                ),
+                (
+                    "d".to_string(),
+                    aws_smithy_types::Document::Array(vec![
+                        aws_smithy_types::Document::Bool(true),
+                        aws_smithy_types::Document::String("e".to_string()),
+                    ]),
+                ),
+            ]));
+            assert_eq!(expected, document.clone().into());
+            let round_tripped = document
+                .clone()
+                .into_py(py)
+                .extract::<Document>(py)
+                .unwrap();
+            assert_eq!(document, round_tripped);
+        })
+    }
+
+    #[test]
+    fn document_conversion_fails_on_unsupported_values() {
+        crate::tests::initialize();
+        Python::with_gil(|py| {
+            for value in [
+                "{1: 'non string key'}",
+                "[object()]",
+                "18446744073709551616",
+                "-9223372036854775809",
+            ] {
+                let value = py.eval(value, None, None).unwrap();
+                assert!(
+                    value.extract::<Document>().is_err(),
+                    "{value} should not be converted into a Document"
+                );
+            }
+        })
+    }
+
     // TODO(When running in sync python, the iterator is reading the whole content in memory. Figure out why.)
     #[tokio::test]
     async fn bytestream_can_be_used_in_sync_python_when_initialized_in_rust() -> PyResult<()> {
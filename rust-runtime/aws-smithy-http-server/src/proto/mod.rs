@@ -9,3 +9,4 @@ pub mod aws_json_11;
 pub mod rest;
 pub mod rest_json_1;
 pub mod rest_xml;
+pub mod rpc_v2_cbor;
@@ -0,0 +1,24 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+pub mod router;
+
+/// [Smithy RPC v2 CBOR Protocol](https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html).
+pub struct RpcV2Cbor;
+
+/// The `Content-Type` of RPC v2 CBOR requests and responses with a body.
+pub(crate) const CONTENT_TYPE: &str = "application/cbor";
+
+/// The value of the `smithy-protocol` header, required on every RPC v2 CBOR request and response.
+pub(crate) const SMITHY_PROTOCOL: &str = "rpc-v2-cbor";
+
+/// Serializes the body of an error response, a map holding the error's shape name under `__type`.
+pub(crate) fn error_body(type_name: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut object = aws_smithy_cbor::serialize::CborObjectWriter::new(&mut out);
+    object.key("__type").string(type_name);
+    object.finish();
+    out
+}
@@ -0,0 +1,196 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::convert::Infallible;
+
+use tower::Layer;
+use tower::Service;
+
+use crate::body::{to_boxed, BoxBody};
+use crate::extension::RuntimeErrorExtension;
+use crate::response::IntoResponse;
+use crate::routers::{method_disallowed, Router, UNKNOWN_OPERATION_EXCEPTION};
+use crate::routing::tiny_map::TinyMap;
+use crate::routing::Route;
+
+use http::header::ToStrError;
+use thiserror::Error;
+
+use super::{error_body, RpcV2Cbor, CONTENT_TYPE, SMITHY_PROTOCOL};
+
+/// An RPC v2 CBOR routing error.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Relative URI did not end in `/service/{ServiceName}/operation/{OperationName}`.
+    #[error("relative URI does not end in \"/service/{{ServiceName}}/operation/{{OperationName}}\"")]
+    InvalidUri,
+    /// Method was not `POST`.
+    #[error("method not POST")]
+    MethodNotAllowed,
+    /// Missing the `smithy-protocol` header.
+    #[error("missing the \"smithy-protocol\" header")]
+    MissingHeader,
+    /// Unable to parse header into UTF-8.
+    #[error("failed to parse header: {0}")]
+    InvalidHeader(ToStrError),
+    /// The `smithy-protocol` header was not `rpc-v2-cbor`.
+    #[error("the \"smithy-protocol\" header is not \"rpc-v2-cbor\"")]
+    InvalidProtocol,
+    /// Operation not found.
+    #[error("operation not found")]
+    NotFound,
+}
+
+impl IntoResponse<RpcV2Cbor> for Error {
+    fn into_response(self) -> http::Response<BoxBody> {
+        match self {
+            Error::MethodNotAllowed => {
+                let mut response = method_disallowed();
+                response
+                    .headers_mut()
+                    .insert("smithy-protocol", http::HeaderValue::from_static(SMITHY_PROTOCOL));
+                response
+            }
+            _ => http::Response::builder()
+                .status(http::StatusCode::NOT_FOUND)
+                .header(http::header::CONTENT_TYPE, CONTENT_TYPE)
+                .header("smithy-protocol", SMITHY_PROTOCOL)
+                .extension(RuntimeErrorExtension::new(
+                    UNKNOWN_OPERATION_EXCEPTION.to_string(),
+                ))
+                .body(to_boxed(error_body(UNKNOWN_OPERATION_EXCEPTION)))
+                .expect("invalid HTTP response for RPC v2 CBOR routing error; please file a bug report under https://github.com/awslabs/smithy-rs/issues"),
+        }
+    }
+}
+
+// This constant determines when the `TinyMap` implementation switches from being a `Vec` to a
+// `HashMap`. This is chosen to be 15 as a result of the discussion around
+// https://github.com/awslabs/smithy-rs/pull/1429#issuecomment-1147516546
+const ROUTE_CUTOFF: usize = 15;
+
+/// A [`Router`] supporting the [`Smithy RPC v2 CBOR`] protocol.
+///
+/// Routes are keyed by `{ServiceName}.{OperationName}`, taken from the last two segments of the
+/// request path.
+///
+/// [Smithy RPC v2 CBOR]: https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html
+#[derive(Debug, Clone)]
+pub struct RpcV2CborRouter<S> {
+    routes: TinyMap<String, S, ROUTE_CUTOFF>,
+}
+
+impl<S> RpcV2CborRouter<S> {
+    /// Applies a [`Layer`] uniformly to all routes.
+    pub fn layer<L>(self, layer: L) -> RpcV2CborRouter<L::Service>
+    where
+        L: Layer<S>,
+    {
+        RpcV2CborRouter {
+            routes: self
+                .routes
+                .into_iter()
+                .map(|(key, route)| (key, layer.layer(route)))
+                .collect(),
+        }
+    }
+
+    /// Applies type erasure to the inner route using [`Route::new`].
+    pub fn boxed<B>(self) -> RpcV2CborRouter<Route<B>>
+    where
+        S: Service<http::Request<B>, Response = http::Response<BoxBody>, Error = Infallible>,
+        S: Send + Clone + 'static,
+        S::Future: Send + 'static,
+    {
+        RpcV2CborRouter {
+            routes: self.routes.into_iter().map(|(key, s)| (key, Route::new(s))).collect(),
+        }
+    }
+}
+
+/// Extracts `{ServiceName}.{OperationName}` from a path ending in
+/// `/service/{ServiceName}/operation/{OperationName}`.
+fn operation_key(path: &str) -> Option<String> {
+    let mut segments = path.rsplit('/');
+    let operation = segments.next()?;
+    if segments.next()? != "operation" {
+        return None;
+    }
+    let service = segments.next()?;
+    if segments.next()? != "service" || service.is_empty() || operation.is_empty() {
+        return None;
+    }
+    Some(format!("{}.{}", service, operation))
+}
+
+impl<B, S> Router<B> for RpcV2CborRouter<S>
+where
+    S: Clone,
+{
+    type Service = S;
+    type Error = Error;
+
+    fn match_route(&self, request: &http::Request<B>) -> Result<S, Self::Error> {
+        // The URI must end in `/service/{ServiceName}/operation/{OperationName}`.
+        let key = operation_key(request.uri().path()).ok_or(Error::InvalidUri)?;
+
+        // Only `Method::POST` is allowed.
+        if request.method() != http::Method::POST {
+            return Err(Error::MethodNotAllowed);
+        }
+
+        // The `smithy-protocol` header must identify this protocol.
+        let protocol = request.headers().get("smithy-protocol").ok_or(Error::MissingHeader)?;
+        let protocol = protocol.to_str().map_err(Error::InvalidHeader)?;
+        if protocol != SMITHY_PROTOCOL {
+            return Err(Error::InvalidProtocol);
+        }
+
+        // Lookup in the `TinyMap` for a route for the operation.
+        let route = self.routes.get(key.as_str()).ok_or(Error::NotFound)?;
+        Ok(route.clone())
+    }
+}
+
+impl<S> FromIterator<(String, S)> for RpcV2CborRouter<S> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (String, S)>>(iter: T) -> Self {
+        Self {
+            routes: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{operation_key, Error};
+    use crate::proto::rpc_v2_cbor::RpcV2Cbor;
+    use crate::response::IntoResponse;
+
+    #[test]
+    fn operation_keys() {
+        assert_eq!(
+            Some("Service.Operation".to_string()),
+            operation_key("/service/Service/operation/Operation")
+        );
+        assert_eq!(
+            Some("Service.Operation".to_string()),
+            operation_key("/prefix/service/Service/operation/Operation")
+        );
+        assert_eq!(None, operation_key("/"));
+        assert_eq!(None, operation_key("/service/Service/operation/"));
+        assert_eq!(None, operation_key("/service//operation/Operation"));
+        assert_eq!(None, operation_key("/services/Service/operation/Operation"));
+        assert_eq!(None, operation_key("/service/Service/operations/Operation"));
+    }
+
+    #[test]
+    fn errors_have_the_protocol_header() {
+        for error in [Error::MethodNotAllowed, Error::NotFound] {
+            let response = <Error as IntoResponse<RpcV2Cbor>>::into_response(error);
+            assert_eq!("rpc-v2-cbor", response.headers()["smithy-protocol"]);
+        }
+    }
+}
@@ -144,6 +144,9 @@ pub enum RequestRejection {
     /// Used when failing to deserialize the HTTP body's bytes into a XML conforming to the modeled
     /// input it should represent.
     XmlDeserialize(crate::Error),
+    /// Used when failing to deserialize the HTTP body's bytes into a CBOR data item conforming to
+    /// the modeled input it should represent.
+    CborDeserialize(crate::Error),
 
     /// Used when attempting to take the request's headers, and they have already been taken (presumably
     /// by an outer `Service` that handled the request before us).
@@ -237,6 +240,7 @@ impl From<MissingContentTypeReason> for RequestRejection {
 
 convert_to_request_rejection!(aws_smithy_json::deserialize::Error, JsonDeserialize);
 convert_to_request_rejection!(aws_smithy_xml::decode::XmlError, XmlDeserialize);
+convert_to_request_rejection!(aws_smithy_cbor::deserialize::Error, CborDeserialize);
 convert_to_request_rejection!(aws_smithy_http::operation::BuildError, Build);
 convert_to_request_rejection!(aws_smithy_http::header::ParseError, HeaderParse);
 convert_to_request_rejection!(aws_smithy_types::date_time::DateTimeParseError, DateTimeParse);
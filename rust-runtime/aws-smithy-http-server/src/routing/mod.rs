@@ -17,7 +17,7 @@ use crate::{
     body::{boxed, Body, BoxBody, HttpBody},
     proto::{
         aws_json::router::AwsJsonRouter, aws_json_10::AwsJson1_0, aws_json_11::AwsJson1_1, rest::router::RestRouter,
-        rest_json_1::RestJson1, rest_xml::RestXml,
+        rest_json_1::RestJson1, rest_xml::RestXml, rpc_v2_cbor::router::RpcV2CborRouter, rpc_v2_cbor::RpcV2Cbor,
     },
 };
 use crate::{error::BoxError, routers::RoutingService};
@@ -47,7 +47,7 @@ pub use self::{future::RouterFuture, into_make_service::IntoMakeService, route::
 /// the [AwsJson specification].
 ///
 /// The router is also [Protocol] aware and currently supports REST based protocols like [restJson1] or [restXml]
-/// and RPC based protocols like [awsJson1.0] or [awsJson1.1].
+/// and RPC based protocols like [awsJson1.0], [awsJson1.1] or [rpcv2Cbor].
 /// It currently does not support Smithy's [endpoint trait].
 ///
 /// You should not **instantiate** this router directly; it will be created for you from the
@@ -60,6 +60,7 @@ pub use self::{future::RouterFuture, into_make_service::IntoMakeService, route::
 /// [restXml]: https://awslabs.github.io/smithy/1.0/spec/aws/aws-restxml-protocol.html
 /// [awsJson1.0]: https://awslabs.github.io/smithy/1.0/spec/aws/aws-json-1_0-protocol.html
 /// [awsJson1.1]: https://awslabs.github.io/smithy/1.0/spec/aws/aws-json-1_1-protocol.html
+/// [rpcv2Cbor]: https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html
 /// [endpoint trait]: https://awslabs.github.io/smithy/1.0/spec/core/endpoint-traits.html#endpoint-trait
 #[derive(Debug)]
 pub struct Router<B = Body> {
@@ -72,13 +73,15 @@ pub struct Router<B = Body> {
 /// request URI and we thus need to iterate the whole list and use a ranking mechanism to choose.
 ///
 /// AwsJson 1.0 and 1.1 routes can be stored in a `HashMap` since the requested operation can be
-/// directly found in the `X-Amz-Target` HTTP header.
+/// directly found in the `X-Amz-Target` HTTP header. Likewise, RPC v2 CBOR routes are keyed by
+/// the service and operation names at the end of the request URI.
 #[derive(Debug)]
 enum Routes<B = Body> {
     RestXml(RoutingService<RestRouter<Route<B>>, RestXml>),
     RestJson1(RoutingService<RestRouter<Route<B>>, RestJson1>),
     AwsJson1_0(RoutingService<AwsJsonRouter<Route<B>>, AwsJson1_0>),
     AwsJson1_1(RoutingService<AwsJsonRouter<Route<B>>, AwsJson1_1>),
+    RpcV2Cbor(RoutingService<RpcV2CborRouter<Route<B>>, RpcV2Cbor>),
 }
 
 impl<B> Clone for Router<B> {
@@ -96,6 +99,9 @@ impl<B> Clone for Router<B> {
             Routes::AwsJson1_1(routes) => Router {
                 routes: Routes::AwsJson1_1(routes.clone()),
             },
+            Routes::RpcV2Cbor(routes) => Router {
+                routes: Routes::RpcV2Cbor(routes.clone()),
+            },
         }
     }
 }
@@ -147,6 +153,9 @@ where
             Routes::AwsJson1_1(routes) => Router {
                 routes: Routes::AwsJson1_1(routes.map(|router| router.layer(layer).boxed())),
             },
+            Routes::RpcV2Cbor(routes) => Router {
+                routes: Routes::RpcV2Cbor(routes.map(|router| router.layer(layer).boxed())),
+            },
         }
     }
 
@@ -247,6 +256,32 @@ where
             routes: Routes::AwsJson1_1(svc),
         }
     }
+
+    /// Create a new RPC v2 CBOR `Router` from an iterator over pairs of `{ServiceName}.{OperationName}`
+    /// keys and services.
+    ///
+    /// If the iterator is empty the router will respond `404 Not Found` to all requests.
+    #[doc(hidden)]
+    pub fn new_rpc_v2_cbor_router<T>(routes: T) -> Self
+    where
+        T: IntoIterator<
+            Item = (
+                tower::util::BoxCloneService<Request<B>, Response<BoxBody>, Infallible>,
+                String,
+            ),
+        >,
+    {
+        let svc = RoutingService::new(
+            routes
+                .into_iter()
+                .map(|(svc, operation)| (operation, Route::from_box_clone_service(svc)))
+                .collect(),
+        );
+
+        Self {
+            routes: Routes::RpcV2Cbor(svc),
+        }
+    }
 }
 
 impl<B> Service<Request<B>> for Router<B>
@@ -271,6 +306,8 @@ where
             // AwsJson routes.
             Routes::AwsJson1_0(routes) => routes.call(req),
             Routes::AwsJson1_1(routes) => routes.call(req),
+            // RPC v2 CBOR routes.
+            Routes::RpcV2Cbor(routes) => routes.call(req),
         };
         RouterFuture::new(fut)
     }
@@ -588,3 +625,95 @@ mod awsjson_tests {
         }
     }
 }
+
+#[cfg(test)]
+mod rpc_v2_cbor_tests {
+    use super::rest_tests::{get_body_as_string, req};
+    use super::*;
+    use crate::body::boxed;
+    use futures_util::Future;
+    use http::{HeaderMap, HeaderValue, Method, StatusCode};
+    use pretty_assertions::assert_eq;
+    use std::pin::Pin;
+
+    /// A service that returns its name and the request's URI in the response body.
+    #[derive(Clone)]
+    struct NamedEchoUriService(String);
+
+    impl<B> Service<Request<B>> for NamedEchoUriService {
+        type Response = Response<BoxBody>;
+        type Error = Infallible;
+        type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+        #[inline]
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        #[inline]
+        fn call(&mut self, req: Request<B>) -> Self::Future {
+            let body = boxed(Body::from(format!("{} :: {}", self.0, req.uri())));
+            let fut = async { Ok(Response::builder().status(&http::StatusCode::OK).body(body).unwrap()) };
+            Box::pin(fut)
+        }
+    }
+
+    #[tokio::test]
+    async fn simple_routing() {
+        let mut router = Router::new_rpc_v2_cbor_router(vec![(
+            tower::util::BoxCloneService::new(NamedEchoUriService(String::from("A"))),
+            "Service.Operation".to_string(),
+        )]);
+        let mut headers = HeaderMap::new();
+        headers.insert("smithy-protocol", HeaderValue::from_static("rpc-v2-cbor"));
+        let uri = "/service/Service/operation/Operation";
+
+        // Valid request, should return a valid body.
+        let mut res = router
+            .call(req(&Method::POST, uri, Some(headers.clone())))
+            .await
+            .unwrap();
+        let actual_body = get_body_as_string(&mut res).await;
+        assert_eq!(format!("{} :: {}", "A", uri), actual_body);
+
+        // No headers, should return NOT_FOUND.
+        let res = router.call(req(&Method::POST, uri, None)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(res.headers()["smithy-protocol"], "rpc-v2-cbor");
+
+        // Wrong HTTP method, should return METHOD_NOT_ALLOWED.
+        let res = router
+            .call(req(&Method::GET, uri, Some(headers.clone())))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        // Unknown operation, should return NOT_FOUND with an `UnknownOperationException`.
+        let mut res = router
+            .call(req(
+                &Method::POST,
+                "/service/Service/operation/Unknown",
+                Some(headers.clone()),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(res.headers()["content-type"], "application/cbor");
+        let body = hyper::body::to_bytes(res.body_mut()).await.unwrap();
+        let mut tokens = aws_smithy_cbor::deserialize::cbor_token_iter(&body);
+        tokens.next();
+        assert!(matches!(
+            tokens.next(),
+            Some(Ok(aws_smithy_cbor::deserialize::Token::ObjectKey { key, .. })) if key == "__type"
+        ));
+        assert!(matches!(
+            tokens.next(),
+            Some(Ok(aws_smithy_cbor::deserialize::Token::ValueString { value, .. }))
+                if value == "UnknownOperationException"
+        ));
+
+        // Wrong URI, should return NOT_FOUND.
+        let res = router.call(req(&Method::POST, "/", Some(headers))).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}
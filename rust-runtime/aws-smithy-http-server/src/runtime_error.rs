@@ -28,9 +28,11 @@ use crate::proto::aws_json_10::AwsJson1_0;
 use crate::proto::aws_json_11::AwsJson1_1;
 use crate::proto::rest_json_1::RestJson1;
 use crate::proto::rest_xml::RestXml;
+use crate::proto::rpc_v2_cbor::{self, RpcV2Cbor};
 use crate::response::IntoResponse;
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum RuntimeError {
     /// Request failed to deserialize or response failed to serialize.
     Serialization(crate::Error),
@@ -40,6 +42,8 @@ pub enum RuntimeError {
     // TODO(https://github.com/awslabs/smithy-rs/issues/1663)
     NotAcceptable,
     UnsupportedMediaType,
+    /// The server is overloaded and rejected the request before processing it.
+    Throttling,
 }
 
 /// String representation of the runtime error type.
@@ -52,6 +56,7 @@ impl RuntimeError {
             Self::InternalFailure(_) => "InternalFailureException",
             Self::NotAcceptable => "NotAcceptableException",
             Self::UnsupportedMediaType => "UnsupportedMediaTypeException",
+            Self::Throttling => "ThrottlingException",
         }
     }
 
@@ -61,6 +66,7 @@ impl RuntimeError {
             Self::InternalFailure(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
             Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::Throttling => StatusCode::TOO_MANY_REQUESTS,
         }
     }
 }
@@ -91,6 +97,12 @@ impl IntoResponse<RestXml> for InternalFailureException {
     }
 }
 
+impl IntoResponse<RpcV2Cbor> for InternalFailureException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RpcV2Cbor>::into_response(RuntimeError::InternalFailure(crate::Error::new(String::new())))
+    }
+}
+
 impl IntoResponse<RestJson1> for RuntimeError {
     fn into_response(self) -> http::Response<crate::body::BoxBody> {
         http::Response::builder()
@@ -139,6 +151,19 @@ impl IntoResponse<AwsJson1_1> for RuntimeError {
     }
 }
 
+impl IntoResponse<RpcV2Cbor> for RuntimeError {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        http::Response::builder()
+            .status(self.status_code())
+            .header("Content-Type", rpc_v2_cbor::CONTENT_TYPE)
+            .header("smithy-protocol", rpc_v2_cbor::SMITHY_PROTOCOL)
+            .extension(RuntimeErrorExtension::new(self.name().to_string()))
+            // See https://smithy.io/2.0/additional-specs/protocols/smithy-rpc-v2.html#operation-error-serialization
+            .body(crate::body::to_boxed(rpc_v2_cbor::error_body(self.name())))
+            .expect("invalid HTTP response for `RuntimeError`; please file a bug report under https://github.com/awslabs/smithy-rs/issues")
+    }
+}
+
 impl From<crate::rejection::RequestExtensionNotFoundRejection> for RuntimeError {
     fn from(err: crate::rejection::RequestExtensionNotFoundRejection) -> Self {
         Self::InternalFailure(crate::Error::new(err))
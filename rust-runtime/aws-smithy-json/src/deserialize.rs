@@ -5,9 +5,11 @@
 
 use aws_smithy_types::Number;
 
+pub mod document;
 mod error;
 pub mod token;
 
+pub use document::{expect_document_ref, DocumentRef};
 pub use error::{Error, ErrorReason};
 pub use token::{EscapeError, EscapedStr, Offset, Token};
 
@@ -0,0 +1,334 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Borrowed view of a JSON document.
+
+use crate::deserialize::error::Error;
+use crate::deserialize::token::{EscapedStr, Token};
+use aws_smithy_types::{Document, DocumentMap, Number};
+use std::borrow::Cow;
+use std::iter::Peekable;
+
+const MAX_DOCUMENT_RECURSION: usize = 256;
+
+/// Document value borrowed from the JSON input it was parsed from.
+///
+/// Unlike [`Document`], strings and object keys are left escaped as [`EscapedStr`] and only get
+/// unescaped when they're read, and objects keep their members in the order they appeared in.
+/// Object keys without escapes are compared to lookup keys as they are, so lookups only unescape
+/// the keys that have escapes.
+/// Use [`DocumentRef::pointer`] to pluck values out of the document, and
+/// [`DocumentRef::to_document`] to convert it into an owned [`Document`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DocumentRef<'a> {
+    /// JSON object, with its members in input order
+    Object(Vec<(EscapedStr<'a>, DocumentRef<'a>)>),
+    /// JSON array
+    Array(Vec<DocumentRef<'a>>),
+    /// JSON number
+    Number(Number),
+    /// JSON string
+    String(EscapedStr<'a>),
+    /// JSON boolean
+    Bool(bool),
+    /// JSON null
+    Null,
+}
+
+impl<'a> DocumentRef<'a> {
+    /// Returns the value of the member with the given unescaped `key` if this is an object.
+    ///
+    /// When a key appears more than once, the last value wins, as it does in [`Document`].
+    pub fn get(&self, key: &str) -> Option<&DocumentRef<'a>> {
+        match self {
+            DocumentRef::Object(members) => members
+                .iter()
+                .rev()
+                .find(|(member_key, _)| key_matches(member_key, key))
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Returns the element at `index` if this is an array.
+    pub fn get_index(&self, index: usize) -> Option<&DocumentRef<'a>> {
+        match self {
+            DocumentRef::Array(elements) => elements.get(index),
+            _ => None,
+        }
+    }
+
+    /// Looks up a value by [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901), e.g. `/a/b/0`.
+    ///
+    /// The empty pointer refers to the whole document. Returns `None` if the pointer is malformed
+    /// or doesn't refer to a value.
+    pub fn pointer(&self, pointer: &str) -> Option<&DocumentRef<'a>> {
+        parse_pointer(pointer)?
+            .iter()
+            .try_fold(self, |target, token| match target {
+                DocumentRef::Object(_) => target.get(token),
+                DocumentRef::Array(_) => target.get_index(parse_index(token)?),
+                _ => None,
+            })
+    }
+
+    /// Converts this into an owned [`Document`], unescaping all of its strings.
+    pub fn to_document(&self) -> Result<Document, Error> {
+        Ok(match self {
+            DocumentRef::Object(members) => {
+                let mut object = DocumentMap::with_capacity(members.len());
+                for (key, value) in members {
+                    object.insert(key.to_unescaped()?.into_owned(), value.to_document()?);
+                }
+                Document::Object(object)
+            }
+            DocumentRef::Array(elements) => Document::Array(
+                elements
+                    .iter()
+                    .map(DocumentRef::to_document)
+                    .collect::<Result<_, _>>()?,
+            ),
+            DocumentRef::Number(value) => Document::Number(*value),
+            DocumentRef::String(value) => Document::String(value.to_unescaped()?.into_owned()),
+            DocumentRef::Bool(value) => Document::Bool(*value),
+            DocumentRef::Null => Document::Null,
+        })
+    }
+}
+
+/// Returns true if the escaped `member_key` unescapes to `key`.
+fn key_matches(member_key: &EscapedStr<'_>, key: &str) -> bool {
+    let escaped = member_key.as_escaped_str();
+    // Unescaping only shortens strings, and strings without escapes are their own unescaped form
+    if escaped.len() < key.len() || !escaped.contains('\\') {
+        return escaped == key;
+    }
+    matches!(member_key.to_unescaped(), Ok(member_key) if member_key == key)
+}
+
+/// Splits a JSON Pointer into its unescaped reference tokens.
+///
+/// Returns `None` if the pointer doesn't start with `/` or has a `~` that isn't followed by `0`
+/// or `1`.
+fn parse_pointer(pointer: &str) -> Option<Vec<Cow<'_, str>>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    pointer
+        .strip_prefix('/')?
+        .split('/')
+        .map(|token| {
+            if !token.contains('~') {
+                return Some(Cow::Borrowed(token));
+            }
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '~' => match chars.next()? {
+                        '0' => unescaped.push('~'),
+                        '1' => unescaped.push('/'),
+                        _ => return None,
+                    },
+                    c => unescaped.push(c),
+                }
+            }
+            Some(Cow::Owned(unescaped))
+        })
+        .collect()
+}
+
+/// Parses a JSON Pointer array index, which can't have leading zeros.
+fn parse_index(token: &str) -> Option<usize> {
+    if token.is_empty()
+        || (token.len() > 1 && token.starts_with('0'))
+        || !token.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    token.parse().ok()
+}
+
+/// Expects and parses a complete document value without copying or unescaping its strings.
+pub fn expect_document_ref<'a, I>(tokens: &mut Peekable<I>) -> Result<DocumentRef<'a>, Error>
+where
+    I: Iterator<Item = Result<Token<'a>, Error>>,
+{
+    expect_document_ref_inner(tokens, 0)
+}
+
+fn expect_document_ref_inner<'a, I>(
+    tokens: &mut Peekable<I>,
+    depth: usize,
+) -> Result<DocumentRef<'a>, Error>
+where
+    I: Iterator<Item = Result<Token<'a>, Error>>,
+{
+    if depth >= MAX_DOCUMENT_RECURSION {
+        return Err(Error::custom(
+            "exceeded max recursion depth while parsing document",
+        ));
+    }
+    match tokens.next().transpose()? {
+        Some(Token::ValueNull { .. }) => Ok(DocumentRef::Null),
+        Some(Token::ValueBool { value, .. }) => Ok(DocumentRef::Bool(value)),
+        Some(Token::ValueNumber { value, .. }) => Ok(DocumentRef::Number(value)),
+        Some(Token::ValueString { value, .. }) => Ok(DocumentRef::String(value)),
+        Some(Token::StartObject { .. }) => {
+            let mut members = Vec::new();
+            loop {
+                match tokens.next().transpose()? {
+                    Some(Token::EndObject { .. }) => break,
+                    Some(Token::ObjectKey { key, .. }) => {
+                        let value = expect_document_ref_inner(tokens, depth + 1)?;
+                        members.push((key, value));
+                    }
+                    _ => return Err(Error::custom("expected object key or end object")),
+                }
+            }
+            Ok(DocumentRef::Object(members))
+        }
+        Some(Token::StartArray { .. }) => {
+            let mut elements = Vec::new();
+            loop {
+                match tokens.peek() {
+                    Some(Ok(Token::EndArray { .. })) => {
+                        tokens.next().transpose().unwrap();
+                        break;
+                    }
+                    _ => elements.push(expect_document_ref_inner(tokens, depth + 1)?),
+                }
+            }
+            Ok(DocumentRef::Array(elements))
+        }
+        Some(Token::EndObject { .. }) | Some(Token::ObjectKey { .. }) => {
+            unreachable!("end object and object key are handled in start object")
+        }
+        Some(Token::EndArray { .. }) => unreachable!("end array is handled in start array"),
+        None => Err(Error::custom("expected value")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{expect_document_ref, DocumentRef};
+    use crate::deserialize::{json_token_iter, EscapedStr};
+    use aws_smithy_types::{Document, Number};
+
+    fn parse(input: &str) -> DocumentRef<'_> {
+        expect_document_ref(&mut json_token_iter(input.as_bytes()).peekable()).unwrap()
+    }
+
+    const INPUT: &str = r#"{
+        "z": 1,
+        "a": {"b": [true, {"c": "d\nd"}]},
+        "a/b": "slash",
+        "m~n": "tilde",
+        "esc\u0061ped": null,
+        "": [],
+        "dup": 1,
+        "dup": 2
+    }"#;
+
+    #[test]
+    fn preserves_order_and_escapes() {
+        match parse(INPUT) {
+            DocumentRef::Object(members) => {
+                let keys: Vec<_> = members
+                    .iter()
+                    .map(|(key, _)| key.as_escaped_str())
+                    .collect();
+                assert_eq!(
+                    vec!["z", "a", "a/b", "m~n", "esc\\u0061ped", "", "dup", "dup"],
+                    keys
+                );
+            }
+            _ => panic!("expected an object"),
+        }
+        assert_eq!(
+            Some(&DocumentRef::String(EscapedStr::new("d\\nd"))),
+            parse(INPUT).pointer("/a/b/1/c")
+        );
+    }
+
+    #[test]
+    fn pointer() {
+        let document = parse(INPUT);
+        assert_eq!(Some(&document), document.pointer(""));
+        assert_eq!(
+            Some(&DocumentRef::Number(Number::PosInt(1))),
+            document.pointer("/z")
+        );
+        assert_eq!(Some(&DocumentRef::Bool(true)), document.pointer("/a/b/0"));
+        assert_eq!(
+            Some(&DocumentRef::String(EscapedStr::new("slash"))),
+            document.pointer("/a~1b")
+        );
+        assert_eq!(
+            Some(&DocumentRef::String(EscapedStr::new("tilde"))),
+            document.pointer("/m~0n")
+        );
+        assert_eq!(Some(&DocumentRef::Null), document.pointer("/escaped"));
+        assert_eq!(None, document.get("esc\\u0061ped"));
+        assert_eq!(Some(&DocumentRef::Array(vec![])), document.pointer("/"));
+        assert_eq!(
+            Some(&DocumentRef::Number(Number::PosInt(2))),
+            document.pointer("/dup")
+        );
+
+        assert_eq!(None, document.pointer("z"));
+        assert_eq!(None, document.pointer("/missing"));
+        assert_eq!(None, document.pointer("/a/b/2"));
+        assert_eq!(None, document.pointer("/a/b/01"));
+        assert_eq!(None, document.pointer("/a/b/-"));
+        assert_eq!(None, document.pointer("/z/0"));
+        assert_eq!(None, document.pointer("/m~2n"));
+        assert_eq!(None, document.pointer("/m~"));
+        assert_eq!(None, document.pointer("/~/z"));
+    }
+
+    #[test]
+    fn to_document() {
+        let input = r#"{"a": [1, -2, 3.5, "s\"", false, null, {}], "b": {"c": []}}"#;
+        let expected = Document::Object(
+            vec![
+                (
+                    "a".to_string(),
+                    Document::Array(vec![
+                        Document::Number(Number::PosInt(1)),
+                        Document::Number(Number::NegInt(-2)),
+                        Document::Number(Number::Float(3.5)),
+                        Document::String("s\"".into()),
+                        Document::Bool(false),
+                        Document::Null,
+                        Document::Object(Default::default()),
+                    ]),
+                ),
+                (
+                    "b".to_string(),
+                    Document::Object(
+                        vec![("c".to_string(), Document::Array(vec![]))]
+                            .into_iter()
+                            .collect(),
+                    ),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        assert_eq!(expected, parse(input).to_document().unwrap());
+        assert_eq!(
+            Document::Number(Number::PosInt(2)),
+            parse(INPUT).pointer("/dup").unwrap().to_document().unwrap()
+        );
+        assert!(parse(r#""\q""#).to_document().is_err());
+    }
+
+    #[test]
+    fn recursion_limit() {
+        let input = format!("{}{}", "[".repeat(300), "]".repeat(300));
+        assert!(expect_document_ref(&mut json_token_iter(input.as_bytes()).peekable()).is_err());
+    }
+}
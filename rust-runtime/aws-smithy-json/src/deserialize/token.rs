@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::deserialize::document::expect_document_ref;
 use crate::deserialize::error::{Error, ErrorReason};
 use crate::escape::unescape_string;
 use aws_smithy_types::date_time::Format;
@@ -12,7 +13,6 @@ use std::borrow::Cow;
 use crate::deserialize::must_not_be_finite;
 pub use crate::escape::EscapeError;
 use aws_smithy_types::primitive::Parse;
-use std::collections::HashMap;
 use std::iter::Peekable;
 
 /// New-type around `&str` that indicates the string is an escaped JSON string.
@@ -253,61 +253,7 @@ pub fn expect_document<'a, I>(tokens: &mut Peekable<I>) -> Result<Document, Erro
 where
     I: Iterator<Item = Result<Token<'a>, Error>>,
 {
-    expect_document_inner(tokens, 0)
-}
-
-const MAX_DOCUMENT_RECURSION: usize = 256;
-
-fn expect_document_inner<'a, I>(tokens: &mut Peekable<I>, depth: usize) -> Result<Document, Error>
-where
-    I: Iterator<Item = Result<Token<'a>, Error>>,
-{
-    if depth >= MAX_DOCUMENT_RECURSION {
-        return Err(Error::custom(
-            "exceeded max recursion depth while parsing document",
-        ));
-    }
-    match tokens.next().transpose()? {
-        Some(Token::ValueNull { .. }) => Ok(Document::Null),
-        Some(Token::ValueBool { value, .. }) => Ok(Document::Bool(value)),
-        Some(Token::ValueNumber { value, .. }) => Ok(Document::Number(value)),
-        Some(Token::ValueString { value, .. }) => {
-            Ok(Document::String(value.to_unescaped()?.into_owned()))
-        }
-        Some(Token::StartObject { .. }) => {
-            let mut object = HashMap::new();
-            loop {
-                match tokens.next().transpose()? {
-                    Some(Token::EndObject { .. }) => break,
-                    Some(Token::ObjectKey { key, .. }) => {
-                        let key = key.to_unescaped()?.into_owned();
-                        let value = expect_document_inner(tokens, depth + 1)?;
-                        object.insert(key, value);
-                    }
-                    _ => return Err(Error::custom("expected object key or end object")),
-                }
-            }
-            Ok(Document::Object(object))
-        }
-        Some(Token::StartArray { .. }) => {
-            let mut array = Vec::new();
-            loop {
-                match tokens.peek() {
-                    Some(Ok(Token::EndArray { .. })) => {
-                        tokens.next().transpose().unwrap();
-                        break;
-                    }
-                    _ => array.push(expect_document_inner(tokens, depth + 1)?),
-                }
-            }
-            Ok(Document::Array(array))
-        }
-        Some(Token::EndObject { .. }) | Some(Token::ObjectKey { .. }) => {
-            unreachable!("end object and object key are handled in start object")
-        }
-        Some(Token::EndArray { .. }) => unreachable!("end array is handled in start array"),
-        None => Err(Error::custom("expected value")),
-    }
+    expect_document_ref(tokens)?.to_document()
 }
 
 /// Skips an entire value in the token stream. Errors if it isn't a value.
@@ -361,6 +307,7 @@ pub mod test {
     use super::*;
     use crate::deserialize::error::ErrorReason::UnexpectedToken;
     use crate::deserialize::json_token_iter;
+    use aws_smithy_types::DocumentMap;
 
     pub fn start_array<'a>(offset: usize) -> Option<Result<Token<'a>, Error>> {
         Some(Ok(Token::StartArray {
@@ -646,14 +593,14 @@ pub mod test {
         assert_eq!(Document::Number(Number::Float(3.2)), test(b"3.2"));
         assert_eq!(Document::String("Foo\nBar".into()), test(b"\"Foo\\nBar\""));
         assert_eq!(Document::Array(Vec::new()), test(b"[]"));
-        assert_eq!(Document::Object(HashMap::new()), test(b"{}"));
+        assert_eq!(Document::Object(DocumentMap::new()), test(b"{}"));
         assert_eq!(
             Document::Array(vec![
                 Document::Number(Number::PosInt(1)),
                 Document::Bool(false),
                 Document::String("s".into()),
                 Document::Array(Vec::new()),
-                Document::Object(HashMap::new()),
+                Document::Object(DocumentMap::new()),
             ]),
             test(b"[1,false,\"s\",[],{}]")
         );
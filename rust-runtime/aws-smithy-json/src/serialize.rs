@@ -398,6 +398,17 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "preserve-order")]
+    #[test]
+    fn document_preserves_order() {
+        use crate::deserialize::json_token_iter;
+        use crate::deserialize::token::expect_document;
+
+        let input = r#"{"z":1,"a":{"y":true,"b":null},"m":[{"k":"v","c":"d"}]}"#;
+        let document = expect_document(&mut json_token_iter(input.as_bytes()).peekable()).unwrap();
+        assert_eq!(input, format_document(document));
+    }
+
     fn format_test_number(number: Number) -> String {
         let mut formatted = String::new();
         JsonValueWriter::new(&mut formatted).number(number);
@@ -0,0 +1,22 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+#![no_main]
+
+use aws_smithy_types::{DateTime, Document};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // Looking for panics. Don't care if the deserialization fails.
+    if let Ok(document) = serde_json::from_slice::<Document>(data) {
+        let serialized = serde_json::to_vec(&document).unwrap();
+        assert_eq!(document, serde_json::from_slice(&serialized).unwrap());
+    }
+    if let Ok(date_time) = serde_json::from_slice::<DateTime>(data) {
+        if let Ok(serialized) = serde_json::to_vec(&date_time) {
+            assert_eq!(date_time, serde_json::from_slice(&serialized).unwrap());
+        }
+    }
+});
@@ -396,8 +396,22 @@ pub(crate) mod rfc3339 {
         Ok((parse(head)?, rest))
     }
 
-    /// Format a [DateTime] in the RFC-3339 date format
+    /// Format a [DateTime] in the RFC-3339 date format, truncated to microsecond precision
     pub(crate) fn format(date_time: &DateTime) -> Result<String, DateTimeFormatError> {
+        format_with_fraction_digits(date_time, 6)
+    }
+
+    /// Format a [DateTime] in the RFC-3339 date format with full nanosecond precision
+    #[cfg(feature = "serde")]
+    pub(crate) fn format_nanos(date_time: &DateTime) -> Result<String, DateTimeFormatError> {
+        format_with_fraction_digits(date_time, 9)
+    }
+
+    /// Format a [DateTime] in the RFC-3339 date format with at most `digits` (1 to 9) fraction digits
+    fn format_with_fraction_digits(
+        date_time: &DateTime,
+        digits: u32,
+    ) -> Result<String, DateTimeFormatError> {
         use std::fmt::Write;
         fn out_of_range<E: std::fmt::Display>(cause: E) -> DateTimeFormatError {
             DateTimeFormatError::OutOfRange(
@@ -409,7 +423,7 @@ pub(crate) mod rfc3339 {
                 .into(),
             )
         }
-        let (year, month, day, hour, minute, second, micros) = {
+        let (year, month, day, hour, minute, second, nanos) = {
             let s = OffsetDateTime::from_unix_timestamp_nanos(date_time.as_nanos())
                 .map_err(out_of_range)?;
             (
@@ -419,7 +433,7 @@ pub(crate) mod rfc3339 {
                 s.hour(),
                 s.minute(),
                 s.second(),
-                s.microsecond(),
+                s.nanosecond(),
             )
         };
 
@@ -436,18 +450,18 @@ pub(crate) mod rfc3339 {
             year, month, day, hour, minute, second
         )
         .unwrap();
-        format_subsecond_fraction(&mut out, micros);
+        format_subsecond_fraction(&mut out, nanos / 10_u32.pow(9 - digits), digits);
         out.push('Z');
         Ok(out)
     }
 
     /// Formats sub-second fraction for RFC-3339 (including the '.').
-    /// Expects to be called with a number of `micros` between 0 and 999_999 inclusive.
-    fn format_subsecond_fraction(into: &mut String, micros: u32) {
-        debug_assert!(micros < 1_000_000);
-        if micros > 0 {
+    /// Expects to be called with a `fraction` of at most `digits` digits.
+    fn format_subsecond_fraction(into: &mut String, fraction: u32, digits: u32) {
+        debug_assert!(fraction < 10_u32.pow(digits));
+        if fraction > 0 {
             into.push('.');
-            let (mut remaining, mut place) = (micros, 100_000);
+            let (mut remaining, mut place) = (fraction, 10_u32.pow(digits - 1));
             while remaining > 0 {
                 let digit = (remaining / place) % 10;
                 into.push(char::from(b'0' + (digit as u8)));
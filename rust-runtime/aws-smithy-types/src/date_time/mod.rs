@@ -235,6 +235,13 @@ impl DateTime {
             Format::HttpDate => format::http_date::format(self),
         }
     }
+
+    /// Formats the `DateTime` as an RFC-3339 date-time with full nanosecond precision, unlike
+    /// [`Format::DateTime`], which truncates to microseconds.
+    #[cfg(feature = "serde")]
+    pub(crate) fn fmt_rfc3339_nanos(&self) -> Result<String, DateTimeFormatError> {
+        format::rfc3339::format_nanos(self)
+    }
 }
 
 /// Tries to convert a [`DateTime`] into a [`SystemTime`].
@@ -0,0 +1,526 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Accessors, [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901) lookups and
+//! [JSON Merge Patch](https://www.rfc-editor.org/rfc/rfc7386) for [`Document`].
+
+use crate::{Document, DocumentMap, Number};
+use std::error::Error;
+use std::fmt;
+
+/// Error returned when a JSON Pointer can't be applied to a [`Document`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PointerError {
+    /// The given pointer isn't a valid JSON Pointer.
+    #[non_exhaustive]
+    Invalid(String),
+    /// The parent of the value the pointer refers to doesn't exist or can't hold the value.
+    #[non_exhaustive]
+    NotFound(String),
+}
+
+impl Error for PointerError {}
+
+impl fmt::Display for PointerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(pointer) => write!(f, "invalid JSON pointer `{}`", pointer),
+            Self::NotFound(pointer) => {
+                write!(
+                    f,
+                    "JSON pointer `{}` doesn't refer to a location in the document",
+                    pointer
+                )
+            }
+        }
+    }
+}
+
+impl Document {
+    /// Returns the members if this is an object.
+    pub fn as_object(&self) -> Option<&DocumentMap> {
+        match self {
+            Document::Object(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Returns the members mutably if this is an object.
+    pub fn as_object_mut(&mut self) -> Option<&mut DocumentMap> {
+        match self {
+            Document::Object(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements if this is an array.
+    pub fn as_array(&self) -> Option<&Vec<Document>> {
+        match self {
+            Document::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements mutably if this is an array.
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Document>> {
+        match self {
+            Document::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Returns the number if this is a number.
+    pub fn as_number(&self) -> Option<Number> {
+        match self {
+            Document::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the string if this is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Document::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the boolean if this is a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Document::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this is null.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Document::Null)
+    }
+
+    /// Looks up a value by following object keys and array indices.
+    ///
+    /// Each segment is used as-is, without JSON Pointer escaping. For example,
+    /// `document.get_path(["a", "b", "0"])` looks up the first element of the `b` array in
+    /// the `a` object.
+    pub fn get_path<I, S>(&self, path: I) -> Option<&Document>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        path.into_iter()
+            .try_fold(self, |target, segment| target.child(segment.as_ref()))
+    }
+
+    /// Mutable version of [`Document::get_path`].
+    pub fn get_path_mut<I, S>(&mut self, path: I) -> Option<&mut Document>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        path.into_iter()
+            .try_fold(self, |target, segment| target.child_mut(segment.as_ref()))
+    }
+
+    /// Looks up a value by JSON Pointer, e.g. `/a/b/0`.
+    ///
+    /// The empty pointer refers to the whole document. Returns `None` if the pointer is invalid
+    /// or doesn't refer to a value.
+    pub fn pointer(&self, pointer: &str) -> Option<&Document> {
+        self.get_path(parse_pointer(pointer).ok()?)
+    }
+
+    /// Mutable version of [`Document::pointer`].
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Document> {
+        self.get_path_mut(parse_pointer(pointer).ok()?)
+    }
+
+    /// Sets the value at the given JSON Pointer, and returns the value it replaced, if any.
+    ///
+    /// The parent of the location must already exist. Setting a key that isn't in an object
+    /// adds it, and the array index equal to the length of the array, or `-`, appends to it.
+    pub fn set_pointer(
+        &mut self,
+        pointer: &str,
+        value: Document,
+    ) -> Result<Option<Document>, PointerError> {
+        let mut path = parse_pointer(pointer)?;
+        let last = match path.pop() {
+            Some(last) => last,
+            None => return Ok(Some(std::mem::replace(self, value))),
+        };
+        let not_found = || PointerError::NotFound(pointer.into());
+        match self.get_path_mut(path).ok_or_else(not_found)? {
+            Document::Object(values) => Ok(values.insert(last, value)),
+            Document::Array(values) => {
+                let index = if last == "-" {
+                    values.len()
+                } else {
+                    parse_index(&last).ok_or_else(not_found)?
+                };
+                if index < values.len() {
+                    Ok(Some(std::mem::replace(&mut values[index], value)))
+                } else if index == values.len() {
+                    values.push(value);
+                    Ok(None)
+                } else {
+                    Err(not_found())
+                }
+            }
+            _ => Err(not_found()),
+        }
+    }
+
+    /// Applies a JSON Merge Patch to this document.
+    ///
+    /// Objects in the `patch` are merged into this document recursively, with `null` members
+    /// removing the corresponding keys. Any other value in the `patch` replaces the target.
+    pub fn merge_patch(&mut self, patch: &Document) {
+        let patch = match patch {
+            Document::Object(patch) => patch,
+            _ => {
+                *self = patch.clone();
+                return;
+            }
+        };
+        if !matches!(self, Document::Object(_)) {
+            *self = Document::Object(DocumentMap::new());
+        }
+        let target = self.as_object_mut().expect("set to an object above");
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                target
+                    .entry(key.clone())
+                    .or_insert(Document::Null)
+                    .merge_patch(value);
+            }
+        }
+    }
+
+    fn child(&self, segment: &str) -> Option<&Document> {
+        match self {
+            Document::Object(values) => values.get(segment),
+            Document::Array(values) => values.get(parse_index(segment)?),
+            _ => None,
+        }
+    }
+
+    fn child_mut(&mut self, segment: &str) -> Option<&mut Document> {
+        match self {
+            Document::Object(values) => values.get_mut(segment),
+            Document::Array(values) => values.get_mut(parse_index(segment)?),
+            _ => None,
+        }
+    }
+}
+
+/// Splits a JSON Pointer into its unescaped reference tokens.
+///
+/// The empty pointer has no tokens. Returns [`PointerError::Invalid`] if the pointer doesn't
+/// start with `/` or has a `~` that isn't followed by `0` or `1`.
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, PointerError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let invalid = || PointerError::Invalid(pointer.into());
+    pointer
+        .strip_prefix('/')
+        .ok_or_else(invalid)?
+        .split('/')
+        .map(|token| {
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '~' => match chars.next() {
+                        Some('0') => unescaped.push('~'),
+                        Some('1') => unescaped.push('/'),
+                        _ => return Err(invalid()),
+                    },
+                    c => unescaped.push(c),
+                }
+            }
+            Ok(unescaped)
+        })
+        .collect()
+}
+
+/// Parses a JSON Pointer array index, which can't have leading zeros.
+fn parse_index(segment: &str) -> Option<usize> {
+    if segment.is_empty()
+        || (segment.len() > 1 && segment.starts_with('0'))
+        || !segment.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    segment.parse().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_index, parse_pointer, PointerError};
+    use crate::{Document, Number};
+
+    fn object<const N: usize>(members: [(&str, Document); N]) -> Document {
+        Document::Object(
+            members
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    fn string(value: &str) -> Document {
+        Document::String(value.into())
+    }
+
+    fn int(value: u64) -> Document {
+        Document::Number(Number::PosInt(value))
+    }
+
+    // https://www.rfc-editor.org/rfc/rfc6901#section-5
+    fn rfc6901_example() -> Document {
+        object([
+            ("foo", Document::Array(vec![string("bar"), string("baz")])),
+            ("", int(0)),
+            ("a/b", int(1)),
+            ("c%d", int(2)),
+            ("e^f", int(3)),
+            ("g|h", int(4)),
+            ("i\\j", int(5)),
+            ("k\"l", int(6)),
+            (" ", int(7)),
+            ("m~n", int(8)),
+        ])
+    }
+
+    #[test]
+    fn accessors() {
+        let document = object([
+            (
+                "a",
+                Document::Array(vec![Document::Bool(true), Document::Null]),
+            ),
+            ("s", string("value")),
+        ]);
+        assert_eq!(2, document.as_object().unwrap().len());
+        assert_eq!(None, document.as_array());
+        assert_eq!(Some("value"), document.get_path(["s"]).unwrap().as_str());
+        assert_eq!(Some(true), document.get_path(["a", "0"]).unwrap().as_bool());
+        assert!(document.get_path(["a", "1"]).unwrap().is_null());
+        assert_eq!(None, document.get_path(["a", "2"]));
+        assert_eq!(None, document.get_path(["s", "0"]));
+        assert_eq!(Some(Number::PosInt(1)), int(1).as_number());
+        assert_eq!(None, string("1").as_number());
+    }
+
+    #[test]
+    fn pointer() {
+        let document = rfc6901_example();
+        assert_eq!(Some(&document), document.pointer(""));
+        assert_eq!(
+            Some(&Document::Array(vec![string("bar"), string("baz")])),
+            document.pointer("/foo")
+        );
+        for (pointer, expected) in [
+            ("/foo/0", string("bar")),
+            ("/", int(0)),
+            ("/a~1b", int(1)),
+            ("/c%d", int(2)),
+            ("/e^f", int(3)),
+            ("/g|h", int(4)),
+            ("/i\\j", int(5)),
+            ("/k\"l", int(6)),
+            ("/ ", int(7)),
+            ("/m~0n", int(8)),
+        ] {
+            assert_eq!(Some(&expected), document.pointer(pointer), "{}", pointer);
+        }
+
+        for pointer in [
+            "foo", "/foo/00", "/foo/-", "/foo/2", "/m~2n", "/m~", "/missing",
+        ] {
+            assert_eq!(None, document.pointer(pointer), "{}", pointer);
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Ok(Vec::<String>::new()), parse_pointer(""));
+        assert_eq!(Ok(vec!["".to_string()]), parse_pointer("/"));
+        assert_eq!(
+            Ok(vec!["a/b".to_string(), "~1".to_string()]),
+            parse_pointer("/a~1b/~01")
+        );
+        for pointer in ["a", "/~", "/m~2n", "/~a/b"] {
+            assert_eq!(
+                Err(PointerError::Invalid(pointer.into())),
+                parse_pointer(pointer),
+                "{}",
+                pointer
+            );
+        }
+
+        assert_eq!(Some(0), parse_index("0"));
+        assert_eq!(Some(10), parse_index("10"));
+        for index in ["", "-", "01", "+1", "1a"] {
+            assert_eq!(None, parse_index(index), "{}", index);
+        }
+    }
+
+    #[test]
+    fn set_pointer() {
+        let mut document = rfc6901_example();
+        *document.pointer_mut("/foo/1").unwrap() = string("qux");
+        assert_eq!(
+            Some(string("qux")),
+            document.set_pointer("/foo/1", int(1)).unwrap()
+        );
+        assert_eq!(None, document.set_pointer("/foo/2", int(2)).unwrap());
+        assert_eq!(None, document.set_pointer("/foo/-", int(3)).unwrap());
+        assert_eq!(
+            Some(&Document::Array(vec![
+                string("bar"),
+                int(1),
+                int(2),
+                int(3)
+            ])),
+            document.pointer("/foo")
+        );
+        assert_eq!(Some(int(8)), document.set_pointer("/m~0n", int(9)).unwrap());
+        assert_eq!(None, document.set_pointer("/new", int(10)).unwrap());
+        assert_eq!(Some(&int(10)), document.pointer("/new"));
+
+        assert_eq!(
+            Err(PointerError::NotFound("/foo/9".into())),
+            document.set_pointer("/foo/9", Document::Null)
+        );
+        assert_eq!(
+            Err(PointerError::NotFound("/missing/a".into())),
+            document.set_pointer("/missing/a", Document::Null)
+        );
+        assert_eq!(
+            Err(PointerError::NotFound("/new/a".into())),
+            document.set_pointer("/new/a", Document::Null)
+        );
+        assert_eq!(
+            Err(PointerError::Invalid("new".into())),
+            document.set_pointer("new", Document::Null)
+        );
+
+        let previous = document.clone();
+        assert_eq!(
+            Some(previous),
+            document.set_pointer("", Document::Null).unwrap()
+        );
+        assert_eq!(Document::Null, document);
+    }
+
+    #[test]
+    fn merge_patch() {
+        // https://www.rfc-editor.org/rfc/rfc7386#appendix-A
+        let array = |values: Vec<Document>| Document::Array(values);
+        let cases = vec![
+            (
+                object([("a", string("b"))]),
+                object([("a", string("c"))]),
+                object([("a", string("c"))]),
+            ),
+            (
+                object([("a", string("b"))]),
+                object([("b", string("c"))]),
+                object([("a", string("b")), ("b", string("c"))]),
+            ),
+            (
+                object([("a", string("b"))]),
+                object([("a", Document::Null)]),
+                object([]),
+            ),
+            (
+                object([("a", string("b")), ("b", string("c"))]),
+                object([("a", Document::Null)]),
+                object([("b", string("c"))]),
+            ),
+            (
+                object([("a", array(vec![string("b")]))]),
+                object([("a", string("c"))]),
+                object([("a", string("c"))]),
+            ),
+            (
+                object([("a", string("c"))]),
+                object([("a", array(vec![string("b")]))]),
+                object([("a", array(vec![string("b")]))]),
+            ),
+            (
+                object([("a", object([("b", string("c"))]))]),
+                object([("a", object([("b", string("d")), ("c", Document::Null)]))]),
+                object([("a", object([("b", string("d"))]))]),
+            ),
+            (
+                object([("a", array(vec![object([("b", string("c"))])]))]),
+                object([("a", array(vec![int(1)]))]),
+                object([("a", array(vec![int(1)]))]),
+            ),
+            (
+                array(vec![string("a"), string("b")]),
+                array(vec![string("c"), string("d")]),
+                array(vec![string("c"), string("d")]),
+            ),
+            (
+                object([("a", string("b"))]),
+                array(vec![string("c")]),
+                array(vec![string("c")]),
+            ),
+            (
+                object([("a", string("foo"))]),
+                Document::Null,
+                Document::Null,
+            ),
+            (object([("a", string("foo"))]), string("bar"), string("bar")),
+            (
+                object([("e", Document::Null)]),
+                object([("a", int(1))]),
+                object([("e", Document::Null), ("a", int(1))]),
+            ),
+            (
+                array(vec![int(1), int(2)]),
+                object([("a", string("b")), ("c", Document::Null)]),
+                object([("a", string("b"))]),
+            ),
+            (
+                object([]),
+                object([("a", object([("bb", object([("ccc", Document::Null)]))]))]),
+                object([("a", object([("bb", object([]))]))]),
+            ),
+        ];
+        for (mut target, patch, expected) in cases {
+            target.merge_patch(&patch);
+            assert_eq!(expected, target);
+        }
+    }
+
+    #[cfg(feature = "preserve-order")]
+    #[test]
+    fn preserves_order() {
+        let mut document = object([("z", int(1)), ("a", int(2)), ("m", int(3))]);
+        document.merge_patch(&object([("z", Document::Null), ("b", int(4))]));
+        document.set_pointer("/c", int(5)).unwrap();
+        let keys: Vec<_> = document.as_object().unwrap().keys().cloned().collect();
+        assert_eq!(vec!["a", "m", "b", "c"], keys);
+        assert_eq!(
+            Document::Object(crate::DocumentMap::from([(
+                "k".to_string(),
+                Document::Null
+            )])),
+            Document::from(std::collections::HashMap::from([(
+                "k".to_string(),
+                Document::Null
+            )]))
+        );
+    }
+}
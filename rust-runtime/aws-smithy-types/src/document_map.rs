@@ -0,0 +1,382 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Map of string keys to [`Document`] values that backs [`Document::Object`].
+
+use crate::Document;
+use std::collections::HashMap;
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ops::{Index, IndexMut};
+
+#[cfg(feature = "preserve-order")]
+use indexmap::map as map_impl;
+#[cfg(not(feature = "preserve-order"))]
+use std::collections::hash_map as map_impl;
+
+#[cfg(not(feature = "preserve-order"))]
+type Inner = HashMap<String, Document>;
+#[cfg(feature = "preserve-order")]
+type Inner = indexmap::IndexMap<String, Document>;
+
+/// Map of string keys to [`Document`] values that backs [`Document::Object`].
+///
+/// By default, members are iterated in an arbitrary order. With the `preserve-order` feature
+/// enabled, they're iterated in insertion order, so that documents round trip with their keys in
+/// the same order. The API of the map is the same either way.
+#[derive(Clone, Default, PartialEq)]
+pub struct DocumentMap {
+    inner: Inner,
+}
+
+impl DocumentMap {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty map with room for at least `capacity` members.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Inner::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of members in the map.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the map has no members.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Removes all members from the map.
+    pub fn clear(&mut self) {
+        self.inner.clear()
+    }
+
+    /// Returns true if the map has a member with the given `key`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.inner.contains_key(key)
+    }
+
+    /// Returns the value of the member with the given `key`.
+    pub fn get(&self, key: &str) -> Option<&Document> {
+        self.inner.get(key)
+    }
+
+    /// Returns a mutable reference to the value of the member with the given `key`.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Document> {
+        self.inner.get_mut(key)
+    }
+
+    /// Inserts a member, and returns the value it replaced, if any.
+    ///
+    /// Replacing the value of an existing member doesn't change its position in the map.
+    pub fn insert(&mut self, key: String, value: Document) -> Option<Document> {
+        self.inner.insert(key, value)
+    }
+
+    /// Removes the member with the given `key`, and returns its value, if any.
+    #[cfg(not(feature = "preserve-order"))]
+    pub fn remove(&mut self, key: &str) -> Option<Document> {
+        self.inner.remove(key)
+    }
+
+    /// Removes the member with the given `key`, and returns its value, if any.
+    ///
+    /// The remaining members keep their order.
+    #[cfg(feature = "preserve-order")]
+    pub fn remove(&mut self, key: &str) -> Option<Document> {
+        self.inner.shift_remove(key)
+    }
+
+    /// Returns the entry of the given `key` for in-place manipulation.
+    pub fn entry(&mut self, key: impl Into<String>) -> Entry<'_> {
+        Entry {
+            inner: self.inner.entry(key.into()),
+        }
+    }
+
+    /// Returns an iterator over the members of the map.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.inner.iter(),
+        }
+    }
+
+    /// Returns an iterator over the members of the map, with mutable references to the values.
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut {
+            inner: self.inner.iter_mut(),
+        }
+    }
+
+    /// Returns an iterator over the keys of the map.
+    pub fn keys(&self) -> Keys<'_> {
+        Keys {
+            inner: self.inner.keys(),
+        }
+    }
+
+    /// Returns an iterator over the values of the map.
+    pub fn values(&self) -> Values<'_> {
+        Values {
+            inner: self.inner.values(),
+        }
+    }
+
+    /// Returns an iterator over mutable references to the values of the map.
+    pub fn values_mut(&mut self) -> ValuesMut<'_> {
+        ValuesMut {
+            inner: self.inner.values_mut(),
+        }
+    }
+}
+
+impl fmt::Debug for DocumentMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl Index<&str> for DocumentMap {
+    type Output = Document;
+
+    /// Returns the value of the member with the given `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map doesn't have a member with the given `key`.
+    fn index(&self, key: &str) -> &Document {
+        self.get(key).expect("no member with the given key")
+    }
+}
+
+impl IndexMut<&str> for DocumentMap {
+    /// Returns a mutable reference to the value of the member with the given `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map doesn't have a member with the given `key`.
+    fn index_mut(&mut self, key: &str) -> &mut Document {
+        self.get_mut(key).expect("no member with the given key")
+    }
+}
+
+impl From<HashMap<String, Document>> for DocumentMap {
+    fn from(values: HashMap<String, Document>) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl<const N: usize> From<[(String, Document); N]> for DocumentMap {
+    fn from(values: [(String, Document); N]) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl FromIterator<(String, Document)> for DocumentMap {
+    fn from_iter<T: IntoIterator<Item = (String, Document)>>(iter: T) -> Self {
+        Self {
+            inner: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<(String, Document)> for DocumentMap {
+    fn extend<T: IntoIterator<Item = (String, Document)>>(&mut self, iter: T) {
+        self.inner.extend(iter)
+    }
+}
+
+impl IntoIterator for DocumentMap {
+    type Item = (String, Document);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        IntoIter {
+            inner: self.inner.into_iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a DocumentMap {
+    type Item = (&'a String, &'a Document);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut DocumentMap {
+    type Item = (&'a String, &'a mut Document);
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> IterMut<'a> {
+        self.iter_mut()
+    }
+}
+
+/// Entry of a [`DocumentMap`], returned by [`DocumentMap::entry`].
+#[derive(Debug)]
+pub struct Entry<'a> {
+    inner: map_impl::Entry<'a, String, Document>,
+}
+
+impl<'a> Entry<'a> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &String {
+        self.inner.key()
+    }
+
+    /// Inserts `default` if the entry is vacant, and returns a mutable reference to its value.
+    pub fn or_insert(self, default: Document) -> &'a mut Document {
+        self.inner.or_insert(default)
+    }
+
+    /// Inserts the result of `default` if the entry is vacant, and returns a mutable reference
+    /// to its value.
+    pub fn or_insert_with<F: FnOnce() -> Document>(self, default: F) -> &'a mut Document {
+        self.inner.or_insert_with(default)
+    }
+
+    /// Calls `f` with the value of the entry if it's occupied.
+    pub fn and_modify<F: FnOnce(&mut Document)>(self, f: F) -> Self {
+        Self {
+            inner: self.inner.and_modify(f),
+        }
+    }
+}
+
+macro_rules! delegate_iterator {
+    ($(#[$doc:meta])* $name:ident$(<$lt:lifetime>)?, $item:ty) => {
+        $(#[$doc])*
+        #[derive(Debug)]
+        pub struct $name$(<$lt>)? {
+            inner: map_impl::$name<$($lt,)? String, Document>,
+        }
+
+        impl$(<$lt>)? Iterator for $name$(<$lt>)? {
+            type Item = $item;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
+            }
+        }
+
+        impl$(<$lt>)? ExactSizeIterator for $name$(<$lt>)? {
+            fn len(&self) -> usize {
+                self.inner.len()
+            }
+        }
+
+        impl$(<$lt>)? FusedIterator for $name$(<$lt>)? {}
+    };
+}
+
+delegate_iterator!(
+    /// Iterator over the members of a [`DocumentMap`], returned by [`DocumentMap::iter`].
+    Iter<'a>,
+    (&'a String, &'a Document)
+);
+delegate_iterator!(
+    /// Iterator over the members of a [`DocumentMap`], returned by [`DocumentMap::iter_mut`].
+    IterMut<'a>,
+    (&'a String, &'a mut Document)
+);
+delegate_iterator!(
+    /// Owning iterator over the members of a [`DocumentMap`].
+    IntoIter,
+    (String, Document)
+);
+delegate_iterator!(
+    /// Iterator over the keys of a [`DocumentMap`], returned by [`DocumentMap::keys`].
+    Keys<'a>,
+    &'a String
+);
+delegate_iterator!(
+    /// Iterator over the values of a [`DocumentMap`], returned by [`DocumentMap::values`].
+    Values<'a>,
+    &'a Document
+);
+delegate_iterator!(
+    /// Iterator over the values of a [`DocumentMap`], returned by [`DocumentMap::values_mut`].
+    ValuesMut<'a>,
+    &'a mut Document
+);
+
+#[cfg(test)]
+mod test {
+    use super::DocumentMap;
+    use crate::Document;
+    use std::collections::HashMap;
+
+    fn member(key: &str, value: bool) -> (String, Document) {
+        (key.to_string(), Document::Bool(value))
+    }
+
+    #[test]
+    fn map() {
+        let mut map = DocumentMap::from([member("a", true), member("b", false)]);
+        assert_eq!(2, map.len());
+        assert!(map.contains_key("a"));
+        assert_eq!(Some(&Document::Bool(true)), map.get("a"));
+        assert_eq!(Document::Bool(false), map["b"]);
+        assert_eq!(
+            Some(Document::Bool(true)),
+            map.insert("a".into(), Document::Null)
+        );
+        map["b"] = Document::Null;
+        assert!(map.values().all(Document::is_null));
+
+        *map.entry("c").or_insert(Document::Bool(true)) = Document::Bool(false);
+        map.entry("c")
+            .and_modify(|value| *value = Document::Null)
+            .or_insert(Document::Bool(true));
+        assert_eq!(Some(&Document::Null), map.get("c"));
+
+        assert_eq!(Some(Document::Null), map.remove("a"));
+        assert_eq!(None, map.remove("a"));
+        assert_eq!(
+            HashMap::from([
+                ("b".to_string(), Document::Null),
+                ("c".into(), Document::Null)
+            ]),
+            map.into_iter().collect::<HashMap<_, _>>()
+        );
+    }
+
+    #[test]
+    fn equality_ignores_order() {
+        assert_eq!(
+            DocumentMap::from([member("a", true), member("b", false)]),
+            DocumentMap::from(HashMap::from([member("b", false), member("a", true)]))
+        );
+    }
+
+    #[cfg(feature = "preserve-order")]
+    #[test]
+    fn preserves_order() {
+        let mut map: DocumentMap = ["z", "a", "m", "b"]
+            .iter()
+            .map(|key| member(key, true))
+            .collect();
+        map.remove("a");
+        map.extend([member("c", true), member("z", false)]);
+        assert_eq!(vec!["z", "m", "b", "c"], map.keys().collect::<Vec<_>>());
+        assert_eq!(
+            r#"{"z": Bool(false), "m": Bool(true), "b": Bool(true), "c": Bool(true)}"#,
+            format!("{:?}", map)
+        );
+    }
+}
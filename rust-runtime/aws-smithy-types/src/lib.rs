@@ -17,13 +17,19 @@ use std::collections::HashMap;
 
 pub mod base64;
 pub mod date_time;
+pub mod document;
+pub mod document_map;
 pub mod endpoint;
 pub mod error;
 pub mod primitive;
 pub mod retry;
 pub mod timeout;
 
+#[cfg(feature = "serde")]
+mod serde_impl;
+
 pub use crate::date_time::DateTime;
+pub use crate::document_map::DocumentMap;
 pub use error::Error;
 
 /// Binary Blob Type
@@ -65,7 +71,7 @@ impl AsRef<[u8]> for Blob {
 #[derive(Debug, Clone, PartialEq)]
 pub enum Document {
     /// JSON object
-    Object(HashMap<String, Document>),
+    Object(DocumentMap),
     /// JSON array
     Array(Vec<Document>),
     /// JSON number
@@ -96,9 +102,15 @@ impl From<Vec<Document>> for Document {
     }
 }
 
+impl From<DocumentMap> for Document {
+    fn from(values: DocumentMap) -> Self {
+        Document::Object(values)
+    }
+}
+
 impl From<HashMap<String, Document>> for Document {
     fn from(values: HashMap<String, Document>) -> Self {
-        Document::Object(values)
+        Document::Object(values.into_iter().collect())
     }
 }
 
@@ -0,0 +1,728 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! [`serde`] implementations for the types of this crate, enabled by the `serde` feature.
+//!
+//! - [`Blob`] is a base64 encoded string in human-readable formats, and raw bytes otherwise.
+//! - [`DateTime`] is an RFC-3339 string with nanosecond precision in human-readable formats, and
+//!   a `(seconds, subsecond_nanos)` tuple otherwise.
+//! - [`Number`] is a plain number, and [`Document`] maps to the equivalent map, sequence,
+//!   number, string, boolean or unit value. Deserializing them requires a self-describing format.
+//! - [`RetryConfig`] and [`TimeoutConfig`] are maps of their settings. Missing settings take
+//!   their default value, and durations are strings such as `"250ms"` or `"3s"` in human-readable
+//!   formats, and `(seconds, subsecond_nanos)` tuples otherwise.
+
+use crate::date_time::Format;
+use crate::retry::{RetryConfig, RetryConfigBuilder, RetryMode};
+use crate::timeout::TimeoutConfig;
+use crate::{base64, Blob, DateTime, Document, DocumentMap, Number};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{self, Serialize, SerializeMap, SerializeSeq, SerializeStruct, Serializer};
+use std::fmt;
+use std::time::Duration;
+
+impl Serialize for Blob {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&base64::encode(&self.inner))
+        } else {
+            serializer.serialize_bytes(&self.inner)
+        }
+    }
+}
+
+struct BlobVisitor;
+
+impl<'de> Visitor<'de> for BlobVisitor {
+    type Value = Blob;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a base64 encoded string or bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        base64::decode(v).map(Blob::new).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(Blob::new(v))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(Blob::new(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(Blob::new(bytes))
+    }
+}
+
+impl<'de> Deserialize<'de> for Blob {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BlobVisitor)
+        } else {
+            deserializer.deserialize_byte_buf(BlobVisitor)
+        }
+    }
+}
+
+impl Serialize for DateTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let formatted = self.fmt_rfc3339_nanos().map_err(ser::Error::custom)?;
+            serializer.serialize_str(&formatted)
+        } else {
+            (self.secs(), self.subsec_nanos()).serialize(serializer)
+        }
+    }
+}
+
+struct DateTimeVisitor;
+
+impl<'de> Visitor<'de> for DateTimeVisitor {
+    type Value = DateTime;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an RFC-3339 date-time string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        DateTime::from_str(v, Format::DateTime).map_err(E::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(DateTimeVisitor)
+        } else {
+            let (seconds, subsecond_nanos) = <(i64, u32)>::deserialize(deserializer)?;
+            if subsecond_nanos >= 1_000_000_000 {
+                return Err(de::Error::invalid_value(
+                    de::Unexpected::Unsigned(subsecond_nanos as u64),
+                    &"fewer than 1,000,000,000 subsecond nanos",
+                ));
+            }
+            Ok(DateTime::from_secs_and_nanos(seconds, subsecond_nanos))
+        }
+    }
+}
+
+impl Serialize for Number {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Number::PosInt(value) => serializer.serialize_u64(value),
+            Number::NegInt(value) => serializer.serialize_i64(value),
+            Number::Float(value) => serializer.serialize_f64(value),
+        }
+    }
+}
+
+struct NumberVisitor;
+
+impl<'de> Visitor<'de> for NumberVisitor {
+    type Value = Number;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a number")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        // `Number::NegInt` must always hold a negative value
+        Ok(if v < 0 {
+            Number::NegInt(v)
+        } else {
+            Number::PosInt(v as u64)
+        })
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Number::PosInt(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Number::Float(v))
+    }
+}
+
+impl<'de> Deserialize<'de> for Number {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(NumberVisitor)
+    }
+}
+
+impl Serialize for Document {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Document::Object(values) => {
+                let mut map = serializer.serialize_map(Some(values.len()))?;
+                for (key, value) in values {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Document::Array(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            Document::Number(value) => value.serialize(serializer),
+            Document::String(value) => serializer.serialize_str(value),
+            Document::Bool(value) => serializer.serialize_bool(*value),
+            Document::Null => serializer.serialize_unit(),
+        }
+    }
+}
+
+struct DocumentVisitor;
+
+impl<'de> Visitor<'de> for DocumentVisitor {
+    type Value = Document;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a document")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Document::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        NumberVisitor.visit_i64(v).map(Document::Number)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        NumberVisitor.visit_u64(v).map(Document::Number)
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        NumberVisitor.visit_f64(v).map(Document::Number)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Document::String(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Document::String(v))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Document::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        Document::deserialize(deserializer)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Document::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Document::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut values = DocumentMap::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some((key, value)) = map.next_entry()? {
+            values.insert(key, value);
+        }
+        Ok(Document::Object(values))
+    }
+}
+
+impl<'de> Deserialize<'de> for Document {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(DocumentVisitor)
+    }
+}
+
+/// A [`Duration`] setting of a configuration
+struct ConfigDuration(Duration);
+
+/// Units of human-readable durations, from the largest to the smallest
+const DURATION_UNITS: &[(&str, u128)] = &[
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+impl Serialize for ConfigDuration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let nanos = self.0.as_nanos();
+            // Durations are written with the largest unit that represents them exactly
+            let (unit, unit_nanos) = DURATION_UNITS
+                .iter()
+                .find(|(_, unit_nanos)| nanos / unit_nanos * unit_nanos == nanos)
+                .expect("every duration is a number of nanoseconds");
+            serializer.collect_str(&format_args!("{}{}", nanos / unit_nanos, unit))
+        } else {
+            (self.0.as_secs(), self.0.subsec_nanos()).serialize(serializer)
+        }
+    }
+}
+
+struct ConfigDurationVisitor;
+
+impl<'de> Visitor<'de> for ConfigDurationVisitor {
+    type Value = ConfigDuration;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a duration such as \"250ms\" or \"3s\"")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        let invalid = || E::invalid_value(de::Unexpected::Str(v), &self);
+        let digits = v.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+        let (value, unit) = v.split_at(digits);
+        let unit_nanos = DURATION_UNITS
+            .iter()
+            .find(|(name, _)| *name == unit)
+            .map(|(_, unit_nanos)| unit_nanos)
+            .ok_or_else(invalid)?;
+        let nanos = value
+            .parse::<u128>()
+            .ok()
+            .and_then(|value| value.checked_mul(*unit_nanos))
+            .ok_or_else(invalid)?;
+        let secs = u64::try_from(nanos / 1_000_000_000).map_err(|_| invalid())?;
+        Ok(ConfigDuration(Duration::new(
+            secs,
+            (nanos % 1_000_000_000) as u32,
+        )))
+    }
+}
+
+impl<'de> Deserialize<'de> for ConfigDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(ConfigDurationVisitor)
+        } else {
+            let (secs, nanos) = <(u64, u32)>::deserialize(deserializer)?;
+            if nanos >= 1_000_000_000 {
+                return Err(de::Error::invalid_value(
+                    de::Unexpected::Unsigned(nanos as u64),
+                    &"fewer than 1,000,000,000 subsecond nanos",
+                ));
+            }
+            Ok(ConfigDuration(Duration::new(secs, nanos)))
+        }
+    }
+}
+
+impl Serialize for RetryConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut config = serializer.serialize_struct("RetryConfig", 3)?;
+        config.serialize_field("mode", &self.mode())?;
+        config.serialize_field("max_attempts", &self.max_attempts())?;
+        config.serialize_field("initial_backoff", &ConfigDuration(self.initial_backoff()))?;
+        config.end()
+    }
+}
+
+impl Serialize for RetryMode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            RetryMode::Standard => serializer.serialize_unit_variant("RetryMode", 0, "standard"),
+            RetryMode::Adaptive => serializer.serialize_unit_variant("RetryMode", 1, "adaptive"),
+        }
+    }
+}
+
+const RETRY_MODES: &[&str] = &["standard", "adaptive"];
+
+struct RetryModeVisitor;
+
+impl<'de> Visitor<'de> for RetryModeVisitor {
+    type Value = RetryMode;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a retry mode")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        match v {
+            "standard" => Ok(RetryMode::Standard),
+            "adaptive" => Ok(RetryMode::Adaptive),
+            _ => Err(E::unknown_variant(v, RETRY_MODES)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RetryMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(RetryModeVisitor)
+    }
+}
+
+/// Stores the value of a configuration setting, rejecting settings that appear twice
+fn set_once<T, E: de::Error>(
+    setting: &mut Option<Option<T>>,
+    name: &'static str,
+    value: Option<T>,
+) -> Result<(), E> {
+    if setting.is_some() {
+        return Err(E::duplicate_field(name));
+    }
+    *setting = Some(value);
+    Ok(())
+}
+
+const RETRY_CONFIG_FIELDS: &[&str] = &["mode", "max_attempts", "initial_backoff"];
+
+struct RetryConfigVisitor;
+
+impl<'de> Visitor<'de> for RetryConfigVisitor {
+    type Value = RetryConfig;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a retry config")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let (mut mode, mut max_attempts, mut initial_backoff) = (None, None, None);
+        while let Some(name) = map.next_key::<String>()? {
+            match name.as_str() {
+                "mode" => set_once(&mut mode, "mode", map.next_value()?)?,
+                "max_attempts" => set_once(&mut max_attempts, "max_attempts", map.next_value()?)?,
+                "initial_backoff" => set_once(
+                    &mut initial_backoff,
+                    "initial_backoff",
+                    map.next_value::<Option<ConfigDuration>>()?,
+                )?,
+                _ => return Err(de::Error::unknown_field(&name, RETRY_CONFIG_FIELDS)),
+            }
+        }
+        let max_attempts = max_attempts.flatten();
+        if max_attempts == Some(0) {
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(0),
+                &"max attempts greater than zero",
+            ));
+        }
+        let mut builder = RetryConfigBuilder::new();
+        builder
+            .set_mode(mode.flatten())
+            .set_max_attempts(max_attempts)
+            .set_initial_backoff(initial_backoff.flatten().map(|backoff| backoff.0));
+        Ok(builder.build())
+    }
+}
+
+impl<'de> Deserialize<'de> for RetryConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("RetryConfig", RETRY_CONFIG_FIELDS, RetryConfigVisitor)
+    }
+}
+
+impl Serialize for TimeoutConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let timeouts = [
+            ("connect_timeout", self.connect_timeout()),
+            ("read_timeout", self.read_timeout()),
+            ("operation_timeout", self.operation_timeout()),
+            (
+                "operation_attempt_timeout",
+                self.operation_attempt_timeout(),
+            ),
+        ];
+        // Timeouts that aren't set are left out
+        let len = timeouts
+            .iter()
+            .filter(|(_, timeout)| timeout.is_some())
+            .count();
+        let mut config = serializer.serialize_struct("TimeoutConfig", len)?;
+        for (name, timeout) in timeouts {
+            match timeout {
+                Some(timeout) => config.serialize_field(name, &ConfigDuration(timeout))?,
+                None => config.skip_field(name)?,
+            }
+        }
+        config.end()
+    }
+}
+
+const TIMEOUT_CONFIG_FIELDS: &[&str] = &[
+    "connect_timeout",
+    "read_timeout",
+    "operation_timeout",
+    "operation_attempt_timeout",
+];
+
+struct TimeoutConfigVisitor;
+
+impl<'de> Visitor<'de> for TimeoutConfigVisitor {
+    type Value = TimeoutConfig;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a timeout config")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut timeouts: [Option<Option<ConfigDuration>>; 4] = [None, None, None, None];
+        while let Some(name) = map.next_key::<String>()? {
+            let index = TIMEOUT_CONFIG_FIELDS
+                .iter()
+                .position(|field| *field == name)
+                .ok_or_else(|| de::Error::unknown_field(&name, TIMEOUT_CONFIG_FIELDS))?;
+            set_once(
+                &mut timeouts[index],
+                TIMEOUT_CONFIG_FIELDS[index],
+                map.next_value()?,
+            )?;
+        }
+        let [connect, read, operation, operation_attempt] =
+            timeouts.map(|timeout| timeout.flatten().map(|timeout| timeout.0));
+        let mut builder = TimeoutConfig::builder();
+        builder
+            .set_connect_timeout(connect)
+            .set_read_timeout(read)
+            .set_operation_timeout(operation)
+            .set_operation_attempt_timeout(operation_attempt);
+        Ok(builder.build())
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeoutConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct(
+            "TimeoutConfig",
+            TIMEOUT_CONFIG_FIELDS,
+            TimeoutConfigVisitor,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::retry::{RetryConfig, RetryMode};
+    use crate::timeout::TimeoutConfig;
+    use crate::{Blob, DateTime, Document, Number};
+    use proptest::prelude::*;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use std::fmt::Debug;
+    use std::time::Duration;
+
+    /// Serializes `value` to JSON, a human-readable format, and back.
+    fn json_round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+        serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+    }
+
+    /// Serializes `value` to CBOR, a binary format, and back.
+    fn cbor_round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(value, &mut bytes).unwrap();
+        ciborium::de::from_reader(bytes.as_slice()).unwrap()
+    }
+
+    fn assert_round_trips<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: T) {
+        assert_eq!(value, json_round_trip(&value));
+        assert_eq!(value, cbor_round_trip(&value));
+    }
+
+    #[test]
+    fn blob() {
+        let blob = Blob::new("hello!");
+        assert_eq!("\"aGVsbG8h\"", serde_json::to_string(&blob).unwrap());
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&blob, &mut bytes).unwrap();
+        assert_eq!(b"\x46hello!".as_slice(), bytes);
+        assert!(serde_json::from_str::<Blob>("\"not base64!\"").is_err());
+    }
+
+    #[test]
+    fn date_time() {
+        let date_time = DateTime::from_secs_and_nanos(1576540098, 52_000_000);
+        assert_eq!(
+            "\"2019-12-16T23:48:18.052Z\"",
+            serde_json::to_string(&date_time).unwrap()
+        );
+        assert_eq!(
+            date_time,
+            serde_json::from_str("\"2019-12-16T23:48:18.052Z\"").unwrap()
+        );
+        let date_time = DateTime::from_secs_and_nanos(1576540098, 123_456_789);
+        assert_eq!(
+            "\"2019-12-16T23:48:18.123456789Z\"",
+            serde_json::to_string(&date_time).unwrap()
+        );
+        assert_eq!(date_time, json_round_trip(&date_time));
+        assert!(serde_json::to_string(&DateTime::from_secs(i64::MAX)).is_err());
+        assert!(serde_json::from_str::<DateTime>("\"2019-12-16T23:48:18+01:00\"").is_err());
+
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&(0, 1_000_000_000u32), &mut bytes).unwrap();
+        assert!(ciborium::de::from_reader::<DateTime, _>(bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn document() {
+        let document: Document = serde_json::from_str(
+            r#"{"num": 1, "neg": -1, "float": 1.5, "string": "s", "bool": true, "null": null, "array": [{}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            Document::Object(
+                vec![
+                    ("num".to_string(), Document::Number(Number::PosInt(1))),
+                    ("neg".to_string(), Document::Number(Number::NegInt(-1))),
+                    ("float".to_string(), Document::Number(Number::Float(1.5))),
+                    ("string".to_string(), Document::String("s".into())),
+                    ("bool".to_string(), Document::Bool(true)),
+                    ("null".to_string(), Document::Null),
+                    (
+                        "array".to_string(),
+                        Document::Array(vec![Document::Object(Default::default())])
+                    ),
+                ]
+                .into_iter()
+                .collect()
+            ),
+            document
+        );
+        assert_eq!(
+            "[1,-1,1.5,\"s\",true,null]",
+            serde_json::to_string(&Document::Array(vec![
+                Document::Number(Number::PosInt(1)),
+                Document::Number(Number::NegInt(-1)),
+                Document::Number(Number::Float(1.5)),
+                Document::String("s".into()),
+                Document::Bool(true),
+                Document::Null,
+            ]))
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn retry_config() {
+        let config = RetryConfig::standard()
+            .with_max_attempts(5)
+            .with_initial_backoff(Duration::from_millis(250));
+        assert_eq!(
+            r#"{"mode":"standard","max_attempts":5,"initial_backoff":"250ms"}"#,
+            serde_json::to_string(&config).unwrap()
+        );
+        assert_round_trips(config);
+        assert_round_trips(RetryConfig::disabled().with_retry_mode(RetryMode::Adaptive));
+
+        // Missing settings take their default value
+        assert_eq!(
+            RetryConfig::standard(),
+            serde_json::from_str(r#"{"mode":"standard"}"#).unwrap()
+        );
+        assert_eq!(
+            RetryConfig::standard().with_initial_backoff(Duration::from_secs(120)),
+            serde_json::from_str(r#"{"initial_backoff":"2m"}"#).unwrap()
+        );
+        assert!(serde_json::from_str::<RetryConfig>(r#"{"max_attempts":0}"#).is_err());
+        assert!(serde_json::from_str::<RetryConfig>(r#"{"max_attempt":3}"#).is_err());
+        assert!(serde_json::from_str::<RetryConfig>(r#"{"mode":"Standard"}"#).is_err());
+        assert!(
+            serde_json::from_str::<RetryConfig>(r#"{"max_attempts":3,"max_attempts":4}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn timeout_config() {
+        let config = TimeoutConfig::builder()
+            .connect_timeout(Duration::from_secs(1))
+            .build();
+        assert_round_trips(config.clone());
+        assert_eq!(
+            r#"{"connect_timeout":"1s"}"#,
+            serde_json::to_string(&config).unwrap()
+        );
+        assert_eq!(
+            config,
+            serde_json::from_str(r#"{"connect_timeout":"1000ms"}"#).unwrap()
+        );
+        assert_round_trips(TimeoutConfig::disabled());
+        assert_round_trips(
+            TimeoutConfig::builder()
+                .read_timeout(Duration::new(1, 5))
+                .operation_timeout(Duration::from_secs(3600))
+                .build(),
+        );
+
+        for invalid in [
+            "1",
+            "s",
+            "1.5s",
+            "-1s",
+            "1 s",
+            "1d",
+            "99999999999999999999999h",
+        ] {
+            let input = format!(r#"{{"read_timeout":"{}"}}"#, invalid);
+            assert!(
+                serde_json::from_str::<TimeoutConfig>(&input).is_err(),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    fn arb_document() -> impl Strategy<Value = Document> {
+        let leaf = prop_oneof![
+            Just(Document::Null),
+            any::<bool>().prop_map(Document::Bool),
+            any::<u64>().prop_map(|value| Document::Number(Number::PosInt(value))),
+            (i64::MIN..0).prop_map(|value| Document::Number(Number::NegInt(value))),
+            any::<f64>()
+                .prop_filter("must be finite for JSON", |v| v.is_finite())
+                .prop_map(|value| Document::Number(Number::Float(value))),
+            ".*".prop_map(Document::String),
+        ];
+        leaf.prop_recursive(4, 64, 8, |inner| {
+            prop_oneof![
+                proptest::collection::vec(inner.clone(), 0..8).prop_map(Document::Array),
+                proptest::collection::vec((".*", inner), 0..8)
+                    .prop_map(|members| Document::Object(members.into_iter().collect())),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn blob_round_trip(bytes in proptest::collection::vec(any::<u8>(), 0..256)) {
+            assert_round_trips(Blob::new(bytes));
+        }
+
+        #[test]
+        fn date_time_round_trip(secs in -62_135_596_800i64..253_402_300_800, nanos in 0..1_000_000_000u32) {
+            // Between 0001-01-01T00:00:00Z and 9999-12-31T23:59:59.999999999Z, the range of RFC-3339
+            assert_round_trips(DateTime::from_secs_and_nanos(secs, nanos));
+        }
+
+        #[test]
+        fn binary_date_time_round_trip(secs in any::<i64>(), nanos in 0..1_000_000_000u32) {
+            let date_time = DateTime::from_secs_and_nanos(secs, nanos);
+            prop_assert_eq!(date_time, cbor_round_trip(&date_time));
+        }
+
+        #[test]
+        fn document_round_trip(document in arb_document()) {
+            assert_round_trips(document);
+        }
+    }
+}